@@ -0,0 +1,88 @@
+-- Per-card default currency and optional per-row currency column
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'USD';
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS currency_column TEXT;
+
+-- Currency of `amount`, plus the pre-conversion amount when the statement reports one
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'USD';
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS original_amount NUMERIC(12,2);
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS original_currency TEXT;
+
+-- Daily exchange rates: 1 unit of `base` = `rate` units of `quote`
+CREATE TABLE IF NOT EXISTS fx_rates (
+    date DATE NOT NULL,
+    base TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    rate NUMERIC(18,8) NOT NULL,
+    source TEXT NOT NULL DEFAULT 'csv',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (base, quote, date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fx_rates_quote ON fx_rates(quote, date);
+
+-- Most recent rate on or before the date (falls back to the earliest later rate).
+-- Tries the direct pair, the inverse pair, then a cross rate through any shared base.
+CREATE OR REPLACE FUNCTION fx_rate(from_ccy TEXT, to_ccy TEXT, on_date DATE)
+RETURNS DOUBLE PRECISION
+LANGUAGE plpgsql STABLE AS $$
+DECLARE
+    result DOUBLE PRECISION;
+BEGIN
+    IF from_ccy = to_ccy THEN
+        RETURN 1.0;
+    END IF;
+
+    SELECT rate::float8 INTO result FROM fx_rates
+    WHERE base = from_ccy AND quote = to_ccy
+    ORDER BY (date > on_date), abs(date - on_date)
+    LIMIT 1;
+    IF result IS NOT NULL THEN
+        RETURN result;
+    END IF;
+
+    SELECT 1.0 / NULLIF(rate::float8, 0) INTO result FROM fx_rates
+    WHERE base = to_ccy AND quote = from_ccy
+    ORDER BY (date > on_date), abs(date - on_date)
+    LIMIT 1;
+    IF result IS NOT NULL THEN
+        RETURN result;
+    END IF;
+
+    SELECT t.rate::float8 / NULLIF(f.rate::float8, 0) INTO result
+    FROM fx_rates f
+    JOIN fx_rates t ON t.base = f.base AND t.date = f.date
+    WHERE f.quote = from_ccy AND t.quote = to_ccy
+    ORDER BY (f.date > on_date), abs(f.date - on_date)
+    LIMIT 1;
+
+    RETURN result;
+END;
+$$;
+
+-- Analytics read from this view: same rows as `transactions`, with `amount`
+-- converted into the home currency configured in user_config (default USD).
+-- Amounts without a known rate pass through unconverted.
+CREATE OR REPLACE VIEW transactions_home AS
+SELECT
+    t.id,
+    t.date,
+    t.description,
+    CASE
+        WHEN t.currency = h.home THEN t.amount
+        ELSE (t.amount * COALESCE(fx_rate(t.currency, h.home, t.date), 1.0))::numeric(12,2)
+    END AS amount,
+    t.category,
+    t.card,
+    t.card_label,
+    t.raw_data,
+    t.hash,
+    t.created_at,
+    t.merchant_normalized,
+    t.import_id,
+    t.currency,
+    t.original_amount,
+    t.original_currency
+FROM transactions t
+CROSS JOIN (
+    SELECT COALESCE((SELECT value FROM user_config WHERE key = 'home_currency'), 'USD') AS home
+) h;
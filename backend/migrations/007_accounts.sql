@@ -0,0 +1,25 @@
+-- Cards double as accounts: credit cards, bank accounts and cash
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS account_type TEXT NOT NULL DEFAULT 'credit';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'cards_account_type_check') THEN
+        ALTER TABLE cards ADD CONSTRAINT cards_account_type_check
+            CHECK (account_type IN ('credit', 'checking', 'savings', 'cash'));
+    END IF;
+END $$;
+
+-- Opening balances anchor the running balance; assertions check it on a given date
+CREATE TABLE IF NOT EXISTS account_balances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    card_id UUID NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('opening', 'assertion')),
+    date DATE NOT NULL,
+    balance NUMERIC(12,2) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_balances_card ON account_balances(card_id, date);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_account_balances_opening
+    ON account_balances(card_id) WHERE kind = 'opening';
@@ -0,0 +1,55 @@
+-- Sign handling for bank exports: which sign is money out, and whether credits are imported
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS amount_sign TEXT NOT NULL DEFAULT 'debit_positive';
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS import_credits BOOLEAN NOT NULL DEFAULT false;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'cards_amount_sign_check') THEN
+        ALTER TABLE cards ADD CONSTRAINT cards_amount_sign_check
+            CHECK (amount_sign IN ('debit_positive', 'debit_negative'));
+    END IF;
+END $$;
+
+-- Positive amounts are money out, negative amounts money in.
+-- Income is kept out of every spending aggregate.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS classification TEXT NOT NULL DEFAULT 'expense';
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'transactions_classification_check') THEN
+        ALTER TABLE transactions ADD CONSTRAINT transactions_classification_check
+            CHECK (classification IN ('expense', 'income'));
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_classification ON transactions(classification);
+
+CREATE OR REPLACE VIEW transactions_home AS
+SELECT
+    t.id,
+    t.date,
+    t.description,
+    CASE
+        WHEN t.currency = h.home THEN t.amount
+        ELSE (t.amount * COALESCE(fx_rate(t.currency, h.home, t.date), 1.0))::numeric(12,2)
+    END AS amount,
+    t.category,
+    t.card,
+    t.card_label,
+    t.raw_data,
+    t.hash,
+    t.created_at,
+    t.merchant_normalized,
+    t.import_id,
+    t.currency,
+    t.original_amount,
+    t.original_currency,
+    t.classification
+FROM transactions t
+CROSS JOIN (
+    SELECT COALESCE((SELECT value FROM user_config WHERE key = 'home_currency'), 'USD') AS home
+) h;
+
+-- Spending analytics read from this view: expenses only, in the home currency
+CREATE OR REPLACE VIEW spending_home AS
+SELECT * FROM transactions_home WHERE classification = 'expense';
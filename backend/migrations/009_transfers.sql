@@ -0,0 +1,48 @@
+-- Transfers between own accounts: the debit on one account paired with the credit on another
+CREATE TABLE IF NOT EXISTS transfer_pairs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    debit_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    credit_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'suggested' CHECK (status IN ('suggested', 'confirmed', 'unlinked')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (debit_id, credit_id)
+);
+
+-- A transaction belongs to at most one live pair; unlinked pairs are kept so they aren't re-suggested
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transfer_pairs_debit ON transfer_pairs(debit_id) WHERE status <> 'unlinked';
+CREATE UNIQUE INDEX IF NOT EXISTS idx_transfer_pairs_credit ON transfer_pairs(credit_id) WHERE status <> 'unlinked';
+
+CREATE OR REPLACE VIEW transactions_home AS
+SELECT
+    t.id,
+    t.date,
+    t.description,
+    CASE
+        WHEN t.currency = h.home THEN t.amount
+        ELSE (t.amount * COALESCE(fx_rate(t.currency, h.home, t.date), 1.0))::numeric(12,2)
+    END AS amount,
+    t.category,
+    t.card,
+    t.card_label,
+    t.raw_data,
+    t.hash,
+    t.created_at,
+    t.merchant_normalized,
+    t.import_id,
+    t.currency,
+    t.original_amount,
+    t.original_currency,
+    t.classification,
+    EXISTS (
+        SELECT 1 FROM transfer_pairs p
+        WHERE p.status <> 'unlinked' AND (p.debit_id = t.id OR p.credit_id = t.id)
+    ) AS is_transfer
+FROM transactions t
+CROSS JOIN (
+    SELECT COALESCE((SELECT value FROM user_config WHERE key = 'home_currency'), 'USD') AS home
+) h;
+
+-- Paired transfers are neither spending nor income
+CREATE OR REPLACE VIEW spending_home AS
+SELECT * FROM transactions_home WHERE classification = 'expense' AND NOT is_transfer;
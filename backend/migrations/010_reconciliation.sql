@@ -0,0 +1,8 @@
+-- Statement details supplied with (or parsed from) an import, and the reconciliation result
+ALTER TABLE import_history ADD COLUMN IF NOT EXISTS statement_start DATE;
+ALTER TABLE import_history ADD COLUMN IF NOT EXISTS statement_end DATE;
+ALTER TABLE import_history ADD COLUMN IF NOT EXISTS opening_balance NUMERIC(12,2);
+ALTER TABLE import_history ADD COLUMN IF NOT EXISTS closing_balance NUMERIC(12,2);
+ALTER TABLE import_history ADD COLUMN IF NOT EXISTS reconciliation JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_import_history_card_period ON import_history(card, statement_start);
@@ -0,0 +1,18 @@
+-- Recurring charges with an identity: seeded from detection, then confirmed, ignored or cancelled by the user
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    merchant TEXT NOT NULL,
+    frequency TEXT NOT NULL,
+    -- Latest detected charge amount; expected_amount is the user's own figure
+    amount NUMERIC(12,2) NOT NULL,
+    expected_amount NUMERIC(12,2),
+    status TEXT NOT NULL DEFAULT 'detected' CHECK (status IN ('detected', 'confirmed', 'ignored', 'cancelled')),
+    cancelled_on DATE,
+    first_seen DATE NOT NULL,
+    last_charge_date DATE NOT NULL,
+    next_expected_date DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_merchant ON subscriptions(merchant);
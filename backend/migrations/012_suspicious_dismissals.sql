@@ -0,0 +1,6 @@
+-- Suspicious-charge findings the user has reviewed, keyed by a fingerprint of the finding's kind and transactions
+CREATE TABLE IF NOT EXISTS suspicious_dismissals (
+    finding_id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    dismissed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,26 @@
+-- Generated insights, one row per fingerprint (type + subject + period), with the user's response to each
+CREATE TABLE IF NOT EXISTS insights (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fingerprint TEXT NOT NULL UNIQUE,
+    type TEXT NOT NULL,
+    -- Category, or another key (subscription, finding, title) for insights without one
+    subject TEXT NOT NULL,
+    category TEXT,
+    -- Month the insight is about, as YYYY-MM
+    period TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    icon TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    metric JSONB,
+    action TEXT,
+    priority DOUBLE PRECISION NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    dismissed_at TIMESTAMPTZ,
+    snoozed_until DATE,
+    helpful BOOLEAN
+);
+
+CREATE INDEX IF NOT EXISTS idx_insights_period ON insights(period);
+CREATE INDEX IF NOT EXISTS idx_insights_type ON insights(type);
@@ -0,0 +1,22 @@
+-- Budgets over weekly, monthly, quarterly, annual or custom windows; the limit applies per window
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM information_schema.columns
+               WHERE table_name = 'budgets' AND column_name = 'monthly_limit') THEN
+        ALTER TABLE budgets RENAME COLUMN monthly_limit TO amount;
+    END IF;
+END $$;
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS period TEXT NOT NULL DEFAULT 'monthly'
+    CHECK (period IN ('weekly', 'monthly', 'quarterly', 'annual', 'custom'));
+-- First day of the first window; repeating windows keep its weekday / day of month
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS start_date DATE;
+-- Last day of a custom window
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS end_date DATE;
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'budgets_custom_range') THEN
+        ALTER TABLE budgets ADD CONSTRAINT budgets_custom_range CHECK (
+            period <> 'custom' OR (start_date IS NOT NULL AND end_date IS NOT NULL AND end_date >= start_date)
+        );
+    END IF;
+END $$;
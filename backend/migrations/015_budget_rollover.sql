@@ -0,0 +1,18 @@
+-- Carry what's left (or overspent) of a budget's period into the next one, optionally capped
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS rollover BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS rollover_cap NUMERIC(12,2) CHECK (rollover_cap >= 0);
+
+-- Envelope moves between category budgets. Rows are never edited; a move is undone by moving it back
+CREATE TABLE IF NOT EXISTS budget_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    from_category TEXT NOT NULL,
+    to_category TEXT NOT NULL,
+    amount NUMERIC(12,2) NOT NULL CHECK (amount > 0),
+    -- The move counts toward each budget's period containing this date
+    date DATE NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (from_category <> to_category)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_transfers_date ON budget_transfers(date);
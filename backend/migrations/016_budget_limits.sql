@@ -0,0 +1,13 @@
+-- Every limit a budget has had. budgets.amount mirrors the latest version
+CREATE TABLE IF NOT EXISTS budget_limits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    amount NUMERIC(12,2) NOT NULL,
+    effective_from DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (budget_id, effective_from)
+);
+
+INSERT INTO budget_limits (budget_id, amount, effective_from)
+SELECT id, amount, COALESCE(start_date, created_at::date) FROM budgets
+ON CONFLICT DO NOTHING;
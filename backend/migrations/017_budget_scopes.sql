@@ -0,0 +1,42 @@
+-- Free-form labels on transactions, for budgets (and anything else) that cut across categories
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS idx_transactions_tags ON transactions USING GIN (tags);
+
+-- A budget can track a filter instead of its category; NULL keeps matching the category
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS scope JSONB;
+
+-- Same as 009 plus tags
+CREATE OR REPLACE VIEW transactions_home AS
+SELECT
+    t.id,
+    t.date,
+    t.description,
+    CASE
+        WHEN t.currency = h.home THEN t.amount
+        ELSE (t.amount * COALESCE(fx_rate(t.currency, h.home, t.date), 1.0))::numeric(12,2)
+    END AS amount,
+    t.category,
+    t.card,
+    t.card_label,
+    t.raw_data,
+    t.hash,
+    t.created_at,
+    t.merchant_normalized,
+    t.import_id,
+    t.currency,
+    t.original_amount,
+    t.original_currency,
+    t.classification,
+    EXISTS (
+        SELECT 1 FROM transfer_pairs p
+        WHERE p.status <> 'unlinked' AND (p.debit_id = t.id OR p.credit_id = t.id)
+    ) AS is_transfer,
+    t.tags
+FROM transactions t
+CROSS JOIN (
+    SELECT COALESCE((SELECT value FROM user_config WHERE key = 'home_currency'), 'USD') AS home
+) h;
+
+-- Recreated so the view picks up the new column
+CREATE OR REPLACE VIEW spending_home AS
+SELECT * FROM transactions_home WHERE classification = 'expense' AND NOT is_transfer;
@@ -0,0 +1,29 @@
+-- Savings goals. Money counts toward a goal from a linked account (deposits less
+-- withdrawals), from transactions carrying a tag, and from manual contributions
+CREATE TABLE IF NOT EXISTS goals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    target_amount NUMERIC(12,2) NOT NULL CHECK (target_amount > 0),
+    target_date DATE NOT NULL,
+    start_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    -- Already saved when the goal was set up
+    initial_amount NUMERIC(12,2) NOT NULL DEFAULT 0 CHECK (initial_amount >= 0),
+    account TEXT REFERENCES cards(code) ON UPDATE CASCADE ON DELETE SET NULL,
+    tag TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (target_date > start_date),
+    CHECK (account IS NULL OR tag IS NULL)
+);
+
+CREATE TABLE IF NOT EXISTS goal_contributions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    goal_id UUID NOT NULL REFERENCES goals(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    -- Negative for money taken back out
+    amount NUMERIC(12,2) NOT NULL CHECK (amount <> 0),
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_goal_contributions_goal ON goal_contributions(goal_id, date);
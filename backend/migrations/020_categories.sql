@@ -0,0 +1,69 @@
+-- The managed category list. Categories nest one level: a top-level category can have
+-- children, and spending in a child rolls up into its parent
+CREATE TABLE IF NOT EXISTS categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE,
+    parent_id UUID REFERENCES categories(id) ON DELETE SET NULL,
+    color TEXT,
+    -- Icon name, e.g. `ShoppingCart`
+    icon TEXT,
+    -- Spending categories are essential or discretionary; income and transfers are neither
+    kind TEXT CHECK (kind IN ('essential', 'discretionary')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (parent_id IS NULL OR parent_id <> id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_name_lower ON categories(LOWER(name));
+
+-- What the importer assigns, plus common categories to start from
+INSERT INTO categories (name, color, icon, kind) VALUES
+    ('Groceries', '#10B981', 'ShoppingCart', 'essential'),
+    ('Dining', '#F59E0B', 'Utensils', 'discretionary'),
+    ('Gas', '#EF4444', 'Fuel', 'essential'),
+    ('Transportation', '#06B6D4', 'Car', 'essential'),
+    ('Travel', '#3B82F6', 'Plane', 'discretionary'),
+    ('Shopping', '#8B5CF6', 'ShoppingBag', 'discretionary'),
+    ('Subscriptions', '#EC4899', 'Repeat', 'discretionary'),
+    ('Health', '#14B8A6', 'HeartPulse', 'essential'),
+    ('Housing', '#6366F1', 'Home', 'essential'),
+    ('Utilities', '#0EA5E9', 'Zap', 'essential'),
+    ('Insurance', '#64748B', 'Shield', 'essential'),
+    ('Entertainment', '#F97316', 'Film', 'discretionary'),
+    ('Fitness', '#84CC16', 'Dumbbell', 'discretionary'),
+    ('Gifts', '#F43F5E', 'Gift', 'discretionary'),
+    ('Income', '#22C55E', 'Wallet', NULL),
+    ('Transfer', '#94A3B8', 'ArrowLeftRight', NULL),
+    ('Uncategorized', '#6B7280', 'HelpCircle', NULL)
+ON CONFLICT DO NOTHING;
+
+-- Everything already in use, as written; typos can be merged away afterwards
+INSERT INTO categories (name)
+SELECT DISTINCT category FROM transactions
+WHERE NOT EXISTS (SELECT 1 FROM categories c WHERE LOWER(c.name) = LOWER(transactions.category))
+ON CONFLICT DO NOTHING;
+
+INSERT INTO categories (name)
+SELECT DISTINCT category FROM budgets
+WHERE scope IS NULL
+  AND NOT EXISTS (SELECT 1 FROM categories c WHERE LOWER(c.name) = LOWER(budgets.category))
+ON CONFLICT DO NOTHING;
+
+INSERT INTO categories (name)
+SELECT DISTINCT jsonb_array_elements_text(scope->'categories') FROM budgets
+WHERE scope ? 'categories'
+ON CONFLICT DO NOTHING;
+
+-- Spellings differing only in case were folded into one category
+UPDATE transactions t SET category = c.name
+FROM categories c
+WHERE LOWER(c.name) = LOWER(t.category) AND c.name <> t.category;
+
+-- Transactions must name a listed category; renaming one renames it everywhere
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'transactions_category_fkey') THEN
+        ALTER TABLE transactions ADD CONSTRAINT transactions_category_fkey
+            FOREIGN KEY (category) REFERENCES categories(name) ON UPDATE CASCADE;
+    END IF;
+END $$;
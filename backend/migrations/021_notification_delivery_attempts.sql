@@ -0,0 +1,37 @@
+-- One delivery row per alert and channel: a failed delivery is retried in place, counting
+-- its attempts, until it's sent or runs out of attempts
+ALTER TABLE notification_deliveries ADD COLUMN IF NOT EXISTS attempts INT NOT NULL DEFAULT 1;
+ALTER TABLE notification_deliveries ADD COLUMN IF NOT EXISTS last_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+UPDATE notification_deliveries SET last_attempt_at = created_at;
+
+-- Failures of alerts that were sent in the end are history; the rest fold into their latest
+DELETE FROM notification_deliveries f
+WHERE f.status = 'failed'
+  AND EXISTS (
+      SELECT 1 FROM notification_deliveries s
+      WHERE s.channel_id = f.channel_id AND s.alert_key = f.alert_key AND s.status = 'sent'
+  );
+
+WITH ranked AS (
+    SELECT id,
+           ROW_NUMBER() OVER (PARTITION BY channel_id, alert_key ORDER BY created_at DESC, id) AS rn,
+           COUNT(*) OVER (PARTITION BY channel_id, alert_key) AS total,
+           MIN(created_at) OVER (PARTITION BY channel_id, alert_key) AS first_at
+    FROM notification_deliveries
+)
+UPDATE notification_deliveries d
+SET attempts = r.total, last_attempt_at = d.created_at, created_at = r.first_at
+FROM ranked r
+WHERE r.id = d.id AND r.rn = 1;
+
+DELETE FROM notification_deliveries d
+USING (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY channel_id, alert_key ORDER BY attempts DESC, last_attempt_at DESC, id) AS rn
+    FROM notification_deliveries
+) r
+WHERE r.id = d.id AND r.rn > 1;
+
+DROP INDEX IF EXISTS idx_notification_deliveries_sent;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notification_deliveries_alert
+    ON notification_deliveries(channel_id, alert_key);
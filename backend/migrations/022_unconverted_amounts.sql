@@ -0,0 +1,37 @@
+-- Same as 017, except an amount with no rate into the home currency is NULL instead of
+-- passing through unconverted, so other currencies don't get added in as if they were home
+CREATE OR REPLACE VIEW transactions_home AS
+SELECT
+    t.id,
+    t.date,
+    t.description,
+    CASE
+        WHEN t.currency = h.home THEN t.amount
+        ELSE (t.amount * fx_rate(t.currency, h.home, t.date))::numeric(12,2)
+    END AS amount,
+    t.category,
+    t.card,
+    t.card_label,
+    t.raw_data,
+    t.hash,
+    t.created_at,
+    t.merchant_normalized,
+    t.import_id,
+    t.currency,
+    t.original_amount,
+    t.original_currency,
+    t.classification,
+    EXISTS (
+        SELECT 1 FROM transfer_pairs p
+        WHERE p.status <> 'unlinked' AND (p.debit_id = t.id OR p.credit_id = t.id)
+    ) AS is_transfer,
+    t.tags
+FROM transactions t
+CROSS JOIN (
+    SELECT COALESCE((SELECT value FROM user_config WHERE key = 'home_currency'), 'USD') AS home
+) h;
+
+-- Spending totals leave unconverted rows out; the stats report how many there are
+CREATE OR REPLACE VIEW spending_home AS
+SELECT * FROM transactions_home
+WHERE classification = 'expense' AND NOT is_transfer AND amount IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Budget insights now carry their stage in the subject (`Dining:warning`, `Dining:exceeded`),
+-- so the 80% warning and the overspend alert are stored and dismissed separately. Move the
+-- stored ones over, with the fingerprint recomputed the way services::insights does it.
+UPDATE insights SET
+    subject = staged.subject,
+    fingerprint = substr(encode(sha256(
+        convert_to(type, 'UTF8') || '\x00'::bytea ||
+        convert_to(staged.subject, 'UTF8') || '\x00'::bytea ||
+        convert_to(period, 'UTF8') || '\x00'::bytea
+    ), 'hex'), 1, 16)
+FROM (
+    SELECT id, subject || CASE WHEN severity = 'high' THEN ':exceeded' ELSE ':warning' END AS subject
+    FROM insights
+    WHERE type = 'budget' AND subject = category
+) staged
+WHERE insights.id = staged.id;
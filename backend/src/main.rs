@@ -15,6 +15,8 @@ async fn main() {
     let pool = db::create_pool().await;
     db::run_migrations(&pool).await;
 
+    tokio::spawn(routes::notifications::run_schedule(pool.clone()));
+
     let cors = CorsLayer::new()
         .allow_origin("http://localhost:3000".parse::<http::HeaderValue>().unwrap())
         .allow_methods(Any)
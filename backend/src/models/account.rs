@@ -0,0 +1,77 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const ACCOUNT_TYPES: &[&str] = &["credit", "checking", "savings", "cash"];
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BalanceRecord {
+    pub id: Uuid,
+    pub card_id: Uuid,
+    pub kind: String,
+    pub date: NaiveDate,
+    pub balance: f64,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewBalanceRecord {
+    pub kind: String,
+    pub date: NaiveDate,
+    pub balance: f64,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountSummary {
+    pub id: Uuid,
+    pub code: String,
+    pub label: String,
+    pub account_type: String,
+    pub currency: String,
+    pub balance: f64,
+    pub as_of: Option<NaiveDate>,
+    pub failed_assertions: usize,
+    /// Transactions left out of the balance for lack of a rate into the account's currency
+    pub unconverted_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalancePoint {
+    pub date: NaiveDate,
+    pub change: f64,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssertionCheck {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub expected: f64,
+    pub computed: f64,
+    pub difference: f64,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BalanceHistory {
+    pub account: AccountSummary,
+    pub opening_balance: f64,
+    pub opening_date: Option<NaiveDate>,
+    pub history: Vec<BalancePoint>,
+    pub assertions: Vec<AssertionCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetWorthPoint {
+    pub month: String,
+    pub date: NaiveDate,
+    pub assets: f64,
+    pub liabilities: f64,
+    pub net_worth: f64,
+    /// Balance per account code in the home currency; null for accounts without a rate
+    pub by_account: serde_json::Value,
+    /// Accounts left out of the totals for lack of a rate into the home currency
+    pub unconverted_accounts: Vec<String>,
+}
@@ -1,5 +1,6 @@
 use chrono::NaiveDate;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 // ── Recurring ──
 
@@ -15,6 +16,50 @@ pub struct RecurringTransaction {
     pub status: String,
     pub last_gap_days: i64,
     pub potentially_forgotten: bool,
+    pub charge_count: i32,
+    pub median_gap_days: f64,
+    pub gap_stddev_days: f64,
+    /// Latest amount spread over a month, whatever the cadence
+    pub monthly_amount: f64,
+    /// Card of the latest charge
+    pub card: String,
+    pub next_expected_date: NaiveDate,
+    pub next_expected_amount: f64,
+    /// 0–1: gap regularity, amount stability and length of history
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PredictedCharge {
+    pub merchant: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub card: String,
+    pub frequency: String,
+    pub confidence: f64,
+    /// Expected before today and not charged yet
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CardImpact {
+    pub card: String,
+    pub account_type: String,
+    pub total: f64,
+    pub due_by_month_end: f64,
+    /// Signed change to the account balance by month end (credit balances grow)
+    pub balance_change_by_month_end: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpcomingData {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub month_end: NaiveDate,
+    pub charges: Vec<PredictedCharge>,
+    pub total: f64,
+    pub due_by_month_end: f64,
+    pub by_card: Vec<CardImpact>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,12 +74,19 @@ pub struct RecurringData {
 #[derive(Debug, Serialize)]
 pub struct CategoryAnomaly {
     pub category: String,
+    /// Spent so far this month
     pub current_month: f64,
+    /// Median of the completed months
     pub avg_monthly: f64,
+    /// Robust spread of month-to-date totals
     pub stddev: f64,
     pub z_score: f64,
     pub severity: String,
+    /// Projected month against `avg_monthly`
     pub pct_above_avg: f64,
+    /// Median spent by this day of the month
+    pub expected_to_date: f64,
+    pub projected: f64,
     pub message: String,
 }
 
@@ -45,8 +97,26 @@ pub struct TransactionAnomaly {
     pub description: String,
     pub amount: f64,
     pub category: String,
+    /// Median transaction in the category before this month
     pub category_avg: f64,
     pub times_avg: f64,
+    pub z_score: f64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MerchantAnomaly {
+    pub id: uuid::Uuid,
+    pub date: NaiveDate,
+    pub merchant: String,
+    pub description: String,
+    pub amount: f64,
+    pub category: String,
+    /// `first_time` or `unusual_amount`
+    pub kind: String,
+    pub prior_charges: usize,
+    pub merchant_median: Option<f64>,
+    pub z_score: Option<f64>,
     pub message: String,
 }
 
@@ -54,6 +124,7 @@ pub struct TransactionAnomaly {
 pub struct AnomaliesData {
     pub category_anomalies: Vec<CategoryAnomaly>,
     pub transaction_anomalies: Vec<TransactionAnomaly>,
+    pub merchant_anomalies: Vec<MerchantAnomaly>,
 }
 
 // ── Forecast ──
@@ -71,19 +142,53 @@ pub struct Projections {
     pub linear: f64,
     pub day_weighted: f64,
     pub ewma: f64,
+    /// EWMA scaled by the same-month-last-year index; needs a year of completed months
+    pub seasonal: Option<f64>,
+    /// Methods blended by inverse backtest error
     pub recommended: f64,
+    pub interval: PredictionInterval,
+    pub accuracy: Vec<MethodAccuracy>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PredictionInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64,
+}
+
+/// How a projection method did when replayed over past months.
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodAccuracy {
+    pub method: String,
+    /// Mean absolute percentage error; `None` if never backtested
+    pub mape: Option<f64>,
+    pub samples: usize,
+    pub weight: f64,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CategoryForecast {
     pub category: String,
     pub spent_so_far: f64,
+    /// Blend of `linear` and `ewma`, leaning on the pace as the month goes on
     pub projected: f64,
+    pub linear: f64,
+    pub ewma: Option<f64>,
     pub avg_monthly: f64,
     pub vs_avg_pct: f64,
     pub trend: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct CashflowForecast {
+    pub income_so_far: f64,
+    pub projected_income: f64,
+    pub projected_expenses: f64,
+    pub projected_net: f64,
+    pub projected_savings_rate: Option<f64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ForecastData {
     pub current_month: CurrentMonthStatus,
@@ -92,6 +197,107 @@ pub struct ForecastData {
     pub vs_average: serde_json::Value,
     pub category_forecasts: Vec<CategoryForecast>,
     pub trajectory: String,
+    pub cashflow: Option<CashflowForecast>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HorizonCategory {
+    pub category: String,
+    pub projected: f64,
+    /// Predicted charges of known recurring series, included in `projected`
+    pub recurring: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HorizonMonth {
+    /// YYYY-MM
+    pub month: String,
+    pub total: f64,
+    pub recurring: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub categories: Vec<HorizonCategory>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HorizonData {
+    pub months: Vec<HorizonMonth>,
+    pub total: f64,
+    pub recurring_total: f64,
+    /// Coverage of every `lower`–`upper` band
+    pub confidence: f64,
+}
+
+pub const ADJUSTMENT_TYPES: &[&str] = &["category_pct", "cancel_subscription", "add_expense"];
+
+/// A what-if change. Fields used depend on `type`:
+/// `category_pct` takes `category` and `pct` (-20 cuts by 20%),
+/// `cancel_subscription` takes `subscription_id` or `merchant`,
+/// `add_expense` takes a monthly `amount` and an optional `category` and `description`.
+#[derive(Debug, Deserialize)]
+pub struct Adjustment {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub category: Option<String>,
+    pub pct: Option<f64>,
+    pub subscription_id: Option<Uuid>,
+    pub merchant: Option<String>,
+    pub amount: Option<f64>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScenarioRequest {
+    pub adjustments: Vec<Adjustment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioMonth {
+    pub month: String,
+    pub baseline: f64,
+    pub scenario: f64,
+    pub difference: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdjustmentImpact {
+    pub description: String,
+    /// Change over the whole horizon with only this adjustment applied
+    pub difference: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioData {
+    pub months: Vec<ScenarioMonth>,
+    pub baseline_total: f64,
+    pub scenario_total: f64,
+    pub difference: f64,
+    pub avg_monthly_difference: f64,
+    pub adjustments: Vec<AdjustmentImpact>,
+}
+
+// ── Cashflow ──
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CashflowMonth {
+    pub month: String,
+    pub income: f64,
+    pub expenses: f64,
+    pub net: f64,
+    pub savings_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CashflowData {
+    pub months: Vec<CashflowMonth>,
+    pub total_income: f64,
+    pub total_expenses: f64,
+    pub net: f64,
+    pub avg_savings_rate: Option<f64>,
+    /// Transactions matching the filter left out for lack of a rate into the home currency
+    pub unconverted_count: i64,
 }
 
 // ── Habits ──
@@ -131,6 +337,8 @@ pub struct SubscriptionBloat {
     pub count: i32,
     pub potentially_forgotten: Vec<String>,
     pub message: String,
+    pub next_30_days: f64,
+    pub next_charges: Vec<PredictedCharge>,
 }
 
 #[derive(Debug, Serialize)]
@@ -182,6 +390,8 @@ pub struct CategoryRecentTransaction {
 #[derive(Debug, Serialize)]
 pub struct CategoryDeepDive {
     pub category: String,
+    /// Spending per subcategory when `category` has any; the totals below include them
+    pub subcategories: Vec<crate::models::category::CategoryTotal>,
     pub total_spent: f64,
     pub transaction_count: i64,
     pub avg_amount: f64,
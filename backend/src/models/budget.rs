@@ -1,36 +1,237 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub const BUDGET_PERIODS: &[&str] = &["weekly", "monthly", "quarterly", "annual", "custom"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Budget {
     pub id: Uuid,
     pub category: String,
-    pub monthly_limit: f64,
+    /// Limit per period
+    pub amount: f64,
+    /// One of [`BUDGET_PERIODS`]
+    pub period: String,
+    /// First day of the first period; repeating periods keep its weekday or day of month
+    pub start_date: Option<NaiveDate>,
+    /// Last day of a custom period
+    pub end_date: Option<NaiveDate>,
+    /// Carry what's left or overspent into the next period
+    pub rollover: bool,
+    /// Largest amount carried either way; `None` for no cap
+    pub rollover_cap: Option<f64>,
+    /// A [`BudgetScope`] when the budget tracks more than its category
+    pub scope: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Creates the category's budget or replaces it. Leaving out `period` keeps the current
+/// period and dates; leaving out `rollover` keeps the rollover settings.
 #[derive(Debug, Deserialize)]
 pub struct NewBudget {
     pub category: String,
-    pub monthly_limit: f64,
+    #[serde(alias = "monthly_limit")]
+    pub amount: f64,
+    pub period: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub rollover: Option<bool>,
+    pub rollover_cap: Option<f64>,
+    /// When the amount takes effect; defaults to the start of the current period
+    pub effective_from: Option<NaiveDate>,
+    /// Leaving it out keeps the current scope; an empty one goes back to the category
+    pub scope: Option<BudgetScope>,
+}
+
+/// Which spending a budget counts. Every field given must match; a list matches any of
+/// its values. With no scope, a budget counts its category.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BudgetScope {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<String>>,
+    /// Normalized merchant names, matched case-insensitively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchants: Option<Vec<String>>,
+    /// Card codes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cards: Option<Vec<String>>,
+    /// Household member, matched exactly (ignoring case) against the card's member column
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateBudget {
-    pub monthly_limit: Option<f64>,
+    pub amount: Option<f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize)]
+pub struct BudgetProgressQuery {
+    /// Reference date for a historical view; defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct BudgetProgress {
     pub category: String,
-    pub monthly_limit: f64,
+    pub period: String,
+    /// The period containing the reference date
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub amount: f64,
+    /// Left over (or overspent, negative) from earlier periods
+    pub carried_in: f64,
+    /// Net envelope moves into this period
+    pub transferred: f64,
+    /// amount + carried_in + transferred; what spending is measured against
+    pub available: f64,
     pub spent: f64,
     pub remaining: f64,
     pub pct_used: f64,
+    /// Spending at the current pace through the end of the period
     pub projected_spend: f64,
     pub projected_pct: f64,
     pub status: String,
     pub days_remaining: u32,
 }
+
+/// One version of a budget's limit. A period uses the latest version effective on or
+/// before its last day; periods before the first version use the first.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BudgetLimit {
+    pub id: Uuid,
+    pub budget_id: Uuid,
+    pub amount: f64,
+    pub effective_from: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BudgetHistoryQuery {
+    /// How many months back to report, counting the current one (1–60, default 12)
+    pub months: Option<u32>,
+    pub as_of: Option<NaiveDate>,
+}
+
+/// Limit vs actual for one period of a budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetPeriodResult {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    /// The limit version in effect
+    pub limit: f64,
+    pub carried_in: f64,
+    pub transferred: f64,
+    pub available: f64,
+    pub spent: f64,
+    /// available − spent; negative when over
+    pub variance: f64,
+    pub variance_pct: f64,
+    pub hit: bool,
+    /// False for the period still in progress, which streaks and averages leave out
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BudgetStreak {
+    /// "hit" or "miss"
+    pub kind: String,
+    pub length: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetCategoryHistory {
+    pub category: String,
+    pub period: String,
+    /// Oldest first
+    pub periods: Vec<BudgetPeriodResult>,
+    pub hits: u32,
+    pub misses: u32,
+    /// The run the most recent complete periods are on
+    pub current_streak: Option<BudgetStreak>,
+    pub longest_hit_streak: u32,
+    pub longest_miss_streak: u32,
+    pub avg_variance: f64,
+    pub avg_variance_pct: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetHistory {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub budgets: Vec<BudgetCategoryHistory>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BudgetSuggestionQuery {
+    /// Completed months to look back over (3–36, default 12)
+    pub months: Option<u32>,
+    /// Percentile of the monthly totals to suggest (50–95, default 50: the median)
+    pub percentile: Option<f64>,
+    pub as_of: Option<NaiveDate>,
+}
+
+/// A proposed monthly limit for next month.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetSuggestion {
+    pub category: String,
+    /// The month the suggestion is for, YYYY-MM
+    pub month: String,
+    /// Rounded up to the next $5
+    pub suggested: f64,
+    /// The percentile of monthly spending without recurring charges
+    pub variable: f64,
+    /// Same-month-last-year factor applied to `variable`; 1.0 without a year of history
+    pub seasonal_index: f64,
+    /// Charges active recurring series are expected to make next month
+    pub recurring: f64,
+    pub months_used: u32,
+    /// Completed months whose actual spending was over `suggested`
+    pub months_exceeded: u32,
+    /// months_exceeded / months_used × 100
+    pub exceed_rate: f64,
+    /// The category's current budget amount and period, if it has one
+    pub current_amount: Option<f64>,
+    pub current_period: Option<String>,
+}
+
+/// Accept suggestions: they're recomputed with the same parameters and upserted.
+#[derive(Debug, Deserialize)]
+pub struct ApplyBudgetSuggestions {
+    pub categories: Vec<String>,
+    pub months: Option<u32>,
+    pub percentile: Option<f64>,
+    pub as_of: Option<NaiveDate>,
+}
+
+/// An envelope move between two category budgets.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BudgetTransfer {
+    pub id: Uuid,
+    pub from_category: String,
+    pub to_category: String,
+    pub amount: f64,
+    pub date: NaiveDate,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewBudgetTransfer {
+    pub from_category: String,
+    pub to_category: String,
+    pub amount: f64,
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BudgetTransferQuery {
+    /// Moves into or out of this category
+    pub category: Option<String>,
+}
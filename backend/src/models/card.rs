@@ -2,6 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// `debit_positive`: charges are positive (card statements). `debit_negative`: withdrawals
+/// are negative (most bank exports).
+pub const AMOUNT_SIGNS: &[&str] = &["debit_positive", "debit_negative"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Card {
     pub id: Uuid,
@@ -20,6 +24,11 @@ pub struct Card {
     pub member_column: Option<String>,
     pub skip_negative_amounts: bool,
     pub created_at: DateTime<Utc>,
+    pub currency: String,
+    pub currency_column: Option<String>,
+    pub account_type: String,
+    pub amount_sign: String,
+    pub import_credits: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,6 +47,11 @@ pub struct NewCard {
     pub category_column: Option<String>,
     pub member_column: Option<String>,
     pub skip_negative_amounts: Option<bool>,
+    pub currency: Option<String>,
+    pub currency_column: Option<String>,
+    pub account_type: Option<String>,
+    pub amount_sign: Option<String>,
+    pub import_credits: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -56,4 +70,9 @@ pub struct UpdateCard {
     pub category_column: Option<String>,
     pub member_column: Option<String>,
     pub skip_negative_amounts: Option<bool>,
+    pub currency: Option<String>,
+    pub currency_column: Option<String>,
+    pub account_type: Option<String>,
+    pub amount_sign: Option<String>,
+    pub import_credits: Option<bool>,
 }
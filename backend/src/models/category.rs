@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const CATEGORY_KINDS: &[&str] = &["essential", "discretionary"];
+
+/// Categories the importer falls back on; they can't be renamed or deleted.
+pub const RESERVED_CATEGORIES: &[&str] = &["Income", "Uncategorized"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// The parent's name
+    pub parent: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    /// One of [`CATEGORY_KINDS`]; none for income, transfers and the like
+    pub kind: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewCategory {
+    pub name: String,
+    /// Parent category's name
+    pub parent: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub kind: Option<String>,
+}
+
+/// Fields left out are unchanged; an empty `parent` makes it top-level and an empty
+/// `color`, `icon` or `kind` clears it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCategory {
+    pub name: Option<String>,
+    pub parent: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// Move the category's transactions here first
+    pub reassign_to: Option<String>,
+}
+
+/// Spending in a leaf category.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total: f64,
+    pub count: i64,
+    pub avg_amount: f64,
+}
+
+/// Spending in a top-level category and its children.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ParentTotal {
+    pub category: String,
+    pub color: Option<String>,
+    pub kind: Option<String>,
+    pub total: f64,
+    pub count: i64,
+    pub avg_amount: f64,
+    /// The parent's own spending and each child's, largest first
+    pub children: Vec<CategoryTotal>,
+}
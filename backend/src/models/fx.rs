@@ -0,0 +1,20 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct FxPairSummary {
+    pub base: String,
+    pub quote: String,
+    pub rate_count: i64,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+    pub latest_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FxRatesOverview {
+    pub home_currency: String,
+    pub pairs: Vec<FxPairSummary>,
+    /// Transaction currencies with no usable rate into the home currency.
+    pub missing_currencies: Vec<String>,
+}
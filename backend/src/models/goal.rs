@@ -0,0 +1,99 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Goal {
+    pub id: Uuid,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: NaiveDate,
+    /// Linked transactions count from this date
+    pub start_date: NaiveDate,
+    /// Already saved when the goal was set up
+    pub initial_amount: f64,
+    /// Card code of a linked checking, savings or cash account
+    pub account: Option<String>,
+    /// Transactions with this tag count as contributions
+    pub tag: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewGoal {
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: NaiveDate,
+    /// Defaults to today
+    pub start_date: Option<NaiveDate>,
+    pub initial_amount: Option<f64>,
+    pub account: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// Fields left out are unchanged; an empty `account` or `tag` unlinks it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateGoal {
+    pub name: Option<String>,
+    pub target_amount: Option<f64>,
+    pub target_date: Option<NaiveDate>,
+    pub start_date: Option<NaiveDate>,
+    pub initial_amount: Option<f64>,
+    pub account: Option<String>,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GoalQuery {
+    /// Reference date; defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewGoalContribution {
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+    /// Negative for a withdrawal
+    pub amount: f64,
+    pub note: Option<String>,
+}
+
+/// Money that counted toward a goal: a manual entry or a linked transaction.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct GoalContribution {
+    pub id: Uuid,
+    /// `manual` or `transaction`
+    pub source: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    /// The note, or the transaction's description
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    pub id: Uuid,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: NaiveDate,
+    pub start_date: NaiveDate,
+    pub account: Option<String>,
+    pub tag: Option<String>,
+    /// Initial amount plus contributions through the reference date
+    pub saved: f64,
+    pub remaining: f64,
+    pub pct_complete: f64,
+    /// What an even plan from start to target date would have saved by now
+    pub expected_saved: f64,
+    pub months_left: f64,
+    /// Needed each month from now to reach the target on time
+    pub required_monthly: f64,
+    /// Average monthly contributions over the last three months
+    pub monthly_pace: f64,
+    /// When the target is (or was) reached at the current pace; `None` when not moving
+    pub projected_completion: Option<NaiveDate>,
+    pub on_track: bool,
+    /// `completed`, `on_track`, `behind` or `overdue`
+    pub status: String,
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,4 +11,57 @@ pub struct ImportRecord {
     pub transaction_count: i32,
     pub duplicate_count: i32,
     pub skipped_user_count: i32,
+    pub statement_start: Option<NaiveDate>,
+    pub statement_end: Option<NaiveDate>,
+    pub opening_balance: Option<f64>,
+    pub closing_balance: Option<f64>,
+    pub reconciliation: Option<serde_json::Value>,
+}
+
+/// Statement period and balances, from the upload form or the file's summary block.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatementSummary {
+    pub period_start: Option<NaiveDate>,
+    pub period_end: Option<NaiveDate>,
+    pub opening_balance: Option<f64>,
+    pub closing_balance: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct UnmatchedDay {
+    pub date: NaiveDate,
+    pub statement_total: f64,
+    pub ledger_total: f64,
+    pub difference: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reconciliation {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub opening_balance: Option<f64>,
+    pub closing_balance: Option<f64>,
+    pub expected_delta: Option<f64>,
+    pub computed_delta: f64,
+    pub difference: Option<f64>,
+    pub unmatched_days: Vec<UnmatchedDay>,
+    /// `balanced`, `unbalanced`, or `unverified` when no balances were given
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CoverageGap {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReconciliationReport {
+    pub card: String,
+    pub statements: Vec<ImportRecord>,
+    pub balanced: usize,
+    pub unbalanced: usize,
+    pub unverified: usize,
+    pub gaps: Vec<CoverageGap>,
 }
@@ -0,0 +1,53 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A generated insight as stored, with the user's response to it.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct StoredInsight {
+    pub id: Uuid,
+    /// Hash of type, subject and period; the same insight next month is a new row
+    pub fingerprint: String,
+    #[sqlx(rename = "type")]
+    pub r#type: String,
+    pub subject: String,
+    pub category: Option<String>,
+    /// `YYYY-MM`
+    pub period: String,
+    pub severity: String,
+    pub icon: String,
+    pub title: String,
+    pub message: String,
+    pub metric: Option<serde_json::Value>,
+    pub action: Option<String>,
+    pub priority: f64,
+    pub first_seen_at: DateTime<Utc>,
+    /// Last time the insight was generated
+    pub last_seen_at: DateTime<Utc>,
+    pub dismissed_at: Option<DateTime<Utc>>,
+    pub snoozed_until: Option<NaiveDate>,
+    pub helpful: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SnoozeInsight {
+    pub until: NaiveDate,
+}
+
+/// `null` clears earlier feedback.
+#[derive(Debug, Deserialize)]
+pub struct InsightFeedback {
+    pub helpful: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InsightHistoryQuery {
+    #[serde(rename = "type")]
+    pub r#type: Option<String>,
+    pub category: Option<String>,
+    /// `YYYY-MM`
+    pub period: Option<String>,
+    /// Leave dismissed insights out (default false: history shows everything)
+    pub hide_dismissed: Option<bool>,
+    pub limit: Option<i64>,
+}
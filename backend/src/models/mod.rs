@@ -1,6 +1,15 @@
+pub mod account;
 pub mod analytics;
 pub mod budget;
 pub mod card;
+pub mod category;
 pub mod config;
+pub mod fx;
+pub mod goal;
 pub mod import;
+pub mod insight;
+pub mod notification;
 pub mod transaction;
+pub mod subscription;
+pub mod suspicious;
+pub mod transfer;
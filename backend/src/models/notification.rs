@@ -0,0 +1,89 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const CHANNEL_KINDS: &[&str] = &["webhook", "smtp", "ntfy", "gotify"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct NotificationChannel {
+    pub id: Uuid,
+    pub name: String,
+    /// One of [`CHANNEL_KINDS`]
+    pub kind: String,
+    /// The kind's settings, e.g. `{ "url": ... }` for a webhook
+    pub config: serde_json::Value,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewChannel {
+    pub name: String,
+    pub kind: String,
+    pub config: serde_json::Value,
+    pub enabled: Option<bool>,
+}
+
+/// Fields left out are unchanged.
+#[derive(Debug, Deserialize)]
+pub struct UpdateChannel {
+    pub name: Option<String>,
+    pub config: Option<serde_json::Value>,
+    pub enabled: Option<bool>,
+}
+
+/// Something worth telling the user about.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Alert {
+    /// Names the alert and its period; an alert is sent to a channel once per key
+    pub key: String,
+    /// `budget_80`, `budget_exceeded`, `budget_projected` or `subscription_<alert type>`
+    pub alert_type: String,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Delivery {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    pub alert_key: String,
+    pub alert_type: String,
+    pub title: String,
+    pub message: String,
+    /// `sent` or `failed`
+    pub status: String,
+    pub error: Option<String>,
+    /// Times delivery was tried, counted again from zero when the channel changes
+    pub attempts: i32,
+    /// First attempt
+    pub created_at: DateTime<Utc>,
+    pub last_attempt_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub channel_id: Option<Uuid>,
+    pub status: Option<String>,
+    /// Newest first; default 100
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotifyQuery {
+    /// Reference date for the alerts; defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+/// What one evaluation did.
+#[derive(Debug, Default, Serialize)]
+pub struct NotificationRun {
+    pub alerts: usize,
+    pub sent: usize,
+    pub failed: usize,
+    /// Alert and channel pairs already sent earlier
+    pub already_sent: usize,
+    /// Alert and channel pairs that failed too often to try again
+    pub gave_up: usize,
+}
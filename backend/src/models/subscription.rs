@@ -0,0 +1,58 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const SUBSCRIPTION_STATUSES: &[&str] = &["detected", "confirmed", "ignored", "cancelled"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Subscription {
+    pub id: Uuid,
+    pub merchant: String,
+    pub frequency: String,
+    pub amount: f64,
+    pub expected_amount: Option<f64>,
+    pub status: String,
+    pub cancelled_on: Option<NaiveDate>,
+    pub first_seen: NaiveDate,
+    pub last_charge_date: NaiveDate,
+    pub next_expected_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionAlert {
+    pub subscription_id: Uuid,
+    pub merchant: String,
+    /// `price_increase`, `cancelled_charged` or `overdue`
+    pub alert_type: String,
+    /// The charge that raised the alert, or the missed expected date
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub previous_amount: Option<f64>,
+    pub days_overdue: Option<i64>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionSync {
+    pub created: usize,
+    pub updated: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CancelSubscription {
+    /// Defaults to today
+    pub cancelled_on: Option<NaiveDate>,
+}
+
+/// Replaces the expected amount; `null` goes back to the detected amount.
+#[derive(Debug, Deserialize)]
+pub struct UpdateSubscription {
+    pub expected_amount: Option<f64>,
+}
@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const FINDING_KINDS: &[&str] = &[
+    "duplicate_charge",
+    "round_amount_new_merchant",
+    "foreign_on_domestic",
+    "test_charge",
+];
+
+/// A transaction involved in a finding.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspectTransaction {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub description: String,
+    pub card: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspiciousFinding {
+    /// Fingerprint of the kind and transactions; stable across requests
+    pub id: String,
+    /// One of [`FINDING_KINDS`]
+    pub kind: String,
+    /// `high`, `medium` or `low`
+    pub severity: String,
+    /// Date of the latest transaction involved
+    pub date: NaiveDate,
+    pub merchant: String,
+    /// The amount in question: the repeated, round, foreign or large charge
+    pub amount: f64,
+    pub transactions: Vec<SuspectTransaction>,
+    pub message: String,
+    pub dismissed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuspiciousData {
+    pub findings: Vec<SuspiciousFinding>,
+    /// Dismissed findings left out of `findings`
+    pub dismissed_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuspiciousQuery {
+    pub include_dismissed: Option<bool>,
+}
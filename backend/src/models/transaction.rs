@@ -2,6 +2,8 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub const CLASSIFICATIONS: &[&str] = &["expense", "income"];
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Transaction {
     pub id: Uuid,
@@ -14,6 +16,11 @@ pub struct Transaction {
     pub raw_data: Option<serde_json::Value>,
     pub hash: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub currency: String,
+    pub original_amount: Option<f64>,
+    pub original_currency: Option<String>,
+    pub classification: String,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +34,10 @@ pub struct NewTransaction {
     pub raw_data: Option<serde_json::Value>,
     pub hash: String,
     pub merchant_normalized: String,
+    pub currency: String,
+    pub original_amount: Option<f64>,
+    pub original_currency: Option<String>,
+    pub classification: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,6 +49,7 @@ pub struct TransactionQuery {
     pub search: Option<String>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
+    pub classification: Option<String>,
     pub page: Option<i64>,
     pub per_page: Option<i64>,
 }
@@ -52,3 +64,13 @@ pub struct BulkCategoryUpdate {
     pub ids: Vec<Uuid>,
     pub category: String,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ClassificationUpdate {
+    pub classification: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagsUpdate {
+    pub tags: Vec<String>,
+}
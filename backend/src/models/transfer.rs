@@ -0,0 +1,51 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const TRANSFER_STATUSES: &[&str] = &["suggested", "confirmed", "unlinked"];
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TransferPair {
+    pub id: Uuid,
+    pub debit_id: Uuid,
+    pub credit_id: Uuid,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A pair joined with both sides of the transfer.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TransferPairDetail {
+    pub id: Uuid,
+    pub status: String,
+    pub amount: f64,
+    pub debit_id: Uuid,
+    pub debit_date: NaiveDate,
+    pub debit_card: String,
+    pub debit_description: String,
+    pub credit_id: Uuid,
+    pub credit_date: NaiveDate,
+    pub credit_card: String,
+    pub credit_description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferCandidate {
+    pub id: Uuid,
+    pub card: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub currency: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferMatchQuery {
+    pub window_days: Option<i64>,
+}
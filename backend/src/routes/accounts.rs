@@ -0,0 +1,461 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::account::{
+    AccountSummary, BalanceHistory, BalancePoint, BalanceRecord, NetWorthPoint, NewBalanceRecord,
+};
+use crate::models::card::Card;
+use crate::models::import::{ImportRecord, ReconciliationReport};
+use crate::routes::fx::home_currency;
+use crate::services::{balances, reconciliation};
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/accounts", get(list_accounts))
+        .route("/accounts/net-worth", get(get_net_worth))
+        .route("/accounts/:id/balances", get(list_balances).post(create_balance))
+        .route("/accounts/:id/balances/:balance_id", delete(delete_balance))
+        .route("/accounts/:id/balance-history", get(get_balance_history))
+        .route("/accounts/:id/reconciliation", get(get_reconciliation))
+}
+
+/// Everything needed to compute one account's running balance.
+struct AccountLedger {
+    card: Card,
+    opening: Option<(NaiveDate, f64)>,
+    assertions: Vec<(Uuid, NaiveDate, f64)>,
+    history: Vec<BalancePoint>,
+    /// Transactions left out for lack of a rate into the account's currency
+    unconverted: i64,
+}
+
+impl AccountLedger {
+    fn summary(&self) -> AccountSummary {
+        let checks = balances::check_assertions(&self.history, self.opening, &self.assertions);
+        AccountSummary {
+            id: self.card.id,
+            code: self.card.code.clone(),
+            label: self.card.label.clone(),
+            account_type: self.card.account_type.clone(),
+            currency: self.card.currency.clone(),
+            balance: self
+                .history
+                .last()
+                .map(|p| p.balance)
+                .or(self.opening.map(|(_, b)| b))
+                .unwrap_or(0.0),
+            as_of: self.history.last().map(|p| p.date).or(self.opening.map(|(d, _)| d)),
+            failed_assertions: checks.iter().filter(|c| !c.passed).count(),
+            unconverted_count: self.unconverted,
+        }
+    }
+}
+
+async fn load_ledger(pool: &PgPool, card: Card) -> AccountLedger {
+    let records: Vec<BalanceRecord> = match sqlx::query_as(
+        "SELECT id, card_id, kind, date, balance::float8 as balance, note, created_at \
+         FROM account_balances WHERE card_id = $1 ORDER BY date",
+    )
+    .bind(card.id)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to fetch balances for account {}: {e}", card.id);
+            Vec::new()
+        }
+    };
+
+    // Rows in another currency are converted into the account's; those without a rate
+    // are left out and counted rather than added in as if they were already converted
+    let converted = "SELECT date, CASE WHEN currency = $2 THEN amount::float8 \
+           ELSE amount::float8 * fx_rate(currency, $2, date) END AS amount \
+         FROM transactions WHERE card = $1";
+    let daily_totals: Vec<(NaiveDate, f64)> = sqlx::query_as(&format!(
+        "SELECT date, SUM(amount) FROM ({converted}) t \
+         WHERE amount IS NOT NULL GROUP BY date ORDER BY date"
+    ))
+    .bind(&card.code)
+    .bind(&card.currency)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+    let unconverted: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*)::bigint FROM ({converted}) t WHERE amount IS NULL"
+    ))
+    .bind(&card.code)
+    .bind(&card.currency)
+    .fetch_one(pool)
+    .await
+    .unwrap_or_default();
+
+    let opening = records
+        .iter()
+        .find(|r| r.kind == "opening")
+        .map(|r| (r.date, r.balance));
+    let assertions = records
+        .iter()
+        .filter(|r| r.kind == "assertion")
+        .map(|r| (r.id, r.date, r.balance))
+        .collect();
+    let history = balances::running_balance(&card.account_type, opening, &daily_totals);
+
+    AccountLedger {
+        card,
+        opening,
+        assertions,
+        history,
+        unconverted,
+    }
+}
+
+async fn fetch_card(pool: &PgPool, id: Uuid) -> Option<Card> {
+    match sqlx::query_as("SELECT * FROM cards WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(row) => row,
+        Err(e) => {
+            tracing::error!("Failed to fetch account {id}: {e}");
+            None
+        }
+    }
+}
+
+async fn list_accounts(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let cards: Vec<Card> = match sqlx::query_as("SELECT * FROM cards ORDER BY created_at ASC")
+        .fetch_all(&pool)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to list accounts: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut accounts = Vec::new();
+    for card in cards {
+        accounts.push(load_ledger(&pool, card).await.summary());
+    }
+
+    Json(serde_json::json!({ "data": accounts }))
+}
+
+async fn list_balances(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    let records: Vec<BalanceRecord> = match sqlx::query_as(
+        "SELECT id, card_id, kind, date, balance::float8 as balance, note, created_at \
+         FROM account_balances WHERE card_id = $1 ORDER BY date",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to list balances for account {id}: {e}");
+            Vec::new()
+        }
+    };
+
+    Json(serde_json::json!({ "data": records }))
+}
+
+async fn create_balance(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<NewBalanceRecord>,
+) -> Json<serde_json::Value> {
+    if body.kind != "opening" && body.kind != "assertion" {
+        return Json(serde_json::json!({ "error": "kind must be 'opening' or 'assertion'" }));
+    }
+    if fetch_card(&pool, id).await.is_none() {
+        return Json(serde_json::json!({ "error": "Account not found" }));
+    }
+
+    // An account has a single opening balance; posting a new one replaces it
+    if body.kind == "opening" {
+        if let Err(e) = sqlx::query("DELETE FROM account_balances WHERE card_id = $1 AND kind = 'opening'")
+            .bind(id)
+            .execute(&pool)
+            .await
+        {
+            tracing::error!("Failed to replace opening balance for account {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    }
+
+    let result: Result<BalanceRecord, _> = sqlx::query_as(
+        "INSERT INTO account_balances (card_id, kind, date, balance, note) \
+         VALUES ($1, $2, $3, $4, $5) \
+         RETURNING id, card_id, kind, date, balance::float8 as balance, note, created_at",
+    )
+    .bind(id)
+    .bind(&body.kind)
+    .bind(body.date)
+    .bind(body.balance)
+    .bind(&body.note)
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(record) => Json(serde_json::json!({ "data": record })),
+        Err(e) => {
+            tracing::error!("Failed to create balance record for account {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn delete_balance(
+    State(pool): State<PgPool>,
+    Path((id, balance_id)): Path<(Uuid, Uuid)>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query("DELETE FROM account_balances WHERE id = $1 AND card_id = $2")
+        .bind(balance_id)
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => Json(serde_json::json!({ "data": "Balance record deleted" })),
+        Ok(_) => Json(serde_json::json!({ "error": "Balance record not found" })),
+        Err(e) => {
+            tracing::error!("Failed to delete balance record {balance_id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BalanceHistoryQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+async fn get_balance_history(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<BalanceHistoryQuery>,
+) -> Json<serde_json::Value> {
+    let card = match fetch_card(&pool, id).await {
+        Some(c) => c,
+        None => return Json(serde_json::json!({ "error": "Account not found" })),
+    };
+
+    let ledger = load_ledger(&pool, card).await;
+    let account = ledger.summary();
+    let assertions = balances::check_assertions(&ledger.history, ledger.opening, &ledger.assertions);
+
+    let history: Vec<BalancePoint> = ledger
+        .history
+        .into_iter()
+        .filter(|p| params.start_date.is_none_or(|s| p.date >= s))
+        .filter(|p| params.end_date.is_none_or(|e| p.date <= e))
+        .collect();
+
+    Json(serde_json::json!({
+        "data": BalanceHistory {
+            account,
+            opening_balance: ledger.opening.map(|(_, b)| b).unwrap_or(0.0),
+            opening_date: ledger.opening.map(|(d, _)| d),
+            history,
+            assertions,
+        }
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct NetWorthQuery {
+    pub months: Option<u32>,
+}
+
+fn month_end(year: i32, month: u32) -> NaiveDate {
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    next.unwrap().pred_opt().unwrap()
+}
+
+async fn get_net_worth(
+    State(pool): State<PgPool>,
+    Query(params): Query<NetWorthQuery>,
+) -> Json<serde_json::Value> {
+    let months = params.months.unwrap_or(12).clamp(1, 120);
+
+    let cards: Vec<Card> = match sqlx::query_as("SELECT * FROM cards ORDER BY created_at ASC")
+        .fetch_all(&pool)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to fetch accounts for net worth: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut ledgers = Vec::new();
+    for card in cards {
+        ledgers.push(load_ledger(&pool, card).await);
+    }
+
+    // Month-ends from `months - 1` months ago through the current month
+    let today = chrono::Local::now().naive_local().date();
+    let mut month_ends = Vec::new();
+    let (mut y, mut m) = (today.year(), today.month());
+    for _ in 0..months {
+        month_ends.push(month_end(y, m));
+        if m == 1 {
+            y -= 1;
+            m = 12;
+        } else {
+            m -= 1;
+        }
+    }
+    month_ends.reverse();
+
+    let home = home_currency(&pool).await;
+    let mut rates: HashMap<String, Vec<Option<f64>>> = HashMap::new();
+    for ledger in &ledgers {
+        let ccy = &ledger.card.currency;
+        if *ccy == home || rates.contains_key(ccy) {
+            continue;
+        }
+        let rows: Vec<(NaiveDate, Option<f64>)> = sqlx::query_as(
+            "SELECT d, fx_rate($1, $2, d) FROM unnest($3::date[]) AS d ORDER BY d",
+        )
+        .bind(ccy)
+        .bind(&home)
+        .bind(&month_ends)
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+        rates.insert(ccy.clone(), rows.into_iter().map(|(_, r)| r).collect());
+    }
+
+    let points: Vec<NetWorthPoint> = month_ends
+        .iter()
+        .enumerate()
+        .map(|(i, date)| {
+            let mut assets = 0.0;
+            let mut liabilities = 0.0;
+            let mut by_account = serde_json::Map::new();
+            let mut unconverted_accounts = Vec::new();
+
+            for ledger in &ledgers {
+                // Accounts with no rate into the home currency stay out of the totals
+                let rate = match rates.get(&ledger.card.currency) {
+                    None => 1.0,
+                    Some(r) => match r.get(i).copied().flatten() {
+                        Some(rate) => rate,
+                        None => {
+                            by_account.insert(ledger.card.code.clone(), serde_json::Value::Null);
+                            unconverted_accounts.push(ledger.card.code.clone());
+                            continue;
+                        }
+                    },
+                };
+                let balance = balances::balance_on(&ledger.history, ledger.opening, *date) * rate;
+                if balances::is_liability(&ledger.card.account_type) {
+                    liabilities += balance;
+                } else {
+                    assets += balance;
+                }
+                by_account.insert(ledger.card.code.clone(), serde_json::json!(balance));
+            }
+
+            NetWorthPoint {
+                month: date.format("%Y-%m").to_string(),
+                date: *date,
+                assets,
+                liabilities,
+                net_worth: assets - liabilities,
+                by_account: by_account.into(),
+                unconverted_accounts,
+            }
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "data": {
+            "currency": home,
+            "points": points
+        }
+    }))
+}
+
+/// Imported statements for one account with their reconciliation results and any
+/// stretches of time no statement covers.
+async fn get_reconciliation(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    let card = match fetch_card(&pool, id).await {
+        Some(c) => c,
+        None => return Json(serde_json::json!({ "error": "Account not found" })),
+    };
+
+    let statements: Vec<ImportRecord> = match sqlx::query_as(
+        "SELECT id, imported_at, card, file_name, transaction_count, duplicate_count, skipped_user_count, \
+           statement_start, statement_end, opening_balance::float8 as opening_balance, \
+           closing_balance::float8 as closing_balance, reconciliation \
+         FROM import_history WHERE card = $1 AND reconciliation IS NOT NULL \
+         ORDER BY statement_start, imported_at",
+    )
+    .bind(&card.code)
+    .fetch_all(&pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to fetch statements for account {id}: {e}");
+            Vec::new()
+        }
+    };
+
+    let count_status = |status: &str| {
+        statements
+            .iter()
+            .filter(|s| {
+                s.reconciliation
+                    .as_ref()
+                    .and_then(|r| r["status"].as_str())
+                    == Some(status)
+            })
+            .count()
+    };
+    let balanced = count_status("balanced");
+    let unbalanced = count_status("unbalanced");
+    let unverified = count_status("unverified");
+
+    let periods: Vec<(NaiveDate, NaiveDate)> = statements
+        .iter()
+        .filter_map(|s| Some((s.statement_start?, s.statement_end?)))
+        .collect();
+
+    Json(serde_json::json!({
+        "data": ReconciliationReport {
+            card: card.code,
+            gaps: reconciliation::coverage_gaps(&periods),
+            statements,
+            balanced,
+            unbalanced,
+            unverified,
+        }
+    }))
+}
@@ -0,0 +1,140 @@
+use axum::{extract::State, routing::get, Json, Router};
+use sqlx::PgPool;
+
+use crate::services::analytics::Thresholds;
+
+/// `user_config` key holding the analytics thresholds as a JSON object.
+pub const SETTINGS_KEY: &str = "analytics_settings";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new().route("/analytics/settings", get(get_settings).put(update_settings))
+}
+
+/// Thresholds for this request: the stored settings, with defaults for anything unsaved
+/// and for stored fields that no longer pass validation on their own.
+pub async fn load_thresholds(pool: &PgPool) -> Thresholds {
+    let stored = match sqlx::query_scalar::<_, String>(
+        "SELECT value FROM user_config WHERE key = $1",
+    )
+    .bind(SETTINGS_KEY)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("Failed to fetch analytics settings: {e}");
+            None
+        }
+    };
+
+    let Some(json) = stored else {
+        return Thresholds::default();
+    };
+    let stored = serde_json::from_str(&json).map(known_fields).map(valid_fields);
+    match stored.map_err(|e| e.to_string()).and_then(parse_settings) {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid analytics settings ({e}); using defaults");
+            Thresholds::default()
+        }
+    }
+}
+
+/// Drop stored fields that are no longer settings, so a renamed threshold doesn't
+/// discard the rest.
+fn known_fields(value: serde_json::Value) -> serde_json::Value {
+    let (serde_json::Value::Object(mut stored), Ok(serde_json::Value::Object(defaults))) =
+        (value.clone(), serde_json::to_value(Thresholds::default()))
+    else {
+        return value;
+    };
+    stored.retain(|key, _| defaults.contains_key(key));
+    serde_json::Value::Object(stored)
+}
+
+/// Drop stored fields that fail validation on top of the defaults, such as a value saved
+/// before its range was tightened, so one stale field doesn't discard the rest.
+fn valid_fields(value: serde_json::Value) -> serde_json::Value {
+    let (serde_json::Value::Object(mut stored), Ok(serde_json::Value::Object(defaults))) =
+        (value.clone(), serde_json::to_value(Thresholds::default()))
+    else {
+        return value;
+    };
+    stored.retain(|key, field| {
+        let mut alone = defaults.clone();
+        alone.insert(key.clone(), field.clone());
+        match parse_settings(serde_json::Value::Object(alone)) {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!("Ignoring stored analytics setting {key} ({e}); using its default");
+                false
+            }
+        }
+    });
+    serde_json::Value::Object(stored)
+}
+
+/// Parse a settings object and check it, as accepted by both the settings and config endpoints.
+pub fn parse_settings(value: serde_json::Value) -> Result<Thresholds, String> {
+    let t: Thresholds = serde_json::from_value(value).map_err(|e| format!("Invalid settings: {e}"))?;
+    t.validate()?;
+    Ok(t)
+}
+
+async fn save_settings(pool: &PgPool, t: &Thresholds) -> Result<(), sqlx::Error> {
+    let json = serde_json::to_string(t).unwrap_or_default();
+    sqlx::query(
+        "INSERT INTO user_config (key, value, updated_at) VALUES ($1, $2, NOW()) \
+         ON CONFLICT (key) DO UPDATE SET value = $2, updated_at = NOW()",
+    )
+    .bind(SETTINGS_KEY)
+    .bind(json)
+    .execute(pool)
+    .await
+    .map(|_| ())
+}
+
+async fn get_settings(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let settings = load_thresholds(&pool).await;
+    Json(serde_json::json!({
+        "data": {
+            "settings": settings,
+            "defaults": Thresholds::default(),
+        }
+    }))
+}
+
+/// Merge the given fields into the current settings. A `null` field resets it to its default.
+async fn update_settings(
+    State(pool): State<PgPool>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let serde_json::Value::Object(changes) = body else {
+        return Json(serde_json::json!({ "error": "Expected a JSON object of settings" }));
+    };
+
+    let current = load_thresholds(&pool).await;
+    let mut merged = match serde_json::to_value(&current) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    for (key, value) in changes {
+        if value.is_null() {
+            merged.remove(&key);
+        } else {
+            merged.insert(key, value);
+        }
+    }
+
+    let settings = match parse_settings(serde_json::Value::Object(merged)) {
+        Ok(t) => t,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+
+    if let Err(e) = save_settings(&pool, &settings).await {
+        tracing::error!("Failed to save analytics settings: {e}");
+        return Json(serde_json::json!({ "error": e.to_string() }));
+    }
+
+    Json(serde_json::json!({ "data": settings }))
+}
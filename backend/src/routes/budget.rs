@@ -1,46 +1,220 @@
 use axum::{
-    extract::State,
-    routing::{delete, get},
+    extract::{Query, State},
+    routing::{delete, get, post},
     Json, Router,
 };
-use chrono::Datelike;
+use chrono::{Months, NaiveDate};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
 
-use crate::models::budget::{Budget, BudgetProgress, NewBudget};
+use crate::models::budget::{
+    ApplyBudgetSuggestions, Budget, BudgetHistory, BudgetHistoryQuery, BudgetLimit,
+    BudgetProgress, BudgetProgressQuery, BudgetSuggestion, BudgetSuggestionQuery, BudgetTransfer,
+    BudgetTransferQuery, NewBudget, NewBudgetTransfer,
+};
+use crate::routes::categories::{fetch_categories, known};
+use crate::routes::import::build_horizon;
+use crate::routes::stats_filter::{member_condition, StatsFilter};
+use crate::services::budgets::{self, BudgetWindow, PeriodRun, PeriodTotals};
+use crate::services::categories;
+use crate::services::forecast;
+
+const BUDGET_COLUMNS: &str = "id, category, amount::float8 as amount, period, start_date, end_date, \
+     rollover, rollover_cap::float8 as rollover_cap, scope, created_at, updated_at";
+
+const LIMIT_COLUMNS: &str =
+    "id, budget_id, amount::float8 as amount, effective_from, created_at";
+
+/// A [`BudgetScope`](crate::models::budget::BudgetScope) over `spending_home s`, bound
+/// as `$3`–`$7`. A category counts its subcategories' spending too. Merchants are stored
+/// upper-cased, as `merchant_normalized` is; the member must match exactly.
+fn scope_conditions() -> String {
+    format!(
+        "($3::text[] IS NULL OR s.category = ANY($3) OR EXISTS ( \
+           SELECT 1 FROM categories c JOIN categories p ON p.id = c.parent_id \
+           WHERE c.name = s.category AND p.name = ANY($3))) \
+         AND ($4::text[] IS NULL OR UPPER(s.merchant_normalized) = ANY($4)) \
+         AND ($5::text[] IS NULL OR s.card = ANY($5)) \
+         AND ($6::text IS NULL OR {}) \
+         AND ($7::text[] IS NULL OR s.tags && $7)",
+        member_condition("$6")
+    )
+}
+
+const TRANSFER_COLUMNS: &str =
+    "id, from_category, to_category, amount::float8 as amount, date, note, created_at";
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
         .route("/budgets", get(list_budgets).post(upsert_budget))
         .route("/budgets/progress", get(budget_progress))
+        .route("/budgets/history", get(budget_history))
+        .route("/budgets/suggestions", get(list_suggestions))
+        .route("/budgets/suggestions/apply", post(apply_suggestions))
+        .route(
+            "/budgets/transfers",
+            get(list_transfers).post(transfer_between_budgets),
+        )
         .route("/budgets/:id", delete(delete_budget))
+        .route("/budgets/:id/limits", get(list_limits))
 }
 
-async fn list_budgets(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let budgets: Vec<Budget> = sqlx::query_as(
-        "SELECT id, category, monthly_limit::float8 as monthly_limit, created_at, updated_at \
-         FROM budgets ORDER BY category",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+fn today() -> NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
 
+async fn fetch_budgets(pool: &PgPool) -> Result<Vec<Budget>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {BUDGET_COLUMNS} FROM budgets ORDER BY category"))
+        .fetch_all(pool)
+        .await
+}
+
+async fn list_budgets(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let budgets = fetch_budgets(&pool).await.unwrap_or_default();
     Json(serde_json::json!({ "data": budgets }))
 }
 
 async fn upsert_budget(
     State(pool): State<PgPool>,
-    Json(body): Json<NewBudget>,
+    Json(mut body): Json<NewBudget>,
 ) -> Json<serde_json::Value> {
-    let result = sqlx::query_as::<_, Budget>(
-        "INSERT INTO budgets (category, monthly_limit) \
-         VALUES ($1, $2) \
+    let all_categories = match fetch_categories(&pool).await {
+        Ok(all) => all,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    // A budget on a category goes by the category's listed spelling
+    body.category = categories::find(&body.category, &all_categories)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| body.category.trim().to_string());
+
+    // Without a period, an existing budget keeps its period and dates
+    let (period, start_date, end_date) = match body.period {
+        Some(period) => (period, body.start_date, body.end_date),
+        None => {
+            let existing: Option<(String, Option<NaiveDate>, Option<NaiveDate>)> = sqlx::query_as(
+                "SELECT period, start_date, end_date FROM budgets WHERE category = $1",
+            )
+            .bind(&body.category)
+            .fetch_optional(&pool)
+            .await
+            .unwrap_or_default();
+            existing.unwrap_or(("monthly".to_string(), body.start_date, body.end_date))
+        }
+    };
+    // Likewise the rollover settings without `rollover`
+    let (rollover, rollover_cap) = match body.rollover {
+        Some(rollover) => (rollover, body.rollover_cap),
+        None => {
+            let existing: Option<(bool, Option<f64>)> = sqlx::query_as(
+                "SELECT rollover, rollover_cap::float8 FROM budgets WHERE category = $1",
+            )
+            .bind(&body.category)
+            .fetch_optional(&pool)
+            .await
+            .unwrap_or_default();
+            existing.unwrap_or((false, None))
+        }
+    };
+    let valid = budgets::validate(body.amount, &period, start_date, end_date)
+        .and_then(|_| budgets::validate_rollover(&period, rollover, rollover_cap));
+    if let Err(e) = valid {
+        return Json(serde_json::json!({ "error": e }));
+    }
+    // And the scope without `scope`
+    let scope = match body.scope {
+        Some(scope) => match budgets::normalize_scope(scope) {
+            Ok(Some(mut scope)) => {
+                if let Some(names) = scope.categories.take() {
+                    let mut listed: Vec<String> = Vec::new();
+                    for name in &names {
+                        match known(name, &all_categories) {
+                            Ok(name) if listed.contains(&name) => {}
+                            Ok(name) => listed.push(name),
+                            Err(e) => return Json(serde_json::json!({ "error": e })),
+                        }
+                    }
+                    scope.categories = Some(listed);
+                }
+                Some(serde_json::to_value(scope).unwrap_or_default())
+            }
+            Ok(None) => None,
+            Err(e) => return Json(serde_json::json!({ "error": e })),
+        },
+        None => sqlx::query_scalar("SELECT scope FROM budgets WHERE category = $1")
+            .bind(&body.category)
+            .fetch_optional(&pool)
+            .await
+            .ok()
+            .flatten()
+            .flatten(),
+    };
+    // Without a scope the name is what's counted, so it has to be a listed category
+    if scope.is_none() {
+        if let Err(e) = known(&body.category, &all_categories) {
+            return Json(serde_json::json!({
+                "error": format!("{e}; give the budget a scope to name it freely")
+            }));
+        }
+    }
+
+    let result = sqlx::query_as::<_, Budget>(&format!(
+        "INSERT INTO budgets (category, amount, period, start_date, end_date, rollover, rollover_cap, scope) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
          ON CONFLICT (category) DO UPDATE SET \
-           monthly_limit = EXCLUDED.monthly_limit, \
+           amount = EXCLUDED.amount, \
+           period = EXCLUDED.period, \
+           start_date = EXCLUDED.start_date, \
+           end_date = EXCLUDED.end_date, \
+           rollover = EXCLUDED.rollover, \
+           rollover_cap = EXCLUDED.rollover_cap, \
+           scope = EXCLUDED.scope, \
            updated_at = NOW() \
-         RETURNING id, category, monthly_limit::float8 as monthly_limit, created_at, updated_at",
-    )
+         RETURNING {BUDGET_COLUMNS}"
+    ))
     .bind(&body.category)
-    .bind(body.monthly_limit)
+    .bind(body.amount)
+    .bind(&period)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(rollover)
+    .bind(rollover_cap)
+    .bind(scope)
+    .fetch_one(&pool)
+    .await;
+
+    let budget = match result {
+        Ok(budget) => budget,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+
+    // The amount is a new limit version; budgets.amount follows the latest one
+    let effective_from = body.effective_from.unwrap_or_else(|| {
+        budgets::window(&budget, today())
+            .map(|w| w.start)
+            .or(budget.start_date)
+            .unwrap_or_else(today)
+    });
+    let result = sqlx::query(
+        "INSERT INTO budget_limits (budget_id, amount, effective_from) VALUES ($1, $2, $3) \
+         ON CONFLICT (budget_id, effective_from) DO UPDATE SET amount = EXCLUDED.amount",
+    )
+    .bind(budget.id)
+    .bind(body.amount)
+    .bind(effective_from)
+    .execute(&pool)
+    .await;
+    if let Err(e) = result {
+        return Json(serde_json::json!({ "error": e.to_string() }));
+    }
+
+    let result = sqlx::query_as::<_, Budget>(&format!(
+        "UPDATE budgets SET amount = ( \
+             SELECT amount FROM budget_limits WHERE budget_id = $1 \
+             ORDER BY effective_from DESC LIMIT 1) \
+         WHERE id = $1 RETURNING {BUDGET_COLUMNS}"
+    ))
+    .bind(budget.id)
     .fetch_one(&pool)
     .await;
 
@@ -50,9 +224,26 @@ async fn upsert_budget(
     }
 }
 
+async fn list_limits(
+    State(pool): State<PgPool>,
+    axum::extract::Path(id): axum::extract::Path<Uuid>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query_as::<_, BudgetLimit>(&format!(
+        "SELECT {LIMIT_COLUMNS} FROM budget_limits WHERE budget_id = $1 ORDER BY effective_from"
+    ))
+    .bind(id)
+    .fetch_all(&pool)
+    .await;
+
+    match result {
+        Ok(limits) => Json(serde_json::json!({ "data": limits })),
+        Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
 async fn delete_budget(
     State(pool): State<PgPool>,
-    axum::extract::Path(id): axum::extract::Path<uuid::Uuid>,
+    axum::extract::Path(id): axum::extract::Path<Uuid>,
 ) -> Json<serde_json::Value> {
     sqlx::query("DELETE FROM budgets WHERE id = $1")
         .bind(id)
@@ -63,86 +254,450 @@ async fn delete_budget(
     Json(serde_json::json!({ "data": "Budget deleted" }))
 }
 
-async fn budget_progress(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let budgets: Vec<Budget> = sqlx::query_as(
-        "SELECT id, category, monthly_limit::float8 as monthly_limit, created_at, updated_at \
-         FROM budgets ORDER BY category",
-    )
-    .fetch_all(&pool)
+async fn fetch_transfers(
+    pool: &PgPool,
+    category: Option<&str>,
+    through: Option<NaiveDate>,
+) -> Result<Vec<BudgetTransfer>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {TRANSFER_COLUMNS} FROM budget_transfers \
+         WHERE ($1::text IS NULL OR from_category = $1 OR to_category = $1) \
+           AND ($2::date IS NULL OR date <= $2) \
+         ORDER BY date DESC, created_at DESC"
+    ))
+    .bind(category)
+    .bind(through)
+    .fetch_all(pool)
     .await
-    .unwrap_or_default();
+}
 
-    if budgets.is_empty() {
-        return Json(serde_json::json!({ "data": [] }));
-    }
+/// Limit versions and envelope moves, loaded once for every budget.
+struct Ledger {
+    /// Per budget, oldest first
+    limits: HashMap<Uuid, Vec<(NaiveDate, f64)>>,
+    transfers: Vec<BudgetTransfer>,
+}
 
-    let spending: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT category, COALESCE(SUM(amount::float8), 0) \
-         FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date \
-         GROUP BY category",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+impl Ledger {
+    async fn load(pool: &PgPool, through: NaiveDate) -> Result<Self, sqlx::Error> {
+        let rows: Vec<(Uuid, NaiveDate, f64)> = sqlx::query_as(
+            "SELECT budget_id, effective_from, amount::float8 FROM budget_limits \
+             ORDER BY effective_from",
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut limits: HashMap<Uuid, Vec<(NaiveDate, f64)>> = HashMap::new();
+        for (budget_id, effective_from, amount) in rows {
+            limits
+                .entry(budget_id)
+                .or_default()
+                .push((effective_from, amount));
+        }
+        Ok(Self {
+            limits,
+            transfers: fetch_transfers(pool, None, Some(through)).await?,
+        })
+    }
 
-    let spending_map: std::collections::HashMap<String, f64> = spending.into_iter().collect();
+    fn limit(&self, budget: &Budget, window: BudgetWindow) -> f64 {
+        self.limits
+            .get(&budget.id)
+            .and_then(|limits| budgets::limit_for(limits, window))
+            .unwrap_or(budget.amount)
+    }
+}
 
-    let now = chrono::Local::now().naive_local().date();
-    let days_elapsed = now.day();
-    let days_in_month = if now.month() == 12 {
-        chrono::NaiveDate::from_ymd_opt(now.year() + 1, 1, 1)
-    } else {
-        chrono::NaiveDate::from_ymd_opt(now.year(), now.month() + 1, 1)
+/// `budget`'s periods from the one containing `from` through the one containing `as_of`,
+/// each with its limit version, spending and envelope moves up to `as_of`, and what
+/// earlier periods carried in. With rollover the periods before `from` are still run.
+async fn periods_for(
+    pool: &PgPool,
+    budget: &Budget,
+    ledger: &Ledger,
+    from: NaiveDate,
+    as_of: NaiveDate,
+) -> Result<Vec<PeriodRun>, sqlx::Error> {
+    let mut start = from;
+    if budget.rollover {
+        start = start.min(budgets::rollover_start(budget));
+    }
+    if let Some(start_date) = budget.start_date {
+        start = start.max(start_date);
     }
-    .unwrap()
-    .signed_duration_since(chrono::NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap())
-    .num_days() as u32;
-    let days_remaining = days_in_month.saturating_sub(days_elapsed);
+    let windows = budgets::windows_between(budget, start, as_of);
+    let Some(first) = windows.first() else {
+        return Ok(Vec::new());
+    };
 
-    let progress: Vec<BudgetProgress> = budgets
+    let scope = budgets::scope_of(budget);
+    let daily: Vec<(NaiveDate, f64)> = sqlx::query_as(&format!(
+        "SELECT date, SUM(amount::float8) FROM spending_home s \
+         WHERE date >= $1 AND date <= $2 AND {} \
+         GROUP BY date",
+        scope_conditions()
+    ))
+    .bind(first.start)
+    .bind(as_of)
+    .bind(&scope.categories)
+    .bind(&scope.merchants)
+    .bind(&scope.cards)
+    .bind(&scope.member)
+    .bind(&scope.tags)
+    .fetch_all(pool)
+    .await?;
+    let spent = budgets::bucket(&windows, daily);
+
+    let moves = ledger
+        .transfers
         .iter()
-        .map(|b| {
-            let spent = spending_map.get(&b.category).copied().unwrap_or(0.0);
-            let remaining = (b.monthly_limit - spent).max(0.0);
-            let pct_used = if b.monthly_limit > 0.0 {
-                (spent / b.monthly_limit) * 100.0
+        .filter(|t| t.date <= as_of)
+        .filter_map(|t| {
+            if t.to_category == budget.category {
+                Some((t.date, t.amount))
+            } else if t.from_category == budget.category {
+                Some((t.date, -t.amount))
             } else {
-                0.0
-            };
+                None
+            }
+        });
+    let transferred = budgets::bucket(&windows, moves);
 
-            let projected_spend = if days_elapsed > 0 {
-                (spent / days_elapsed as f64) * days_in_month as f64
-            } else {
-                0.0
-            };
-            let projected_pct = if b.monthly_limit > 0.0 {
-                (projected_spend / b.monthly_limit) * 100.0
-            } else {
-                0.0
+    let periods: Vec<(BudgetWindow, PeriodTotals)> = windows
+        .iter()
+        .zip(spent.into_iter().zip(transferred))
+        .map(|(&window, (spent, transferred))| {
+            let totals = PeriodTotals {
+                amount: ledger.limit(budget, window),
+                spent,
+                transferred,
             };
+            (window, totals)
+        })
+        .collect();
 
-            let status = if pct_used >= 100.0 {
-                "over_budget".to_string()
-            } else if projected_pct >= 90.0 {
-                "warning".to_string()
-            } else {
-                "on_track".to_string()
-            };
+    let mut runs = budgets::run_periods(budget, &periods);
+    // The last period is kept even when it ended before `from`, as a finished custom one has
+    let keep_from = runs
+        .iter()
+        .position(|run| run.window.end >= from)
+        .unwrap_or(runs.len() - 1);
+    Ok(runs.split_off(keep_from))
+}
 
-            BudgetProgress {
-                category: b.category.clone(),
-                monthly_limit: b.monthly_limit,
-                spent,
-                remaining,
-                pct_used,
-                projected_spend,
-                projected_pct,
-                status,
-                days_remaining,
+/// `budget`'s progress through its period containing `as_of`.
+async fn progress_for(
+    pool: &PgPool,
+    budget: &Budget,
+    ledger: &Ledger,
+    as_of: NaiveDate,
+) -> Result<Option<BudgetProgress>, sqlx::Error> {
+    let runs = periods_for(pool, budget, ledger, as_of, as_of).await?;
+    Ok(runs
+        .last()
+        .map(|run| budgets::progress(budget, run.window, run.totals, run.carried_in, as_of)))
+}
+
+/// Each budget's progress through its period containing `as_of`. Budgets that haven't
+/// started yet are left out.
+pub(crate) async fn progress_as_of(
+    pool: &PgPool,
+    as_of: NaiveDate,
+) -> Result<Vec<BudgetProgress>, sqlx::Error> {
+    let ledger = Ledger::load(pool, as_of).await?;
+    let mut progress = Vec::new();
+    for budget in fetch_budgets(pool).await? {
+        if let Some(p) = progress_for(pool, &budget, &ledger, as_of).await? {
+            progress.push(p);
+        }
+    }
+    Ok(progress)
+}
+
+/// Limit vs actual for every budget period overlapping the last `months` months.
+async fn budget_history(
+    State(pool): State<PgPool>,
+    Query(params): Query<BudgetHistoryQuery>,
+) -> Json<serde_json::Value> {
+    let months = params.months.unwrap_or(12);
+    if !(1..=60).contains(&months) {
+        return Json(serde_json::json!({ "error": "months must be between 1 and 60" }));
+    }
+    let as_of = params.as_of.unwrap_or_else(today);
+    let from = forecast::month_start(as_of) - Months::new(months - 1);
+
+    let result = async {
+        let ledger = Ledger::load(&pool, as_of).await?;
+        let mut history = Vec::new();
+        for budget in fetch_budgets(&pool).await? {
+            let runs = periods_for(&pool, &budget, &ledger, from, as_of).await?;
+            if runs.is_empty() {
+                continue;
             }
-        })
+            let periods = runs
+                .iter()
+                .map(|run| budgets::period_result(run, as_of))
+                .collect();
+            history.push(budgets::summarize(&budget, periods));
+        }
+        Ok::<_, sqlx::Error>(history)
+    }
+    .await;
+
+    match result {
+        Ok(budgets) => Json(serde_json::json!({
+            "data": BudgetHistory { from, to: as_of, budgets }
+        })),
+        Err(e) => {
+            tracing::error!("Failed to build budget history: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn budget_progress(
+    State(pool): State<PgPool>,
+    Query(params): Query<BudgetProgressQuery>,
+) -> Json<serde_json::Value> {
+    match progress_as_of(&pool, params.as_of.unwrap_or_else(today)).await {
+        Ok(progress) => Json(serde_json::json!({ "data": progress })),
+        Err(e) => {
+            tracing::error!("Failed to compute budget progress: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn list_transfers(
+    State(pool): State<PgPool>,
+    Query(params): Query<BudgetTransferQuery>,
+) -> Json<serde_json::Value> {
+    match fetch_transfers(&pool, params.category.as_deref(), None).await {
+        Ok(transfers) => Json(serde_json::json!({ "data": transfers })),
+        Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+/// Move money from one category's budget to another's. Only what the source still has
+/// left in its period can be moved.
+async fn transfer_between_budgets(
+    State(pool): State<PgPool>,
+    Json(body): Json<NewBudgetTransfer>,
+) -> Json<serde_json::Value> {
+    if !body.amount.is_finite() || body.amount <= 0.0 {
+        return Json(serde_json::json!({ "error": "amount must be positive" }));
+    }
+    if body.from_category == body.to_category {
+        return Json(serde_json::json!({ "error": "from_category and to_category must differ" }));
+    }
+    let date = body.date.unwrap_or_else(today);
+
+    let all = match fetch_budgets(&pool).await {
+        Ok(all) => all,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let find = |category: &str| all.iter().find(|b| b.category == category);
+    let (Some(from), Some(to)) = (find(&body.from_category), find(&body.to_category)) else {
+        return Json(serde_json::json!({ "error": "both categories need a budget" }));
+    };
+    if budgets::window(to, date).is_none() {
+        return Json(serde_json::json!({
+            "error": format!("{} budget has no period on {date}", to.category)
+        }));
+    }
+
+    let ledger = match Ledger::load(&pool, date).await {
+        Ok(ledger) => ledger,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let left = match progress_for(&pool, from, &ledger, date).await {
+        Ok(Some(p)) => p.available - p.spent,
+        Ok(None) => {
+            return Json(serde_json::json!({
+                "error": format!("{} budget has no period on {date}", from.category)
+            }))
+        }
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    if body.amount > left + 0.005 {
+        return Json(serde_json::json!({
+            "error": format!("only ${:.2} left to move from {}", left.max(0.0), from.category)
+        }));
+    }
+
+    let result = sqlx::query_as::<_, BudgetTransfer>(&format!(
+        "INSERT INTO budget_transfers (from_category, to_category, amount, date, note) \
+         VALUES ($1, $2, $3, $4, $5) RETURNING {TRANSFER_COLUMNS}"
+    ))
+    .bind(&body.from_category)
+    .bind(&body.to_category)
+    .bind(body.amount)
+    .bind(date)
+    .bind(&body.note)
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(transfer) => Json(serde_json::json!({ "data": transfer })),
+        Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+/// A monthly limit per category for the month after `as_of`'s, from the last `months`
+/// completed months, along with the first day of that month.
+async fn suggestions(
+    pool: &PgPool,
+    months: Option<u32>,
+    percentile: Option<f64>,
+    as_of: Option<NaiveDate>,
+) -> Result<(NaiveDate, Vec<BudgetSuggestion>), String> {
+    let months = months.unwrap_or(12);
+    if !(3..=36).contains(&months) {
+        return Err("months must be between 3 and 36".into());
+    }
+    let p = percentile.unwrap_or(50.0);
+    if !(50.0..=95.0).contains(&p) {
+        return Err("percentile must be between 50 and 95".into());
+    }
+    let as_of = as_of.unwrap_or_else(today);
+    let current = forecast::month_start(as_of);
+    let target = forecast::add_months(current, 1);
+
+    // Non-recurring history per category and next month's recurring charges
+    let filter = StatsFilter {
+        end_date: Some(as_of),
+        ..Default::default()
+    };
+    let (model, _) = build_horizon(pool, &filter, Some(1)).await;
+
+    let rows: Vec<(String, NaiveDate, f64)> = sqlx::query_as(
+        "SELECT category, date_trunc('month', date)::date, SUM(amount::float8) \
+         FROM spending_home WHERE date >= $1 AND date < $2 GROUP BY 1, 2",
+    )
+    .bind(current - Months::new(months))
+    .bind(current)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let actual = forecast::by_category(&rows);
+
+    let existing: HashMap<String, Budget> = fetch_budgets(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|b| b.scope.is_none())
+        .map(|b| (b.category.clone(), b))
         .collect();
 
-    Json(serde_json::json!({ "data": progress }))
+    let mut names: Vec<&str> = model.categories.keys().map(String::as_str).collect();
+    names.extend(model.charges.iter().map(|c| c.category.as_str()));
+    names.sort_unstable();
+    names.dedup();
+
+    let mut out = Vec::new();
+    for name in names {
+        let history = model.categories.get(name).map(Vec::as_slice).unwrap_or(&[]);
+        let recent = &history[history.len().saturating_sub(months as usize)..];
+        if recent.len() < 3 {
+            continue;
+        }
+        let recurring: f64 = model
+            .charges
+            .iter()
+            .filter(|c| c.month == 0 && c.category == name)
+            .fold(0.0, |sum, c| sum + c.amount);
+        let seasonal_index = forecast::seasonal_factor(history, 0);
+        let suggested = budgets::suggest_limit(recent, p, seasonal_index, recurring);
+        if suggested <= 0.0 {
+            continue;
+        }
+
+        let totals = actual.get(name);
+        let actual_months: Vec<f64> = (1..=recent.len() as u32)
+            .rev()
+            .map(|k| {
+                let month = current - Months::new(k);
+                totals.and_then(|t| t.get(&month)).copied().unwrap_or(0.0)
+            })
+            .collect();
+        let months_exceeded = budgets::months_over(&actual_months, suggested);
+        let budget = existing.get(name);
+
+        out.push(BudgetSuggestion {
+            category: name.to_string(),
+            month: target.format("%Y-%m").to_string(),
+            suggested,
+            variable: budgets::percentile(recent, p),
+            seasonal_index,
+            recurring,
+            months_used: recent.len() as u32,
+            months_exceeded,
+            exceed_rate: months_exceeded as f64 / recent.len() as f64 * 100.0,
+            current_amount: budget.map(|b| b.amount),
+            current_period: budget.map(|b| b.period.clone()),
+        });
+    }
+    out.sort_by(|a, b| b.suggested.total_cmp(&a.suggested));
+    Ok((target, out))
+}
+
+async fn list_suggestions(
+    State(pool): State<PgPool>,
+    Query(params): Query<BudgetSuggestionQuery>,
+) -> Json<serde_json::Value> {
+    match suggestions(&pool, params.months, params.percentile, params.as_of).await {
+        Ok((_, suggestions)) => Json(serde_json::json!({ "data": suggestions })),
+        Err(e) => Json(serde_json::json!({ "error": e })),
+    }
+}
+
+/// Upsert the accepted categories' suggestions as monthly budgets. Categories without a
+/// suggestion, or whose budget isn't a plain monthly one, are skipped with a reason.
+async fn apply_suggestions(
+    State(pool): State<PgPool>,
+    Json(body): Json<ApplyBudgetSuggestions>,
+) -> Json<serde_json::Value> {
+    let run = suggestions(&pool, body.months, body.percentile, body.as_of).await;
+    let (target, suggested) = match run {
+        Ok(run) => run,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+    let all = fetch_budgets(&pool).await.unwrap_or_default();
+
+    let mut applied = Vec::new();
+    let mut skipped = Vec::new();
+    for category in &body.categories {
+        let Some(suggestion) = suggested.iter().find(|s| &s.category == category) else {
+            skipped.push(serde_json::json!({ "category": category, "reason": "no suggestion" }));
+            continue;
+        };
+        if let Some(budget) = all.iter().find(|b| &b.category == category) {
+            if budget.period != "monthly" || budget.scope.is_some() {
+                skipped.push(serde_json::json!({
+                    "category": category,
+                    "reason": "the budget isn't a plain monthly one"
+                }));
+                continue;
+            }
+        }
+
+        let new = NewBudget {
+            category: category.clone(),
+            amount: suggestion.suggested,
+            period: None,
+            start_date: None,
+            end_date: None,
+            rollover: None,
+            rollover_cap: None,
+            // The limit starts with the month it was suggested for
+            effective_from: Some(target),
+            scope: None,
+        };
+        let Json(result) = upsert_budget(State(pool.clone()), Json(new)).await;
+        match result.get("error") {
+            Some(e) => skipped.push(serde_json::json!({ "category": category, "reason": e })),
+            None => applied.push(result["data"].clone()),
+        }
+    }
+
+    Json(serde_json::json!({ "data": { "applied": applied, "skipped": skipped } }))
 }
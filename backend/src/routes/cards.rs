@@ -6,7 +6,9 @@ use axum::{
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::models::card::{NewCard, UpdateCard};
+use crate::models::account::ACCOUNT_TYPES;
+use crate::models::card::{NewCard, UpdateCard, AMOUNT_SIGNS};
+use crate::services::fx;
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
@@ -62,12 +64,26 @@ async fn create_card(
     if body.code.is_empty() || body.label.is_empty() {
         return Json(serde_json::json!({ "error": "code and label are required" }));
     }
+    if body.currency.as_deref().is_some_and(|c| !fx::is_currency_code(c.trim())) {
+        return Json(serde_json::json!({ "error": "currency must be a 3-letter code" }));
+    }
+    if body.account_type.as_deref().is_some_and(|t| !ACCOUNT_TYPES.contains(&t)) {
+        return Json(serde_json::json!({
+            "error": format!("account_type must be one of: {}", ACCOUNT_TYPES.join(", "))
+        }));
+    }
+    if body.amount_sign.as_deref().is_some_and(|s| !AMOUNT_SIGNS.contains(&s)) {
+        return Json(serde_json::json!({
+            "error": format!("amount_sign must be one of: {}", AMOUNT_SIGNS.join(", "))
+        }));
+    }
 
     let result: Result<crate::models::card::Card, _> = sqlx::query_as(
         "INSERT INTO cards (code, label, color, header_pattern, delimiter, date_column, date_format, \
          description_column, amount_column, debit_column, credit_column, category_column, \
-         member_column, skip_negative_amounts) \
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) \
+         member_column, skip_negative_amounts, currency, currency_column, account_type, \
+         amount_sign, import_credits) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19) \
          RETURNING *",
     )
     .bind(&body.code)
@@ -84,6 +100,16 @@ async fn create_card(
     .bind(&body.category_column)
     .bind(&body.member_column)
     .bind(body.skip_negative_amounts.unwrap_or(false))
+    .bind(
+        body.currency
+            .as_deref()
+            .map(|c| c.trim().to_uppercase())
+            .unwrap_or_else(|| "USD".to_string()),
+    )
+    .bind(&body.currency_column)
+    .bind(body.account_type.as_deref().unwrap_or("credit"))
+    .bind(body.amount_sign.as_deref().unwrap_or("debit_positive"))
+    .bind(body.import_credits.unwrap_or(false))
     .fetch_one(&pool)
     .await;
 
@@ -101,6 +127,20 @@ async fn update_card(
     Path(id): Path<Uuid>,
     Json(body): Json<UpdateCard>,
 ) -> Json<serde_json::Value> {
+    if body.currency.as_deref().is_some_and(|c| !fx::is_currency_code(c.trim())) {
+        return Json(serde_json::json!({ "error": "currency must be a 3-letter code" }));
+    }
+    if body.account_type.as_deref().is_some_and(|t| !ACCOUNT_TYPES.contains(&t)) {
+        return Json(serde_json::json!({
+            "error": format!("account_type must be one of: {}", ACCOUNT_TYPES.join(", "))
+        }));
+    }
+    if body.amount_sign.as_deref().is_some_and(|s| !AMOUNT_SIGNS.contains(&s)) {
+        return Json(serde_json::json!({
+            "error": format!("amount_sign must be one of: {}", AMOUNT_SIGNS.join(", "))
+        }));
+    }
+
     // Fetch existing card, merge with partial update fields
     let existing: Option<crate::models::card::Card> = match sqlx::query_as(
         "SELECT * FROM cards WHERE id = $1",
@@ -135,12 +175,21 @@ async fn update_card(
     let category_column = body.category_column.or(existing.category_column);
     let member_column = body.member_column.or(existing.member_column);
     let skip_negative_amounts = body.skip_negative_amounts.unwrap_or(existing.skip_negative_amounts);
+    let currency = body
+        .currency
+        .map(|c| c.trim().to_uppercase())
+        .unwrap_or(existing.currency);
+    let currency_column = body.currency_column.or(existing.currency_column);
+    let account_type = body.account_type.unwrap_or(existing.account_type);
+    let amount_sign = body.amount_sign.unwrap_or(existing.amount_sign);
+    let import_credits = body.import_credits.unwrap_or(existing.import_credits);
 
     let result: Result<crate::models::card::Card, _> = sqlx::query_as(
         "UPDATE cards SET code=$1, label=$2, color=$3, header_pattern=$4, delimiter=$5, \
          date_column=$6, date_format=$7, description_column=$8, amount_column=$9, \
          debit_column=$10, credit_column=$11, category_column=$12, member_column=$13, \
-         skip_negative_amounts=$14 WHERE id=$15 RETURNING *",
+         skip_negative_amounts=$14, currency=$15, currency_column=$16, \
+         account_type=$17, amount_sign=$18, import_credits=$19 WHERE id=$20 RETURNING *",
     )
     .bind(&code)
     .bind(&label)
@@ -156,6 +205,11 @@ async fn update_card(
     .bind(&category_column)
     .bind(&member_column)
     .bind(skip_negative_amounts)
+    .bind(&currency)
+    .bind(&currency_column)
+    .bind(&account_type)
+    .bind(&amount_sign)
+    .bind(import_credits)
     .bind(id)
     .fetch_one(&pool)
     .await;
@@ -0,0 +1,346 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, patch},
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::category::{
+    Category, DeleteCategoryQuery, NewCategory, UpdateCategory, RESERVED_CATEGORIES,
+};
+use crate::services::categories;
+
+/// Top-level categories by name, each followed by its children.
+const CATEGORIES: &str = "SELECT c.id, c.name, c.parent_id, p.name AS parent, c.color, c.icon, \
+       c.kind, c.created_at, c.updated_at \
+     FROM categories c LEFT JOIN categories p ON p.id = c.parent_id \
+     ORDER BY COALESCE(p.name, c.name), c.parent_id IS NOT NULL, c.name";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/categories", get(list_categories).post(create_category))
+        .route(
+            "/categories/:id",
+            patch(update_category).delete(delete_category),
+        )
+}
+
+/// Every listed category. Also used to check category references elsewhere.
+pub(crate) async fn fetch_categories(pool: &PgPool) -> Result<Vec<Category>, sqlx::Error> {
+    sqlx::query_as(CATEGORIES).fetch_all(pool).await
+}
+
+/// The listed name a write refers to, or an error naming the unknown category.
+pub(crate) fn known(name: &str, all: &[Category]) -> Result<String, String> {
+    categories::find(name, all)
+        .map(|c| c.name.clone())
+        .ok_or_else(|| format!("Unknown category: {}", name.trim()))
+}
+
+/// Load the categories, then look `name` up in them.
+pub(crate) async fn canonical(pool: &PgPool, name: &str) -> Result<String, String> {
+    let all = fetch_categories(pool).await.map_err(|e| e.to_string())?;
+    known(name, &all)
+}
+
+async fn list_categories(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    match fetch_categories(&pool).await {
+        Ok(all) => Json(serde_json::json!({ "data": all })),
+        Err(e) => {
+            tracing::error!("Failed to list categories: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Check a category's fields against the others before saving; returns the parent's id.
+fn check_category(
+    category: &Category,
+    parent: Option<&str>,
+    all: &[Category],
+) -> Result<Option<Uuid>, String> {
+    let others: Vec<Category> = all
+        .iter()
+        .filter(|c| c.id != category.id)
+        .cloned()
+        .collect();
+    if let Some(existing) = categories::find(&category.name, &others) {
+        return Err(format!("{} already exists", existing.name));
+    }
+    let Some(parent) = parent else {
+        return Ok(None);
+    };
+    let parent = categories::find(parent, all)
+        .ok_or_else(|| format!("Unknown parent category: {}", parent.trim()))?;
+    let id = all
+        .iter()
+        .any(|c| c.id == category.id)
+        .then_some(category.id);
+    categories::check_parent(id, parent, all)?;
+    Ok(Some(parent.id))
+}
+
+fn blank_to_none(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// A new category from the request, checked, with its parent's id.
+fn new_category(body: &NewCategory, all: &[Category]) -> Result<(Category, Option<Uuid>), String> {
+    let now = chrono::Utc::now();
+    let category = Category {
+        id: Uuid::nil(),
+        name: categories::normalize_name(&body.name)?,
+        parent_id: None,
+        parent: None,
+        color: categories::normalize_color(body.color.as_deref().unwrap_or(""))?,
+        icon: blank_to_none(body.icon.clone()),
+        kind: categories::normalize_kind(body.kind.as_deref().unwrap_or(""))?,
+        created_at: now,
+        updated_at: now,
+    };
+    let parent = blank_to_none(body.parent.clone());
+    let parent_id = check_category(&category, parent.as_deref(), all)?;
+    Ok((category, parent_id))
+}
+
+/// `existing` with the request's changes, checked, with its parent's id.
+fn updated_category(
+    existing: &Category,
+    body: &UpdateCategory,
+    all: &[Category],
+) -> Result<(Category, Option<Uuid>), String> {
+    let mut category = existing.clone();
+    if let Some(name) = &body.name {
+        category.name = categories::normalize_name(name)?;
+        if category.name != existing.name && RESERVED_CATEGORIES.contains(&existing.name.as_str()) {
+            return Err(format!("{} can't be renamed", existing.name));
+        }
+    }
+    if let Some(color) = &body.color {
+        category.color = categories::normalize_color(color)?;
+    }
+    if body.icon.is_some() {
+        category.icon = blank_to_none(body.icon.clone());
+    }
+    if let Some(kind) = &body.kind {
+        category.kind = categories::normalize_kind(kind)?;
+    }
+    let parent = match &body.parent {
+        Some(parent) => blank_to_none(Some(parent.clone())),
+        None => existing.parent.clone(),
+    };
+    let parent_id = check_category(&category, parent.as_deref(), all)?;
+    Ok((category, parent_id))
+}
+
+async fn create_category(
+    State(pool): State<PgPool>,
+    Json(body): Json<NewCategory>,
+) -> Json<serde_json::Value> {
+    let all = match fetch_categories(&pool).await {
+        Ok(all) => all,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let (category, parent_id) = match new_category(&body, &all) {
+        Ok(fields) => fields,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+
+    let result: Result<(Uuid,), _> = sqlx::query_as(
+        "INSERT INTO categories (name, parent_id, color, icon, kind) \
+         VALUES ($1, $2, $3, $4, $5) RETURNING id",
+    )
+    .bind(&category.name)
+    .bind(parent_id)
+    .bind(&category.color)
+    .bind(&category.icon)
+    .bind(&category.kind)
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok((id,)) => respond_with(&pool, id).await,
+        Err(e) => {
+            tracing::error!("Failed to create category: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// The saved category, as listed.
+async fn respond_with(pool: &PgPool, id: Uuid) -> Json<serde_json::Value> {
+    match fetch_categories(pool).await {
+        Ok(all) => match all.into_iter().find(|c| c.id == id) {
+            Some(category) => Json(serde_json::json!({ "data": category })),
+            None => Json(serde_json::json!({ "error": "Category not found" })),
+        },
+        Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+/// Renaming carries the new name to transactions (through the foreign key), to budgets
+/// on the category or scoped to it, and to envelope moves between them.
+async fn update_category(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateCategory>,
+) -> Json<serde_json::Value> {
+    let all = match fetch_categories(&pool).await {
+        Ok(all) => all,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let Some(existing) = all.iter().find(|c| c.id == id) else {
+        return Json(serde_json::json!({ "error": "Category not found" }));
+    };
+    let (category, parent_id) = match updated_category(existing, &body, &all) {
+        Ok(fields) => fields,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+
+    let result = save_category(&pool, &existing.name, &category, parent_id).await;
+    match result {
+        Ok(()) => respond_with(&pool, id).await,
+        Err(e) => {
+            tracing::error!("Failed to update category {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn save_category(
+    pool: &PgPool,
+    old_name: &str,
+    category: &Category,
+    parent_id: Option<Uuid>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE categories SET name = $1, parent_id = $2, color = $3, icon = $4, kind = $5, \
+         updated_at = NOW() WHERE id = $6",
+    )
+    .bind(&category.name)
+    .bind(parent_id)
+    .bind(&category.color)
+    .bind(&category.icon)
+    .bind(&category.kind)
+    .bind(category.id)
+    .execute(&mut *tx)
+    .await?;
+
+    if category.name != old_name {
+        sqlx::query("UPDATE budgets SET category = $2 WHERE category = $1 AND scope IS NULL")
+            .bind(old_name)
+            .bind(&category.name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE budgets SET scope = jsonb_set(scope, '{categories}', ( \
+               SELECT jsonb_agg(CASE WHEN v = $1 THEN $2 ELSE v END) \
+               FROM jsonb_array_elements_text(scope->'categories') v)) \
+             WHERE scope->'categories' ? $1",
+        )
+        .bind(old_name)
+        .bind(&category.name)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE budget_transfers SET from_category = $2 WHERE from_category = $1")
+            .bind(old_name)
+            .bind(&category.name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE budget_transfers SET to_category = $2 WHERE to_category = $1")
+            .bind(old_name)
+            .bind(&category.name)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+/// Delete a category. Its transactions must be moved with `reassign_to` first, which
+/// also merges a misspelt category into the right one; budgets that count it and
+/// subcategories have to go or move themselves.
+async fn delete_category(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DeleteCategoryQuery>,
+) -> Json<serde_json::Value> {
+    let all = match fetch_categories(&pool).await {
+        Ok(all) => all,
+        Err(e) => return Json(serde_json::json!({ "error": e.to_string() })),
+    };
+    let Some(category) = all.iter().find(|c| c.id == id) else {
+        return Json(serde_json::json!({ "error": "Category not found" }));
+    };
+    if RESERVED_CATEGORIES.contains(&category.name.as_str()) {
+        return Json(serde_json::json!({ "error": format!("{} can't be deleted", category.name) }));
+    }
+    if all.iter().any(|c| c.parent_id == Some(id)) {
+        return Json(serde_json::json!({
+            "error": format!("{} has subcategories; move or delete them first", category.name)
+        }));
+    }
+    let target = match blank_to_none(params.reassign_to) {
+        Some(target) => match known(&target, &all) {
+            Ok(target) if target == category.name => {
+                return Json(serde_json::json!({ "error": "reassign_to must be another category" }))
+            }
+            Ok(target) => Some(target),
+            Err(e) => return Json(serde_json::json!({ "error": e })),
+        },
+        None => None,
+    };
+
+    let budgets: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*)::bigint FROM budgets \
+         WHERE (category = $1 AND scope IS NULL) OR scope->'categories' ? $1",
+    )
+    .bind(&category.name)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or_default();
+    if budgets > 0 {
+        return Json(serde_json::json!({
+            "error": format!("{} is used by {budgets} budget(s); change or delete them first", category.name)
+        }));
+    }
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+        let moved = match &target {
+            Some(target) => {
+                sqlx::query("UPDATE transactions SET category = $2 WHERE category = $1")
+                    .bind(&category.name)
+                    .bind(target)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected()
+            }
+            None => 0,
+        };
+        sqlx::query("DELETE FROM categories WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(moved)
+    }
+    .await;
+
+    match result {
+        Ok(moved) => Json(serde_json::json!({
+            "data": { "deleted": category.name, "reassigned": moved, "reassigned_to": target }
+        })),
+        // The foreign key keeps categories that transactions still use
+        Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Json(serde_json::json!({
+            "error": format!("{} has transactions; pass reassign_to to move them", category.name)
+        })),
+        Err(e) => {
+            tracing::error!("Failed to delete category {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
@@ -7,6 +7,8 @@ use sqlx::PgPool;
 use std::collections::HashMap;
 
 use crate::models::config::UserConfig;
+use crate::routes::{analytics, insights};
+use crate::services::fx;
 
 pub fn routes() -> Router<PgPool> {
     Router::new().route("/config", get(get_config).put(set_config))
@@ -34,12 +36,37 @@ async fn set_config(
     Json(body): Json<HashMap<String, String>>,
 ) -> Json<serde_json::Value> {
     for (key, value) in &body {
+        let value = if key == "home_currency" {
+            if !fx::is_currency_code(value.trim()) {
+                return Json(serde_json::json!({ "error": "home_currency must be a 3-letter code" }));
+            }
+            value.trim().to_uppercase()
+        } else if key == analytics::SETTINGS_KEY {
+            let parsed = serde_json::from_str(value)
+                .map_err(|e| format!("Invalid settings: {e}"))
+                .and_then(analytics::parse_settings);
+            match parsed {
+                Ok(t) => serde_json::to_string(&t).unwrap_or_default(),
+                Err(e) => return Json(serde_json::json!({ "error": e })),
+            }
+        } else if key == insights::GENERATORS_KEY {
+            let parsed = serde_json::from_str(value)
+                .map_err(|e| format!("Invalid generator settings: {e}"))
+                .and_then(insights::parse_generator_settings);
+            match parsed {
+                Ok(settings) => serde_json::to_string(&settings).unwrap_or_default(),
+                Err(e) => return Json(serde_json::json!({ "error": e })),
+            }
+        } else {
+            value.clone()
+        };
+
         let result = sqlx::query(
             "INSERT INTO user_config (key, value, updated_at) VALUES ($1, $2, NOW()) \
              ON CONFLICT (key) DO UPDATE SET value = $2, updated_at = NOW()",
         )
         .bind(key)
-        .bind(value)
+        .bind(&value)
         .execute(&pool)
         .await;
 
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Multipart, State},
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::PgPool;
+
+use crate::models::fx::{FxPairSummary, FxRatesOverview};
+use crate::services::fx;
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/fx-rates", get(list_rates))
+        .route("/fx-rates/import", post(import_rates))
+}
+
+pub async fn home_currency(pool: &PgPool) -> String {
+    match sqlx::query_scalar::<_, String>(
+        "SELECT value FROM user_config WHERE key = 'home_currency'",
+    )
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => "USD".to_string(),
+        Err(e) => {
+            tracing::error!("Failed to fetch home_currency config: {e}");
+            "USD".to_string()
+        }
+    }
+}
+
+async fn list_rates(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let pairs: Vec<FxPairSummary> = match sqlx::query_as(
+        "SELECT base, quote, COUNT(*)::bigint as rate_count, \
+           MIN(date) as first_date, MAX(date) as last_date, \
+           (ARRAY_AGG(rate::float8 ORDER BY date DESC))[1] as latest_rate \
+         FROM fx_rates GROUP BY base, quote ORDER BY base, quote",
+    )
+    .fetch_all(&pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to list fx rates: {e}");
+            Vec::new()
+        }
+    };
+
+    let home = home_currency(&pool).await;
+
+    let missing_currencies: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT currency FROM transactions \
+         WHERE currency <> $1 AND fx_rate(currency, $1, CURRENT_DATE) IS NULL \
+         ORDER BY currency",
+    )
+    .bind(&home)
+    .fetch_all(&pool)
+    .await
+    .unwrap_or_default();
+
+    Json(serde_json::json!({
+        "data": FxRatesOverview {
+            home_currency: home,
+            pairs,
+            missing_currencies,
+        }
+    }))
+}
+
+async fn import_rates(
+    State(pool): State<PgPool>,
+    mut multipart: Multipart,
+) -> Json<serde_json::Value> {
+    let mut file_name = String::from("rates.csv");
+    let mut data = String::new();
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("file") {
+            if let Some(fname) = field.file_name() {
+                file_name = fname.to_string();
+            }
+            if let Ok(text) = field.text().await {
+                data = text;
+            }
+        } else {
+            let _ = field.text().await;
+        }
+    }
+
+    if data.is_empty() {
+        return Json(serde_json::json!({ "error": "No rate file received" }));
+    }
+
+    let rates = match fx::parse_rates(&data) {
+        Ok(r) => r,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+    let source = if data.trim_start().starts_with('<') { "ecb" } else { "csv" };
+
+    let mut imported = 0i64;
+    for rate in &rates {
+        let result = sqlx::query(
+            "INSERT INTO fx_rates (date, base, quote, rate, source) VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (base, quote, date) DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source",
+        )
+        .bind(rate.date)
+        .bind(&rate.base)
+        .bind(&rate.quote)
+        .bind(rate.rate)
+        .bind(source)
+        .execute(&pool)
+        .await;
+
+        match result {
+            Ok(_) => imported += 1,
+            Err(e) => tracing::warn!("Failed to upsert fx rate: {}", e),
+        }
+    }
+
+    Json(serde_json::json!({
+        "data": {
+            "file_name": file_name,
+            "source": source,
+            "rate_count": imported,
+            "first_date": rates.iter().map(|r| r.date).min(),
+            "last_date": rates.iter().map(|r| r.date).max()
+        }
+    }))
+}
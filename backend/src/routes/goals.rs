@@ -0,0 +1,367 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::goal::{
+    Goal, GoalContribution, GoalProgress, GoalQuery, NewGoal, NewGoalContribution, UpdateGoal,
+};
+use crate::services::goals;
+
+const GOAL_COLUMNS: &str = "id, name, target_amount::float8 as target_amount, target_date, \
+     start_date, initial_amount::float8 as initial_amount, account, tag, created_at, updated_at";
+
+/// Manual entries plus linked transactions, in home currency. Deposits into a linked
+/// account count (its outflows count against); tagged transactions count as set aside.
+/// Transactions with no rate into the home currency are left out.
+const CONTRIBUTIONS: &str = "SELECT id, 'manual' AS source, date, amount::float8 AS amount, note \
+     FROM goal_contributions WHERE goal_id = $1 \
+     UNION ALL \
+     SELECT id, 'transaction' AS source, date, \
+       (CASE WHEN $2::text IS NOT NULL THEN -amount ELSE amount END)::float8 AS amount, \
+       description AS note \
+     FROM transactions_home \
+     WHERE date >= $4 AND (card = $2 OR $3 = ANY(tags)) AND amount IS NOT NULL \
+     ORDER BY date DESC, source";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/goals", get(list_goals).post(create_goal))
+        .route(
+            "/goals/:id",
+            get(get_goal).patch(update_goal).delete(delete_goal),
+        )
+        .route(
+            "/goals/:id/contributions",
+            get(list_contributions).post(add_contribution),
+        )
+        .route(
+            "/goals/:id/contributions/:contribution_id",
+            delete(delete_contribution),
+        )
+}
+
+fn today() -> NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
+
+async fn fetch_goal(pool: &PgPool, id: Uuid) -> Result<Option<Goal>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {GOAL_COLUMNS} FROM goals WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn contributions(pool: &PgPool, goal: &Goal) -> Result<Vec<GoalContribution>, sqlx::Error> {
+    sqlx::query_as(CONTRIBUTIONS)
+        .bind(goal.id)
+        .bind(&goal.account)
+        .bind(&goal.tag)
+        .bind(goal.start_date)
+        .fetch_all(pool)
+        .await
+}
+
+async fn progress_for(
+    pool: &PgPool,
+    goal: &Goal,
+    as_of: NaiveDate,
+) -> Result<GoalProgress, sqlx::Error> {
+    let entries: Vec<(NaiveDate, f64)> = contributions(pool, goal)
+        .await?
+        .iter()
+        .map(|c| (c.date, c.amount))
+        .collect();
+    Ok(goals::progress(goal, &entries, as_of))
+}
+
+/// Every goal's progress as of a date, by target date. Also feeds the insights engine.
+pub(crate) async fn progress_as_of(
+    pool: &PgPool,
+    as_of: NaiveDate,
+) -> Result<Vec<GoalProgress>, sqlx::Error> {
+    let all: Vec<Goal> = sqlx::query_as(&format!(
+        "SELECT {GOAL_COLUMNS} FROM goals ORDER BY target_date, name"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut progress = Vec::with_capacity(all.len());
+    for goal in &all {
+        progress.push(progress_for(pool, goal, as_of).await?);
+    }
+    Ok(progress)
+}
+
+/// Blank means unlinked.
+fn link(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Check a goal's fields and its linked account before saving.
+async fn check_goal(pool: &PgPool, goal: &Goal) -> Result<(), String> {
+    if goal.name.is_empty() {
+        return Err("name must not be empty".into());
+    }
+    goals::validate(
+        goal.target_amount,
+        goal.initial_amount,
+        goal.start_date,
+        goal.target_date,
+    )?;
+    if goal.account.is_some() && goal.tag.is_some() {
+        return Err("link an account or a tag, not both".into());
+    }
+    if let Some(account) = &goal.account {
+        let account_type: Option<(String,)> =
+            sqlx::query_as("SELECT account_type FROM cards WHERE code = $1")
+                .bind(account)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        match account_type {
+            None => return Err(format!("Unknown account: {account}")),
+            Some((t,)) if t == "credit" => {
+                return Err("link a checking, savings or cash account".into())
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+async fn list_goals(
+    State(pool): State<PgPool>,
+    Query(params): Query<GoalQuery>,
+) -> Json<serde_json::Value> {
+    match progress_as_of(&pool, params.as_of.unwrap_or_else(today)).await {
+        Ok(progress) => Json(serde_json::json!({ "data": progress })),
+        Err(e) => {
+            tracing::error!("Failed to compute goal progress: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn get_goal(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<GoalQuery>,
+) -> Json<serde_json::Value> {
+    let goal = match fetch_goal(&pool, id).await {
+        Ok(Some(goal)) => goal,
+        Ok(None) => return Json(serde_json::json!({ "error": "Goal not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch goal {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+    match progress_for(&pool, &goal, params.as_of.unwrap_or_else(today)).await {
+        Ok(progress) => Json(serde_json::json!({ "data": progress })),
+        Err(e) => {
+            tracing::error!("Failed to compute progress for goal {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn create_goal(
+    State(pool): State<PgPool>,
+    Json(body): Json<NewGoal>,
+) -> Json<serde_json::Value> {
+    let now = chrono::Utc::now();
+    let goal = Goal {
+        id: Uuid::nil(),
+        name: body.name.trim().to_string(),
+        target_amount: body.target_amount,
+        target_date: body.target_date,
+        start_date: body.start_date.unwrap_or_else(today),
+        initial_amount: body.initial_amount.unwrap_or(0.0),
+        account: link(body.account),
+        tag: link(body.tag),
+        created_at: now,
+        updated_at: now,
+    };
+    if let Err(e) = check_goal(&pool, &goal).await {
+        return Json(serde_json::json!({ "error": e }));
+    }
+
+    let result: Result<Goal, _> = sqlx::query_as(&format!(
+        "INSERT INTO goals (name, target_amount, target_date, start_date, initial_amount, account, tag) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {GOAL_COLUMNS}"
+    ))
+    .bind(&goal.name)
+    .bind(goal.target_amount)
+    .bind(goal.target_date)
+    .bind(goal.start_date)
+    .bind(goal.initial_amount)
+    .bind(&goal.account)
+    .bind(&goal.tag)
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(goal) => Json(serde_json::json!({ "data": goal })),
+        Err(e) => {
+            tracing::error!("Failed to create goal: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn update_goal(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateGoal>,
+) -> Json<serde_json::Value> {
+    let mut goal = match fetch_goal(&pool, id).await {
+        Ok(Some(goal)) => goal,
+        Ok(None) => return Json(serde_json::json!({ "error": "Goal not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch goal {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+    if let Some(name) = body.name {
+        goal.name = name.trim().to_string();
+    }
+    goal.target_amount = body.target_amount.unwrap_or(goal.target_amount);
+    goal.target_date = body.target_date.unwrap_or(goal.target_date);
+    goal.start_date = body.start_date.unwrap_or(goal.start_date);
+    goal.initial_amount = body.initial_amount.unwrap_or(goal.initial_amount);
+    if body.account.is_some() {
+        goal.account = link(body.account);
+    }
+    if body.tag.is_some() {
+        goal.tag = link(body.tag);
+    }
+    if let Err(e) = check_goal(&pool, &goal).await {
+        return Json(serde_json::json!({ "error": e }));
+    }
+
+    let result: Result<Goal, _> = sqlx::query_as(&format!(
+        "UPDATE goals SET name = $1, target_amount = $2, target_date = $3, start_date = $4, \
+         initial_amount = $5, account = $6, tag = $7, updated_at = NOW() \
+         WHERE id = $8 RETURNING {GOAL_COLUMNS}"
+    ))
+    .bind(&goal.name)
+    .bind(goal.target_amount)
+    .bind(goal.target_date)
+    .bind(goal.start_date)
+    .bind(goal.initial_amount)
+    .bind(&goal.account)
+    .bind(&goal.tag)
+    .bind(id)
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(goal) => Json(serde_json::json!({ "data": goal })),
+        Err(e) => {
+            tracing::error!("Failed to update goal {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn delete_goal(State(pool): State<PgPool>, Path(id): Path<Uuid>) -> Json<serde_json::Value> {
+    let result = sqlx::query("DELETE FROM goals WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => Json(serde_json::json!({ "data": "Goal deleted" })),
+        Ok(_) => Json(serde_json::json!({ "error": "Goal not found" })),
+        Err(e) => {
+            tracing::error!("Failed to delete goal {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Manual entries and linked transactions, newest first.
+async fn list_contributions(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    let goal = match fetch_goal(&pool, id).await {
+        Ok(Some(goal)) => goal,
+        Ok(None) => return Json(serde_json::json!({ "error": "Goal not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch goal {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+    match contributions(&pool, &goal).await {
+        Ok(rows) => Json(serde_json::json!({ "data": rows })),
+        Err(e) => {
+            tracing::error!("Failed to list contributions for goal {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn add_contribution(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<NewGoalContribution>,
+) -> Json<serde_json::Value> {
+    if !body.amount.is_finite() || body.amount.abs() < 0.005 {
+        return Json(serde_json::json!({ "error": "amount must not be zero" }));
+    }
+
+    let result: Result<GoalContribution, _> = sqlx::query_as(
+        "INSERT INTO goal_contributions (goal_id, date, amount, note) \
+         SELECT id, $2, $3, $4 FROM goals WHERE id = $1 \
+         RETURNING id, 'manual' AS source, date, amount::float8 AS amount, note",
+    )
+    .bind(id)
+    .bind(body.date.unwrap_or_else(today))
+    .bind(body.amount)
+    .bind(
+        body.note
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty()),
+    )
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(row) => Json(serde_json::json!({ "data": row })),
+        Err(sqlx::Error::RowNotFound) => Json(serde_json::json!({ "error": "Goal not found" })),
+        Err(e) => {
+            tracing::error!("Failed to add contribution to goal {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn delete_contribution(
+    State(pool): State<PgPool>,
+    Path((id, contribution_id)): Path<(Uuid, Uuid)>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query("DELETE FROM goal_contributions WHERE id = $1 AND goal_id = $2")
+        .bind(contribution_id)
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => {
+            Json(serde_json::json!({ "data": "Contribution deleted" }))
+        }
+        Ok(_) => Json(serde_json::json!({ "error": "Contribution not found" })),
+        Err(e) => {
+            tracing::error!("Failed to delete contribution {contribution_id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
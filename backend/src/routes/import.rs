@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::{delete, get},
+    routing::{delete, get, post},
     Json, Router,
 };
 use chrono::{Datelike, NaiveDate};
@@ -10,7 +10,22 @@ use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::models::analytics::*;
+use crate::models::category::CategoryTotal;
 use crate::models::import::ImportRecord;
+use crate::models::insight::StoredInsight;
+use crate::routes::analytics::load_thresholds;
+use crate::routes::stats_filter::StatsFilter;
+use crate::routes::budget;
+use crate::routes::categories::fetch_categories;
+use crate::routes::goals;
+use crate::routes::suspicious;
+use crate::routes::insights::load_enabled_generators;
+use crate::services::insight_generators::{self, InsightContext};
+use crate::services::{categories, forecast, insights, subscriptions};
+use crate::services::analytics::{
+    self, days_in_month, linear_projection, CategoryBaseline, CategoryMonth, Charge, MonthCharge,
+    SmallPurchaseStats, Thresholds,
+};
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
@@ -23,48 +38,180 @@ pub fn routes() -> Router<PgPool> {
         .route("/stats/recurring", get(get_recurring))
         .route("/stats/anomalies", get(get_anomalies))
         .route("/stats/forecast", get(get_forecast))
+        .route("/stats/forecast/horizon", get(get_horizon))
+        .route("/stats/forecast/scenario", post(post_scenario))
         .route("/stats/habits", get(get_habits))
         .route("/stats/daily", get(get_daily))
         .route("/stats/category/:category", get(get_category_deep_dive))
         .route("/stats/insights", get(get_insights))
+        .route("/stats/upcoming", get(get_upcoming))
+        .route("/stats/cashflow", get(get_cashflow))
 }
 
 // ── Helpers ──
 
-fn days_in_month(year: i32, month: u32) -> u32 {
-    if month == 12 {
-        NaiveDate::from_ymd_opt(year + 1, 1, 1)
-    } else {
-        NaiveDate::from_ymd_opt(year, month + 1, 1)
-    }
-    .unwrap()
-    .signed_duration_since(NaiveDate::from_ymd_opt(year, month, 1).unwrap())
-    .num_days() as u32
+/// Transactions in the filter left out of the totals because there's no rate from their
+/// currency into the home currency.
+async fn count_unconverted(pool: &PgPool, filter: &StatsFilter) -> i64 {
+    filter
+        .bind(sqlx::query_as::<_, (i64,)>(&filter.sql_from(
+            "transactions_home",
+            "SELECT COUNT(*)::bigint FROM spending WHERE amount IS NULL AND NOT is_transfer",
+        )))
+        .fetch_one(pool)
+        .await
+        .map_or(0, |(count,)| count)
 }
 
-fn linear_projection(spent: f64, days_elapsed: u32, days_in_month: u32) -> f64 {
-    if days_elapsed == 0 {
-        return 0.0;
-    }
-    (spent / days_elapsed as f64) * days_in_month as f64
+/// Monthly income vs expenses for the `months` months up to and including the filter's
+/// reference month.
+async fn fetch_cashflow(pool: &PgPool, filter: &StatsFilter, months: i32) -> Vec<CashflowMonth> {
+    let rows: Vec<(String, f64, f64)> = filter.bind(sqlx::query_as(&filter.sql_from(
+        "transactions_home",
+        "SELECT to_char(date, 'YYYY-MM') as month, \
+           COALESCE(-SUM(amount::float8) FILTER (WHERE classification = 'income'), 0), \
+           COALESCE(SUM(amount::float8) FILTER (WHERE classification = 'expense'), 0) \
+         FROM spending \
+         WHERE NOT is_transfer \
+           AND date >= (date_trunc('month', as_of) - make_interval(months => $8))::date \
+           AND date < (date_trunc('month', as_of) + interval '1 month')::date \
+         GROUP BY to_char(date, 'YYYY-MM') ORDER BY month",
+    )))
+    .bind(months - 1)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    rows.into_iter()
+        .map(|(month, income, expenses)| CashflowMonth {
+            month,
+            income,
+            expenses,
+            net: income - expenses,
+            savings_rate: analytics::savings_rate(income, expenses),
+        })
+        .collect()
 }
 
-fn ewma(monthly_totals: &[f64], alpha: f64) -> f64 {
-    if monthly_totals.is_empty() {
-        return 0.0;
-    }
-    let mut result = monthly_totals[0];
-    for &total in &monthly_totals[1..] {
-        result = alpha * total + (1.0 - alpha) * result;
+/// Every charge in scope, for recurring detection.
+async fn fetch_charges(pool: &PgPool, filter: &StatsFilter) -> Vec<Charge> {
+    filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(merchant_normalized, description) as merchant, date, amount::float8 as amount, card \
+         FROM spending WHERE amount > 0 \
+         ORDER BY merchant, date",
+    )))
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+}
+
+/// Detected recurring series with the subscription registry's ignore/cancel decisions applied.
+async fn detect_registered(
+    pool: &PgPool,
+    charges: &[Charge],
+    as_of: NaiveDate,
+    t: &Thresholds,
+) -> Vec<RecurringTransaction> {
+    let detected = analytics::detect_recurring(charges, as_of, t);
+    match subscriptions::list_subscriptions(pool, None).await {
+        Ok(subs) => subscriptions::apply_registry(detected, &subs),
+        Err(e) => {
+            tracing::error!("Failed to load subscriptions: {e}");
+            detected
+        }
     }
-    result
+}
+
+/// Baselines from the completed months before the reference month; month-to-date totals
+/// run through the reference date's day of the month (the whole month when that's the
+/// last day).
+async fn fetch_category_baselines(pool: &PgPool, filter: &StatsFilter) -> Vec<CategoryBaseline> {
+    let rows: Vec<CategoryMonth> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, date_trunc('month', date)::date as month, \
+           SUM(amount::float8) as total, \
+           COALESCE(SUM(amount::float8) FILTER ( \
+             WHERE EXTRACT(DAY FROM date) <= EXTRACT(DAY FROM as_of) \
+               OR as_of = (date_trunc('month', as_of) + interval '1 month - 1 day')::date \
+           ), 0) as to_date \
+         FROM spending WHERE date < date_trunc('month', as_of)::date \
+         GROUP BY 1, 2",
+    )))
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+    analytics::category_baselines(&rows, forecast::month_start(filter.as_of()))
+}
+
+/// Spending per category in the reference month.
+async fn fetch_current_by_category(pool: &PgPool, filter: &StatsFilter) -> HashMap<String, f64> {
+    let rows: Vec<(String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, COALESCE(SUM(amount::float8), 0) as total \
+         FROM spending WHERE date >= date_trunc('month', as_of)::date \
+         GROUP BY category",
+    )))
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+    rows.into_iter().collect()
+}
+
+async fn fetch_small_purchases(pool: &PgPool, filter: &StatsFilter, t: &Thresholds) -> SmallPurchaseStats {
+    filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT \
+           COUNT(*)::int as total_count, \
+           COUNT(*) FILTER (WHERE amount::float8 < $8)::int as small_count, \
+           COALESCE(SUM(amount::float8) FILTER (WHERE amount::float8 < $8), 0) as small_total, \
+           COALESCE(AVG(amount::float8) FILTER (WHERE amount::float8 < $8), 0) as avg_small \
+         FROM spending \
+         WHERE date >= as_of - $9",
+    )))
+    .bind(t.impulse_amount)
+    .bind(t.habit_window_days)
+    .fetch_one(pool)
+    .await
+    .unwrap_or_default()
+}
+
+/// (category, month, total) for the six months before the reference month and the month itself.
+async fn fetch_category_months(pool: &PgPool, filter: &StatsFilter) -> Vec<(String, String, f64)> {
+    filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, to_char(date, 'YYYY-MM') as month, SUM(amount::float8) as total \
+         FROM spending \
+         WHERE date >= (date_trunc('month', as_of) - interval '6 months')::date \
+         GROUP BY category, to_char(date, 'YYYY-MM') \
+         ORDER BY category, month",
+    )))
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+}
+
+/// Average daily spend on weekend days and on weekdays over the habit window.
+async fn fetch_weekend_weekday(pool: &PgPool, filter: &StatsFilter, t: &Thresholds) -> (f64, f64) {
+    filter.bind(sqlx::query_as(&filter.sql(
+        "WITH daily AS ( \
+           SELECT date, SUM(amount::float8) as day_total, EXTRACT(DOW FROM date)::int as dow \
+           FROM spending WHERE date >= as_of - $8 GROUP BY date \
+         ) \
+         SELECT \
+           COALESCE(AVG(day_total) FILTER (WHERE dow IN (0, 6)), 0), \
+           COALESCE(AVG(day_total) FILTER (WHERE dow NOT IN (0, 6)), 0) \
+         FROM daily",
+    )))
+    .bind(t.habit_window_days)
+    .fetch_one(pool)
+    .await
+    .unwrap_or((0.0, 0.0))
 }
 
 // ── Import History ──
 
 async fn get_import_history(State(pool): State<PgPool>) -> Json<serde_json::Value> {
     let records: Vec<ImportRecord> = match sqlx::query_as(
-        "SELECT * FROM import_history ORDER BY imported_at DESC",
+        "SELECT id, imported_at, card, file_name, transaction_count, duplicate_count, skipped_user_count, \
+           statement_start, statement_end, opening_balance::float8 as opening_balance, \
+           closing_balance::float8 as closing_balance, reconciliation \
+         FROM import_history ORDER BY imported_at DESC",
     )
     .fetch_all(&pool)
     .await
@@ -119,65 +266,84 @@ async fn delete_import(
 
 // ── Enhanced Summary ──
 
-async fn get_summary(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let total: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions",
-    )
+async fn get_summary(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let total: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
-    let count: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*)::bigint FROM transactions",
-    )
+    let count: (i64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COUNT(*)::bigint FROM spending",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0,));
 
-    let by_card: Vec<(String, f64, i64)> = sqlx::query_as(
+    let by_card: Vec<(String, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT card, COALESCE(SUM(amount::float8), 0), COUNT(*)::bigint \
-         FROM transactions GROUP BY card ORDER BY SUM(amount) DESC",
-    )
+         FROM spending GROUP BY card ORDER BY SUM(amount) DESC",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let by_category: Vec<(String, f64, i64)> = sqlx::query_as(
+    let by_category: Vec<(String, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT category, COALESCE(SUM(amount::float8), 0), COUNT(*)::bigint \
-         FROM transactions GROUP BY category ORDER BY SUM(amount) DESC",
-    )
+         FROM spending GROUP BY category ORDER BY SUM(amount) DESC",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let this_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date",
-    )
+    // Subcategories roll up into their top-level category
+    let all_categories = fetch_categories(&pool).await.unwrap_or_default();
+    let leaves: Vec<CategoryTotal> = by_category
+        .iter()
+        .map(|(category, total, count)| CategoryTotal {
+            category: category.clone(),
+            total: *total,
+            count: *count,
+            avg_amount: if *count > 0 { *total / *count as f64 } else { 0.0 },
+        })
+        .collect();
+    let by_parent = categories::roll_up(&leaves, &all_categories);
+    let (essential, discretionary, unclassified) = categories::by_kind(&leaves, &all_categories);
+
+    let this_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= date_trunc('month', as_of)::date",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
-    let last_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= (date_trunc('month', CURRENT_DATE) - interval '1 month')::date \
-         AND date < date_trunc('month', CURRENT_DATE)::date",
-    )
+    let last_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= (date_trunc('month', as_of) - interval '1 month')::date \
+         AND date < date_trunc('month', as_of)::date",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
     // New: average monthly spending
-    let avg_monthly: (f64,) = sqlx::query_as(
+    let avg_monthly: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT COALESCE(SUM(amount::float8), 0) / \
          GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) \
-         FROM transactions",
-    )
+         FROM spending",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
-    let now = chrono::Local::now().naive_local().date();
+    let unconverted_count = count_unconverted(&pool, &filter).await;
+
+    let now = filter.as_of();
     let d_elapsed = now.day();
     let d_in_month = days_in_month(now.year(), now.month());
 
@@ -211,22 +377,41 @@ async fn get_summary(State(pool): State<PgPool>) -> Json<serde_json::Value> {
             "vs_avg_pct": vs_avg_pct,
             "daily_rate": daily_rate,
             "projected_month_total": projected_month_total,
+            "unconverted_count": unconverted_count,
             "by_card": by_card.iter().map(|(card, total, count)| {
                 let avg = if *count > 0 { *total / *count as f64 } else { 0.0 };
                 serde_json::json!({ "card": card, "total": total, "count": count, "avg_amount": avg })
             }).collect::<Vec<_>>(),
-            "by_category": by_category.iter().map(|(cat, total, count)| {
-                let avg = if *count > 0 { *total / *count as f64 } else { 0.0 };
-                serde_json::json!({ "category": cat, "total": total, "count": count, "avg_amount": avg })
-            }).collect::<Vec<_>>()
+            "by_category": leaves.iter().map(|leaf| {
+                let parent = all_categories
+                    .iter()
+                    .find(|c| c.name == leaf.category)
+                    .and_then(|c| c.parent.clone());
+                serde_json::json!({
+                    "category": leaf.category, "parent": parent, "total": leaf.total,
+                    "count": leaf.count, "avg_amount": leaf.avg_amount
+                })
+            }).collect::<Vec<_>>(),
+            "by_parent": by_parent,
+            "by_kind": {
+                "essential": essential,
+                "discretionary": discretionary,
+                "unclassified": unclassified
+            }
         }
     }))
 }
 
 // ── Enhanced Monthly ──
 
-async fn get_monthly(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let monthly: Vec<(String, f64, i64, Option<f64>, Option<f64>)> = sqlx::query_as(
+/// (month, total, count, prev_total, rolling_3mo_avg)
+type MonthlyRow = (String, f64, i64, Option<f64>, Option<f64>);
+
+async fn get_monthly(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let monthly: Vec<MonthlyRow> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT \
            to_char(date, 'YYYY-MM') as month, \
            COALESCE(SUM(amount::float8), 0) as total, \
@@ -236,26 +421,26 @@ async fn get_monthly(State(pool): State<PgPool>) -> Json<serde_json::Value> {
              ORDER BY to_char(date, 'YYYY-MM') \
              ROWS BETWEEN 2 PRECEDING AND CURRENT ROW \
            )::float8 as rolling_3mo_avg \
-         FROM transactions \
+         FROM spending \
          GROUP BY to_char(date, 'YYYY-MM') \
          ORDER BY month",
-    )
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let monthly_by_card: Vec<(String, String, f64)> = sqlx::query_as(
+    let monthly_by_card: Vec<(String, String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT to_char(date, 'YYYY-MM') as month, card, COALESCE(SUM(amount::float8), 0) \
-         FROM transactions GROUP BY to_char(date, 'YYYY-MM'), card ORDER BY month",
-    )
+         FROM spending GROUP BY to_char(date, 'YYYY-MM'), card ORDER BY month",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let monthly_by_category: Vec<(String, String, f64)> = sqlx::query_as(
+    let monthly_by_category: Vec<(String, String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT to_char(date, 'YYYY-MM') as month, category, COALESCE(SUM(amount::float8), 0) \
-         FROM transactions GROUP BY to_char(date, 'YYYY-MM'), category ORDER BY month",
-    )
+         FROM spending GROUP BY to_char(date, 'YYYY-MM'), category ORDER BY month",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
@@ -287,8 +472,11 @@ async fn get_monthly(State(pool): State<PgPool>) -> Json<serde_json::Value> {
 
 // ── Enhanced Merchants ──
 
-async fn get_merchants(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let merchants: Vec<(String, f64, i64, f64, NaiveDate, NaiveDate, i32)> = sqlx::query_as(
+async fn get_merchants(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let merchants: Vec<(String, f64, i64, f64, NaiveDate, NaiveDate, i32)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT \
            COALESCE(merchant_normalized, description) as merchant, \
            COALESCE(SUM(amount::float8), 0) as total, \
@@ -297,11 +485,11 @@ async fn get_merchants(State(pool): State<PgPool>) -> Json<serde_json::Value> {
            MIN(date) as first_seen, \
            MAX(date) as last_seen, \
            COUNT(DISTINCT to_char(date, 'YYYY-MM'))::int as active_months \
-         FROM transactions \
+         FROM spending \
          GROUP BY COALESCE(merchant_normalized, description) \
          ORDER BY SUM(amount) DESC \
          LIMIT 20",
-    )
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
@@ -325,19 +513,22 @@ async fn get_merchants(State(pool): State<PgPool>) -> Json<serde_json::Value> {
 
 // ── Patterns (unchanged) ──
 
-async fn get_patterns(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let day_of_week: Vec<(f64, f64, i64)> = sqlx::query_as(
+async fn get_patterns(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let day_of_week: Vec<(f64, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT EXTRACT(DOW FROM date)::float8, COALESCE(SUM(amount::float8), 0), COUNT(*)::bigint \
-         FROM transactions GROUP BY EXTRACT(DOW FROM date) ORDER BY EXTRACT(DOW FROM date)",
-    )
+         FROM spending GROUP BY EXTRACT(DOW FROM date) ORDER BY EXTRACT(DOW FROM date)",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let day_of_month: Vec<(f64, f64, i64)> = sqlx::query_as(
+    let day_of_month: Vec<(f64, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT EXTRACT(DAY FROM date)::float8, COALESCE(SUM(amount::float8), 0), COUNT(*)::bigint \
-         FROM transactions GROUP BY EXTRACT(DAY FROM date) ORDER BY EXTRACT(DAY FROM date)",
-    )
+         FROM spending GROUP BY EXTRACT(DAY FROM date) ORDER BY EXTRACT(DAY FROM date)",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
@@ -361,71 +552,51 @@ async fn get_patterns(State(pool): State<PgPool>) -> Json<serde_json::Value> {
     }))
 }
 
-// ── Recurring Detection ──
+// ── Cashflow ──
 
-async fn get_recurring(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let rows: Vec<(String, i32, i32, f64, f64, NaiveDate, NaiveDate)> = sqlx::query_as(
-        "SELECT \
-           COALESCE(merchant_normalized, description) as merchant, \
-           COUNT(*)::int as total_count, \
-           COUNT(DISTINCT to_char(date, 'YYYY-MM'))::int as active_months, \
-           COALESCE(AVG(amount::float8), 0) as avg_amount, \
-           COALESCE(STDDEV(amount::float8), 0) as amount_stddev, \
-           MIN(date) as first_seen, \
-           MAX(date) as last_seen \
-         FROM transactions \
-         GROUP BY COALESCE(merchant_normalized, description) \
-         HAVING COUNT(DISTINCT to_char(date, 'YYYY-MM')) >= 3 \
-         ORDER BY AVG(amount) DESC",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+#[derive(Deserialize)]
+pub struct CashflowQuery {
+    pub months: Option<i32>,
+}
 
-    let today = chrono::Local::now().naive_local().date();
-    let mut recurring = Vec::new();
+async fn get_cashflow(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Query(params): Query<CashflowQuery>,
+) -> Json<serde_json::Value> {
+    let months = fetch_cashflow(&pool, &filter, params.months.unwrap_or(12).clamp(1, 120)).await;
+    let current_month = filter.as_of().format("%Y-%m").to_string();
 
-    for (merchant, total_count, active_months, avg_amount, stddev, first_seen, last_seen) in &rows {
-        // Filter: stddev < 20% of avg and roughly monthly frequency
-        if avg_amount.abs() < 0.01 {
-            continue;
-        }
-        if *stddev > *avg_amount * 0.2 {
-            continue;
-        }
-        let freq = *total_count as f64 / *active_months as f64;
-        if freq < 0.7 || freq > 1.5 {
-            continue;
-        }
+    let total_income: f64 = months.iter().map(|m| m.income).sum();
+    let total_expenses: f64 = months.iter().map(|m| m.expenses).sum();
+    let avg_savings_rate = analytics::completed_savings_rate(&months, &current_month);
 
-        let last_gap_days = today.signed_duration_since(*last_seen).num_days();
-        let status = if last_gap_days <= 45 { "active" } else { "inactive" };
-        let potentially_forgotten = status == "inactive" && *active_months >= 3;
+    Json(serde_json::json!({
+        "data": CashflowData {
+            months,
+            total_income,
+            total_expenses,
+            net: total_income - total_expenses,
+            avg_savings_rate,
+            unconverted_count: count_unconverted(&pool, &filter).await,
+        }
+    }))
+}
 
-        let frequency = if freq > 1.3 {
-            "biweekly".to_string()
-        } else {
-            "monthly".to_string()
-        };
+// ── Recurring Detection ──
 
-        recurring.push(RecurringTransaction {
-            merchant: merchant.clone(),
-            avg_amount: *avg_amount,
-            frequency,
-            active_months: *active_months,
-            first_seen: *first_seen,
-            last_seen: *last_seen,
-            estimated_annual: *avg_amount * 12.0,
-            status: status.to_string(),
-            last_gap_days,
-            potentially_forgotten,
-        });
-    }
+async fn get_recurring(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    let charges = fetch_charges(&pool, &filter).await;
+    let recurring = detect_registered(&pool, &charges, filter.as_of(), &t).await;
 
     let total_monthly: f64 = recurring
         .iter()
         .filter(|r| r.status == "active")
-        .map(|r| r.avg_amount)
+        .map(|r| r.monthly_amount)
         .sum();
 
     Json(serde_json::json!({
@@ -437,188 +608,166 @@ async fn get_recurring(State(pool): State<PgPool>) -> Json<serde_json::Value> {
     }))
 }
 
-// ── Anomaly Detection ──
-
-async fn get_anomalies(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    // Category baselines
-    let baselines: Vec<(String, f64, f64, i32)> = sqlx::query_as(
-        "WITH monthly_cat AS ( \
-           SELECT category, to_char(date, 'YYYY-MM') as month, SUM(amount::float8) as total \
-           FROM transactions GROUP BY category, to_char(date, 'YYYY-MM') \
-         ) \
-         SELECT category, AVG(total)::float8 as avg_monthly, \
-           COALESCE(STDDEV(total), 0)::float8 as stddev_monthly, \
-           COUNT(*)::int as month_count \
-         FROM monthly_cat GROUP BY category HAVING COUNT(*) >= 2",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+// ── Upcoming Charges ──
 
-    // Current month per category
-    let current: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT category, COALESCE(SUM(amount::float8), 0) as total \
-         FROM transactions WHERE date >= date_trunc('month', CURRENT_DATE)::date \
-         GROUP BY category",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+#[derive(Deserialize)]
+pub struct UpcomingQuery {
+    pub days: Option<i64>,
+}
 
-    let current_map: HashMap<String, f64> = current.into_iter().collect();
+async fn get_upcoming(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Query(params): Query<UpcomingQuery>,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    let from = filter.as_of();
+    let to = from + chrono::Duration::days(params.days.unwrap_or(30).clamp(1, 365));
+    let month_end = NaiveDate::from_ymd_opt(from.year(), from.month(), days_in_month(from.year(), from.month()))
+        .unwrap_or(from);
+
+    let charges = fetch_charges(&pool, &filter).await;
+    let recurring = detect_registered(&pool, &charges, from, &t).await;
+    let upcoming = analytics::upcoming_charges(&recurring, from, to);
+
+    let account_types: HashMap<String, String> =
+        sqlx::query_as("SELECT code, account_type FROM cards")
+            .fetch_all(&pool)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    let by_card = analytics::card_impacts(&upcoming, month_end, &account_types);
 
-    let mut category_anomalies = Vec::new();
-    for (category, avg, stddev, _month_count) in &baselines {
-        if *stddev < 0.01 {
-            continue;
-        }
-        let current_val = current_map.get(category).copied().unwrap_or(0.0);
-        let z = (current_val - avg) / stddev;
-        if z > 1.5 {
-            let severity = if z > 3.0 {
-                "critical"
-            } else if z > 2.0 {
-                "high"
-            } else {
-                "elevated"
-            };
-            let pct_above = (current_val - avg) / avg * 100.0;
-            category_anomalies.push(CategoryAnomaly {
-                category: category.clone(),
-                current_month: current_val,
-                avg_monthly: *avg,
-                stddev: *stddev,
-                z_score: z,
-                severity: severity.to_string(),
-                pct_above_avg: pct_above,
-                message: format!(
-                    "{} spending is {:.0}% above average this month",
-                    category, pct_above
-                ),
-            });
+    Json(serde_json::json!({
+        "data": UpcomingData {
+            from,
+            to,
+            month_end,
+            total: upcoming.iter().map(|c| c.amount).sum(),
+            due_by_month_end: upcoming.iter().filter(|c| c.date <= month_end).map(|c| c.amount).sum(),
+            charges: upcoming,
+            by_card,
         }
-    }
+    }))
+}
 
-    // Transaction anomalies
-    let txn_anomalies: Vec<(uuid::Uuid, NaiveDate, String, f64, String, f64)> = sqlx::query_as(
-        "WITH cat_avg AS ( \
-           SELECT category, AVG(amount::float8) as avg_amount FROM transactions GROUP BY category \
-         ) \
-         SELECT t.id, t.date, t.description, t.amount::float8 as amount, \
-           t.category, ca.avg_amount as category_avg \
-         FROM transactions t \
-         JOIN cat_avg ca ON t.category = ca.category \
-         WHERE t.date >= date_trunc('month', CURRENT_DATE)::date \
-           AND t.amount::float8 > ca.avg_amount * 2 \
-         ORDER BY t.amount::float8 / ca.avg_amount DESC \
-         LIMIT 10",
-    )
+// ── Anomaly Detection ──
+
+async fn get_anomalies(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    let baselines = fetch_category_baselines(&pool, &filter).await;
+    let current_map = fetch_current_by_category(&pool, &filter).await;
+    let category_anomalies = analytics::category_anomalies(&baselines, &current_map, &t);
+
+    // Transactions and merchants against their own history before this month
+    let history: Vec<(String, String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, COALESCE(merchant_normalized, description), amount::float8 \
+         FROM spending WHERE date < date_trunc('month', as_of)::date",
+    )))
+    .fetch_all(&pool)
+    .await
+    .unwrap_or_default();
+    let by_category = analytics::amount_stats(history.iter().map(|(c, _, a)| (c.as_str(), *a)));
+    let by_merchant = analytics::amount_stats(history.iter().map(|(_, m, a)| (m.as_str(), *a)));
+
+    let month_charges: Vec<MonthCharge> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT id, date, description, COALESCE(merchant_normalized, description) as merchant, \
+           category, amount::float8 as amount \
+         FROM spending \
+         WHERE date >= date_trunc('month', as_of)::date AND date <= as_of AND amount > 0",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let transaction_anomalies: Vec<TransactionAnomaly> = txn_anomalies
-        .into_iter()
-        .map(|(id, date, description, amount, category, cat_avg)| {
-            let times = if cat_avg > 0.0 { amount / cat_avg } else { 0.0 };
-            TransactionAnomaly {
-                id,
-                date,
-                description: description.clone(),
-                amount,
-                category: category.clone(),
-                category_avg: cat_avg,
-                times_avg: times,
-                message: format!(
-                    "{} is {:.1}x the average {} transaction",
-                    description, times, category
-                ),
-            }
-        })
-        .collect();
+    let transaction_anomalies = analytics::transaction_anomalies(&month_charges, &by_category, &t);
+    let merchant_anomalies = analytics::merchant_anomalies(&month_charges, &by_merchant, &t);
 
     Json(serde_json::json!({
         "data": AnomaliesData {
             category_anomalies,
             transaction_anomalies,
+            merchant_anomalies,
         }
     }))
 }
 
 // ── Spending Forecast ──
 
-async fn get_forecast(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let now = chrono::Local::now().naive_local().date();
+async fn get_forecast(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    let now = filter.as_of();
     let d_elapsed = now.day();
     let d_in_month = days_in_month(now.year(), now.month());
     let d_remaining = d_in_month - d_elapsed;
 
     // Current month spent
-    let this_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date",
-    )
+    let this_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= date_trunc('month', as_of)::date",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
-    // Historical monthly totals for EWMA
-    let monthly_totals: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT to_char(date, 'YYYY-MM') as month, COALESCE(SUM(amount::float8), 0) \
-         FROM transactions GROUP BY to_char(date, 'YYYY-MM') ORDER BY month",
-    )
+    // Daily history drives every method and the backtest that weighs them
+    let daily_rows: Vec<(NaiveDate, f64)> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT date, SUM(amount::float8) FROM spending \
+         WHERE date <= as_of GROUP BY date ORDER BY date",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
+    let daily: forecast::DailyTotals = daily_rows.into_iter().collect();
 
-    let totals: Vec<f64> = monthly_totals.iter().map(|(_, t)| *t).collect();
+    let month = forecast::month_start(now);
+    let methods = forecast::project_month(&daily, month, d_elapsed, t.ewma_alpha);
+    let samples = forecast::backtest(&daily, now, t.ewma_alpha);
+    let accuracy = forecast::method_accuracy(&samples);
+    let weights = forecast::method_weights(&methods, &accuracy);
 
-    // Day-of-month historical averages for day-weighted projection
-    let dom_avgs: Vec<(f64, f64)> = sqlx::query_as(
-        "SELECT EXTRACT(DAY FROM date)::float8 as dom, AVG(amount::float8) as avg_daily \
-         FROM ( \
-           SELECT date, SUM(amount::float8) as amount FROM transactions GROUP BY date \
-         ) daily \
-         GROUP BY EXTRACT(DAY FROM date) ORDER BY dom",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+    let linear = linear_projection(this_month.0, d_elapsed, d_in_month);
+    let day_weighted = methods[1].unwrap_or(this_month.0);
+    let ewma_val = methods[2].unwrap_or(0.0);
+    let recommended = forecast::blend(&methods, &weights).unwrap_or(linear);
 
-    let dom_map: HashMap<u32, f64> = dom_avgs
-        .iter()
-        .map(|(d, a)| (*d as u32, *a))
+    let completed: Vec<f64> = forecast::completed_months(&daily, month)
+        .into_iter()
+        .map(|(_, total)| total)
         .collect();
-
-    // Day-weighted projection
-    let overall_daily_avg: f64 = if dom_avgs.is_empty() {
-        0.0
-    } else {
-        dom_avgs.iter().map(|(_, a)| a).sum::<f64>() / dom_avgs.len() as f64
+    let spread = forecast::relative_error(
+        &samples,
+        &weights,
+        &completed,
+        d_remaining as f64 / d_in_month as f64,
+    ) * forecast::INTERVAL_Z;
+    let interval = PredictionInterval {
+        lower: (recommended * (1.0 - spread)).max(this_month.0),
+        upper: recommended * (1.0 + spread),
+        confidence: 0.8,
     };
-    let mut day_weighted = this_month.0;
-    for day in (d_elapsed + 1)..=d_in_month {
-        day_weighted += dom_map.get(&day).copied().unwrap_or(overall_daily_avg);
-    }
-
-    let linear = linear_projection(this_month.0, d_elapsed, d_in_month);
-    let ewma_val = ewma(&totals, 0.3);
-    let recommended = (linear + day_weighted + ewma_val) / 3.0;
 
     // Last month & avg for comparison
-    let last_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= (date_trunc('month', CURRENT_DATE) - interval '1 month')::date \
-         AND date < date_trunc('month', CURRENT_DATE)::date",
-    )
+    let last_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= (date_trunc('month', as_of) - interval '1 month')::date \
+         AND date < date_trunc('month', as_of)::date",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
 
-    let avg_monthly: (f64,) = sqlx::query_as(
+    let avg_monthly: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT COALESCE(SUM(amount::float8), 0) / \
-         GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) FROM transactions",
-    )
+         GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) FROM spending",
+    )))
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0,));
@@ -644,30 +793,49 @@ async fn get_forecast(State(pool): State<PgPool>) -> Json<serde_json::Value> {
     };
 
     // Category forecasts
-    let cat_current: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT category, COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date GROUP BY category",
-    )
+    let cat_current: Vec<(String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= date_trunc('month', as_of)::date GROUP BY category",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
-    let cat_avg: Vec<(String, f64)> = sqlx::query_as(
+    let cat_avg: Vec<(String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT category, COALESCE(SUM(amount::float8), 0) / \
          GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) \
-         FROM transactions GROUP BY category",
-    )
+         FROM spending GROUP BY category",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
     let cat_avg_map: HashMap<String, f64> = cat_avg.into_iter().collect();
 
-    let category_forecasts: Vec<CategoryForecast> = cat_current
-        .iter()
+    // Completed months per category for the EWMA side of the blend
+    let cat_history: Vec<(String, NaiveDate, f64)> = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, date_trunc('month', date)::date, SUM(amount::float8) FROM spending \
+         WHERE date < date_trunc('month', as_of)::date GROUP BY 1, 2",
+    )))
+    .fetch_all(&pool)
+    .await
+    .unwrap_or_default();
+    let cat_history = forecast::by_category(&cat_history);
+
+    // Categories with history but nothing yet this month are still expected to show up
+    let mut cat_spent: HashMap<String, f64> = cat_history.keys().map(|c| (c.clone(), 0.0)).collect();
+    cat_spent.extend(cat_current);
+
+    let mut category_forecasts: Vec<CategoryForecast> = cat_spent
+        .into_iter()
         .map(|(cat, spent)| {
-            let projected = linear_projection(*spent, d_elapsed, d_in_month);
-            let avg = cat_avg_map.get(cat).copied().unwrap_or(0.0);
+            let history = cat_history
+                .get(&cat)
+                .map(|h| forecast::contiguous(h, month))
+                .unwrap_or_default();
+            let (linear, ewma, projected) =
+                forecast::category_projection(spent, &history, d_elapsed, d_in_month, t.ewma_alpha);
+            let avg = cat_avg_map.get(&cat).copied().unwrap_or(0.0);
             let vs_avg_pct = if avg > 0.0 { (projected - avg) / avg * 100.0 } else { 0.0 };
             let trend = if projected > avg * 1.1 {
                 "up"
@@ -677,15 +845,19 @@ async fn get_forecast(State(pool): State<PgPool>) -> Json<serde_json::Value> {
                 "stable"
             };
             CategoryForecast {
-                category: cat.clone(),
-                spent_so_far: *spent,
+                category: cat,
+                spent_so_far: spent,
                 projected,
+                linear,
+                ewma,
                 avg_monthly: avg,
                 vs_avg_pct,
                 trend: trend.to_string(),
             }
         })
+        .filter(|c| c.projected > 0.0)
         .collect();
+    category_forecasts.sort_by(|a, b| b.projected.total_cmp(&a.projected));
 
     let trajectory = if avg_monthly.0 > 0.0 {
         let ratio = recommended / avg_monthly.0;
@@ -702,6 +874,37 @@ async fn get_forecast(State(pool): State<PgPool>) -> Json<serde_json::Value> {
         "near_average"
     };
 
+    // Income-aware projection: paychecks are lumpy, so expect at least the recent average
+    let cashflow_months = fetch_cashflow(&pool, &filter, 4).await;
+    let current_key = now.format("%Y-%m").to_string();
+    let income_so_far = cashflow_months
+        .iter()
+        .find(|m| m.month == current_key)
+        .map(|m| m.income)
+        .unwrap_or(0.0);
+    let recent_income: Vec<f64> = cashflow_months
+        .iter()
+        .filter(|m| m.month < current_key && m.income > 0.0)
+        .map(|m| m.income)
+        .collect();
+    let cashflow = if income_so_far > 0.0 || !recent_income.is_empty() {
+        let avg_income = if recent_income.is_empty() {
+            0.0
+        } else {
+            recent_income.iter().sum::<f64>() / recent_income.len() as f64
+        };
+        let projected_income = income_so_far.max(avg_income);
+        Some(CashflowForecast {
+            income_so_far,
+            projected_income,
+            projected_expenses: recommended,
+            projected_net: projected_income - recommended,
+            projected_savings_rate: analytics::savings_rate(projected_income, recommended),
+        })
+    } else {
+        None
+    };
+
     Json(serde_json::json!({
         "data": ForecastData {
             current_month: CurrentMonthStatus {
@@ -714,247 +917,272 @@ async fn get_forecast(State(pool): State<PgPool>) -> Json<serde_json::Value> {
                 linear,
                 day_weighted,
                 ewma: ewma_val,
+                seasonal: methods[3],
                 recommended,
+                interval,
+                accuracy: forecast::accuracy_report(&accuracy, &weights),
             },
             vs_last_month,
             vs_average,
             category_forecasts,
             trajectory: trajectory.to_string(),
+            cashflow,
         }
     }))
 }
 
-// ── Bad Habits Detection ──
+// ── Forecast Horizon & Scenarios ──
 
-async fn get_habits(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    // Impulse spending
-    let impulse: (i32, i32, f64, f64) = sqlx::query_as(
-        "SELECT \
-           COUNT(*)::int as total_count, \
-           COUNT(*) FILTER (WHERE amount::float8 < 15)::int as small_count, \
-           COALESCE(SUM(amount::float8) FILTER (WHERE amount::float8 < 15), 0) as small_total, \
-           COALESCE(AVG(amount::float8) FILTER (WHERE amount::float8 < 15), 0) as avg_small \
-         FROM transactions \
-         WHERE date >= (CURRENT_DATE - interval '90 days')",
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or((0, 0, 0.0, 0.0));
-
-    let small_pct = if impulse.0 > 0 {
-        impulse.1 as f64 / impulse.0 as f64 * 100.0
-    } else {
-        0.0
-    };
-    let (imp_score, imp_label) = if small_pct > 50.0 {
-        (0.8, "high")
-    } else if small_pct > 35.0 {
-        (0.5, "moderate")
-    } else if small_pct > 20.0 {
-        (0.3, "low")
-    } else {
-        (0.1, "minimal")
-    };
-    let monthly_small_total = impulse.2 / 3.0;
-
-    let impulse_spending = ImpulseSpending {
-        score: imp_score,
-        label: imp_label.to_string(),
-        small_transaction_pct: small_pct,
-        avg_small_amount: impulse.3,
-        monthly_small_total,
-        message: format!(
-            "{:.0}% of transactions are under $15 (${:.0}/month in small purchases)",
-            small_pct, monthly_small_total
-        ),
-    };
+#[derive(Deserialize)]
+pub struct HorizonQuery {
+    pub months: Option<u32>,
+}
 
-    // Category creep
-    let cat_monthly: Vec<(String, String, f64)> = sqlx::query_as(
-        "SELECT category, to_char(date, 'YYYY-MM') as month, SUM(amount::float8) as total \
-         FROM transactions \
-         WHERE date >= (date_trunc('month', CURRENT_DATE) - interval '6 months')::date \
-         GROUP BY category, to_char(date, 'YYYY-MM') \
-         ORDER BY category, month",
-    )
-    .fetch_all(&pool)
+/// Every charge up to the reference date with its category.
+async fn fetch_category_charges(pool: &PgPool, filter: &StatsFilter) -> Vec<forecast::CategoryCharge> {
+    filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT category, COALESCE(merchant_normalized, description) as merchant, date, \
+           amount::float8 as amount \
+         FROM spending WHERE date <= as_of",
+    )))
+    .fetch_all(pool)
     .await
-    .unwrap_or_default();
+    .unwrap_or_default()
+}
 
-    let mut cat_map: HashMap<String, Vec<(String, f64)>> = HashMap::new();
-    for (cat, month, total) in &cat_monthly {
-        cat_map
-            .entry(cat.clone())
-            .or_default()
-            .push((month.clone(), *total));
-    }
+/// Horizon model for 1–12 months (default 6) after the reference month, with the
+/// recurring series it predicts from.
+pub(crate) async fn build_horizon(
+    pool: &PgPool,
+    filter: &StatsFilter,
+    months: Option<u32>,
+) -> (forecast::HorizonModel, Vec<RecurringTransaction>) {
+    let t = load_thresholds(pool).await;
+    let as_of = filter.as_of();
+    let charges = fetch_charges(pool, filter).await;
+    let recurring = detect_registered(pool, &charges, as_of, &t).await;
+    let rows = fetch_category_charges(pool, filter).await;
+    let model = forecast::horizon_model(
+        &rows,
+        &recurring,
+        as_of,
+        months.unwrap_or(6).clamp(1, 12),
+        t.ewma_alpha,
+        t.recurring_max_cv,
+    );
+    (model, recurring)
+}
 
-    let mut category_creep = Vec::new();
-    for (cat, months) in &cat_map {
-        if months.len() < 4 {
-            continue;
+async fn get_horizon(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Query(params): Query<HorizonQuery>,
+) -> Json<serde_json::Value> {
+    let (model, _) = build_horizon(&pool, &filter, params.months).await;
+    let months = forecast::project_horizon(&model, &[]);
+
+    Json(serde_json::json!({
+        "data": HorizonData {
+            total: months.iter().map(|m| m.total).sum(),
+            recurring_total: months.iter().map(|m| m.recurring).sum(),
+            confidence: 0.8,
+            months,
         }
-        let mid = months.len() / 2;
-        let prior_avg: f64 =
-            months[..mid].iter().map(|(_, t)| t).sum::<f64>() / mid as f64;
-        let recent_avg: f64 =
-            months[mid..].iter().map(|(_, t)| t).sum::<f64>() / (months.len() - mid) as f64;
-
-        if prior_avg < 1.0 {
-            continue;
+    }))
+}
+
+/// Turn a request adjustment into a model change and a label for it.
+async fn resolve_adjustment(
+    pool: &PgPool,
+    adjustment: &Adjustment,
+    model: &forecast::HorizonModel,
+    recurring: &[RecurringTransaction],
+) -> Result<(String, forecast::Change), String> {
+    match adjustment.kind.as_str() {
+        "category_pct" => {
+            let category = adjustment
+                .category
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .ok_or("category_pct needs a category")?;
+            let pct = adjustment
+                .pct
+                .filter(|p| p.is_finite() && *p >= -100.0)
+                .ok_or("category_pct needs a pct of at least -100")?;
+            let known = model.categories.contains_key(category)
+                || model.charges.iter().any(|c| c.category == category);
+            if !known {
+                return Err(format!("No spending history for category: {category}"));
+            }
+            Ok((
+                format!("{category} {pct:+}%"),
+                forecast::Change::Scale {
+                    category: category.to_string(),
+                    factor: 1.0 + pct / 100.0,
+                },
+            ))
         }
-        let change_pct = (recent_avg - prior_avg) / prior_avg * 100.0;
-        if change_pct.abs() > 15.0 {
-            let trend = if change_pct > 15.0 {
-                "increasing"
+        "cancel_subscription" => {
+            let (name, series): (String, Vec<usize>) = if let Some(id) = adjustment.subscription_id {
+                let sub = match subscriptions::get_subscription(pool, id).await {
+                    Ok(Some(sub)) => sub,
+                    Ok(None) => return Err("Subscription not found".to_string()),
+                    Err(e) => {
+                        tracing::error!("Failed to fetch subscription {id}: {e}");
+                        return Err(e.to_string());
+                    }
+                };
+                let matches = subscriptions::match_series(std::slice::from_ref(&sub), recurring);
+                let series = matches
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| **m == Some(0))
+                    .map(|(i, _)| i)
+                    .collect();
+                (sub.merchant, series)
+            } else if let Some(merchant) = adjustment.merchant.as_deref().map(str::trim) {
+                let series = recurring
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, r)| r.merchant.eq_ignore_ascii_case(merchant))
+                    .map(|(i, _)| i)
+                    .collect();
+                (merchant.to_string(), series)
             } else {
-                "decreasing"
+                return Err("cancel_subscription needs a subscription_id or merchant".to_string());
             };
-            category_creep.push(CategoryCreep {
-                category: cat.clone(),
-                trend: trend.to_string(),
-                three_month_change_pct: change_pct,
-                monthly_totals: months.iter().map(|(_, t)| *t).collect(),
-                message: format!(
-                    "{} spending {} {:.0}% over the last 3 months",
-                    cat,
-                    if change_pct > 0.0 { "up" } else { "down" },
-                    change_pct.abs()
-                ),
-            });
+            if !model.charges.iter().any(|c| series.contains(&c.series)) {
+                return Err(format!("No upcoming recurring charges from {name}"));
+            }
+            Ok((format!("Cancel {name}"), forecast::Change::Drop { series }))
         }
+        "add_expense" => {
+            let amount = adjustment
+                .amount
+                .filter(|a| a.is_finite() && *a != 0.0)
+                .ok_or("add_expense needs a non-zero amount")?;
+            let category = adjustment
+                .category
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .unwrap_or("Uncategorized")
+                .to_string();
+            let description = adjustment
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("{amount:.2}/month in {category}"));
+            Ok((description, forecast::Change::Add { category, amount }))
+        }
+        other => Err(format!(
+            "Invalid adjustment type: {other}. Must be one of: {}",
+            ADJUSTMENT_TYPES.join(", ")
+        )),
     }
+}
 
-    // Weekend splurge
-    let weekend: (f64, f64) = sqlx::query_as(
-        "WITH daily AS ( \
-           SELECT date, SUM(amount::float8) as day_total, EXTRACT(DOW FROM date)::int as dow \
-           FROM transactions WHERE date >= (CURRENT_DATE - interval '90 days') GROUP BY date \
-         ) \
-         SELECT \
-           COALESCE(AVG(day_total) FILTER (WHERE dow IN (0, 6)), 0), \
-           COALESCE(AVG(day_total) FILTER (WHERE dow NOT IN (0, 6)), 0) \
-         FROM daily",
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or((0.0, 0.0));
-
-    let ratio = if weekend.1 > 0.0 { weekend.0 / weekend.1 } else { 1.0 };
-    let wk_label = if ratio > 2.0 {
-        "high"
-    } else if ratio > 1.5 {
-        "moderate"
-    } else if ratio > 1.2 {
-        "slight"
-    } else {
-        "balanced"
-    };
+/// Projected difference of a set of what-if adjustments against the plain horizon.
+async fn post_scenario(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Query(params): Query<HorizonQuery>,
+    Json(body): Json<ScenarioRequest>,
+) -> Json<serde_json::Value> {
+    let (model, recurring) = build_horizon(&pool, &filter, params.months).await;
+
+    let mut labels = Vec::new();
+    let mut changes = Vec::new();
+    for adjustment in &body.adjustments {
+        match resolve_adjustment(&pool, adjustment, &model, &recurring).await {
+            Ok((label, change)) => {
+                labels.push(label);
+                changes.push(change);
+            }
+            Err(e) => return Json(serde_json::json!({ "error": e })),
+        }
+    }
 
-    let weekend_splurge = WeekendSplurge {
-        weekend_avg_daily: weekend.0,
-        weekday_avg_daily: weekend.1,
-        ratio,
-        label: wk_label.to_string(),
-        message: format!(
-            "Weekend spending is {:.1}x weekday spending (${:.0} vs ${:.0} daily avg)",
-            ratio, weekend.0, weekend.1
-        ),
-    };
+    let baseline = forecast::project_horizon(&model, &[]);
+    let scenario = forecast::project_horizon(&model, &changes);
+    let baseline_total: f64 = baseline.iter().map(|m| m.total).sum();
+    let scenario_total: f64 = scenario.iter().map(|m| m.total).sum();
+    let adjustments = labels
+        .into_iter()
+        .zip(&changes)
+        .map(|(description, change)| {
+            let alone: f64 = forecast::project_horizon(&model, std::slice::from_ref(change))
+                .iter()
+                .map(|m| m.total)
+                .sum();
+            AdjustmentImpact {
+                description,
+                difference: alone - baseline_total,
+            }
+        })
+        .collect();
+    let months: Vec<ScenarioMonth> = baseline
+        .iter()
+        .zip(&scenario)
+        .map(|(b, s)| ScenarioMonth {
+            month: b.month.clone(),
+            baseline: b.total,
+            scenario: s.total,
+            difference: s.total - b.total,
+        })
+        .collect();
 
-    // Subscription bloat — reuse recurring logic inline
-    let recurring_rows: Vec<(String, i32, i32, f64, f64, NaiveDate, NaiveDate)> = sqlx::query_as(
-        "SELECT \
-           COALESCE(merchant_normalized, description) as merchant, \
-           COUNT(*)::int, COUNT(DISTINCT to_char(date, 'YYYY-MM'))::int, \
-           COALESCE(AVG(amount::float8), 0), COALESCE(STDDEV(amount::float8), 0), \
-           MIN(date), MAX(date) \
-         FROM transactions \
-         GROUP BY COALESCE(merchant_normalized, description) \
-         HAVING COUNT(DISTINCT to_char(date, 'YYYY-MM')) >= 3 \
-         ORDER BY AVG(amount) DESC",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+    Json(serde_json::json!({
+        "data": ScenarioData {
+            avg_monthly_difference: (scenario_total - baseline_total) / months.len().max(1) as f64,
+            months,
+            baseline_total,
+            scenario_total,
+            difference: scenario_total - baseline_total,
+            adjustments,
+        }
+    }))
+}
 
-    let today = chrono::Local::now().naive_local().date();
-    let mut sub_total = 0.0f64;
-    let mut sub_count = 0i32;
-    let mut forgotten = Vec::new();
+// ── Bad Habits Detection ──
 
-    for (merchant, total_count, active_months, avg_amount, stddev, _first, last) in &recurring_rows
-    {
-        if avg_amount.abs() < 0.01 || *stddev > *avg_amount * 0.2 {
-            continue;
-        }
-        let freq = *total_count as f64 / *active_months as f64;
-        if freq < 0.7 || freq > 1.5 {
-            continue;
-        }
-        let gap = today.signed_duration_since(*last).num_days();
-        if gap <= 45 {
-            sub_total += avg_amount;
-            sub_count += 1;
-        } else if *active_months >= 3 {
-            forgotten.push(merchant.clone());
-        }
-    }
+async fn get_habits(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
 
-    let subscription_bloat = SubscriptionBloat {
-        total_monthly: sub_total,
-        total_annual: sub_total * 12.0,
-        count: sub_count,
-        potentially_forgotten: forgotten.clone(),
-        message: format!(
-            "{} active subscriptions totaling ${:.0}/month",
-            sub_count, sub_total
-        ),
-    };
+    let small_purchases = fetch_small_purchases(&pool, &filter, &t).await;
+    let impulse_spending = analytics::impulse_spending(&small_purchases, &t);
+
+    let cat_monthly = fetch_category_months(&pool, &filter).await;
+    let category_creep = analytics::category_creep(&cat_monthly, &t);
+
+    let (weekend_avg, weekday_avg) = fetch_weekend_weekday(&pool, &filter, &t).await;
+    let weekend_splurge = analytics::weekend_splurge(weekend_avg, weekday_avg, &t);
+
+    let charges = fetch_charges(&pool, &filter).await;
+    let recurring = detect_registered(&pool, &charges, filter.as_of(), &t).await;
+    let subscription_bloat = analytics::subscription_bloat(&recurring, filter.as_of());
 
     // Merchant concentration
-    let conc_rows: Vec<(String, f64, f64)> = sqlx::query_as(
+    let shares: Vec<(String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "WITH merchant_totals AS ( \
            SELECT COALESCE(merchant_normalized, description) as merchant, SUM(amount::float8) as total \
-           FROM transactions WHERE date >= (CURRENT_DATE - interval '90 days') \
+           FROM spending WHERE date >= as_of - $8 \
            GROUP BY COALESCE(merchant_normalized, description) \
          ), \
          with_share AS ( \
            SELECT merchant, total, total / NULLIF(SUM(total) OVER (), 0) as share \
            FROM merchant_totals \
          ) \
-         SELECT merchant, total, COALESCE(share, 0) FROM with_share ORDER BY total DESC",
-    )
+         SELECT merchant, COALESCE(share, 0) FROM with_share ORDER BY total DESC",
+    )))
+    .bind(t.habit_window_days)
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
-
-    let hhi: f64 = conc_rows.iter().map(|(_, _, s)| s * s).sum();
-    let top_merchant = conc_rows.first().map(|(m, _, _)| m.clone()).unwrap_or_default();
-    let top_merchant_pct = conc_rows.first().map(|(_, _, s)| s * 100.0).unwrap_or(0.0);
-    let top_3_pct: f64 = conc_rows.iter().take(3).map(|(_, _, s)| s * 100.0).sum();
-
-    let conc_label = if hhi > 0.25 {
-        "high"
-    } else if hhi > 0.15 {
-        "moderate"
-    } else if hhi > 0.10 {
-        "mild"
-    } else {
-        "diversified"
-    };
-
-    let merchant_concentration = MerchantConcentration {
-        top_merchant: top_merchant.clone(),
-        top_merchant_pct,
-        top_3_pct,
-        hhi,
-        label: conc_label.to_string(),
-        message: format!(
-            "Top merchant ({}) accounts for {:.0}% of spending",
-            top_merchant, top_merchant_pct
-        ),
-    };
+    let merchant_concentration = analytics::merchant_concentration(&shares, &t);
 
     Json(serde_json::json!({
         "data": HabitsData {
@@ -969,33 +1197,22 @@ async fn get_habits(State(pool): State<PgPool>) -> Json<serde_json::Value> {
 
 // ── Daily Spending ──
 
-#[derive(Deserialize)]
-pub struct DailyQuery {
-    pub start_date: Option<String>,
-    pub end_date: Option<String>,
-}
-
 async fn get_daily(
     State(pool): State<PgPool>,
-    Query(params): Query<DailyQuery>,
+    filter: StatsFilter,
 ) -> Json<serde_json::Value> {
-    let today = chrono::Local::now().naive_local().date();
-    let start = params
-        .start_date
-        .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok())
-        .unwrap_or(today - chrono::Duration::days(365));
-    let end = params
-        .end_date
-        .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok())
-        .unwrap_or(today);
-
-    let rows: Vec<(NaiveDate, f64, i32)> = sqlx::query_as(
+    // The heatmap defaults to the year leading up to the reference date
+    let as_of = filter.as_of();
+    let filter = StatsFilter {
+        start_date: Some(filter.start_date.unwrap_or(as_of - chrono::Duration::days(365))),
+        end_date: Some(filter.end_date.unwrap_or(as_of)),
+        ..filter
+    };
+
+    let rows: Vec<(NaiveDate, f64, i32)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT date, COALESCE(SUM(amount::float8), 0) as total, COUNT(*)::int as count \
-         FROM transactions WHERE date >= $1 AND date <= $2 \
-         GROUP BY date ORDER BY date",
-    )
-    .bind(start)
-    .bind(end)
+         FROM spending GROUP BY date ORDER BY date",
+    )))
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
@@ -1013,68 +1230,97 @@ async fn get_daily(
 async fn get_category_deep_dive(
     State(pool): State<PgPool>,
     axum::extract::Path(category): axum::extract::Path<String>,
+    filter: StatsFilter,
 ) -> Json<serde_json::Value> {
+    // A top-level category includes its subcategories
+    let all_categories = fetch_categories(&pool).await.unwrap_or_default();
+    let names = categories::family(&category, &all_categories);
+
     // Total and count
-    let summary: (f64, i64, f64) = sqlx::query_as(
+    let summary: (f64, i64, f64) = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT COALESCE(SUM(amount::float8), 0), COUNT(*)::bigint, COALESCE(AVG(amount::float8), 0) \
-         FROM transactions WHERE category = $1",
-    )
-    .bind(&category)
+         FROM spending WHERE category = ANY($8)",
+    )))
+    .bind(&names)
     .fetch_one(&pool)
     .await
     .unwrap_or((0.0, 0, 0.0));
 
     // Monthly trend
-    let monthly: Vec<(String, f64, i64)> = sqlx::query_as(
+    let monthly: Vec<(String, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT to_char(date, 'YYYY-MM') as month, SUM(amount::float8) as total, COUNT(*)::bigint as count \
-         FROM transactions WHERE category = $1 \
+         FROM spending WHERE category = ANY($8) \
          GROUP BY to_char(date, 'YYYY-MM') ORDER BY month",
-    )
-    .bind(&category)
+    )))
+    .bind(&names)
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
     // Top merchants
-    let merchants: Vec<(String, f64, i64, f64)> = sqlx::query_as(
+    let merchants: Vec<(String, f64, i64, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT COALESCE(merchant_normalized, description) as merchant, \
            SUM(amount::float8) as total, COUNT(*)::bigint as count, AVG(amount::float8) as avg_amount \
-         FROM transactions WHERE category = $1 \
+         FROM spending WHERE category = ANY($8) \
          GROUP BY COALESCE(merchant_normalized, description) \
          ORDER BY SUM(amount) DESC LIMIT 10",
-    )
-    .bind(&category)
+    )))
+    .bind(&names)
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
     // Day of week
-    let dow: Vec<(i32, f64, i64)> = sqlx::query_as(
+    let dow: Vec<(i32, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT EXTRACT(DOW FROM date)::int as dow, SUM(amount::float8) as total, COUNT(*)::bigint as count \
-         FROM transactions WHERE category = $1 \
+         FROM spending WHERE category = ANY($8) \
          GROUP BY EXTRACT(DOW FROM date) ORDER BY dow",
-    )
-    .bind(&category)
+    )))
+    .bind(&names)
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
     // Recent transactions
-    let recent: Vec<(uuid::Uuid, NaiveDate, String, f64)> = sqlx::query_as(
+    let recent: Vec<(uuid::Uuid, NaiveDate, String, f64)> = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT id, date, description, amount::float8 as amount \
-         FROM transactions WHERE category = $1 \
+         FROM spending WHERE category = ANY($8) \
          ORDER BY date DESC LIMIT 10",
-    )
-    .bind(&category)
+    )))
+    .bind(&names)
     .fetch_all(&pool)
     .await
     .unwrap_or_default();
 
+    // Each subcategory's share, when there are any
+    let subcategories: Vec<CategoryTotal> = if names.len() > 1 {
+        let rows: Vec<(String, f64, i64)> = filter.bind(sqlx::query_as(&filter.sql(
+            "SELECT category, SUM(amount::float8), COUNT(*)::bigint \
+             FROM spending WHERE category = ANY($8) \
+             GROUP BY category ORDER BY SUM(amount) DESC",
+        )))
+        .bind(&names)
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+        rows.into_iter()
+            .map(|(category, total, count)| CategoryTotal {
+                category,
+                total,
+                count,
+                avg_amount: if count > 0 { total / count as f64 } else { 0.0 },
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     let day_names = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
 
     Json(serde_json::json!({
         "data": CategoryDeepDive {
-            category: category.clone(),
+            category: names[0].clone(),
+            subcategories,
             total_spent: summary.0,
             transaction_count: summary.1,
             avg_amount: summary.2,
@@ -1106,450 +1352,155 @@ async fn get_category_deep_dive(
 
 // ── Smart Insights Engine ──
 
-struct ScoredInsight {
-    insight: Insight,
-    priority: f64,
-}
-
-fn score_anomaly(z_score: f64) -> f64 {
-    90.0 + (z_score.min(5.0) * 2.0)
-}
-
-fn score_trend(change_pct: f64) -> f64 {
-    70.0 + (change_pct.abs().min(50.0) * 0.3)
-}
-
-async fn get_insights(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    let mut scored: Vec<ScoredInsight> = Vec::new();
-
-    // 1. Anomaly insights
-    let baselines: Vec<(String, f64, f64, i32)> = sqlx::query_as(
-        "WITH monthly_cat AS ( \
-           SELECT category, to_char(date, 'YYYY-MM') as month, SUM(amount::float8) as total \
-           FROM transactions GROUP BY category, to_char(date, 'YYYY-MM') \
-         ) \
-         SELECT category, AVG(total)::float8, COALESCE(STDDEV(total), 0)::float8, COUNT(*)::int \
-         FROM monthly_cat GROUP BY category HAVING COUNT(*) >= 2",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
-
-    let current_cat: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT category, COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date GROUP BY category",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
-
-    let current_map: HashMap<String, f64> = current_cat.into_iter().collect();
-
-    for (category, avg, stddev, _) in &baselines {
-        if *stddev < 0.01 {
-            continue;
-        }
-        let current = current_map.get(category).copied().unwrap_or(0.0);
-        let z = (current - avg) / stddev;
-        if z > 2.0 {
-            let pct = (current - avg) / avg * 100.0;
-            scored.push(ScoredInsight {
-                priority: score_anomaly(z),
-                insight: Insight {
-                    r#type: "anomaly".into(),
-                    severity: if z > 3.0 { "high" } else { "medium" }.into(),
-                    icon: "AlertTriangle".into(),
-                    title: format!("{} spending spike", category),
-                    message: format!(
-                        "{} spending is {:.0}% above your average this month",
-                        category, pct
-                    ),
-                    metric: Some(serde_json::json!({ "z_score": z, "pct_above": pct })),
-                    action: Some(format!("Review your {} transactions", category)),
-                    category: Some(category.clone()),
-                },
-            });
-        }
-    }
+/// Fetch everything the insight generators look at, once per request.
+async fn load_insight_context(pool: &PgPool, filter: &StatsFilter) -> InsightContext {
+    let t = load_thresholds(pool).await;
+    let as_of = filter.as_of();
 
-    // 2. MoM trend
-    let this_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= date_trunc('month', CURRENT_DATE)::date",
-    )
-    .fetch_one(&pool)
+    let this_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= date_trunc('month', as_of)::date",
+    )))
+    .fetch_one(pool)
     .await
     .unwrap_or((0.0,));
 
-    let last_month: (f64,) = sqlx::query_as(
-        "SELECT COALESCE(SUM(amount::float8), 0) FROM transactions \
-         WHERE date >= (date_trunc('month', CURRENT_DATE) - interval '1 month')::date \
-         AND date < date_trunc('month', CURRENT_DATE)::date",
-    )
-    .fetch_one(&pool)
+    let last_month: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
+        "SELECT COALESCE(SUM(amount::float8), 0) FROM spending \
+         WHERE date >= (date_trunc('month', as_of) - interval '1 month')::date \
+         AND date < date_trunc('month', as_of)::date",
+    )))
+    .fetch_one(pool)
     .await
     .unwrap_or((0.0,));
 
-    if last_month.0 > 0.0 {
-        let mom_pct = (this_month.0 - last_month.0) / last_month.0 * 100.0;
-        if mom_pct.abs() > 20.0 {
-            let (title, msg, severity) = if mom_pct > 0.0 {
-                (
-                    "Spending up vs last month".into(),
-                    format!("You've spent {:.0}% more than last month so far", mom_pct),
-                    "medium",
-                )
-            } else {
-                (
-                    "Spending down vs last month".into(),
-                    format!(
-                        "You've spent {:.0}% less than last month so far",
-                        mom_pct.abs()
-                    ),
-                    "low",
-                )
-            };
-            scored.push(ScoredInsight {
-                priority: score_trend(mom_pct),
-                insight: Insight {
-                    r#type: "trend".into(),
-                    severity: severity.into(),
-                    icon: if mom_pct > 0.0 {
-                        "TrendingUp"
-                    } else {
-                        "TrendingDown"
-                    }
-                    .into(),
-                    title,
-                    message: msg,
-                    metric: Some(serde_json::json!({ "mom_change_pct": mom_pct })),
-                    action: None,
-                    category: None,
-                },
-            });
-        }
-    }
-
-    // 3. Forecast insight
-    let now = chrono::Local::now().naive_local().date();
-    let d_elapsed = now.day();
-    let d_in_month = days_in_month(now.year(), now.month());
-    let projected = linear_projection(this_month.0, d_elapsed, d_in_month);
-
-    let avg_monthly: (f64,) = sqlx::query_as(
+    let avg_monthly: (f64,) = filter.bind(sqlx::query_as(&filter.sql(
         "SELECT COALESCE(SUM(amount::float8), 0) / \
-         GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) FROM transactions",
-    )
-    .fetch_one(&pool)
+         GREATEST(COUNT(DISTINCT to_char(date, 'YYYY-MM')), 1) FROM spending",
+    )))
+    .fetch_one(pool)
     .await
     .unwrap_or((0.0,));
 
-    if avg_monthly.0 > 0.0 {
-        let vs_avg_pct = (projected - avg_monthly.0) / avg_monthly.0 * 100.0;
-        if vs_avg_pct > 15.0 {
-            scored.push(ScoredInsight {
-                priority: 60.0 + vs_avg_pct.min(40.0) * 0.375,
-                insight: Insight {
-                    r#type: "forecast".into(),
-                    severity: if vs_avg_pct > 30.0 { "high" } else { "medium" }.into(),
-                    icon: "Activity".into(),
-                    title: "On track to overspend".into(),
-                    message: format!(
-                        "Projected spending is {:.0}% above your monthly average",
-                        vs_avg_pct
-                    ),
-                    metric: Some(serde_json::json!({
-                        "projected": projected,
-                        "avg_monthly": avg_monthly.0,
-                        "vs_avg_pct": vs_avg_pct
-                    })),
-                    action: Some("Consider reducing discretionary spending".into()),
-                    category: None,
-                },
-            });
-        }
-    }
+    let (weekend_avg_daily, weekday_avg_daily) = fetch_weekend_weekday(pool, filter, &t).await;
+    let charges = fetch_charges(pool, filter).await;
 
-    // 4. Habit insights
-    let impulse: (i32, i32, f64, f64) = sqlx::query_as(
-        "SELECT COUNT(*)::int, \
-           COUNT(*) FILTER (WHERE amount::float8 < 15)::int, \
-           COALESCE(SUM(amount::float8) FILTER (WHERE amount::float8 < 15), 0), \
-           COALESCE(AVG(amount::float8) FILTER (WHERE amount::float8 < 15), 0) \
-         FROM transactions WHERE date >= (CURRENT_DATE - interval '90 days')",
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or((0, 0, 0.0, 0.0));
-
-    if impulse.0 > 0 {
-        let small_pct = impulse.1 as f64 / impulse.0 as f64 * 100.0;
-        if small_pct > 35.0 {
-            scored.push(ScoredInsight {
-                priority: 45.0 + small_pct * 0.3,
-                insight: Insight {
-                    r#type: "habit".into(),
-                    severity: "medium".into(),
-                    icon: "Coffee".into(),
-                    title: "Frequent small purchases".into(),
-                    message: format!(
-                        "{:.0}% of your transactions are under $15 — totaling ${:.0}/month",
-                        small_pct,
-                        impulse.2 / 3.0
-                    ),
-                    metric: Some(serde_json::json!({ "small_pct": small_pct })),
-                    action: Some("Track small daily expenses".into()),
-                    category: None,
-                },
-            });
-        }
-    }
-
-    // Category creep insight
-    let cat_monthly: Vec<(String, String, f64)> = sqlx::query_as(
-        "SELECT category, to_char(date, 'YYYY-MM') as month, SUM(amount::float8) \
-         FROM transactions \
-         WHERE date >= (date_trunc('month', CURRENT_DATE) - interval '6 months')::date \
-         GROUP BY category, to_char(date, 'YYYY-MM') ORDER BY category, month",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+    let subscription_alerts = subscriptions::current_alerts(pool, as_of)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("Failed to compute subscription alerts: {e}");
+            Vec::new()
+        });
 
-    let mut cat_map: HashMap<String, Vec<f64>> = HashMap::new();
-    for (cat, _, total) in &cat_monthly {
-        cat_map.entry(cat.clone()).or_default().push(*total);
-    }
-    for (cat, months) in &cat_map {
-        if months.len() < 4 {
-            continue;
-        }
-        let mid = months.len() / 2;
-        let prior: f64 = months[..mid].iter().sum::<f64>() / mid as f64;
-        let recent: f64 = months[mid..].iter().sum::<f64>() / (months.len() - mid) as f64;
-        if prior < 1.0 {
-            continue;
-        }
-        let change = (recent - prior) / prior * 100.0;
-        if change > 25.0 {
-            scored.push(ScoredInsight {
-                priority: 50.0 + change.min(50.0) * 0.2,
-                insight: Insight {
-                    r#type: "habit".into(),
-                    severity: "medium".into(),
-                    icon: "TrendingUp".into(),
-                    title: format!("{} spending creeping up", cat),
-                    message: format!("{} spending up {:.0}% over the last 3 months", cat, change),
-                    metric: Some(serde_json::json!({ "change_pct": change })),
-                    action: Some(format!("Set a {} budget", cat)),
-                    category: Some(cat.clone()),
-                },
-            });
-        }
+    let budgets = budget::progress_as_of(pool, as_of).await.unwrap_or_else(|e| {
+        tracing::error!("Failed to compute budget progress: {e}");
+        Vec::new()
+    });
+
+    let suspicious = suspicious::findings(pool, filter, &t).await.unwrap_or_else(|e| {
+        tracing::error!("Failed to detect suspicious charges: {e}");
+        Vec::new()
+    });
+
+    let goals = goals::progress_as_of(pool, as_of).await.unwrap_or_else(|e| {
+        tracing::error!("Failed to compute goal progress: {e}");
+        Vec::new()
+    });
+
+    InsightContext {
+        as_of,
+        baselines: fetch_category_baselines(pool, filter).await,
+        current_by_category: fetch_current_by_category(pool, filter).await,
+        this_month: this_month.0,
+        last_month: last_month.0,
+        avg_monthly: avg_monthly.0,
+        small_purchases: fetch_small_purchases(pool, filter, &t).await,
+        category_months: fetch_category_months(pool, filter).await,
+        weekend_avg_daily,
+        weekday_avg_daily,
+        recurring: detect_registered(pool, &charges, as_of, &t).await,
+        subscription_alerts,
+        budgets,
+        cashflow: fetch_cashflow(pool, filter, 7).await,
+        suspicious,
+        goals,
+        thresholds: t,
     }
+}
 
-    // Weekend splurge
-    let weekend: (f64, f64) = sqlx::query_as(
-        "WITH daily AS ( \
-           SELECT date, SUM(amount::float8) as day_total, EXTRACT(DOW FROM date)::int as dow \
-           FROM transactions WHERE date >= (CURRENT_DATE - interval '90 days') GROUP BY date \
-         ) \
-         SELECT \
-           COALESCE(AVG(day_total) FILTER (WHERE dow IN (0, 6)), 0), \
-           COALESCE(AVG(day_total) FILTER (WHERE dow NOT IN (0, 6)), 0) \
-         FROM daily",
-    )
-    .fetch_one(&pool)
-    .await
-    .unwrap_or((0.0, 0.0));
-
-    if weekend.1 > 0.0 {
-        let ratio = weekend.0 / weekend.1;
-        if ratio > 1.5 {
-            scored.push(ScoredInsight {
-                priority: 40.0 + (ratio - 1.0).min(2.0) * 10.0,
-                insight: Insight {
-                    r#type: "habit".into(),
-                    severity: "low".into(),
-                    icon: "Calendar".into(),
-                    title: "Weekend spending spike".into(),
-                    message: format!(
-                        "You spend {:.1}x more on weekends than weekdays",
-                        ratio
-                    ),
-                    metric: Some(serde_json::json!({ "ratio": ratio })),
-                    action: Some("Plan weekend activities with a budget".into()),
-                    category: None,
-                },
-            });
+/// Persist everything generated, then take the top 8 the user hasn't dismissed or snoozed.
+async fn record_feed(
+    pool: &PgPool,
+    scored: &[(Insight, f64)],
+    period: &str,
+    today: NaiveDate,
+) -> Result<Vec<StoredInsight>, sqlx::Error> {
+    let mut feed = Vec::new();
+    for (insight, priority) in scored {
+        let stored = insights::record(pool, insight, *priority, period).await?;
+        if insights::is_active(&stored, today) && feed.len() < 8 {
+            feed.push(stored);
         }
     }
+    Ok(feed)
+}
 
-    // 5. Recurring insights
-    let recurring_rows: Vec<(String, i32, i32, f64, f64, NaiveDate, NaiveDate)> = sqlx::query_as(
-        "SELECT \
-           COALESCE(merchant_normalized, description), \
-           COUNT(*)::int, COUNT(DISTINCT to_char(date, 'YYYY-MM'))::int, \
-           COALESCE(AVG(amount::float8), 0), COALESCE(STDDEV(amount::float8), 0), \
-           MIN(date), MAX(date) \
-         FROM transactions \
-         GROUP BY COALESCE(merchant_normalized, description) \
-         HAVING COUNT(DISTINCT to_char(date, 'YYYY-MM')) >= 3",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
+/// The top 8 for a filtered view, left unsaved so they don't overwrite the month's
+/// stored insights, but still hiding those the user dismissed or snoozed.
+async fn preview_feed(
+    pool: &PgPool,
+    scored: &[(Insight, f64)],
+    period: &str,
+    today: NaiveDate,
+) -> Result<Vec<StoredInsight>, sqlx::Error> {
+    let fingerprints: Vec<String> = scored
+        .iter()
+        .map(|(insight, _)| {
+            insights::fingerprint(&insight.r#type, &insights::subject(insight), period)
+        })
+        .collect();
+    let stored: HashMap<String, StoredInsight> =
+        insights::find_by_fingerprints(pool, &fingerprints)
+            .await?
+            .into_iter()
+            .map(|s| (s.fingerprint.clone(), s))
+            .collect();
+    Ok(scored
+        .iter()
+        .zip(&fingerprints)
+        .map(|((insight, priority), fingerprint)| {
+            insights::preview(insight, *priority, period, stored.get(fingerprint))
+        })
+        .filter(|insight| insights::is_active(insight, today))
+        .take(8)
+        .collect())
+}
 
+async fn get_insights(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+) -> Json<serde_json::Value> {
+    let ctx = load_insight_context(&pool, &filter).await;
+    let enabled = load_enabled_generators(&pool).await;
+    let scored = insight_generators::generate_all(&insight_generators::registry(), &ctx, |name| {
+        enabled.get(name).copied().unwrap_or(true)
+    })
+    .await;
+
+    let period = insights::period(ctx.as_of);
     let today = chrono::Local::now().naive_local().date();
-    let mut recurring_total = 0.0f64;
-    let mut forgotten_subs = Vec::new();
-
-    for (merchant, total_count, active_months, avg_amount, stddev, _first, last) in &recurring_rows
-    {
-        if avg_amount.abs() < 0.01 || *stddev > *avg_amount * 0.2 {
-            continue;
-        }
-        let freq = *total_count as f64 / *active_months as f64;
-        if freq < 0.7 || freq > 1.5 {
-            continue;
-        }
-        let gap = today.signed_duration_since(*last).num_days();
-        if gap <= 45 {
-            recurring_total += avg_amount;
-        } else if *active_months >= 3 {
-            forgotten_subs.push(merchant.clone());
-        }
-    }
-
-    if !forgotten_subs.is_empty() {
-        scored.push(ScoredInsight {
-            priority: 55.0 + forgotten_subs.len() as f64 * 5.0,
-            insight: Insight {
-                r#type: "recurring".into(),
-                severity: "medium".into(),
-                icon: "AlertCircle".into(),
-                title: "Potentially forgotten subscriptions".into(),
-                message: format!(
-                    "{} subscriptions haven't been charged recently: {}",
-                    forgotten_subs.len(),
-                    forgotten_subs.join(", ")
-                ),
-                metric: Some(serde_json::json!({ "count": forgotten_subs.len() })),
-                action: Some("Check if these subscriptions are still needed".into()),
-                category: None,
-            },
-        });
-    }
-
-    if recurring_total > 100.0 {
-        scored.push(ScoredInsight {
-            priority: 50.0,
-            insight: Insight {
-                r#type: "recurring".into(),
-                severity: "low".into(),
-                icon: "Repeat".into(),
-                title: "Monthly recurring summary".into(),
-                message: format!(
-                    "Your recurring charges total ${:.0}/month (${:.0}/year)",
-                    recurring_total,
-                    recurring_total * 12.0
-                ),
-                metric: Some(serde_json::json!({
-                    "monthly": recurring_total,
-                    "annual": recurring_total * 12.0
-                })),
-                action: None,
-                category: None,
-            },
-        });
-    }
-
-    // 6. Budget insights
-    let budgets: Vec<(String, f64)> = sqlx::query_as(
-        "SELECT category, monthly_limit::float8 FROM budgets",
-    )
-    .fetch_all(&pool)
-    .await
-    .unwrap_or_default();
-
-    let days_remaining = d_in_month.saturating_sub(d_elapsed);
-
-    for (budget_cat, budget_limit) in &budgets {
-        let spent = current_map.get(budget_cat).copied().unwrap_or(0.0);
-        let pct = if *budget_limit > 0.0 {
-            spent / budget_limit * 100.0
-        } else {
-            0.0
-        };
-
-        if pct >= 100.0 {
-            scored.push(ScoredInsight {
-                priority: 85.0,
-                insight: Insight {
-                    r#type: "budget".into(),
-                    severity: "high".into(),
-                    icon: "AlertCircle".into(),
-                    title: format!("{} budget exceeded", budget_cat),
-                    message: format!(
-                        "You've spent ${:.0} on {} this month, exceeding your ${:.0} budget.",
-                        spent, budget_cat, budget_limit
-                    ),
-                    metric: Some(serde_json::json!({
-                        "value": spent,
-                        "comparison": budget_limit,
-                        "unit": "dollars"
-                    })),
-                    action: Some(format!("Review {} transactions", budget_cat)),
-                    category: Some(budget_cat.clone()),
-                },
-            });
-        } else if pct >= 80.0 {
-            scored.push(ScoredInsight {
-                priority: 55.0,
-                insight: Insight {
-                    r#type: "budget".into(),
-                    severity: "medium".into(),
-                    icon: "AlertTriangle".into(),
-                    title: format!("{} budget at {:.0}%", budget_cat, pct),
-                    message: format!(
-                        "${:.0} of ${:.0} {} budget used with {} days remaining.",
-                        spent, budget_limit, budget_cat, days_remaining
-                    ),
-                    metric: Some(serde_json::json!({
-                        "value": spent,
-                        "comparison": budget_limit,
-                        "unit": "dollars"
-                    })),
-                    action: None,
-                    category: Some(budget_cat.clone()),
-                },
-            });
+    let result = if filter.is_scoped() {
+        preview_feed(&pool, &scored, &period, today).await
+    } else {
+        record_feed(&pool, &scored, &period, today).await
+    };
+    let feed = match result {
+        Ok(feed) => feed,
+        Err(e) => {
+            tracing::error!("Failed to load insight feed: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
         }
-    }
-
-    // 7. Positive insights
-    if avg_monthly.0 > 0.0 && this_month.0 < avg_monthly.0 * 0.9 {
-        scored.push(ScoredInsight {
-            priority: 25.0,
-            insight: Insight {
-                r#type: "positive".into(),
-                severity: "low".into(),
-                icon: "ThumbsUp".into(),
-                title: "Great month so far!".into(),
-                message: format!(
-                    "You're spending {:.0}% below your monthly average",
-                    (1.0 - this_month.0 / avg_monthly.0) * 100.0
-                ),
-                metric: None,
-                action: None,
-                category: None,
-            },
-        });
-    }
-
-    // Sort by priority descending, take top 8
-    scored.sort_by(|a, b| b.priority.partial_cmp(&a.priority).unwrap_or(std::cmp::Ordering::Equal));
-    let insights: Vec<Insight> = scored.into_iter().take(8).map(|s| s.insight).collect();
+    };
 
-    Json(serde_json::json!({ "data": insights }))
+    Json(serde_json::json!({ "data": feed }))
 }
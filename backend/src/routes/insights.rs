@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::models::insight::{InsightFeedback, InsightHistoryQuery, SnoozeInsight};
+use crate::services::{insight_generators, insights};
+
+/// `user_config` key holding `{ "<generator>": true | false }`; generators not listed are on.
+pub const GENERATORS_KEY: &str = "insight_generators";
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/insights/history", get(get_history))
+        .route(
+            "/insights/generators",
+            get(get_generators).put(update_generators),
+        )
+        .route(
+            "/insights/:id/dismiss",
+            post(dismiss_insight).delete(restore_insight),
+        )
+        .route(
+            "/insights/:id/snooze",
+            post(snooze_insight).delete(unsnooze_insight),
+        )
+        .route("/insights/:id/feedback", post(insight_feedback))
+}
+
+/// Stored on/off switches per generator, dropping names that are no longer registered.
+pub async fn load_enabled_generators(pool: &PgPool) -> BTreeMap<String, bool> {
+    let stored =
+        match sqlx::query_scalar::<_, String>("SELECT value FROM user_config WHERE key = $1")
+            .bind(GENERATORS_KEY)
+            .fetch_optional(pool)
+            .await
+        {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::error!("Failed to fetch insight generator settings: {e}");
+                None
+            }
+        };
+
+    let names = insight_generators::generator_names();
+    let mut enabled: BTreeMap<String, bool> = stored
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    enabled.retain(|name, _| names.contains(&name.as_str()));
+    enabled
+}
+
+/// Parse a `{ "<generator>": bool }` object, as accepted by both the generators and
+/// config endpoints.
+pub fn parse_generator_settings(
+    value: serde_json::Value,
+) -> Result<BTreeMap<String, bool>, String> {
+    let settings: BTreeMap<String, bool> =
+        serde_json::from_value(value).map_err(|e| format!("Invalid generator settings: {e}"))?;
+    let names = insight_generators::generator_names();
+    if let Some(unknown) = settings.keys().find(|name| !names.contains(&name.as_str())) {
+        return Err(format!(
+            "Unknown insight generator '{unknown}'; expected one of: {}",
+            names.join(", ")
+        ));
+    }
+    Ok(settings)
+}
+
+async fn generator_list(pool: &PgPool) -> Json<serde_json::Value> {
+    let enabled = load_enabled_generators(pool).await;
+    let generators: Vec<serde_json::Value> = insight_generators::generator_names()
+        .into_iter()
+        .map(|name| {
+            serde_json::json!({
+                "name": name,
+                "enabled": enabled.get(name).copied().unwrap_or(true),
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "data": generators }))
+}
+
+async fn get_generators(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    generator_list(&pool).await
+}
+
+/// Merge `{ "<generator>": true | false | null }` into the stored switches; `null` goes
+/// back to the default (on).
+async fn update_generators(
+    State(pool): State<PgPool>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let serde_json::Value::Object(changes) = body else {
+        return Json(serde_json::json!({ "error": "Expected a JSON object of generator names" }));
+    };
+
+    let mut merged: serde_json::Map<String, serde_json::Value> = load_enabled_generators(&pool)
+        .await
+        .into_iter()
+        .map(|(name, on)| (name, serde_json::Value::Bool(on)))
+        .collect();
+    for (name, value) in changes {
+        if value.is_null() {
+            merged.remove(&name);
+        } else {
+            merged.insert(name, value);
+        }
+    }
+
+    let settings = match parse_generator_settings(serde_json::Value::Object(merged)) {
+        Ok(settings) => settings,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO user_config (key, value, updated_at) VALUES ($1, $2, NOW()) \
+         ON CONFLICT (key) DO UPDATE SET value = $2, updated_at = NOW()",
+    )
+    .bind(GENERATORS_KEY)
+    .bind(serde_json::to_string(&settings).unwrap_or_default())
+    .execute(&pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to save insight generator settings: {e}");
+        return Json(serde_json::json!({ "error": e.to_string() }));
+    }
+    generator_list(&pool).await
+}
+
+fn today() -> chrono::NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
+
+async fn get_history(
+    State(pool): State<PgPool>,
+    Query(params): Query<InsightHistoryQuery>,
+) -> Json<serde_json::Value> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    match insights::history(&pool, &params, limit).await {
+        Ok(rows) => Json(serde_json::json!({ "data": rows })),
+        Err(e) => {
+            tracing::error!("Failed to fetch insight history: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Run `sql` (with the insight id as `$1` and `value` as `$2`) and return the updated row.
+async fn update_insight<T>(pool: &PgPool, id: Uuid, sql: &str, value: T) -> Json<serde_json::Value>
+where
+    T: for<'q> sqlx::Encode<'q, sqlx::Postgres> + sqlx::Type<sqlx::Postgres> + Send,
+{
+    let result = sqlx::query(sql).bind(id).bind(value).execute(pool).await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            Json(serde_json::json!({ "error": "Insight not found" }))
+        }
+        Ok(_) => match insights::get_insight(pool, id).await {
+            Ok(Some(insight)) => Json(serde_json::json!({ "data": insight })),
+            Ok(None) => Json(serde_json::json!({ "error": "Insight not found" })),
+            Err(e) => Json(serde_json::json!({ "error": e.to_string() })),
+        },
+        Err(e) => {
+            tracing::error!("Failed to update insight {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn dismiss_insight(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    update_insight(
+        &pool,
+        id,
+        "UPDATE insights SET dismissed_at = COALESCE(dismissed_at, $2) WHERE id = $1",
+        chrono::Utc::now(),
+    )
+    .await
+}
+
+async fn restore_insight(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    update_insight(
+        &pool,
+        id,
+        "UPDATE insights SET dismissed_at = $2 WHERE id = $1",
+        None::<chrono::DateTime<chrono::Utc>>,
+    )
+    .await
+}
+
+/// Hide the insight from the feed; it comes back on `until`.
+async fn snooze_insight(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SnoozeInsight>,
+) -> Json<serde_json::Value> {
+    if body.until <= today() {
+        return Json(serde_json::json!({ "error": "until must be after today" }));
+    }
+    update_insight(
+        &pool,
+        id,
+        "UPDATE insights SET snoozed_until = $2 WHERE id = $1",
+        body.until,
+    )
+    .await
+}
+
+async fn unsnooze_insight(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    update_insight(
+        &pool,
+        id,
+        "UPDATE insights SET snoozed_until = $2 WHERE id = $1",
+        None::<chrono::NaiveDate>,
+    )
+    .await
+}
+
+async fn insight_feedback(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<InsightFeedback>,
+) -> Json<serde_json::Value> {
+    update_insight(
+        &pool,
+        id,
+        "UPDATE insights SET helpful = $2 WHERE id = $1",
+        body.helpful,
+    )
+    .await
+}
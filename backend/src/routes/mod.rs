@@ -1,8 +1,19 @@
+pub mod accounts;
+pub mod analytics;
 pub mod budget;
 pub mod cards;
+pub mod categories;
 pub mod config;
+pub mod fx;
+pub mod goals;
 pub mod import;
+pub mod insights;
+pub mod notifications;
+pub mod stats_filter;
+pub mod subscriptions;
+pub mod suspicious;
 pub mod transactions;
+pub mod transfers;
 
 use axum::Router;
 use sqlx::PgPool;
@@ -12,7 +23,17 @@ pub fn api_routes(pool: PgPool) -> Router {
         .merge(transactions::routes())
         .merge(import::routes())
         .merge(cards::routes())
+        .merge(categories::routes())
         .merge(config::routes())
         .merge(budget::routes())
+        .merge(goals::routes())
+        .merge(fx::routes())
+        .merge(accounts::routes())
+        .merge(transfers::routes())
+        .merge(analytics::routes())
+        .merge(subscriptions::routes())
+        .merge(suspicious::routes())
+        .merge(insights::routes())
+        .merge(notifications::routes())
         .with_state(pool)
 }
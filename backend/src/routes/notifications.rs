@@ -0,0 +1,392 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::notification::{
+    Alert, Delivery, DeliveryQuery, NewChannel, NotificationChannel, NotificationRun, NotifyQuery,
+    UpdateChannel, CHANNEL_KINDS,
+};
+use crate::routes::budget;
+use crate::services::{channels, notifications, subscriptions};
+
+const CHANNEL_COLUMNS: &str = "id, name, kind, config, enabled, created_at, updated_at";
+
+/// How often the scheduler re-evaluates alerts between imports.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A delivery that has failed this many times isn't retried until its channel is changed.
+pub(crate) const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+
+/// Runs from imports and the scheduler don't overlap, so an alert can't be sent twice.
+static RUN_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route(
+            "/notifications/channels",
+            get(list_channels).post(create_channel),
+        )
+        .route(
+            "/notifications/channels/:id",
+            patch(update_channel).delete(delete_channel),
+        )
+        .route("/notifications/channels/:id/test", post(test_channel))
+        .route("/notifications/run", post(run_notifications))
+        .route("/notifications/deliveries", get(list_deliveries))
+}
+
+fn today() -> NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
+
+async fn fetch_channel(
+    pool: &PgPool,
+    id: Uuid,
+) -> Result<Option<NotificationChannel>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {CHANNEL_COLUMNS} FROM notification_channels WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Evaluates budget and subscription alerts as of a date and delivers the new ones to
+/// every enabled channel. An alert is sent to a channel once; a failed delivery is
+/// retried on later runs until it has failed [`MAX_DELIVERY_ATTEMPTS`] times.
+pub(crate) async fn notify(
+    pool: &PgPool,
+    as_of: NaiveDate,
+) -> Result<NotificationRun, sqlx::Error> {
+    let _guard = RUN_LOCK.lock().await;
+
+    let enabled: Vec<NotificationChannel> = sqlx::query_as(&format!(
+        "SELECT {CHANNEL_COLUMNS} FROM notification_channels WHERE enabled ORDER BY created_at"
+    ))
+    .fetch_all(pool)
+    .await?;
+    if enabled.is_empty() {
+        return Ok(NotificationRun::default());
+    }
+
+    let mut alerts = notifications::budget_alerts(&budget::progress_as_of(pool, as_of).await?);
+    alerts.extend(notifications::subscription_alerts(
+        &subscriptions::current_alerts(pool, as_of).await?,
+    ));
+    let mut run = NotificationRun {
+        alerts: alerts.len(),
+        ..Default::default()
+    };
+    if alerts.is_empty() {
+        return Ok(run);
+    }
+
+    let keys: Vec<&str> = alerts.iter().map(|a| a.key.as_str()).collect();
+    let delivered: HashMap<(Uuid, String), (String, i32)> =
+        sqlx::query_as::<_, (Uuid, String, String, i32)>(
+            "SELECT channel_id, alert_key, status, attempts FROM notification_deliveries \
+             WHERE alert_key = ANY($1)",
+        )
+        .bind(&keys)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|(channel_id, key, status, attempts)| ((channel_id, key), (status, attempts)))
+        .collect();
+
+    for row in &enabled {
+        let channel = channels::channel(&row.kind, &row.config);
+        for alert in &alerts {
+            match delivered.get(&(row.id, alert.key.clone())) {
+                Some((status, _)) if status == "sent" => {
+                    run.already_sent += 1;
+                    continue;
+                }
+                Some((_, attempts)) if *attempts >= MAX_DELIVERY_ATTEMPTS => {
+                    run.gave_up += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let outcome = match &channel {
+                Ok(channel) => channel.send(alert).await,
+                Err(e) => Err(e.clone()),
+            };
+            if let Err(e) = &outcome {
+                tracing::warn!(
+                    "Failed to deliver {} to channel {}: {e}",
+                    alert.key,
+                    row.name
+                );
+                run.failed += 1;
+            } else {
+                run.sent += 1;
+            }
+            record_delivery(pool, row.id, alert, outcome.err()).await?;
+        }
+    }
+    Ok(run)
+}
+
+/// Record an attempt on the alert's single row for the channel. A sent row is final.
+async fn record_delivery(
+    pool: &PgPool,
+    channel_id: Uuid,
+    alert: &Alert,
+    error: Option<String>,
+) -> Result<(), sqlx::Error> {
+    let status = if error.is_none() { "sent" } else { "failed" };
+    sqlx::query(
+        "INSERT INTO notification_deliveries \
+         (channel_id, alert_key, alert_type, title, message, status, error) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+         ON CONFLICT (channel_id, alert_key) DO UPDATE SET \
+           title = EXCLUDED.title, message = EXCLUDED.message, status = EXCLUDED.status, \
+           error = EXCLUDED.error, attempts = notification_deliveries.attempts + 1, \
+           last_attempt_at = NOW() \
+         WHERE notification_deliveries.status = 'failed'",
+    )
+    .bind(channel_id)
+    .bind(&alert.key)
+    .bind(&alert.alert_type)
+    .bind(&alert.title)
+    .bind(&alert.message)
+    .bind(status)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Re-evaluates alerts on a fixed interval, so thresholds crossed by the calendar (an
+/// overdue subscription, a projection) go out without waiting for the next import.
+pub async fn run_schedule(pool: PgPool) {
+    let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match notify(&pool, today()).await {
+            Ok(run) if run.sent + run.failed > 0 => {
+                tracing::info!(
+                    "Scheduled notifications: {} sent, {} failed",
+                    run.sent,
+                    run.failed
+                )
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Scheduled notification run failed: {e}"),
+        }
+    }
+}
+
+async fn list_channels(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let result: Result<Vec<NotificationChannel>, _> = sqlx::query_as(&format!(
+        "SELECT {CHANNEL_COLUMNS} FROM notification_channels ORDER BY created_at"
+    ))
+    .fetch_all(&pool)
+    .await;
+
+    match result {
+        Ok(rows) => Json(serde_json::json!({ "data": rows })),
+        Err(e) => {
+            tracing::error!("Failed to list notification channels: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn create_channel(
+    State(pool): State<PgPool>,
+    Json(body): Json<NewChannel>,
+) -> Json<serde_json::Value> {
+    let name = body.name.trim();
+    if name.is_empty() {
+        return Json(serde_json::json!({ "error": "name must not be empty" }));
+    }
+    if !CHANNEL_KINDS.contains(&body.kind.as_str()) {
+        return Json(serde_json::json!({
+            "error": format!("kind must be one of: {}", CHANNEL_KINDS.join(", "))
+        }));
+    }
+    if let Err(e) = channels::channel(&body.kind, &body.config) {
+        return Json(serde_json::json!({ "error": e }));
+    }
+
+    let result: Result<NotificationChannel, _> = sqlx::query_as(&format!(
+        "INSERT INTO notification_channels (name, kind, config, enabled) \
+         VALUES ($1, $2, $3, $4) RETURNING {CHANNEL_COLUMNS}"
+    ))
+    .bind(name)
+    .bind(&body.kind)
+    .bind(&body.config)
+    .bind(body.enabled.unwrap_or(true))
+    .fetch_one(&pool)
+    .await;
+
+    match result {
+        Ok(row) => Json(serde_json::json!({ "data": row })),
+        Err(e) => {
+            tracing::error!("Failed to create notification channel: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn update_channel(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateChannel>,
+) -> Json<serde_json::Value> {
+    let existing = match fetch_channel(&pool, id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return Json(serde_json::json!({ "error": "Channel not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch notification channel {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+
+    let name = body
+        .name
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or(&existing.name);
+    if name.is_empty() {
+        return Json(serde_json::json!({ "error": "name must not be empty" }));
+    }
+    let config = body.config.unwrap_or(existing.config);
+    if let Err(e) = channels::channel(&existing.kind, &config) {
+        return Json(serde_json::json!({ "error": e }));
+    }
+
+    let result: Result<NotificationChannel, _> = sqlx::query_as(&format!(
+        "UPDATE notification_channels SET name = $1, config = $2, enabled = $3, updated_at = NOW() \
+         WHERE id = $4 RETURNING {CHANNEL_COLUMNS}"
+    ))
+    .bind(name)
+    .bind(&config)
+    .bind(body.enabled.unwrap_or(existing.enabled))
+    .bind(id)
+    .fetch_one(&pool)
+    .await;
+
+    let result = match result {
+        // A changed channel gets a fresh round of attempts at what it failed to deliver
+        Ok(row) => sqlx::query(
+            "UPDATE notification_deliveries SET attempts = 0 \
+             WHERE channel_id = $1 AND status = 'failed'",
+        )
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map(|_| row),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(row) => Json(serde_json::json!({ "data": row })),
+        Err(e) => {
+            tracing::error!("Failed to update notification channel {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn delete_channel(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query("DELETE FROM notification_channels WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => Json(serde_json::json!({ "data": "Channel deleted" })),
+        Ok(_) => Json(serde_json::json!({ "error": "Channel not found" })),
+        Err(e) => {
+            tracing::error!("Failed to delete notification channel {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Sends a sample alert, whether or not the channel is enabled. Not recorded as a delivery.
+async fn test_channel(State(pool): State<PgPool>, Path(id): Path<Uuid>) -> Json<serde_json::Value> {
+    let row = match fetch_channel(&pool, id).await {
+        Ok(Some(row)) => row,
+        Ok(None) => return Json(serde_json::json!({ "error": "Channel not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch notification channel {id}: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+
+    let alert = Alert {
+        key: format!("test:{id}"),
+        alert_type: "test".into(),
+        title: "ledgr test notification".into(),
+        message: format!("Notifications from ledgr will arrive on \"{}\".", row.name),
+    };
+    let outcome = match channels::channel(&row.kind, &row.config) {
+        Ok(channel) => channel.send(&alert).await,
+        Err(e) => Err(e),
+    };
+    match outcome {
+        Ok(()) => Json(serde_json::json!({ "data": { "sent": true } })),
+        Err(e) => Json(serde_json::json!({ "error": e })),
+    }
+}
+
+async fn run_notifications(
+    State(pool): State<PgPool>,
+    Query(params): Query<NotifyQuery>,
+) -> Json<serde_json::Value> {
+    match notify(&pool, params.as_of.unwrap_or_else(today)).await {
+        Ok(run) => Json(serde_json::json!({ "data": run })),
+        Err(e) => {
+            tracing::error!("Failed to run notifications: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn list_deliveries(
+    State(pool): State<PgPool>,
+    Query(params): Query<DeliveryQuery>,
+) -> Json<serde_json::Value> {
+    if let Some(status) = &params.status {
+        if !["sent", "failed"].contains(&status.as_str()) {
+            return Json(serde_json::json!({ "error": "status must be one of: sent, failed" }));
+        }
+    }
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+
+    let result: Result<Vec<Delivery>, _> = sqlx::query_as(
+        "SELECT id, channel_id, alert_key, alert_type, title, message, status, error, attempts, \
+           created_at, last_attempt_at \
+         FROM notification_deliveries \
+         WHERE ($1::uuid IS NULL OR channel_id = $1) AND ($2::text IS NULL OR status = $2) \
+         ORDER BY last_attempt_at DESC, alert_key LIMIT $3",
+    )
+    .bind(params.channel_id)
+    .bind(&params.status)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await;
+
+    match result {
+        Ok(rows) => Json(serde_json::json!({ "data": rows })),
+        Err(e) => {
+            tracing::error!("Failed to list notification deliveries: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
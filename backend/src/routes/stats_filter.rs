@@ -0,0 +1,143 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use sqlx::{postgres::PgArguments, query::QueryAs, Postgres};
+
+/// Raw query string: list parameters are comma-separated (`?cards=amex,citi`).
+#[derive(Debug, Deserialize)]
+pub struct StatsFilterQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub cards: Option<String>,
+    pub categories: Option<String>,
+    pub exclude_categories: Option<String>,
+    pub member: Option<String>,
+}
+
+/// Scope shared by every `/stats` endpoint.
+///
+/// Queries built with [`StatsFilter::sql`] read from a `spending` CTE that applies the
+/// filter to `spending_home` and carries an `as_of` column: the end date (or today),
+/// which stands in for `CURRENT_DATE` in "this month" and "last 90 days" windows.
+/// The filter occupies `$1`–`$7`, so a query's own parameters start at `$8`.
+#[derive(Debug, Clone, Default)]
+pub struct StatsFilter {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub cards: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub exclude_categories: Option<Vec<String>>,
+    pub member: Option<String>,
+}
+
+fn split_list(value: Option<String>) -> Option<Vec<String>> {
+    let items: Vec<String> = value?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+impl From<StatsFilterQuery> for StatsFilter {
+    fn from(q: StatsFilterQuery) -> Self {
+        StatsFilter {
+            start_date: q.start_date,
+            end_date: q.end_date,
+            cards: split_list(q.cards),
+            categories: split_list(q.categories),
+            exclude_categories: split_list(q.exclude_categories),
+            member: q.member.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for StatsFilter {
+    type Rejection = <Query<StatsFilterQuery> as FromRequestParts<S>>::Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<StatsFilterQuery>::from_request_parts(parts, state).await?;
+        Ok(query.into())
+    }
+}
+
+/// Rows from a card with a member column whose member is `param`, ignoring case and
+/// surrounding whitespace. `s` is the row being filtered.
+pub(crate) fn member_condition(param: &str) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM cards c \
+           WHERE c.code = s.card AND c.member_column IS NOT NULL \
+             AND LOWER(TRIM(s.raw_data->>c.member_column)) = LOWER(TRIM({param})))"
+    )
+}
+
+fn filter_conditions() -> String {
+    format!(
+        "($1::date IS NULL OR s.date >= $1) \
+         AND ($2::date IS NULL OR s.date <= $2) \
+         AND ($3::text[] IS NULL OR s.card = ANY($3)) \
+         AND ($4::text[] IS NULL OR s.category = ANY($4)) \
+         AND ($5::text[] IS NULL OR NOT (s.category = ANY($5))) \
+         AND ($6::text IS NULL OR {})",
+        member_condition("$6")
+    )
+}
+
+impl StatsFilter {
+    /// Reference date for relative windows: the end of the range, never later than today.
+    pub fn as_of(&self) -> NaiveDate {
+        let today = chrono::Local::now().naive_local().date();
+        self.end_date.map_or(today, |end| end.min(today))
+    }
+
+    /// Whether anything beyond the end date narrows the spending.
+    pub fn is_scoped(&self) -> bool {
+        self.start_date.is_some()
+            || self.cards.is_some()
+            || self.categories.is_some()
+            || self.exclude_categories.is_some()
+            || self.member.is_some()
+    }
+
+    /// Prefix `body` with the filtered `spending` CTE over `spending_home`.
+    pub fn sql(&self, body: &str) -> String {
+        self.sql_from("spending_home", body)
+    }
+
+    /// Prefix `body` with the filtered `spending` CTE over `base`. A body that starts
+    /// with its own `WITH` gets the CTE prepended to its list.
+    pub fn sql_from(&self, base: &str, body: &str) -> String {
+        let cte = format!(
+            "spending AS (SELECT s.*, $7::date AS as_of FROM {base} s WHERE {})",
+            filter_conditions()
+        );
+        match body.strip_prefix("WITH ") {
+            Some(rest) => format!("WITH {cte}, {rest}"),
+            None => format!("WITH {cte} {body}"),
+        }
+    }
+
+    /// Bind the filter's `$1`–`$7`.
+    pub fn bind<'q, O>(
+        &'q self,
+        query: QueryAs<'q, Postgres, O, PgArguments>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        query
+            .bind(self.start_date)
+            .bind(self.end_date)
+            .bind(&self.cards)
+            .bind(&self.categories)
+            .bind(&self.exclude_categories)
+            .bind(&self.member)
+            .bind(self.as_of())
+    }
+}
@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::subscription::{
+    CancelSubscription, SubscriptionQuery, UpdateSubscription, SUBSCRIPTION_STATUSES,
+};
+use crate::routes::analytics::load_thresholds;
+use crate::services::subscriptions;
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/subscriptions", get(list_subscriptions))
+        .route("/subscriptions/sync", post(sync_subscriptions))
+        .route("/subscriptions/alerts", get(list_alerts))
+        .route("/subscriptions/:id", patch(update_subscription))
+        .route("/subscriptions/:id/confirm", post(confirm_subscription))
+        .route("/subscriptions/:id/ignore", post(ignore_subscription))
+        .route("/subscriptions/:id/cancel", post(cancel_subscription))
+}
+
+fn today() -> chrono::NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
+
+async fn list_subscriptions(
+    State(pool): State<PgPool>,
+    Query(params): Query<SubscriptionQuery>,
+) -> Json<serde_json::Value> {
+    if let Some(status) = &params.status {
+        if !SUBSCRIPTION_STATUSES.contains(&status.as_str()) {
+            return Json(serde_json::json!({
+                "error": format!("status must be one of: {}", SUBSCRIPTION_STATUSES.join(", "))
+            }));
+        }
+    }
+
+    match subscriptions::list_subscriptions(&pool, params.status.as_deref()).await {
+        Ok(subs) => Json(serde_json::json!({ "data": subs })),
+        Err(e) => {
+            tracing::error!("Failed to list subscriptions: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn sync_subscriptions(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    match subscriptions::sync_subscriptions(&pool, &t, today()).await {
+        Ok(summary) => Json(serde_json::json!({ "data": summary })),
+        Err(e) => {
+            tracing::error!("Failed to sync subscriptions: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn list_alerts(State(pool): State<PgPool>) -> Json<serde_json::Value> {
+    match subscriptions::current_alerts(&pool, today()).await {
+        Ok(alerts) => Json(serde_json::json!({ "data": alerts })),
+        Err(e) => {
+            tracing::error!("Failed to compute subscription alerts: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Set the status; anything but `cancelled` clears the cancellation date.
+async fn set_status(
+    pool: &PgPool,
+    id: Uuid,
+    status: &str,
+    cancelled_on: Option<chrono::NaiveDate>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query(
+        "UPDATE subscriptions SET status = $1, cancelled_on = $2, updated_at = NOW() WHERE id = $3",
+    )
+    .bind(status)
+    .bind(cancelled_on)
+    .bind(id)
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            Json(serde_json::json!({ "error": "Subscription not found" }))
+        }
+        Ok(_) => fetch_one(pool, id).await,
+        Err(e) => {
+            tracing::error!("Failed to set subscription {id} to {status}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn fetch_one(pool: &PgPool, id: Uuid) -> Json<serde_json::Value> {
+    match subscriptions::get_subscription(pool, id).await {
+        Ok(Some(sub)) => Json(serde_json::json!({ "data": sub })),
+        Ok(None) => Json(serde_json::json!({ "error": "Subscription not found" })),
+        Err(e) => {
+            tracing::error!("Failed to fetch subscription {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn confirm_subscription(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    set_status(&pool, id, "confirmed", None).await
+}
+
+/// Not a subscription: dropped from recurring totals and never alerted on.
+async fn ignore_subscription(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    set_status(&pool, id, "ignored", None).await
+}
+
+async fn cancel_subscription(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<CancelSubscription>>,
+) -> Json<serde_json::Value> {
+    let cancelled_on = body.and_then(|Json(b)| b.cancelled_on).unwrap_or_else(today);
+    set_status(&pool, id, "cancelled", Some(cancelled_on)).await
+}
+
+async fn update_subscription(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateSubscription>,
+) -> Json<serde_json::Value> {
+    if body.expected_amount.is_some_and(|a| a <= 0.0) {
+        return Json(serde_json::json!({ "error": "expected_amount must be positive" }));
+    }
+
+    let result = sqlx::query(
+        "UPDATE subscriptions SET expected_amount = $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(body.expected_amount)
+    .bind(id)
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            Json(serde_json::json!({ "error": "Subscription not found" }))
+        }
+        Ok(_) => fetch_one(&pool, id).await,
+        Err(e) => {
+            tracing::error!("Failed to update subscription {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
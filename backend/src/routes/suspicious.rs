@@ -0,0 +1,158 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+
+use crate::models::suspicious::{SuspiciousData, SuspiciousFinding, SuspiciousQuery};
+use crate::routes::analytics::load_thresholds;
+use crate::routes::fx::home_currency;
+use crate::routes::stats_filter::StatsFilter;
+use crate::services::analytics::Thresholds;
+use crate::services::suspicious::{self, SuspectCharge};
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/stats/suspicious", get(get_suspicious))
+        .route(
+            "/stats/suspicious/:id/dismiss",
+            post(dismiss_finding).delete(restore_finding),
+        )
+}
+
+/// Every finding in the filter's window, each marked with its dismiss state.
+pub(crate) async fn findings(
+    pool: &PgPool,
+    filter: &StatsFilter,
+    t: &Thresholds,
+) -> Result<Vec<SuspiciousFinding>, sqlx::Error> {
+    let charges: Vec<SuspectCharge> = filter
+        .bind(sqlx::query_as(&filter.sql(
+            "SELECT s.id, s.date, s.description, \
+               COALESCE(s.merchant_normalized, s.description) as merchant, s.card, \
+               s.amount::float8 as amount, s.currency, s.original_currency, \
+               COALESCE(c.currency, 'USD') as card_currency, s.raw_data \
+             FROM spending s LEFT JOIN cards c ON c.code = s.card \
+             WHERE s.date > s.as_of - $8 AND s.date <= s.as_of AND s.amount > 0 \
+             ORDER BY s.date, s.id",
+        )))
+        .bind(t.suspicious_window_days)
+        .fetch_all(pool)
+        .await?;
+
+    // First sightings come from all history, not just the filtered range
+    let first_seen: HashMap<String, NaiveDate> = sqlx::query_as(
+        "SELECT COALESCE(merchant_normalized, description), MIN(date) \
+         FROM spending_home GROUP BY 1",
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+    let history_start = first_seen
+        .values()
+        .min()
+        .copied()
+        .unwrap_or_else(|| filter.as_of());
+
+    let home = home_currency(pool).await;
+    let mut findings = suspicious::detect(&charges, &first_seen, history_start, &home, t);
+
+    let dismissed: HashSet<String> =
+        sqlx::query_scalar("SELECT finding_id FROM suspicious_dismissals")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    for finding in &mut findings {
+        finding.dismissed = dismissed.contains(&finding.id);
+    }
+    Ok(findings)
+}
+
+async fn get_suspicious(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Query(params): Query<SuspiciousQuery>,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    match findings(&pool, &filter, &t).await {
+        Ok(mut findings) => {
+            let dismissed_count = findings.iter().filter(|f| f.dismissed).count();
+            if !params.include_dismissed.unwrap_or(false) {
+                findings.retain(|f| !f.dismissed);
+            }
+            Json(serde_json::json!({
+                "data": SuspiciousData { findings, dismissed_count }
+            }))
+        }
+        Err(e) => {
+            tracing::error!("Failed to detect suspicious charges: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Mark a current finding as reviewed. The id must match a finding under the same
+/// filter, so stale or made-up ids are rejected.
+async fn dismiss_finding(
+    State(pool): State<PgPool>,
+    filter: StatsFilter,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    let t = load_thresholds(&pool).await;
+    let mut finding = match findings(&pool, &filter, &t).await {
+        Ok(findings) => match findings.into_iter().find(|f| f.id == id) {
+            Some(f) => f,
+            None => return Json(serde_json::json!({ "error": "Finding not found" })),
+        },
+        Err(e) => {
+            tracing::error!("Failed to detect suspicious charges: {e}");
+            return Json(serde_json::json!({ "error": e.to_string() }));
+        }
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO suspicious_dismissals (finding_id, kind) VALUES ($1, $2) \
+         ON CONFLICT (finding_id) DO NOTHING",
+    )
+    .bind(&finding.id)
+    .bind(&finding.kind)
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(_) => {
+            finding.dismissed = true;
+            Json(serde_json::json!({ "data": finding }))
+        }
+        Err(e) => {
+            tracing::error!("Failed to dismiss finding {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn restore_finding(
+    State(pool): State<PgPool>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    let result = sqlx::query("DELETE FROM suspicious_dismissals WHERE finding_id = $1")
+        .bind(&id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            Json(serde_json::json!({ "error": "Finding not dismissed" }))
+        }
+        Ok(_) => Json(serde_json::json!({ "data": { "id": id, "dismissed": false } })),
+        Err(e) => {
+            tracing::error!("Failed to restore finding {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
@@ -3,12 +3,21 @@ use axum::{
     routing::{get, patch, post},
     Json, Router,
 };
+use chrono::NaiveDate;
 use sqlx::PgPool;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 use crate::models::card::Card;
-use crate::models::transaction::{BulkCategoryUpdate, CategoryUpdate, TransactionQuery};
-use crate::services::{csv_parser, dedup};
+use crate::models::import::{Reconciliation, StatementSummary};
+use crate::models::transaction::{
+    BulkCategoryUpdate, CategoryUpdate, ClassificationUpdate, NewTransaction, TagsUpdate,
+    TransactionQuery, CLASSIFICATIONS,
+};
+use crate::routes::analytics::load_thresholds;
+use crate::routes::categories::{canonical, fetch_categories};
+use crate::routes::notifications;
+use crate::services::{categories, csv_parser, dedup, reconciliation, subscriptions, transfers};
 
 pub fn routes() -> Router<PgPool> {
     Router::new()
@@ -16,6 +25,8 @@ pub fn routes() -> Router<PgPool> {
         .route("/transactions/import", post(import_csv))
         .route("/transactions/bulk-category", patch(bulk_update_category))
         .route("/transactions/:id", patch(update_category))
+        .route("/transactions/:id/classification", patch(update_classification))
+        .route("/transactions/:id/tags", patch(update_tags))
 }
 
 async fn list_transactions(
@@ -50,6 +61,10 @@ async fn list_transactions(
         conditions.push(format!("category = ${}", bind_idx));
         bind_idx += 1;
     }
+    if params.classification.is_some() {
+        conditions.push(format!("classification = ${}", bind_idx));
+        bind_idx += 1;
+    }
     if params.start_date.is_some() {
         conditions.push(format!("date >= ${}", bind_idx));
         bind_idx += 1;
@@ -70,7 +85,8 @@ async fn list_transactions(
     };
 
     let data_sql = format!(
-        "SELECT id, date, description, amount::float8 as amount, category, card, card_label, raw_data, hash, created_at \
+        "SELECT id, date, description, amount::float8 as amount, category, card, card_label, raw_data, hash, created_at, \
+         currency, original_amount::float8 as original_amount, original_currency, classification, tags \
          FROM transactions {} ORDER BY {} {} LIMIT ${} OFFSET ${}",
         where_clause, sort_col, sort_dir, bind_idx, bind_idx + 1
     );
@@ -91,6 +107,10 @@ async fn list_transactions(
         data_query = data_query.bind(category);
         count_query = count_query.bind(category);
     }
+    if let Some(ref classification) = params.classification {
+        data_query = data_query.bind(classification);
+        count_query = count_query.bind(classification);
+    }
     if let Some(ref start_date) = params.start_date {
         data_query = data_query.bind(start_date);
         count_query = count_query.bind(start_date);
@@ -141,6 +161,7 @@ async fn import_csv(
     let mut file_name = String::from("upload.csv");
     let mut csv_data = String::new();
     let mut card_code: Option<String> = None;
+    let mut statement_fields: Vec<(String, String)> = Vec::new();
 
     while let Ok(Some(field)) = multipart.next_field().await {
         let name = field.name().unwrap_or("").to_string();
@@ -160,6 +181,13 @@ async fn import_csv(
                     }
                 }
             }
+            "statement_start" | "statement_end" | "opening_balance" | "closing_balance" => {
+                if let Ok(text) = field.text().await {
+                    if !text.trim().is_empty() {
+                        statement_fields.push((name, text.trim().to_string()));
+                    }
+                }
+            }
             _ => {
                 let _ = field.text().await;
             }
@@ -170,6 +198,42 @@ async fn import_csv(
         return Json(serde_json::json!({ "error": "No CSV data received" }));
     }
 
+    // Statement details typed in the upload form win over the file's summary block
+    let (mut statement, rest) = reconciliation::split_statement_summary(&csv_data);
+    let csv_data = rest.to_string();
+    for (name, value) in &statement_fields {
+        match name.as_str() {
+            "statement_start" | "statement_end" => {
+                let date = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    Ok(d) => d,
+                    Err(_) => {
+                        return Json(serde_json::json!({
+                            "error": format!("{name} must be a YYYY-MM-DD date")
+                        }));
+                    }
+                };
+                if name == "statement_start" {
+                    statement.period_start = Some(date);
+                } else {
+                    statement.period_end = Some(date);
+                }
+            }
+            _ => {
+                let balance = match value.replace([',', '$'], "").parse::<f64>() {
+                    Ok(b) => b,
+                    Err(_) => {
+                        return Json(serde_json::json!({ "error": format!("{name} must be a number") }));
+                    }
+                };
+                if name == "opening_balance" {
+                    statement.opening_balance = Some(balance);
+                } else {
+                    statement.closing_balance = Some(balance);
+                }
+            }
+        }
+    }
+
     let all_cards: Vec<Card> = match sqlx::query_as("SELECT * FROM cards ORDER BY created_at ASC")
         .fetch_all(&pool)
         .await
@@ -220,6 +284,16 @@ async fn import_csv(
     };
 
     let existing_hashes = dedup::get_existing_hashes(&pool).await;
+    // Categories the parser came up with that aren't listed file as Uncategorized. Without
+    // the list every row would, so the import stops instead
+    let known_categories = match fetch_categories(&pool).await {
+        Ok(all) => all,
+        Err(e) => {
+            tracing::error!("Failed to fetch categories for import: {e}");
+            return Json(serde_json::json!({ "error": format!("Failed to load categories: {e}") }));
+        }
+    };
+    let mut unlisted: BTreeMap<String, i32> = BTreeMap::new();
 
     let mut new_count = 0i32;
     let mut dup_count = 0i32;
@@ -249,25 +323,36 @@ async fn import_csv(
             continue;
         }
 
+        let category = categories::resolve(&txn.category, &known_categories);
         let result = sqlx::query(
-            "INSERT INTO transactions (date, description, amount, category, card, card_label, raw_data, hash, merchant_normalized, import_id) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            "INSERT INTO transactions (date, description, amount, category, card, card_label, raw_data, hash, merchant_normalized, import_id, \
+             currency, original_amount, original_currency, classification) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
         )
         .bind(txn.date)
         .bind(&txn.description)
         .bind(txn.amount)
-        .bind(&txn.category)
+        .bind(&category)
         .bind(&txn.card)
         .bind(&txn.card_label)
         .bind(&txn.raw_data)
         .bind(&txn.hash)
         .bind(&txn.merchant_normalized)
         .bind(import_id)
+        .bind(&txn.currency)
+        .bind(txn.original_amount)
+        .bind(&txn.original_currency)
+        .bind(&txn.classification)
         .execute(&pool)
         .await;
 
         match result {
-            Ok(_) => new_count += 1,
+            Ok(_) => {
+                new_count += 1;
+                if categories::find(&txn.category, &known_categories).is_none() {
+                    *unlisted.entry(txn.category.clone()).or_default() += 1;
+                }
+            }
             Err(e) => tracing::warn!("Failed to insert transaction: {}", e),
         }
     }
@@ -287,6 +372,40 @@ async fn import_csv(
         }
     }
 
+    let reconciliation = match import_id {
+        Some(id) => reconcile_import(&pool, id, &card, &statement, &parse_result.transactions).await,
+        None => None,
+    };
+
+    // The other side of a transfer may already be imported from another account
+    let transfer_suggestions = if new_count > 0 {
+        transfers::suggest_transfers(&pool, transfers::DEFAULT_WINDOW_DAYS)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to match transfers after import: {e}");
+                0
+            })
+    } else {
+        0
+    };
+
+    // New charges may start a subscription or move a known one forward
+    if new_count > 0 {
+        let t = load_thresholds(&pool).await;
+        let today = chrono::Local::now().naive_local().date();
+        if let Err(e) = subscriptions::sync_subscriptions(&pool, &t, today).await {
+            tracing::error!("Failed to sync subscriptions after import: {e}");
+        }
+        // ...and may cross a budget threshold or raise a subscription alert. Channels can be
+        // slow, so alerts go out in the background rather than holding up the response
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifications::notify(&pool, today).await {
+                tracing::error!("Failed to send notifications after import: {e}");
+            }
+        });
+    }
+
     Json(serde_json::json!({
         "data": {
             "card": card.code,
@@ -295,11 +414,74 @@ async fn import_csv(
             "new_count": new_count,
             "duplicate_count": dup_count,
             "skipped_user_count": parse_result.skipped_user_count,
-            "total_parsed": total_parsed
+            "total_parsed": total_parsed,
+            "transfer_suggestions": transfer_suggestions,
+            "unlisted_categories": unlisted
+                .into_iter()
+                .map(|(category, count)| serde_json::json!({ "category": category, "count": count }))
+                .collect::<Vec<_>>(),
+            "reconciliation": reconciliation
         }
     }))
 }
 
+/// Reconcile an import against the ledger and store the result on its history record.
+///
+/// The ledger is read after this import's rows are stored, so it holds them too: a
+/// statement day only comes up short when its rows failed to insert. What the check
+/// catches is stored rows the statement doesn't list (an overlapping export, a manual
+/// entry) and, with balances, a movement the stored rows don't add up to.
+async fn reconcile_import(
+    pool: &PgPool,
+    import_id: Uuid,
+    card: &Card,
+    statement: &StatementSummary,
+    rows: &[NewTransaction],
+) -> Option<Reconciliation> {
+    let period_start = statement.period_start.or(rows.iter().map(|t| t.date).min())?;
+    let period_end = statement.period_end.or(rows.iter().map(|t| t.date).max())?;
+
+    let statement_days: Vec<(NaiveDate, f64)> = rows.iter().map(|t| (t.date, t.amount)).collect();
+    let ledger_days: Vec<(NaiveDate, f64)> = sqlx::query_as(
+        "SELECT date, SUM(amount::float8) FROM transactions \
+         WHERE card = $1 AND date BETWEEN $2 AND $3 GROUP BY date ORDER BY date",
+    )
+    .bind(&card.code)
+    .bind(period_start)
+    .bind(period_end)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let result = reconciliation::reconcile(
+        &card.account_type,
+        period_start,
+        period_end,
+        statement.opening_balance,
+        statement.closing_balance,
+        &statement_days,
+        &ledger_days,
+    );
+
+    if let Err(e) = sqlx::query(
+        "UPDATE import_history SET statement_start = $1, statement_end = $2, opening_balance = $3, \
+         closing_balance = $4, reconciliation = $5 WHERE id = $6",
+    )
+    .bind(period_start)
+    .bind(period_end)
+    .bind(statement.opening_balance)
+    .bind(statement.closing_balance)
+    .bind(serde_json::to_value(&result).ok())
+    .bind(import_id)
+    .execute(pool)
+    .await
+    {
+        tracing::error!("Failed to store reconciliation for import {import_id}: {e}");
+    }
+
+    Some(result)
+}
+
 async fn delete_all(State(pool): State<PgPool>) -> Json<serde_json::Value> {
     if let Err(e) = sqlx::query("DELETE FROM transactions")
         .execute(&pool)
@@ -321,8 +503,12 @@ async fn update_category(
     Path(id): Path<Uuid>,
     Json(body): Json<CategoryUpdate>,
 ) -> Json<serde_json::Value> {
+    let category = match canonical(&pool, &body.category).await {
+        Ok(category) => category,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
     let result = sqlx::query("UPDATE transactions SET category = $1 WHERE id = $2")
-        .bind(&body.category)
+        .bind(&category)
         .bind(id)
         .execute(&pool)
         .await;
@@ -336,12 +522,74 @@ async fn update_category(
     }
 }
 
+async fn update_classification(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ClassificationUpdate>,
+) -> Json<serde_json::Value> {
+    if !CLASSIFICATIONS.contains(&body.classification.as_str()) {
+        return Json(serde_json::json!({
+            "error": format!("classification must be one of: {}", CLASSIFICATIONS.join(", "))
+        }));
+    }
+
+    let result = sqlx::query("UPDATE transactions SET classification = $1 WHERE id = $2")
+        .bind(&body.classification)
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => {
+            Json(serde_json::json!({ "data": "Classification updated" }))
+        }
+        Ok(_) => Json(serde_json::json!({ "error": "Transaction not found" })),
+        Err(e) => {
+            tracing::error!("Failed to update classification for transaction {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// Replace a transaction's tags. Tags are trimmed, and blanks and repeats dropped.
+async fn update_tags(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<TagsUpdate>,
+) -> Json<serde_json::Value> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in body.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    let result = sqlx::query("UPDATE transactions SET tags = $1 WHERE id = $2")
+        .bind(&tags)
+        .bind(id)
+        .execute(&pool)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => Json(serde_json::json!({ "data": tags })),
+        Ok(_) => Json(serde_json::json!({ "error": "Transaction not found" })),
+        Err(e) => {
+            tracing::error!("Failed to update tags for transaction {id}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
 async fn bulk_update_category(
     State(pool): State<PgPool>,
     Json(body): Json<BulkCategoryUpdate>,
 ) -> Json<serde_json::Value> {
+    let category = match canonical(&pool, &body.category).await {
+        Ok(category) => category,
+        Err(e) => return Json(serde_json::json!({ "error": e })),
+    };
     let result = sqlx::query("UPDATE transactions SET category = $1 WHERE id = ANY($2)")
-        .bind(&body.category)
+        .bind(&category)
         .bind(&body.ids)
         .execute(&pool)
         .await;
@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::transfer::{
+    TransferMatchQuery, TransferPair, TransferPairDetail, TransferQuery, TRANSFER_STATUSES,
+};
+use crate::services::transfers;
+
+pub fn routes() -> Router<PgPool> {
+    Router::new()
+        .route("/transfers", get(list_transfers))
+        .route("/transfers/match", post(match_transfers))
+        .route("/transfers/:id/confirm", post(confirm_transfer))
+        .route("/transfers/:id", delete(unlink_transfer))
+}
+
+async fn list_transfers(
+    State(pool): State<PgPool>,
+    Query(params): Query<TransferQuery>,
+) -> Json<serde_json::Value> {
+    if let Some(status) = &params.status {
+        if !TRANSFER_STATUSES.contains(&status.as_str()) {
+            return Json(serde_json::json!({
+                "error": format!("status must be one of: {}", TRANSFER_STATUSES.join(", "))
+            }));
+        }
+    }
+
+    let pairs: Vec<TransferPairDetail> = match sqlx::query_as(
+        "SELECT p.id, p.status, d.amount::float8 as amount, \
+           d.id as debit_id, d.date as debit_date, d.card as debit_card, d.description as debit_description, \
+           c.id as credit_id, c.date as credit_date, c.card as credit_card, c.description as credit_description, \
+           p.created_at \
+         FROM transfer_pairs p \
+         JOIN transactions d ON d.id = p.debit_id \
+         JOIN transactions c ON c.id = p.credit_id \
+         WHERE ($1::text IS NULL AND p.status <> 'unlinked') OR p.status = $1 \
+         ORDER BY d.date DESC",
+    )
+    .bind(&params.status)
+    .fetch_all(&pool)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to list transfers: {e}");
+            Vec::new()
+        }
+    };
+
+    Json(serde_json::json!({ "data": pairs }))
+}
+
+async fn match_transfers(
+    State(pool): State<PgPool>,
+    Query(params): Query<TransferMatchQuery>,
+) -> Json<serde_json::Value> {
+    let window_days = params
+        .window_days
+        .unwrap_or(transfers::DEFAULT_WINDOW_DAYS)
+        .clamp(0, 31);
+
+    match transfers::suggest_transfers(&pool, window_days).await {
+        Ok(count) => Json(serde_json::json!({
+            "data": { "suggested": count, "window_days": window_days }
+        })),
+        Err(e) => {
+            tracing::error!("Failed to match transfers: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn set_status(pool: &PgPool, id: Uuid, status: &str) -> Json<serde_json::Value> {
+    let result: Result<Option<TransferPair>, _> = sqlx::query_as(
+        "UPDATE transfer_pairs SET status = $1, updated_at = NOW() WHERE id = $2 RETURNING *",
+    )
+    .bind(status)
+    .bind(id)
+    .fetch_optional(pool)
+    .await;
+
+    match result {
+        Ok(Some(pair)) => Json(serde_json::json!({ "data": pair })),
+        Ok(None) => Json(serde_json::json!({ "error": "Transfer pair not found" })),
+        Err(e) => {
+            tracing::error!("Failed to set transfer {id} to {status}: {e}");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn confirm_transfer(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    set_status(&pool, id, "confirmed").await
+}
+
+/// Unlinked pairs are kept so the matcher doesn't suggest them again.
+async fn unlink_transfer(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Json<serde_json::Value> {
+    set_status(&pool, id, "unlinked").await
+}
@@ -0,0 +1,1382 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::analytics::{
+    CardImpact, CashflowMonth, CategoryAnomaly, CategoryCreep, ImpulseSpending, MerchantAnomaly,
+    MerchantConcentration, PredictedCharge, RecurringTransaction, SubscriptionBloat,
+    TransactionAnomaly, WeekendSplurge,
+};
+use crate::services::balances;
+
+/// Tunable cut-offs shared by the stats endpoints and the insights engine.
+///
+/// Stored as JSON under the `analytics_settings` config key; missing fields take
+/// their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    /// Purchases under this amount count as impulse spending
+    pub impulse_amount: f64,
+    /// Look-back window for impulse, weekend and concentration analysis
+    pub habit_window_days: i32,
+    /// Small-purchase share (%) for the high / moderate / low impulse labels
+    pub impulse_pct_bands: [f64; 3],
+    /// z-scores for the elevated / high / critical anomaly severities
+    pub anomaly_z_bands: [f64; 3],
+    /// A transaction this many times its category's median is an outlier
+    pub transaction_outlier_multiple: f64,
+    /// First charges at a new merchant below this amount aren't reported
+    pub new_merchant_min_amount: f64,
+    /// Past charges a merchant needs before an amount can be unusual for it
+    pub merchant_min_charges: usize,
+    /// Minimum charges before a weekly, biweekly or monthly series is recurring
+    /// (quarterly and annual series need two)
+    pub recurring_min_charges: usize,
+    /// Amount tolerance within one price cluster, as a fraction of the average
+    pub recurring_max_cv: f64,
+    /// Maximum gap standard deviation, as a fraction of the median gap
+    pub recurring_max_gap_cv: f64,
+    /// Days without a charge before a monthly series is inactive; other cadences scale
+    pub inactive_after_days: i64,
+    /// Change (%) between the older and newer half of the window that counts as creep
+    pub creep_pct: f64,
+    /// Months of history a category needs before creep is measured
+    pub creep_min_months: usize,
+    /// Weekend / weekday daily ratio for the high / moderate / slight labels
+    pub weekend_ratio_bands: [f64; 3],
+    /// Herfindahl index for the high / moderate / mild concentration labels
+    pub hhi_bands: [f64; 3],
+    /// Smoothing factor for the EWMA forecast
+    pub ewma_alpha: f64,
+    /// Look-back window for suspicious-charge detection
+    pub suspicious_window_days: i32,
+    /// Repeats of the same amount at a merchant within this many days are duplicates
+    pub duplicate_window_days: i64,
+    /// Repeated charges below this amount aren't reported as duplicates
+    pub duplicate_min_amount: f64,
+    /// Charges up to this amount can be card tests
+    pub test_charge_max: f64,
+    /// Days after a small charge in which a large one marks it as a card test
+    pub test_charge_window_days: i64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            impulse_amount: 15.0,
+            habit_window_days: 90,
+            impulse_pct_bands: [50.0, 35.0, 20.0],
+            anomaly_z_bands: [1.5, 2.0, 3.0],
+            transaction_outlier_multiple: 2.0,
+            new_merchant_min_amount: 50.0,
+            merchant_min_charges: 3,
+            recurring_min_charges: 3,
+            recurring_max_cv: 0.2,
+            recurring_max_gap_cv: 0.25,
+            inactive_after_days: 45,
+            creep_pct: 15.0,
+            creep_min_months: 4,
+            weekend_ratio_bands: [2.0, 1.5, 1.2],
+            hhi_bands: [0.25, 0.15, 0.10],
+            ewma_alpha: 0.3,
+            suspicious_window_days: 90,
+            duplicate_window_days: 3,
+            duplicate_min_amount: 10.0,
+            test_charge_max: 2.0,
+            test_charge_window_days: 3,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Reject settings that would make the detectors meaningless.
+    pub fn validate(&self) -> Result<(), String> {
+        fn descending(name: &str, bands: &[f64; 3]) -> Result<(), String> {
+            if bands[0] > bands[1] && bands[1] > bands[2] && bands[2] > 0.0 {
+                Ok(())
+            } else {
+                Err(format!("{name} must be positive and strictly descending"))
+            }
+        }
+
+        if self.impulse_amount <= 0.0 {
+            return Err("impulse_amount must be positive".into());
+        }
+        if !(7..=730).contains(&self.habit_window_days) {
+            return Err("habit_window_days must be between 7 and 730".into());
+        }
+        descending("impulse_pct_bands", &self.impulse_pct_bands)?;
+        if self.impulse_pct_bands[0] > 100.0 {
+            return Err("impulse_pct_bands must not exceed 100".into());
+        }
+        let [elevated, high, critical] = self.anomaly_z_bands;
+        if !(elevated > 0.0 && high > elevated && critical > high) {
+            return Err("anomaly_z_bands must be positive and strictly ascending".into());
+        }
+        if self.transaction_outlier_multiple <= 1.0 {
+            return Err("transaction_outlier_multiple must be greater than 1".into());
+        }
+        if self.new_merchant_min_amount < 0.0 {
+            return Err("new_merchant_min_amount must not be negative".into());
+        }
+        if self.merchant_min_charges < 2 {
+            return Err("merchant_min_charges must be at least 2".into());
+        }
+        if self.recurring_min_charges < 3 {
+            return Err("recurring_min_charges must be at least 3".into());
+        }
+        if !(self.recurring_max_cv > 0.0 && self.recurring_max_cv <= 1.0) {
+            return Err("recurring_max_cv must be between 0 and 1".into());
+        }
+        if !(self.recurring_max_gap_cv > 0.0 && self.recurring_max_gap_cv <= 1.0) {
+            return Err("recurring_max_gap_cv must be between 0 and 1".into());
+        }
+        if self.inactive_after_days <= 31 {
+            return Err("inactive_after_days must be more than 31".into());
+        }
+        if self.creep_pct <= 0.0 {
+            return Err("creep_pct must be positive".into());
+        }
+        if self.creep_min_months < 2 {
+            return Err("creep_min_months must be at least 2".into());
+        }
+        descending("weekend_ratio_bands", &self.weekend_ratio_bands)?;
+        descending("hhi_bands", &self.hhi_bands)?;
+        if self.hhi_bands[0] > 1.0 {
+            return Err("hhi_bands must not exceed 1".into());
+        }
+        if !(self.ewma_alpha > 0.0 && self.ewma_alpha <= 1.0) {
+            return Err("ewma_alpha must be in (0, 1]".into());
+        }
+        if !(7..=730).contains(&self.suspicious_window_days) {
+            return Err("suspicious_window_days must be between 7 and 730".into());
+        }
+        if !(0..=31).contains(&self.duplicate_window_days) {
+            return Err("duplicate_window_days must be between 0 and 31".into());
+        }
+        if self.duplicate_min_amount < 0.0 {
+            return Err("duplicate_min_amount must not be negative".into());
+        }
+        if self.test_charge_max <= 0.0 {
+            return Err("test_charge_max must be positive".into());
+        }
+        if !(1..=31).contains(&self.test_charge_window_days) {
+            return Err("test_charge_window_days must be between 1 and 31".into());
+        }
+        Ok(())
+    }
+}
+
+/// One charge, as read for recurring detection.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Charge {
+    pub merchant: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub card: String,
+}
+
+/// A category's spending in one completed month: the full month, and through the same
+/// day of the month as the reference date.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CategoryMonth {
+    pub category: String,
+    pub month: NaiveDate,
+    pub total: f64,
+    pub to_date: f64,
+}
+
+/// Median and spread of a category's completed months, for full months and month-to-date.
+#[derive(Debug, Clone)]
+pub struct CategoryBaseline {
+    pub category: String,
+    pub monthly: RobustStats,
+    pub to_date: RobustStats,
+}
+
+/// A charge in the reference month, checked against category and merchant history.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MonthCharge {
+    pub id: uuid::Uuid,
+    pub date: NaiveDate,
+    pub description: String,
+    pub merchant: String,
+    pub category: String,
+    pub amount: f64,
+}
+
+/// Transaction counts over the habit window, split at the impulse amount.
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct SmallPurchaseStats {
+    pub total_count: i32,
+    pub small_count: i32,
+    pub small_total: f64,
+    pub avg_small: f64,
+}
+
+// ── Projections ──
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+    .unwrap()
+    .signed_duration_since(NaiveDate::from_ymd_opt(year, month, 1).unwrap())
+    .num_days() as u32
+}
+
+pub fn linear_projection(spent: f64, days_elapsed: u32, days_in_month: u32) -> f64 {
+    if days_elapsed == 0 {
+        return 0.0;
+    }
+    (spent / days_elapsed as f64) * days_in_month as f64
+}
+
+pub fn ewma(monthly_totals: &[f64], alpha: f64) -> f64 {
+    if monthly_totals.is_empty() {
+        return 0.0;
+    }
+    let mut result = monthly_totals[0];
+    for &total in &monthly_totals[1..] {
+        result = alpha * total + (1.0 - alpha) * result;
+    }
+    result
+}
+
+// ── Cashflow ──
+
+pub fn savings_rate(income: f64, expenses: f64) -> Option<f64> {
+    if income > 0.0 {
+        Some((income - expenses) / income * 100.0)
+    } else {
+        None
+    }
+}
+
+/// Aggregate savings rate over completed months that had income.
+pub fn completed_savings_rate(months: &[CashflowMonth], current_month: &str) -> Option<f64> {
+    let (income, expenses) = months
+        .iter()
+        .filter(|m| m.month.as_str() < current_month && m.income > 0.0)
+        .fold((0.0, 0.0), |(i, e), m| (i + m.income, e + m.expenses));
+    savings_rate(income, expenses)
+}
+
+// ── Recurring ──
+
+/// A billing cadence and the median gaps (in days) classified as it.
+#[derive(Debug)]
+pub struct Cadence {
+    pub name: &'static str,
+    pub days: f64,
+    pub per_year: f64,
+    min_gap: f64,
+    max_gap: f64,
+    /// Calendar months per period; 0 for the day-based cadences
+    months: u32,
+}
+
+pub const CADENCES: [Cadence; 5] = [
+    Cadence { name: "weekly", days: 7.0, per_year: 52.0, min_gap: 5.0, max_gap: 9.0, months: 0 },
+    Cadence { name: "biweekly", days: 14.0, per_year: 26.0, min_gap: 12.0, max_gap: 17.0, months: 0 },
+    Cadence { name: "monthly", days: 30.44, per_year: 12.0, min_gap: 26.0, max_gap: 35.0, months: 1 },
+    Cadence { name: "quarterly", days: 91.3, per_year: 4.0, min_gap: 80.0, max_gap: 100.0, months: 3 },
+    Cadence { name: "annual", days: 365.25, per_year: 1.0, min_gap: 340.0, max_gap: 390.0, months: 12 },
+];
+
+pub fn cadence(name: &str) -> Option<&'static Cadence> {
+    CADENCES.iter().find(|c| c.name == name)
+}
+
+impl Cadence {
+    /// The `n`th charge date after `date`. Month-based cadences keep the day of month.
+    pub fn nth_after(&self, date: NaiveDate, n: u32) -> NaiveDate {
+        if self.months > 0 {
+            if let Some(next) = date.checked_add_months(Months::new(self.months * n)) {
+                return next;
+            }
+        }
+        date + Duration::days((self.days * n as f64).round() as i64)
+    }
+
+    /// Quarterly and annual series can't be expected to show many charges.
+    fn min_charges(&self, t: &Thresholds) -> usize {
+        if self.days >= 90.0 {
+            2
+        } else {
+            t.recurring_min_charges
+        }
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.is_empty() {
+        0.0
+    } else if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Mean and sample standard deviation.
+fn mean_stddev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    (mean, var.sqrt())
+}
+
+/// Split a merchant's charges into price clusters, so two plans at the same merchant are
+/// analysed as separate series. In date order, a charge joins the cluster whose opening amount
+/// is nearest and within `tolerance` of it. A cluster that only starts after the previous one
+/// ends is a price change, not a second plan, and is merged into it; amounts that wander back
+/// and forth keep their clusters interleaved and stay split. Each cluster is returned in date
+/// order, one charge per day.
+fn amount_clusters<'a>(charges: &[&'a Charge], tolerance: f64) -> Vec<Vec<&'a Charge>> {
+    let mut by_date = charges.to_vec();
+    by_date.sort_by_key(|c| c.date);
+
+    let mut clusters: Vec<Vec<&Charge>> = Vec::new();
+    for charge in by_date {
+        let drift = |cluster: &Vec<&Charge>| {
+            let opening = cluster[0].amount;
+            (charge.amount - opening).abs() / opening.max(0.01)
+        };
+        let nearest = clusters
+            .iter_mut()
+            .map(|cluster| (drift(cluster), cluster))
+            .filter(|(d, _)| *d <= tolerance)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        match nearest {
+            Some((_, cluster)) => cluster.push(charge),
+            None => clusters.push(vec![charge]),
+        }
+    }
+
+    let mut merged: Vec<Vec<&Charge>> = Vec::new();
+    for cluster in clusters {
+        match merged.last_mut() {
+            Some(prev) if cluster[0].date > prev[prev.len() - 1].date => prev.extend(cluster),
+            _ => merged.push(cluster),
+        }
+    }
+    for cluster in &mut merged {
+        cluster.dedup_by_key(|c| c.date);
+    }
+    merged
+}
+
+/// Interval analysis of one price cluster: classify the median gap, require regular gaps
+/// and a steady amount, and predict the next charge.
+fn analyze_series(
+    merchant: &str,
+    charges: &[&Charge],
+    today: NaiveDate,
+    t: &Thresholds,
+) -> Option<RecurringTransaction> {
+    if charges.len() < 2 {
+        return None;
+    }
+    let gaps: Vec<f64> = charges
+        .windows(2)
+        .map(|w| (w[1].date - w[0].date).num_days() as f64)
+        .collect();
+    let median_gap = median(&gaps);
+    let cadence = CADENCES
+        .iter()
+        .find(|c| (c.min_gap..=c.max_gap).contains(&median_gap))?;
+    if charges.len() < cadence.min_charges(t) {
+        return None;
+    }
+
+    let (_, gap_stddev) = mean_stddev(&gaps);
+    let gap_cv = gap_stddev / median_gap;
+    if gap_cv > t.recurring_max_gap_cv {
+        return None;
+    }
+
+    let amounts: Vec<f64> = charges.iter().map(|c| c.amount).collect();
+    let (avg_amount, amount_stddev) = mean_stddev(&amounts);
+    if avg_amount < 0.01 {
+        return None;
+    }
+    let amount_cv = amount_stddev / avg_amount;
+    if amount_cv > t.recurring_max_cv {
+        return None;
+    }
+
+    let first = charges[0];
+    let last = charges[charges.len() - 1];
+    let last_gap_days = (today - last.date).num_days();
+    let allowed_silence = cadence.days * t.inactive_after_days as f64 / 30.44;
+    let active = last_gap_days as f64 <= allowed_silence;
+
+    let mut months: Vec<(i32, u32)> = charges.iter().map(|c| (c.date.year(), c.date.month())).collect();
+    months.dedup();
+
+    let regularity = (1.0 - gap_cv / t.recurring_max_gap_cv).clamp(0.0, 1.0);
+    let stability = (1.0 - amount_cv / t.recurring_max_cv).clamp(0.0, 1.0);
+    let history = (charges.len() as f64 / (cadence.min_charges(t) * 2) as f64).min(1.0);
+    let estimated_annual = last.amount * cadence.per_year;
+
+    Some(RecurringTransaction {
+        merchant: merchant.to_string(),
+        avg_amount,
+        frequency: cadence.name.to_string(),
+        active_months: months.len() as i32,
+        first_seen: first.date,
+        last_seen: last.date,
+        estimated_annual,
+        status: if active { "active" } else { "inactive" }.to_string(),
+        last_gap_days,
+        potentially_forgotten: !active && months.len() >= 3,
+        charge_count: charges.len() as i32,
+        median_gap_days: median_gap,
+        gap_stddev_days: gap_stddev,
+        monthly_amount: estimated_annual / 12.0,
+        card: last.card.clone(),
+        next_expected_date: cadence.nth_after(last.date, 1),
+        next_expected_amount: last.amount,
+        confidence: 0.5 * regularity + 0.2 * stability + 0.3 * history,
+    })
+}
+
+/// Recurring series found by interval analysis of each merchant's charges, largest
+/// monthly cost first. A merchant can yield one series per price cluster.
+pub fn detect_recurring(charges: &[Charge], today: NaiveDate, t: &Thresholds) -> Vec<RecurringTransaction> {
+    let mut by_merchant: BTreeMap<&str, Vec<&Charge>> = BTreeMap::new();
+    for charge in charges.iter().filter(|c| c.amount > 0.0) {
+        by_merchant.entry(charge.merchant.as_str()).or_default().push(charge);
+    }
+
+    let mut recurring: Vec<RecurringTransaction> = by_merchant
+        .into_iter()
+        .flat_map(|(merchant, charges)| {
+            amount_clusters(&charges, t.recurring_max_cv)
+                .into_iter()
+                .filter_map(move |cluster| analyze_series(merchant, &cluster, today, t))
+        })
+        .collect();
+    recurring.sort_by(|a, b| b.monthly_amount.total_cmp(&a.monthly_amount));
+    recurring
+}
+
+fn predicted(r: &RecurringTransaction, date: NaiveDate, overdue: bool) -> PredictedCharge {
+    PredictedCharge {
+        merchant: r.merchant.clone(),
+        date,
+        amount: r.next_expected_amount,
+        card: r.card.clone(),
+        frequency: r.frequency.clone(),
+        confidence: r.confidence,
+        overdue,
+    }
+}
+
+/// Predicted charges of a recurring series falling within `from..=to`.
+pub fn predicted_charges(r: &RecurringTransaction, from: NaiveDate, to: NaiveDate) -> Vec<PredictedCharge> {
+    let Some(cadence) = cadence(&r.frequency) else {
+        return Vec::new();
+    };
+    (1..)
+        .map(|n| cadence.nth_after(r.last_seen, n))
+        .skip_while(|date| *date < from)
+        .take_while(|date| *date <= to)
+        .map(|date| predicted(r, date, false))
+        .collect()
+}
+
+/// Charges the active series will make within `from..=to`, by date. A series whose next
+/// charge is already past due (but still within its grace period) is listed first, on
+/// its expected date, marked overdue.
+pub fn upcoming_charges(recurring: &[RecurringTransaction], from: NaiveDate, to: NaiveDate) -> Vec<PredictedCharge> {
+    let mut charges: Vec<PredictedCharge> = recurring
+        .iter()
+        .filter(|r| r.status == "active")
+        .flat_map(|r| {
+            let overdue = (r.next_expected_date < from).then(|| predicted(r, r.next_expected_date, true));
+            overdue.into_iter().chain(predicted_charges(r, from, to))
+        })
+        .collect();
+    charges.sort_by(|a, b| a.date.cmp(&b.date).then(a.merchant.cmp(&b.merchant)));
+    charges
+}
+
+/// Per-card totals of upcoming charges, and how the ones due by `month_end` move each
+/// account's balance (`account_types` maps card code to account type).
+pub fn card_impacts(
+    charges: &[PredictedCharge],
+    month_end: NaiveDate,
+    account_types: &HashMap<String, String>,
+) -> Vec<CardImpact> {
+    let mut by_card: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for charge in charges {
+        let entry = by_card.entry(charge.card.as_str()).or_default();
+        entry.0 += charge.amount;
+        if charge.date <= month_end {
+            entry.1 += charge.amount;
+        }
+    }
+    by_card
+        .into_iter()
+        .map(|(card, (total, due_by_month_end))| {
+            let account_type = account_types.get(card).cloned().unwrap_or_else(|| "credit".to_string());
+            CardImpact {
+                card: card.to_string(),
+                balance_change_by_month_end: due_by_month_end * balances::balance_sign(&account_type),
+                account_type,
+                total,
+                due_by_month_end,
+            }
+        })
+        .collect()
+}
+
+/// Monthly cost of active recurring charges, what they will charge in the next 30 days,
+/// and the ones that stopped.
+pub fn subscription_bloat(recurring: &[RecurringTransaction], today: NaiveDate) -> SubscriptionBloat {
+    let active: Vec<&RecurringTransaction> =
+        recurring.iter().filter(|r| r.status == "active").collect();
+    let total_monthly: f64 = active.iter().map(|r| r.monthly_amount).sum();
+    let count = active.len() as i32;
+
+    let horizon = today + Duration::days(30);
+    let mut next_charges: Vec<PredictedCharge> = active
+        .iter()
+        .flat_map(|r| predicted_charges(r, today, horizon))
+        .collect();
+    next_charges.sort_by(|a, b| a.date.cmp(&b.date).then(a.merchant.cmp(&b.merchant)));
+    let next_30_days = next_charges.iter().map(|c| c.amount).sum();
+
+    SubscriptionBloat {
+        total_monthly,
+        total_annual: total_monthly * 12.0,
+        count,
+        potentially_forgotten: recurring
+            .iter()
+            .filter(|r| r.potentially_forgotten)
+            .map(|r| r.merchant.clone())
+            .collect(),
+        message: format!(
+            "{} active subscriptions totaling ${:.0}/month",
+            count, total_monthly
+        ),
+        next_30_days,
+        next_charges,
+    }
+}
+
+// ── Anomalies ──
+
+/// Severity label for a z-score, or `None` below the elevated band.
+pub fn anomaly_severity(z: f64, t: &Thresholds) -> Option<&'static str> {
+    let [elevated, high, critical] = t.anomaly_z_bands;
+    if z > critical {
+        Some("critical")
+    } else if z > high {
+        Some("high")
+    } else if z > elevated {
+        Some("elevated")
+    } else {
+        None
+    }
+}
+
+/// Scales a median absolute deviation to a standard deviation for normal data.
+const MAD_SCALE: f64 = 1.4826;
+
+/// Scales a mean absolute deviation to a standard deviation for normal data.
+const MEAN_AD_SCALE: f64 = 1.2533;
+
+/// Completed months a category needs before it has a baseline.
+const BASELINE_MIN_MONTHS: usize = 3;
+
+/// Past charges a category needs before one of its transactions can be an outlier.
+const OUTLIER_MIN_CHARGES: usize = 5;
+
+/// Median-based centre and spread, so one large purchase doesn't move the baseline.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RobustStats {
+    pub median: f64,
+    pub mad: f64,
+    /// Spread used for z-scores: the scaled MAD, or the scaled mean absolute deviation
+    /// when more than half the values are identical, or 10% of the median when all are
+    pub spread: f64,
+    pub count: usize,
+}
+
+impl RobustStats {
+    pub fn of(values: &[f64]) -> Self {
+        let centre = median(values);
+        let deviations: Vec<f64> = values.iter().map(|v| (v - centre).abs()).collect();
+        let mad = median(&deviations);
+        let mean_ad = if values.is_empty() {
+            0.0
+        } else {
+            deviations.iter().sum::<f64>() / values.len() as f64
+        };
+        let spread = if mad > 0.0 {
+            MAD_SCALE * mad
+        } else if mean_ad > 0.0 {
+            MEAN_AD_SCALE * mean_ad
+        } else {
+            0.1 * centre.abs()
+        };
+        RobustStats { median: centre, mad, spread, count: values.len() }
+    }
+
+    /// Robust z-score, or `None` when there is no spread to measure against.
+    pub fn z(&self, value: f64) -> Option<f64> {
+        (self.spread >= 0.01).then(|| (value - self.median) / self.spread)
+    }
+}
+
+/// Baselines from completed months only. Months without spending between a category's
+/// first month and the reference month count as zero, so a category used a few times a
+/// year isn't treated as a monthly one.
+pub fn category_baselines(rows: &[CategoryMonth], current_month: NaiveDate) -> Vec<CategoryBaseline> {
+    let mut by_category: BTreeMap<&str, BTreeMap<NaiveDate, (f64, f64)>> = BTreeMap::new();
+    for r in rows.iter().filter(|r| r.month < current_month) {
+        let month = by_category.entry(&r.category).or_default().entry(r.month).or_default();
+        month.0 += r.total;
+        month.1 += r.to_date;
+    }
+    by_category
+        .into_iter()
+        .filter_map(|(category, months)| {
+            let first = *months.keys().next()?;
+            let (mut totals, mut to_date) = (Vec::new(), Vec::new());
+            let mut m = first;
+            while m < current_month {
+                let (total, partial) = months.get(&m).copied().unwrap_or_default();
+                totals.push(total);
+                to_date.push(partial);
+                m = m.checked_add_months(Months::new(1))?;
+            }
+            (totals.len() >= BASELINE_MIN_MONTHS).then(|| CategoryBaseline {
+                category: category.to_string(),
+                monthly: RobustStats::of(&totals),
+                to_date: RobustStats::of(&to_date),
+            })
+        })
+        .collect()
+}
+
+/// Categories whose spending so far this month is well above what they usually have spent
+/// by the same day. The projection adds the usual rest of the month to what's spent.
+pub fn category_anomalies(
+    baselines: &[CategoryBaseline],
+    current: &HashMap<String, f64>,
+    t: &Thresholds,
+) -> Vec<CategoryAnomaly> {
+    baselines
+        .iter()
+        .filter_map(|b| {
+            let current_month = current.get(&b.category).copied().unwrap_or(0.0);
+            let z = b.to_date.z(current_month)?;
+            let severity = anomaly_severity(z, t)?;
+            let typical = b.monthly.median;
+            let projected = current_month + (typical - b.to_date.median).max(0.0);
+            let (pct_above, message) = if typical > 0.0 {
+                let pct = (projected - typical) / typical * 100.0;
+                (
+                    pct,
+                    format!("{} spending is on pace to be {:.0}% above a typical month", b.category, pct),
+                )
+            } else {
+                (
+                    0.0,
+                    format!(
+                        "{} has ${:.2} this month; most months have none",
+                        b.category, current_month
+                    ),
+                )
+            };
+            Some(CategoryAnomaly {
+                category: b.category.clone(),
+                current_month,
+                avg_monthly: typical,
+                stddev: b.to_date.spread,
+                z_score: z,
+                severity: severity.to_string(),
+                pct_above_avg: pct_above,
+                expected_to_date: b.to_date.median,
+                projected,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Robust statistics of positive amounts grouped by key.
+pub fn amount_stats<'a>(rows: impl IntoIterator<Item = (&'a str, f64)>) -> HashMap<String, RobustStats> {
+    let mut grouped: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (key, amount) in rows.into_iter().filter(|(_, a)| *a > 0.0) {
+        grouped.entry(key).or_default().push(amount);
+    }
+    grouped
+        .into_iter()
+        .map(|(key, amounts)| (key.to_string(), RobustStats::of(&amounts)))
+        .collect()
+}
+
+/// This month's transactions far above their category's median: over the outlier multiple
+/// and past the critical z band. Largest first, at most ten.
+pub fn transaction_anomalies(
+    charges: &[MonthCharge],
+    by_category: &HashMap<String, RobustStats>,
+    t: &Thresholds,
+) -> Vec<TransactionAnomaly> {
+    let critical = t.anomaly_z_bands[2];
+    let mut anomalies: Vec<TransactionAnomaly> = charges
+        .iter()
+        .filter_map(|c| {
+            let stats = by_category.get(&c.category)?;
+            if stats.count < OUTLIER_MIN_CHARGES || stats.median <= 0.0 {
+                return None;
+            }
+            let times = c.amount / stats.median;
+            let z = stats.z(c.amount)?;
+            if times <= t.transaction_outlier_multiple || z <= critical {
+                return None;
+            }
+            Some(TransactionAnomaly {
+                id: c.id,
+                date: c.date,
+                description: c.description.clone(),
+                amount: c.amount,
+                category: c.category.clone(),
+                category_avg: stats.median,
+                times_avg: times,
+                z_score: z,
+                message: format!(
+                    "{} is {:.1}x the typical {} transaction",
+                    c.description, times, c.category
+                ),
+            })
+        })
+        .collect();
+    anomalies.sort_by(|a, b| b.times_avg.total_cmp(&a.times_avg));
+    anomalies.truncate(10);
+    anomalies
+}
+
+/// First charges at merchants never seen before (only once there is history to compare
+/// against), and charges far above a merchant's usual amount.
+pub fn merchant_anomalies(
+    charges: &[MonthCharge],
+    by_merchant: &HashMap<String, RobustStats>,
+    t: &Thresholds,
+) -> Vec<MerchantAnomaly> {
+    let critical = t.anomaly_z_bands[2];
+    let mut sorted: Vec<&MonthCharge> = charges.iter().filter(|c| c.amount > 0.0).collect();
+    sorted.sort_by_key(|c| c.date);
+
+    let mut reported_new: Vec<&str> = Vec::new();
+    let mut anomalies = Vec::new();
+    for c in sorted {
+        let anomaly = |kind: &str, stats: Option<&RobustStats>, z: Option<f64>, message: String| MerchantAnomaly {
+            id: c.id,
+            date: c.date,
+            merchant: c.merchant.clone(),
+            description: c.description.clone(),
+            amount: c.amount,
+            category: c.category.clone(),
+            kind: kind.to_string(),
+            prior_charges: stats.map_or(0, |s| s.count),
+            merchant_median: stats.map(|s| s.median),
+            z_score: z,
+            message,
+        };
+        match by_merchant.get(&c.merchant) {
+            None if !by_merchant.is_empty()
+                && c.amount >= t.new_merchant_min_amount
+                && !reported_new.contains(&c.merchant.as_str()) =>
+            {
+                reported_new.push(&c.merchant);
+                anomalies.push(anomaly(
+                    "first_time",
+                    None,
+                    None,
+                    format!("First charge at {}: ${:.2}", c.merchant, c.amount),
+                ));
+            }
+            Some(stats) if stats.count >= t.merchant_min_charges => {
+                if let Some(z) = stats.z(c.amount).filter(|z| *z > critical) {
+                    anomalies.push(anomaly(
+                        "unusual_amount",
+                        Some(stats),
+                        Some(z),
+                        format!(
+                            "{} charged ${:.2}; it's usually ${:.2}",
+                            c.merchant, c.amount, stats.median
+                        ),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    anomalies
+}
+
+// ── Habits ──
+
+pub fn impulse_spending(stats: &SmallPurchaseStats, t: &Thresholds) -> ImpulseSpending {
+    let small_pct = if stats.total_count > 0 {
+        stats.small_count as f64 / stats.total_count as f64 * 100.0
+    } else {
+        0.0
+    };
+    let [high, moderate, low] = t.impulse_pct_bands;
+    let (score, label) = if small_pct > high {
+        (0.8, "high")
+    } else if small_pct > moderate {
+        (0.5, "moderate")
+    } else if small_pct > low {
+        (0.3, "low")
+    } else {
+        (0.1, "minimal")
+    };
+    let months = (t.habit_window_days as f64 / 30.0).max(1.0);
+    let monthly_small_total = stats.small_total / months;
+
+    ImpulseSpending {
+        score,
+        label: label.to_string(),
+        small_transaction_pct: small_pct,
+        avg_small_amount: stats.avg_small,
+        monthly_small_total,
+        message: format!(
+            "{:.0}% of transactions are under ${:.0} (${:.0}/month in small purchases)",
+            small_pct, t.impulse_amount, monthly_small_total
+        ),
+    }
+}
+
+/// Categories whose recent months differ from the earlier months of the window.
+///
+/// `monthly` holds (category, month, total) rows; months are compared in order.
+pub fn category_creep(monthly: &[(String, String, f64)], t: &Thresholds) -> Vec<CategoryCreep> {
+    let mut by_category: BTreeMap<&str, BTreeMap<&str, f64>> = BTreeMap::new();
+    for (category, month, total) in monthly {
+        *by_category
+            .entry(category.as_str())
+            .or_default()
+            .entry(month.as_str())
+            .or_insert(0.0) += total;
+    }
+
+    let mut creep = Vec::new();
+    for (category, months) in by_category {
+        let totals: Vec<f64> = months.values().copied().collect();
+        if totals.len() < t.creep_min_months {
+            continue;
+        }
+        let mid = totals.len() / 2;
+        let prior_avg = totals[..mid].iter().sum::<f64>() / mid as f64;
+        let recent_avg = totals[mid..].iter().sum::<f64>() / (totals.len() - mid) as f64;
+        if prior_avg < 1.0 {
+            continue;
+        }
+        let change_pct = (recent_avg - prior_avg) / prior_avg * 100.0;
+        if change_pct.abs() <= t.creep_pct {
+            continue;
+        }
+        creep.push(CategoryCreep {
+            category: category.to_string(),
+            trend: if change_pct > 0.0 { "increasing" } else { "decreasing" }.to_string(),
+            three_month_change_pct: change_pct,
+            monthly_totals: totals,
+            message: format!(
+                "{} spending {} {:.0}% over the last 3 months",
+                category,
+                if change_pct > 0.0 { "up" } else { "down" },
+                change_pct.abs()
+            ),
+        });
+    }
+    creep
+}
+
+pub fn weekend_splurge(weekend_avg_daily: f64, weekday_avg_daily: f64, t: &Thresholds) -> WeekendSplurge {
+    let ratio = if weekday_avg_daily > 0.0 {
+        weekend_avg_daily / weekday_avg_daily
+    } else {
+        1.0
+    };
+    let [high, moderate, slight] = t.weekend_ratio_bands;
+    let label = if ratio > high {
+        "high"
+    } else if ratio > moderate {
+        "moderate"
+    } else if ratio > slight {
+        "slight"
+    } else {
+        "balanced"
+    };
+
+    WeekendSplurge {
+        weekend_avg_daily,
+        weekday_avg_daily,
+        ratio,
+        label: label.to_string(),
+        message: format!(
+            "Weekend spending is {:.1}x weekday spending (${:.0} vs ${:.0} daily avg)",
+            ratio, weekend_avg_daily, weekday_avg_daily
+        ),
+    }
+}
+
+/// Herfindahl index over merchant shares, given (merchant, share) sorted by total descending.
+pub fn merchant_concentration(shares: &[(String, f64)], t: &Thresholds) -> MerchantConcentration {
+    let hhi: f64 = shares.iter().map(|(_, s)| s * s).sum();
+    let top_merchant = shares.first().map(|(m, _)| m.clone()).unwrap_or_default();
+    let top_merchant_pct = shares.first().map(|(_, s)| s * 100.0).unwrap_or(0.0);
+    let top_3_pct: f64 = shares.iter().take(3).map(|(_, s)| s * 100.0).sum();
+
+    let [high, moderate, mild] = t.hhi_bands;
+    let label = if hhi > high {
+        "high"
+    } else if hhi > moderate {
+        "moderate"
+    } else if hhi > mild {
+        "mild"
+    } else {
+        "diversified"
+    };
+
+    MerchantConcentration {
+        message: format!(
+            "Top merchant ({}) accounts for {:.0}% of spending",
+            top_merchant, top_merchant_pct
+        ),
+        top_merchant,
+        top_merchant_pct,
+        top_3_pct,
+        hhi,
+        label: label.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    /// `count` charges of `amount` starting at `start`, `step_days` apart.
+    fn series(merchant: &str, start: NaiveDate, step_days: i64, count: i64, amount: f64) -> Vec<Charge> {
+        (0..count)
+            .map(|i| Charge {
+                merchant: merchant.to_string(),
+                date: start + Duration::days(i * step_days),
+                amount,
+                card: "amex".to_string(),
+            })
+            .collect()
+    }
+
+    fn monthly(merchant: &str, start: NaiveDate, count: u32, amount: f64) -> Vec<Charge> {
+        (0..count)
+            .map(|i| Charge {
+                merchant: merchant.to_string(),
+                date: start.checked_add_months(Months::new(i)).unwrap(),
+                amount,
+                card: "amex".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_thresholds_validate() {
+        assert!(Thresholds::default().validate().is_ok());
+
+        let t = Thresholds { anomaly_z_bands: [2.0, 1.5, 3.0], ..Thresholds::default() };
+        assert!(t.validate().unwrap_err().contains("anomaly_z_bands"));
+        let t = Thresholds { ewma_alpha: 0.0, ..Thresholds::default() };
+        assert!(t.validate().is_err());
+        let t = Thresholds { hhi_bands: [0.1, 0.15, 0.25], ..Thresholds::default() };
+        assert!(t.validate().is_err());
+        let t = Thresholds { impulse_amount: 0.0, ..Thresholds::default() };
+        assert!(t.validate().is_err());
+    }
+
+    #[test]
+    fn test_thresholds_partial_json_keeps_defaults() {
+        let t: Thresholds = serde_json::from_str(r#"{"impulse_amount": 25}"#).unwrap();
+        assert_eq!(t.impulse_amount, 25.0);
+        assert_eq!(t.anomaly_z_bands, [1.5, 2.0, 3.0]);
+        assert!(serde_json::from_str::<Thresholds>(r#"{"impulse": 25}"#).is_err());
+    }
+
+    #[test]
+    fn test_days_in_month_and_projection() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2025, 12), 31);
+        assert_eq!(linear_projection(100.0, 10, 30), 300.0);
+        assert_eq!(linear_projection(100.0, 0, 30), 0.0);
+    }
+
+    #[test]
+    fn test_ewma_weights_recent_months() {
+        assert_eq!(ewma(&[], 0.3), 0.0);
+        let v = ewma(&[100.0, 200.0], 0.3);
+        assert!((v - 130.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_recurring_cadences() {
+        let t = Thresholds::default();
+        let today = d(2026, 2, 20);
+        let mut charges = series("GYM CLASS", d(2025, 12, 5), 7, 11, 20.0);
+        charges.extend(monthly("NETFLIX", d(2025, 9, 15), 6, 15.99));
+        charges.extend(series("PAYROLL FEE", d(2025, 11, 6), 14, 8, 4.0));
+        charges.extend(monthly("INSURANCE", d(2025, 5, 1), 1, 300.0));
+        charges.extend(monthly("INSURANCE", d(2025, 8, 1), 1, 300.0));
+        charges.extend(monthly("INSURANCE", d(2025, 11, 1), 1, 300.0));
+        charges.extend(monthly("INSURANCE", d(2026, 2, 1), 1, 300.0));
+        charges.extend(monthly("DOMAIN", d(2025, 3, 10), 1, 12.0));
+        charges.extend(monthly("DOMAIN", d(2026, 3, 10), 1, 12.0));
+
+        let recurring = detect_recurring(&charges, today, &t);
+        let freq = |m: &str| recurring.iter().find(|r| r.merchant == m).map(|r| r.frequency.as_str());
+        assert_eq!(freq("GYM CLASS"), Some("weekly"));
+        assert_eq!(freq("PAYROLL FEE"), Some("biweekly"));
+        assert_eq!(freq("NETFLIX"), Some("monthly"));
+        assert_eq!(freq("INSURANCE"), Some("quarterly"));
+        assert_eq!(freq("DOMAIN"), Some("annual"));
+
+        let gym = recurring.iter().find(|r| r.merchant == "GYM CLASS").unwrap();
+        assert_eq!(gym.next_expected_date, d(2026, 2, 20));
+        assert!((gym.monthly_amount - 20.0 * 52.0 / 12.0).abs() < 1e-9);
+        let netflix = recurring.iter().find(|r| r.merchant == "NETFLIX").unwrap();
+        assert_eq!(netflix.next_expected_date, d(2026, 3, 15));
+        assert_eq!(netflix.status, "active");
+        assert!(netflix.confidence > 0.8);
+    }
+
+    #[test]
+    fn test_detect_recurring_splits_price_clusters() {
+        let t = Thresholds::default();
+        let mut charges = monthly("APPLE", d(2025, 10, 3), 5, 0.99);
+        charges.extend(monthly("APPLE", d(2025, 10, 20), 5, 9.99));
+        let recurring = detect_recurring(&charges, d(2026, 2, 25), &t);
+        assert_eq!(recurring.len(), 2);
+        assert!(recurring.iter().all(|r| r.merchant == "APPLE" && r.frequency == "monthly"));
+        assert!((recurring[0].avg_amount - 9.99).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_recurring_follows_price_rise() {
+        let t = Thresholds::default();
+        let mut charges = monthly("STREAMING", d(2025, 9, 8), 4, 10.0);
+        charges.extend(monthly("STREAMING", d(2026, 1, 8), 3, 13.0));
+        let recurring = detect_recurring(&charges, d(2026, 3, 20), &t);
+        assert_eq!(recurring.len(), 1);
+        assert_eq!(recurring[0].frequency, "monthly");
+        assert_eq!(recurring[0].charge_count, 7);
+        assert_eq!(recurring[0].first_seen, d(2025, 9, 8));
+        assert_eq!(recurring[0].next_expected_amount, 13.0);
+
+        // Amounts that go up and down are not a price rise
+        let groceries: Vec<Charge> = [87.32, 92.10, 105.20, 95.40, 110.0]
+            .iter()
+            .zip(monthly("MARKET", d(2025, 10, 5), 5, 0.0))
+            .map(|(&amount, c)| Charge { amount, ..c })
+            .collect();
+        assert!(detect_recurring(&groceries, d(2026, 2, 20), &t).is_empty());
+    }
+
+    #[test]
+    fn test_detect_recurring_rejects_irregular_and_tracks_price() {
+        let t = Thresholds::default();
+        let irregular: Vec<Charge> = [(2025, 10, 1), (2025, 10, 20), (2025, 12, 2), (2025, 12, 10), (2026, 1, 30)]
+            .iter()
+            .map(|&(y, m, day)| Charge { merchant: "SHOP".into(), date: d(y, m, day), amount: 30.0, card: "amex".into() })
+            .collect();
+        assert!(detect_recurring(&irregular, d(2026, 2, 10), &t).is_empty());
+
+        // A small price increase stays in the series and sets the next amount
+        let mut charges = monthly("SPOTIFY", d(2025, 9, 12), 4, 10.99);
+        charges.extend(monthly("SPOTIFY", d(2026, 1, 12), 2, 11.99));
+        let recurring = detect_recurring(&charges, d(2026, 2, 20), &t);
+        assert_eq!(recurring.len(), 1);
+        assert_eq!(recurring[0].next_expected_amount, 11.99);
+        assert_eq!(recurring[0].charge_count, 6);
+    }
+
+    #[test]
+    fn test_recurring_status_and_subscription_bloat() {
+        let t = Thresholds::default();
+        let mut charges = monthly("NETFLIX", d(2025, 10, 15), 5, 15.99);
+        charges.extend(monthly("GYM", d(2025, 8, 1), 4, 40.0));
+        charges.extend(series("LUNCH CLUB", d(2025, 12, 1), 7, 12, 10.0));
+        let today = d(2026, 2, 20);
+        let recurring = detect_recurring(&charges, today, &t);
+        assert_eq!(recurring.len(), 3);
+
+        let gym = recurring.iter().find(|r| r.merchant == "GYM").unwrap();
+        assert_eq!(gym.status, "inactive");
+        assert!(gym.potentially_forgotten);
+        // Last weekly charge 2026-02-16; 4 days ago is within the scaled grace period
+        let lunch = recurring.iter().find(|r| r.merchant == "LUNCH CLUB").unwrap();
+        assert_eq!(lunch.status, "active");
+
+        let bloat = subscription_bloat(&recurring, today);
+        assert_eq!(bloat.count, 2);
+        assert!((bloat.total_monthly - (15.99 + 10.0 * 52.0 / 12.0)).abs() < 1e-9);
+        assert_eq!(bloat.potentially_forgotten, vec!["GYM".to_string()]);
+        // Weekly on 02-23, 03-02, 03-09, 03-16; Netflix on 03-15
+        assert_eq!(bloat.next_charges.len(), 5);
+        assert_eq!(bloat.next_charges[0].date, d(2026, 2, 23));
+        assert!((bloat.next_30_days - (40.0 + 15.99)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_upcoming_charges_and_card_impacts() {
+        let t = Thresholds::default();
+        let mut charges = monthly("NETFLIX", d(2025, 10, 15), 4, 15.99);
+        charges.extend(series("GYM CLASS", d(2025, 12, 2), 7, 12, 20.0));
+        for c in charges.iter_mut().filter(|c| c.merchant == "GYM CLASS") {
+            c.card = "checking".to_string();
+        }
+        // Netflix was due 2026-02-15 and hasn't charged yet
+        let today = d(2026, 2, 18);
+        let recurring = detect_recurring(&charges, today, &t);
+        let upcoming = upcoming_charges(&recurring, today, today + Duration::days(14));
+
+        assert_eq!(upcoming[0].merchant, "NETFLIX");
+        assert_eq!(upcoming[0].date, d(2026, 2, 15));
+        assert!(upcoming[0].overdue);
+        assert_eq!(upcoming[0].card, "amex");
+        let gym: Vec<NaiveDate> = upcoming.iter().filter(|c| c.merchant == "GYM CLASS").map(|c| c.date).collect();
+        assert_eq!(gym, vec![d(2026, 2, 24), d(2026, 3, 3)]);
+        assert!(upcoming.iter().filter(|c| c.merchant == "NETFLIX").count() == 1);
+
+        let types = HashMap::from([("checking".to_string(), "checking".to_string())]);
+        let impacts = card_impacts(&upcoming, d(2026, 2, 28), &types);
+        assert_eq!(impacts.len(), 2);
+        assert_eq!(impacts[0].card, "amex");
+        assert_eq!(impacts[0].balance_change_by_month_end, 15.99);
+        assert_eq!(impacts[1].due_by_month_end, 20.0);
+        assert_eq!(impacts[1].total, 40.0);
+        assert_eq!(impacts[1].balance_change_by_month_end, -20.0);
+    }
+
+    #[test]
+    fn test_category_anomalies_severity_bands() {
+        let t = Thresholds::default();
+        assert_eq!(anomaly_severity(1.0, &t), None);
+        assert_eq!(anomaly_severity(1.8, &t), Some("elevated"));
+        assert_eq!(anomaly_severity(2.5, &t), Some("high"));
+        assert_eq!(anomaly_severity(3.5, &t), Some("critical"));
+
+        let month = |category: &str, m: u32, total: f64, to_date: f64| CategoryMonth {
+            category: category.into(),
+            month: d(2025, m, 1),
+            total,
+            to_date,
+        };
+        let mut rows = Vec::new();
+        for (m, dining_to_date) in [(7, 40.0), (8, 50.0), (9, 60.0), (10, 50.0), (11, 45.0), (12, 55.0)] {
+            rows.push(month("Dining", m, 100.0, dining_to_date));
+            rows.push(month("Rent", m, 1500.0, 1500.0));
+        }
+        // The current month never feeds its own baseline
+        rows.push(CategoryMonth { month: d(2026, 1, 1), ..month("Dining", 1, 900.0, 900.0) });
+        let baselines = category_baselines(&rows, d(2026, 1, 1));
+        let dining = baselines.iter().find(|b| b.category == "Dining").unwrap();
+        assert_eq!(dining.monthly.count, 6);
+        assert_eq!(dining.to_date.median, 50.0);
+
+        // Halfway through the month: 120 against a usual 50 by now
+        let current = HashMap::from([("Dining".to_string(), 120.0), ("Rent".to_string(), 1500.0)]);
+        let anomalies = category_anomalies(&baselines, &current, &t);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].severity, "critical");
+        assert_eq!(anomalies[0].expected_to_date, 50.0);
+        assert!((anomalies[0].projected - 170.0).abs() < 1e-9);
+        assert!((anomalies[0].pct_above_avg - 70.0).abs() < 1e-9);
+
+        // A constant category still has a spread to measure a jump against
+        let current = HashMap::from([("Rent".to_string(), 3000.0)]);
+        let anomalies = category_anomalies(&baselines, &current, &t);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].category, "Rent");
+    }
+
+    #[test]
+    fn test_sparse_category_baseline_counts_empty_months() {
+        let rows = vec![
+            CategoryMonth { category: "Travel".into(), month: d(2025, 6, 1), total: 800.0, to_date: 0.0 },
+            CategoryMonth { category: "Travel".into(), month: d(2025, 11, 1), total: 600.0, to_date: 600.0 },
+        ];
+        let baselines = category_baselines(&rows, d(2026, 1, 1));
+        assert_eq!(baselines[0].monthly.count, 7);
+        assert_eq!(baselines[0].monthly.median, 0.0);
+
+        let current = HashMap::from([("Travel".to_string(), 500.0)]);
+        let anomalies = category_anomalies(&baselines, &current, &Thresholds::default());
+        assert_eq!(anomalies.len(), 1);
+        assert!(anomalies[0].message.contains("most months have none"));
+
+        // Too little history for a baseline
+        let short = category_baselines(&rows[1..], d(2026, 1, 1));
+        assert!(short.is_empty());
+    }
+
+    #[test]
+    fn test_robust_stats_resist_one_large_purchase() {
+        let stats = RobustStats::of(&[20.0, 25.0, 30.0, 22.0, 28.0, 1500.0]);
+        assert_eq!(stats.median, 26.5);
+        assert!(stats.spread < 10.0);
+        assert!(stats.z(80.0).unwrap() > 3.0);
+        assert_eq!(RobustStats::of(&[10.0, 10.0, 10.0]).spread, 1.0);
+        assert_eq!(RobustStats::of(&[0.0, 0.0]).z(5.0), None);
+    }
+
+    fn month_charge(merchant: &str, category: &str, date: NaiveDate, amount: f64) -> MonthCharge {
+        MonthCharge {
+            id: uuid::Uuid::new_v4(),
+            date,
+            description: format!("{merchant} #123"),
+            merchant: merchant.into(),
+            category: category.into(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_transaction_anomalies_against_category_median() {
+        let t = Thresholds::default();
+        let history = [
+            ("Shopping", 30.0),
+            ("Shopping", 45.0),
+            ("Shopping", 25.0),
+            ("Shopping", 40.0),
+            ("Shopping", 35.0),
+            ("Shopping", 2000.0),
+            ("Dining", 15.0),
+        ];
+        let by_category = amount_stats(history);
+        let charges = vec![
+            month_charge("AMAZON", "Shopping", d(2026, 2, 3), 350.0),
+            month_charge("TARGET", "Shopping", d(2026, 2, 4), 60.0),
+            month_charge("BISTRO", "Dining", d(2026, 2, 5), 200.0),
+        ];
+        let anomalies = transaction_anomalies(&charges, &by_category, &t);
+        // The one 2000 purchase doesn't hide a 350 one; Dining has too little history
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].amount, 350.0);
+        assert_eq!(anomalies[0].category_avg, 37.5);
+        assert!(anomalies[0].times_avg > 9.0);
+    }
+
+    #[test]
+    fn test_merchant_anomalies_first_time_and_unusual_amount() {
+        let t = Thresholds::default();
+        let by_merchant = amount_stats([
+            ("NETFLIX", 15.99),
+            ("NETFLIX", 15.99),
+            ("NETFLIX", 15.99),
+            ("SHELL", 40.0),
+            ("SHELL", 45.0),
+            ("SHELL", 38.0),
+            ("SHELL", 42.0),
+            ("CAFE", 5.0),
+        ]);
+        let charges = vec![
+            month_charge("NEW FURNITURE", "Shopping", d(2026, 2, 2), 480.0),
+            month_charge("NEW FURNITURE", "Shopping", d(2026, 2, 9), 120.0),
+            month_charge("NEW BAKERY", "Dining", d(2026, 2, 3), 8.0),
+            month_charge("SHELL", "Gas", d(2026, 2, 4), 140.0),
+            month_charge("SHELL", "Gas", d(2026, 2, 12), 44.0),
+            month_charge("NETFLIX", "Subscriptions", d(2026, 2, 15), 15.99),
+            month_charge("CAFE", "Dining", d(2026, 2, 16), 50.0),
+        ];
+        let anomalies = merchant_anomalies(&charges, &by_merchant, &t);
+        let kinds: Vec<(&str, &str)> = anomalies.iter().map(|a| (a.kind.as_str(), a.merchant.as_str())).collect();
+        // Small first charges and merchants with little history aren't reported
+        assert_eq!(kinds, vec![("first_time", "NEW FURNITURE"), ("unusual_amount", "SHELL")]);
+        assert_eq!(anomalies[0].amount, 480.0);
+        assert_eq!(anomalies[1].prior_charges, 4);
+        assert_eq!(anomalies[1].merchant_median, Some(41.0));
+
+        // Without any history every merchant would be new, so nothing is reported
+        assert!(merchant_anomalies(&charges, &HashMap::new(), &t).is_empty());
+    }
+
+    #[test]
+    fn test_impulse_spending_labels() {
+        let t = Thresholds::default();
+        let stats = SmallPurchaseStats { total_count: 10, small_count: 6, small_total: 90.0, avg_small: 15.0 };
+        let impulse = impulse_spending(&stats, &t);
+        assert_eq!(impulse.label, "high");
+        assert!((impulse.monthly_small_total - 30.0).abs() < 1e-9);
+        assert_eq!(impulse_spending(&SmallPurchaseStats::default(), &t).label, "minimal");
+    }
+
+    #[test]
+    fn test_category_creep_uses_single_threshold() {
+        let t = Thresholds::default();
+        let row = |cat: &str, m: &str, v: f64| (cat.to_string(), m.to_string(), v);
+        let monthly = vec![
+            row("Dining", "2025-09", 100.0),
+            row("Dining", "2025-10", 100.0),
+            row("Dining", "2025-11", 120.0),
+            row("Dining", "2025-12", 120.0),
+            row("Gas", "2025-09", 100.0),
+            row("Gas", "2025-10", 100.0),
+            row("Gas", "2025-11", 110.0),
+            row("Gas", "2025-12", 110.0),
+            row("Travel", "2025-11", 500.0),
+        ];
+        let creep = category_creep(&monthly, &t);
+        assert_eq!(creep.len(), 1);
+        assert_eq!(creep[0].category, "Dining");
+        assert_eq!(creep[0].trend, "increasing");
+        assert!((creep[0].three_month_change_pct - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weekend_and_concentration_labels() {
+        let t = Thresholds::default();
+        assert_eq!(weekend_splurge(90.0, 50.0, &t).label, "moderate");
+        assert_eq!(weekend_splurge(10.0, 0.0, &t).label, "balanced");
+
+        let shares = vec![("RENT".to_string(), 0.6), ("GROCER".to_string(), 0.4)];
+        let conc = merchant_concentration(&shares, &t);
+        assert_eq!(conc.label, "high");
+        assert_eq!(conc.top_merchant, "RENT");
+        assert!((conc.hhi - 0.52).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_completed_savings_rate_skips_current_month() {
+        let month = |m: &str, income: f64, expenses: f64| CashflowMonth {
+            month: m.to_string(),
+            income,
+            expenses,
+            net: income - expenses,
+            savings_rate: savings_rate(income, expenses),
+        };
+        let months = vec![month("2026-01", 1000.0, 800.0), month("2026-02", 1000.0, 1200.0)];
+        assert_eq!(completed_savings_rate(&months, "2026-02"), Some(20.0));
+        assert_eq!(savings_rate(0.0, 100.0), None);
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::models::account::{AssertionCheck, BalancePoint};
+
+/// Assertions within a cent of the computed balance pass.
+const ASSERTION_TOLERANCE: f64 = 0.005;
+
+/// Credit balances grow with charges (amount owed); asset accounts shrink with spending.
+pub fn balance_sign(account_type: &str) -> f64 {
+    if account_type == "credit" {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// Liabilities count against net worth.
+pub fn is_liability(account_type: &str) -> bool {
+    account_type == "credit"
+}
+
+/// Build a running balance from daily transaction totals (sorted by date).
+///
+/// The opening balance applies at the start of its date; days before it are ignored.
+pub fn running_balance(
+    account_type: &str,
+    opening: Option<(NaiveDate, f64)>,
+    daily_totals: &[(NaiveDate, f64)],
+) -> Vec<BalancePoint> {
+    let sign = balance_sign(account_type);
+    let mut balance = opening.map(|(_, b)| b).unwrap_or(0.0);
+    let start = opening.map(|(d, _)| d);
+
+    daily_totals
+        .iter()
+        .filter(|(date, _)| start.is_none_or(|s| *date >= s))
+        .map(|(date, total)| {
+            let change = total * sign;
+            balance += change;
+            BalancePoint {
+                date: *date,
+                change,
+                balance,
+            }
+        })
+        .collect()
+}
+
+/// Balance at the end of `date`, given a running-balance series.
+pub fn balance_on(history: &[BalancePoint], opening: Option<(NaiveDate, f64)>, date: NaiveDate) -> f64 {
+    let opening_balance = match opening {
+        Some((d, _)) if date < d => return 0.0,
+        Some((_, b)) => b,
+        None => 0.0,
+    };
+    history
+        .iter()
+        .take_while(|p| p.date <= date)
+        .last()
+        .map(|p| p.balance)
+        .unwrap_or(opening_balance)
+}
+
+/// Compare each asserted balance with the computed end-of-day balance.
+pub fn check_assertions(
+    history: &[BalancePoint],
+    opening: Option<(NaiveDate, f64)>,
+    assertions: &[(Uuid, NaiveDate, f64)],
+) -> Vec<AssertionCheck> {
+    assertions
+        .iter()
+        .map(|(id, date, expected)| {
+            let computed = balance_on(history, opening, *date);
+            let difference = computed - expected;
+            AssertionCheck {
+                id: *id,
+                date: *date,
+                expected: *expected,
+                computed,
+                difference,
+                passed: difference.abs() < ASSERTION_TOLERANCE,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn test_running_balance_checking_decreases_with_spending() {
+        let totals = vec![(d(2026, 1, 2), 100.0), (d(2026, 1, 5), 50.0)];
+        let history = running_balance("checking", Some((d(2026, 1, 1), 1000.0)), &totals);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].balance, 900.0);
+        assert_eq!(history[1].balance, 850.0);
+        assert_eq!(history[1].change, -50.0);
+    }
+
+    #[test]
+    fn test_running_balance_credit_grows_with_charges() {
+        let totals = vec![(d(2026, 1, 2), 100.0), (d(2026, 1, 3), -40.0)];
+        let history = running_balance("credit", None, &totals);
+        assert_eq!(history[0].balance, 100.0);
+        assert_eq!(history[1].balance, 60.0);
+    }
+
+    #[test]
+    fn test_running_balance_ignores_days_before_opening() {
+        let totals = vec![(d(2025, 12, 31), 999.0), (d(2026, 1, 1), 10.0)];
+        let history = running_balance("savings", Some((d(2026, 1, 1), 500.0)), &totals);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].balance, 490.0);
+    }
+
+    #[test]
+    fn test_balance_on_between_points() {
+        let totals = vec![(d(2026, 1, 2), 100.0), (d(2026, 1, 10), 50.0)];
+        let opening = Some((d(2026, 1, 1), 1000.0));
+        let history = running_balance("checking", opening, &totals);
+        assert_eq!(balance_on(&history, opening, d(2026, 1, 1)), 1000.0);
+        assert_eq!(balance_on(&history, opening, d(2026, 1, 5)), 900.0);
+        assert_eq!(balance_on(&history, opening, d(2026, 2, 1)), 850.0);
+        assert_eq!(balance_on(&history, opening, d(2025, 12, 1)), 0.0);
+    }
+
+    #[test]
+    fn test_check_assertions_flags_mismatch() {
+        let totals = vec![(d(2026, 1, 2), 100.0)];
+        let opening = Some((d(2026, 1, 1), 1000.0));
+        let history = running_balance("checking", opening, &totals);
+        let ok = Uuid::new_v4();
+        let bad = Uuid::new_v4();
+        let checks = check_assertions(
+            &history,
+            opening,
+            &[(ok, d(2026, 1, 3), 900.0), (bad, d(2026, 1, 3), 850.0)],
+        );
+        assert!(checks[0].passed);
+        assert!(!checks[1].passed);
+        assert_eq!(checks[1].difference, 50.0);
+    }
+}
@@ -0,0 +1,737 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+use crate::models::budget::{
+    Budget, BudgetCategoryHistory, BudgetPeriodResult, BudgetProgress, BudgetScope, BudgetStreak,
+    BUDGET_PERIODS,
+};
+
+/// Anchor for repeating periods without a start date: a Monday and the first of a year,
+/// so weeks start on Monday and months, quarters and years on the 1st of January.
+fn default_anchor() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+}
+
+/// An inclusive date range a budget's limit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl BudgetWindow {
+    pub fn days(&self) -> u32 {
+        ((self.end - self.start).num_days() + 1) as u32
+    }
+
+    /// Days from the start through `as_of`, capped at the window's length.
+    pub fn days_elapsed(&self, as_of: NaiveDate) -> u32 {
+        let elapsed = (as_of.min(self.end) - self.start).num_days() + 1;
+        elapsed.clamp(0, self.days() as i64) as u32
+    }
+}
+
+/// Check a budget's period and dates before saving.
+pub fn validate(
+    amount: f64,
+    period: &str,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<(), String> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err("amount must not be negative".into());
+    }
+    if !BUDGET_PERIODS.contains(&period) {
+        return Err(format!(
+            "period must be one of: {}",
+            BUDGET_PERIODS.join(", ")
+        ));
+    }
+    match (period, start_date, end_date) {
+        ("custom", Some(start), Some(end)) if end >= start => Ok(()),
+        ("custom", _, _) => {
+            Err("custom budgets need a start_date and an end_date on or after it".into())
+        }
+        (_, _, Some(_)) => Err("end_date only applies to custom budgets".into()),
+        _ => Ok(()),
+    }
+}
+
+/// Check a budget's rollover settings before saving.
+pub fn validate_rollover(period: &str, rollover: bool, cap: Option<f64>) -> Result<(), String> {
+    if cap.is_some_and(|cap| !cap.is_finite() || cap < 0.0) {
+        return Err("rollover_cap must not be negative".into());
+    }
+    if rollover && period == "custom" {
+        return Err("custom budgets have a single period and can't roll over".into());
+    }
+    Ok(())
+}
+
+/// Tidy a scope before saving: values are trimmed, merchants upper-cased like
+/// `merchant_normalized`, and a list must have at least one value. `None` means the scope
+/// is empty and the budget counts its category.
+pub fn normalize_scope(scope: BudgetScope) -> Result<Option<BudgetScope>, String> {
+    fn list(
+        field: &str,
+        values: Option<Vec<String>>,
+        upper: bool,
+    ) -> Result<Option<Vec<String>>, String> {
+        let Some(values) = values else {
+            return Ok(None);
+        };
+        let mut cleaned: Vec<String> = Vec::new();
+        for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            let value = if upper {
+                value.to_uppercase()
+            } else {
+                value.to_string()
+            };
+            if !cleaned.contains(&value) {
+                cleaned.push(value);
+            }
+        }
+        if cleaned.is_empty() {
+            return Err(format!("scope.{field} needs at least one value"));
+        }
+        Ok(Some(cleaned))
+    }
+
+    let scope = BudgetScope {
+        categories: list("categories", scope.categories, false)?,
+        merchants: list("merchants", scope.merchants, true)?,
+        cards: list("cards", scope.cards, false)?,
+        member: scope
+            .member
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty()),
+        tags: list("tags", scope.tags, false)?,
+    };
+    Ok((scope != BudgetScope::default()).then_some(scope))
+}
+
+/// What `budget` counts: its stored scope, or just its category.
+pub fn scope_of(budget: &Budget) -> BudgetScope {
+    budget
+        .scope
+        .as_ref()
+        .and_then(|scope| serde_json::from_value(scope.clone()).ok())
+        .unwrap_or_else(|| BudgetScope {
+            categories: Some(vec![budget.category.clone()]),
+            ..Default::default()
+        })
+}
+
+/// Repeating windows `step` months long, counted from `anchor`.
+fn month_window(anchor: NaiveDate, step: u32, as_of: NaiveDate) -> BudgetWindow {
+    let months_between =
+        (as_of.year() - anchor.year()) * 12 + as_of.month() as i32 - anchor.month() as i32;
+    let mut k = months_between.div_euclid(step as i32) * step as i32;
+    let start_at = |k: i32| {
+        if k >= 0 {
+            anchor + Months::new(k as u32)
+        } else {
+            anchor - Months::new(k.unsigned_abs())
+        }
+    };
+    if start_at(k) > as_of {
+        k -= step as i32;
+    }
+    BudgetWindow {
+        start: start_at(k),
+        end: start_at(k + step as i32) - Duration::days(1),
+    }
+}
+
+/// The window of `budget` that contains `as_of`, or `None` before the budget starts.
+/// A custom window stays the current one after it ends.
+pub fn window(budget: &Budget, as_of: NaiveDate) -> Option<BudgetWindow> {
+    if budget.start_date.is_some_and(|start| as_of < start) {
+        return None;
+    }
+    let anchor = budget.start_date.unwrap_or_else(default_anchor);
+    match budget.period.as_str() {
+        "weekly" => {
+            let weeks = (as_of - anchor).num_days().div_euclid(7);
+            let start = anchor + Duration::days(weeks * 7);
+            Some(BudgetWindow {
+                start,
+                end: start + Duration::days(6),
+            })
+        }
+        "quarterly" => Some(month_window(anchor, 3, as_of)),
+        "annual" => Some(month_window(anchor, 12, as_of)),
+        "custom" => Some(BudgetWindow {
+            start: anchor,
+            end: budget.end_date?,
+        }),
+        _ => Some(month_window(anchor, 1, as_of)),
+    }
+}
+
+/// First day rollover counts from: the budget's start date, or the day it was created.
+pub fn rollover_start(budget: &Budget) -> NaiveDate {
+    budget
+        .start_date
+        .unwrap_or_else(|| budget.created_at.date_naive())
+}
+
+/// Consecutive windows from the one containing `from` through the one containing `as_of`.
+pub fn windows_between(budget: &Budget, from: NaiveDate, as_of: NaiveDate) -> Vec<BudgetWindow> {
+    let mut windows = Vec::new();
+    let mut next = window(budget, from.min(as_of));
+    while let Some(w) = next {
+        windows.push(w);
+        if w.end >= as_of || budget.period == "custom" {
+            break;
+        }
+        next = window(budget, w.end + Duration::days(1));
+    }
+    windows
+}
+
+/// Sum dated amounts into the windows they fall in; amounts outside every window are dropped.
+pub fn bucket(
+    windows: &[BudgetWindow],
+    amounts: impl IntoIterator<Item = (NaiveDate, f64)>,
+) -> Vec<f64> {
+    let mut totals = vec![0.0; windows.len()];
+    for (date, amount) in amounts {
+        if let Some(i) = windows
+            .iter()
+            .position(|w| w.start <= date && date <= w.end)
+        {
+            totals[i] += amount;
+        }
+    }
+    totals
+}
+
+/// What one period of a budget had to spend and what it spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PeriodTotals {
+    pub amount: f64,
+    pub spent: f64,
+    /// Net envelope moves in
+    pub transferred: f64,
+}
+
+/// The limit for `window` from `limits` sorted by effective date: the latest effective on
+/// or before the window's last day, or the first when the window predates them all.
+pub fn limit_for(limits: &[(NaiveDate, f64)], window: BudgetWindow) -> Option<f64> {
+    limits
+        .iter()
+        .rev()
+        .find(|(from, _)| *from <= window.end)
+        .or(limits.first())
+        .map(|(_, amount)| *amount)
+}
+
+fn carry_after(budget: &Budget, carry: f64, p: &PeriodTotals) -> f64 {
+    let left = p.amount + carry + p.transferred - p.spent;
+    match budget.rollover_cap {
+        Some(cap) => left.clamp(-cap, cap),
+        None => left,
+    }
+}
+
+/// One period of a budget with what earlier periods carried into it.
+#[derive(Debug, Clone, Copy)]
+pub struct PeriodRun {
+    pub window: BudgetWindow,
+    pub totals: PeriodTotals,
+    pub carried_in: f64,
+}
+
+/// Consecutive periods with the carry into each. Without rollover nothing carries; with a
+/// cap, the carry is held within ±cap after every period.
+pub fn run_periods(budget: &Budget, periods: &[(BudgetWindow, PeriodTotals)]) -> Vec<PeriodRun> {
+    let mut carry = 0.0;
+    periods
+        .iter()
+        .map(|&(window, totals)| {
+            let run = PeriodRun {
+                window,
+                totals,
+                carried_in: carry,
+            };
+            if budget.rollover {
+                carry = carry_after(budget, carry, &totals);
+            }
+            run
+        })
+        .collect()
+}
+
+/// Limit vs actual for a period; it's complete once `as_of` is past its last day.
+pub fn period_result(run: &PeriodRun, as_of: NaiveDate) -> BudgetPeriodResult {
+    let available = run.totals.amount + run.carried_in + run.totals.transferred;
+    let variance = available - run.totals.spent;
+    BudgetPeriodResult {
+        period_start: run.window.start,
+        period_end: run.window.end,
+        limit: run.totals.amount,
+        carried_in: run.carried_in,
+        transferred: run.totals.transferred,
+        available,
+        spent: run.totals.spent,
+        variance,
+        variance_pct: if available > 0.0 {
+            variance / available * 100.0
+        } else {
+            0.0
+        },
+        hit: variance >= -0.005,
+        complete: run.window.end < as_of,
+    }
+}
+
+/// Hit/miss counts, streaks and average variance over a budget's complete periods.
+pub fn summarize(budget: &Budget, periods: Vec<BudgetPeriodResult>) -> BudgetCategoryHistory {
+    let complete: Vec<&BudgetPeriodResult> = periods.iter().filter(|p| p.complete).collect();
+    let hits = complete.iter().filter(|p| p.hit).count() as u32;
+
+    let (mut longest_hit_streak, mut longest_miss_streak) = (0, 0);
+    let mut current_streak: Option<BudgetStreak> = None;
+    for p in &complete {
+        let kind = if p.hit { "hit" } else { "miss" };
+        match current_streak.as_mut() {
+            Some(streak) if streak.kind == kind => streak.length += 1,
+            _ => {
+                current_streak = Some(BudgetStreak {
+                    kind: kind.to_string(),
+                    length: 1,
+                })
+            }
+        }
+        let length = current_streak.as_ref().map_or(0, |s| s.length);
+        if p.hit {
+            longest_hit_streak = longest_hit_streak.max(length);
+        } else {
+            longest_miss_streak = longest_miss_streak.max(length);
+        }
+    }
+
+    let n = complete.len().max(1) as f64;
+    let avg_variance = complete.iter().map(|p| p.variance).sum::<f64>() / n;
+    let avg_variance_pct = complete.iter().map(|p| p.variance_pct).sum::<f64>() / n;
+
+    BudgetCategoryHistory {
+        category: budget.category.clone(),
+        period: budget.period.clone(),
+        hits,
+        misses: complete.len() as u32 - hits,
+        current_streak,
+        longest_hit_streak,
+        longest_miss_streak,
+        avg_variance,
+        avg_variance_pct,
+        periods,
+    }
+}
+
+/// The `p`th percentile (0–100) of `values`, interpolating between neighbours.
+pub fn percentile(values: &[f64], p: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        n => {
+            let rank = p / 100.0 * (n - 1) as f64;
+            let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+        }
+    }
+}
+
+/// A monthly limit: the percentile of the months' spending without recurring charges,
+/// scaled for the season, plus the recurring charges expected, rounded up to the next $5.
+pub fn suggest_limit(variable: &[f64], p: f64, seasonal_index: f64, recurring: f64) -> f64 {
+    let limit = percentile(variable, p) * seasonal_index + recurring;
+    // Cents of float noise shouldn't push a limit up a step
+    ((limit - 0.005) / 5.0).ceil().max(0.0) * 5.0
+}
+
+/// How many of `actual`'s months went over `limit`.
+pub fn months_over(actual: &[f64], limit: f64) -> u32 {
+    actual.iter().filter(|&&a| a > limit + 0.005).count() as u32
+}
+
+/// What to call one period in messages: "month", "week", ...
+pub fn period_noun(period: &str) -> &'static str {
+    match period {
+        "weekly" => "week",
+        "quarterly" => "quarter",
+        "annual" => "year",
+        "custom" => "period",
+        _ => "month",
+    }
+}
+
+/// Progress through `window` given its totals up to `as_of` and what earlier periods
+/// carried in.
+pub fn progress(
+    budget: &Budget,
+    window: BudgetWindow,
+    totals: PeriodTotals,
+    carried_in: f64,
+    as_of: NaiveDate,
+) -> BudgetProgress {
+    let days = window.days();
+    let elapsed = window.days_elapsed(as_of);
+    let available = totals.amount + carried_in + totals.transferred;
+    let spent = totals.spent;
+    // Any spending against nothing available is fully over
+    let pct_of = |value: f64| {
+        if available > 0.0 {
+            value / available * 100.0
+        } else if value > 0.0 {
+            100.0
+        } else {
+            0.0
+        }
+    };
+
+    let pct_used = pct_of(spent);
+    let projected_spend = if elapsed > 0 {
+        spent / elapsed as f64 * days as f64
+    } else {
+        0.0
+    };
+    let projected_pct = pct_of(projected_spend);
+
+    let status = if pct_used >= 100.0 {
+        "over_budget"
+    } else if projected_pct >= 90.0 {
+        "warning"
+    } else {
+        "on_track"
+    };
+
+    BudgetProgress {
+        category: budget.category.clone(),
+        period: budget.period.clone(),
+        period_start: window.start,
+        period_end: window.end,
+        amount: totals.amount,
+        carried_in,
+        transferred: totals.transferred,
+        available,
+        spent,
+        remaining: (available - spent).max(0.0),
+        pct_used,
+        projected_spend,
+        projected_pct,
+        status: status.to_string(),
+        days_remaining: days - elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn budget(period: &str, start: Option<NaiveDate>, end: Option<NaiveDate>) -> Budget {
+        Budget {
+            id: Uuid::new_v4(),
+            category: "Groceries".into(),
+            amount: 100.0,
+            period: period.into(),
+            start_date: start,
+            end_date: end,
+            rollover: false,
+            rollover_cap: None,
+            scope: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn spent(spent: f64) -> PeriodTotals {
+        PeriodTotals {
+            amount: 100.0,
+            spent,
+            transferred: 0.0,
+        }
+    }
+
+    fn span(b: &Budget, as_of: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        window(b, as_of).map(|w| (w.start, w.end))
+    }
+
+    #[test]
+    fn test_default_windows() {
+        let as_of = d(2026, 2, 18); // a Wednesday
+        assert_eq!(
+            span(&budget("weekly", None, None), as_of),
+            Some((d(2026, 2, 16), d(2026, 2, 22)))
+        );
+        assert_eq!(
+            span(&budget("monthly", None, None), as_of),
+            Some((d(2026, 2, 1), d(2026, 2, 28)))
+        );
+        assert_eq!(
+            span(&budget("quarterly", None, None), as_of),
+            Some((d(2026, 1, 1), d(2026, 3, 31)))
+        );
+        assert_eq!(
+            span(&budget("annual", None, None), as_of),
+            Some((d(2026, 1, 1), d(2026, 12, 31)))
+        );
+    }
+
+    #[test]
+    fn test_anchored_windows() {
+        // A pay-cycle month from the 15th, a fiscal year from April, weeks from Saturday
+        let monthly = budget("monthly", Some(d(2025, 6, 15)), None);
+        assert_eq!(
+            span(&monthly, d(2026, 2, 14)),
+            Some((d(2026, 1, 15), d(2026, 2, 14)))
+        );
+        assert_eq!(
+            span(&monthly, d(2026, 2, 15)),
+            Some((d(2026, 2, 15), d(2026, 3, 14)))
+        );
+
+        let annual = budget("annual", Some(d(2025, 4, 1)), None);
+        assert_eq!(
+            span(&annual, d(2026, 2, 18)),
+            Some((d(2025, 4, 1), d(2026, 3, 31)))
+        );
+
+        let quarterly = budget("quarterly", Some(d(2025, 2, 1)), None);
+        assert_eq!(
+            span(&quarterly, d(2026, 2, 18)),
+            Some((d(2026, 2, 1), d(2026, 4, 30)))
+        );
+
+        let weekly = budget("weekly", Some(d(2026, 1, 3)), None);
+        assert_eq!(
+            span(&weekly, d(2026, 2, 18)),
+            Some((d(2026, 2, 14), d(2026, 2, 20)))
+        );
+
+        // Nothing before the start
+        assert_eq!(span(&weekly, d(2026, 1, 2)), None);
+    }
+
+    #[test]
+    fn test_month_end_anchor_is_clamped() {
+        let monthly = budget("monthly", Some(d(2026, 1, 31)), None);
+        assert_eq!(
+            span(&monthly, d(2026, 2, 28)),
+            Some((d(2026, 2, 28), d(2026, 3, 30)))
+        );
+        assert_eq!(
+            span(&monthly, d(2026, 3, 31)),
+            Some((d(2026, 3, 31), d(2026, 4, 29)))
+        );
+    }
+
+    #[test]
+    fn test_custom_window_and_validation() {
+        let trip = budget("custom", Some(d(2025, 12, 20)), Some(d(2026, 1, 5)));
+        assert_eq!(
+            span(&trip, d(2026, 2, 1)),
+            Some((d(2025, 12, 20), d(2026, 1, 5)))
+        );
+
+        assert!(validate(100.0, "custom", Some(d(2026, 1, 5)), Some(d(2026, 1, 1))).is_err());
+        assert!(validate(100.0, "custom", None, None).is_err());
+        assert!(validate(100.0, "weekly", None, Some(d(2026, 1, 1))).is_err());
+        assert!(validate(100.0, "fortnightly", None, None).is_err());
+        assert!(validate(-1.0, "monthly", None, None).is_err());
+        assert!(validate(100.0, "annual", Some(d(2025, 4, 1)), None).is_ok());
+    }
+
+    #[test]
+    fn test_progress_prorates_over_the_window() {
+        let weekly = budget("weekly", None, None);
+        let w = window(&weekly, d(2026, 2, 18)).unwrap();
+        // 60 by Wednesday: on pace for 140 over the week
+        let p = progress(&weekly, w, spent(60.0), 0.0, d(2026, 2, 18));
+        assert_eq!(p.days_remaining, 4);
+        assert!((p.projected_spend - 140.0).abs() < 1e-9);
+        assert_eq!(p.status, "warning");
+        assert_eq!(p.remaining, 40.0);
+
+        // A finished custom window has nothing left and no extrapolation
+        let trip = budget("custom", Some(d(2025, 12, 20)), Some(d(2026, 1, 5)));
+        let w = window(&trip, d(2026, 2, 1)).unwrap();
+        let p = progress(&trip, w, spent(80.0), 0.0, d(2026, 2, 1));
+        assert_eq!(p.days_remaining, 0);
+        assert_eq!(p.projected_spend, 80.0);
+        assert_eq!(p.status, "on_track");
+    }
+
+    #[test]
+    fn test_windows_between_and_bucket() {
+        let monthly = budget("monthly", None, None);
+        let windows = windows_between(&monthly, d(2025, 11, 20), d(2026, 2, 18));
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0].start, d(2025, 11, 1));
+        assert_eq!(windows[3].end, d(2026, 2, 28));
+
+        let totals = bucket(
+            &windows,
+            [
+                (d(2025, 11, 3), 10.0),
+                (d(2025, 11, 30), 5.0),
+                (d(2026, 1, 31), 7.0),
+                (d(2025, 10, 31), 99.0),
+            ],
+        );
+        assert_eq!(totals, vec![15.0, 0.0, 7.0, 0.0]);
+    }
+
+    #[test]
+    fn test_rollover_with_cap() {
+        let mut b = budget("monthly", None, None);
+        let windows = windows_between(&b, d(2025, 11, 1), d(2026, 2, 18));
+        // 40 left, then 30 over, then 50 left with 20 moved in
+        let totals = [
+            spent(60.0),
+            spent(170.0),
+            PeriodTotals {
+                amount: 100.0,
+                spent: 70.0,
+                transferred: 20.0,
+            },
+            spent(0.0),
+        ];
+        let periods: Vec<(BudgetWindow, PeriodTotals)> = windows.into_iter().zip(totals).collect();
+        let carried = |b: &Budget| -> Vec<f64> {
+            run_periods(b, &periods)
+                .iter()
+                .map(|r| r.carried_in)
+                .collect()
+        };
+        assert_eq!(carried(&b), vec![0.0; 4]);
+
+        b.rollover = true;
+        // 40, then 100 + 40 - 170 = -30, then 100 - 30 + 20 - 70 = 20
+        assert_eq!(carried(&b), vec![0.0, 40.0, -30.0, 20.0]);
+
+        b.rollover_cap = Some(25.0);
+        // 25, then 100 + 25 - 170 = -45 held at -25, then 100 - 25 + 20 - 70 = 25
+        assert_eq!(carried(&b), vec![0.0, 25.0, -25.0, 25.0]);
+
+        let w = window(&b, d(2026, 2, 18)).unwrap();
+        let p = progress(&b, w, spent(90.0), -25.0, d(2026, 2, 18));
+        assert_eq!(p.available, 75.0);
+        assert_eq!(p.status, "over_budget");
+        assert_eq!(p.remaining, 0.0);
+
+        assert!(validate_rollover("custom", true, None).is_err());
+        assert!(validate_rollover("monthly", true, Some(-1.0)).is_err());
+        assert!(validate_rollover("weekly", true, Some(50.0)).is_ok());
+    }
+
+    #[test]
+    fn test_limit_versions() {
+        let limits = [(d(2025, 11, 1), 100.0), (d(2026, 1, 15), 150.0)];
+        let monthly = budget("monthly", None, None);
+        let limit = |as_of| limit_for(&limits, window(&monthly, as_of).unwrap());
+        // Before the first version, the first applies
+        assert_eq!(limit(d(2025, 10, 10)), Some(100.0));
+        assert_eq!(limit(d(2025, 12, 10)), Some(100.0));
+        // A change dated mid-period applies to that period
+        assert_eq!(limit(d(2026, 1, 2)), Some(150.0));
+        assert_eq!(limit(d(2026, 2, 2)), Some(150.0));
+        assert_eq!(
+            limit_for(&[], window(&monthly, d(2026, 2, 2)).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_history_streaks_and_variance() {
+        let b = budget("monthly", None, None);
+        let as_of = d(2026, 2, 18);
+        let spends = [120.0, 90.0, 80.0, 130.0, 140.0, 60.0, 50.0];
+        let periods: Vec<(BudgetWindow, PeriodTotals)> = windows_between(&b, d(2025, 8, 1), as_of)
+            .into_iter()
+            .zip(spends)
+            .map(|(w, amount)| (w, spent(amount)))
+            .collect();
+        let results: Vec<BudgetPeriodResult> = run_periods(&b, &periods)
+            .iter()
+            .map(|run| period_result(run, as_of))
+            .collect();
+        assert!(!results.last().unwrap().complete);
+
+        // Complete: miss, hit, hit, miss, miss, hit; February is in progress
+        let history = summarize(&b, results);
+        assert_eq!(history.periods.len(), 7);
+        assert_eq!((history.hits, history.misses), (3, 3));
+        assert_eq!(history.longest_hit_streak, 2);
+        assert_eq!(history.longest_miss_streak, 2);
+        assert_eq!(
+            history.current_streak,
+            Some(BudgetStreak {
+                kind: "hit".into(),
+                length: 1
+            })
+        );
+        // (-20 + 10 + 20 - 30 - 40 + 40) / 6
+        assert!((history.avg_variance - -20.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let mut b = budget("monthly", None, None);
+        assert_eq!(scope_of(&b).categories, Some(vec!["Groceries".to_string()]));
+
+        let coffee = normalize_scope(BudgetScope {
+            merchants: Some(vec![
+                " starbucks".into(),
+                "Dunkin Donuts".into(),
+                "STARBUCKS".into(),
+            ]),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            coffee.merchants,
+            Some(vec!["STARBUCKS".to_string(), "DUNKIN DONUTS".to_string()])
+        );
+        b.scope = Some(serde_json::to_value(&coffee).unwrap());
+        assert_eq!(scope_of(&b), coffee);
+
+        assert_eq!(normalize_scope(BudgetScope::default()), Ok(None));
+        let blank = BudgetScope {
+            member: Some("  ".into()),
+            ..Default::default()
+        };
+        assert_eq!(normalize_scope(blank), Ok(None));
+        let empty_list = BudgetScope {
+            cards: Some(vec![" ".into()]),
+            ..Default::default()
+        };
+        assert!(normalize_scope(empty_list).is_err());
+    }
+
+    #[test]
+    fn test_suggestion_math() {
+        let months = [80.0, 120.0, 100.0, 90.0, 200.0];
+        assert_eq!(percentile(&months, 50.0), 100.0);
+        assert_eq!(percentile(&months, 75.0), 120.0);
+        assert!((percentile(&months, 90.0) - 168.0).abs() < 1e-9);
+        assert_eq!(percentile(&[], 50.0), 0.0);
+
+        // 100 variable, a quiet season, plus a 15.99 subscription
+        assert_eq!(suggest_limit(&months, 50.0, 0.9, 15.99), 110.0);
+        assert_eq!(suggest_limit(&[0.0, 0.0], 50.0, 1.0, 15.99), 20.0);
+        assert_eq!(suggest_limit(&[100.0], 50.0, 1.0, 0.0), 100.0);
+
+        assert_eq!(months_over(&months, 110.0), 2);
+        assert_eq!(months_over(&months, 200.0), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use uuid::Uuid;
+
+use crate::models::category::{Category, CategoryTotal, ParentTotal, CATEGORY_KINDS};
+
+const MAX_NAME_LEN: usize = 50;
+
+fn cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Trim a category name and collapse runs of whitespace.
+pub fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("name must not be empty".into());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("name must be at most {MAX_NAME_LEN} characters"));
+    }
+    Ok(name)
+}
+
+/// `#RGB` or `#RRGGBB`, stored upper-cased. Blank means none.
+pub fn normalize_color(color: &str) -> Result<Option<String>, String> {
+    let color = color.trim();
+    if color.is_empty() {
+        return Ok(None);
+    }
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if !matches!(hex.len(), 3 | 6) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("color must be a hex colour like #10B981".into());
+    }
+    Ok(Some(color.to_uppercase()))
+}
+
+/// One of [`CATEGORY_KINDS`]. Blank means neither.
+pub fn normalize_kind(kind: &str) -> Result<Option<String>, String> {
+    let kind = kind.trim().to_lowercase();
+    if kind.is_empty() {
+        return Ok(None);
+    }
+    if !CATEGORY_KINDS.contains(&kind.as_str()) {
+        return Err(format!(
+            "kind must be one of: {}",
+            CATEGORY_KINDS.join(", ")
+        ));
+    }
+    Ok(Some(kind))
+}
+
+/// The listed category a name refers to, ignoring case and stray whitespace.
+pub fn find<'a>(name: &str, all: &'a [Category]) -> Option<&'a Category> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    all.iter().find(|c| c.name.to_lowercase() == name)
+}
+
+/// The listed category to file an imported transaction under: the parser's category
+/// when it's listed, otherwise `Uncategorized`.
+pub fn resolve(name: &str, all: &[Category]) -> String {
+    find(name, all)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| "Uncategorized".into())
+}
+
+/// Categories nest one level, so a parent must be top-level and a category with
+/// children can't get a parent. `id` is the category being given the parent, if it
+/// exists yet.
+pub fn check_parent(id: Option<Uuid>, parent: &Category, all: &[Category]) -> Result<(), String> {
+    if Some(parent.id) == id {
+        return Err("a category can't be its own parent".into());
+    }
+    if parent.parent_id.is_some() {
+        return Err(format!(
+            "{} is a subcategory; pick a top-level parent",
+            parent.name
+        ));
+    }
+    if id.is_some() && all.iter().any(|c| c.parent_id == id) {
+        return Err("a category with subcategories must stay top-level".into());
+    }
+    Ok(())
+}
+
+/// A category's name followed by its children's: everything that rolls up into it.
+pub fn family(name: &str, all: &[Category]) -> Vec<String> {
+    let Some(category) = find(name, all) else {
+        return vec![name.to_string()];
+    };
+    let mut names = vec![category.name.clone()];
+    names.extend(
+        all.iter()
+            .filter(|c| c.parent_id == Some(category.id))
+            .map(|c| c.name.clone()),
+    );
+    names
+}
+
+/// The top-level category spending in `name` rolls up into.
+fn top_level<'a>(name: &str, all: &'a [Category]) -> Option<&'a Category> {
+    let category = all.iter().find(|c| c.name == name)?;
+    match category.parent_id {
+        Some(parent_id) => all.iter().find(|c| c.id == parent_id),
+        None => Some(category),
+    }
+}
+
+/// Roll leaf totals up into their top-level categories, largest first. Names that
+/// aren't listed stand on their own.
+pub fn roll_up(totals: &[CategoryTotal], all: &[Category]) -> Vec<ParentTotal> {
+    let mut parents: Vec<ParentTotal> = Vec::new();
+    for leaf in totals {
+        let parent = top_level(&leaf.category, all);
+        let name = parent.map_or(leaf.category.as_str(), |p| p.name.as_str());
+        let index = match parents.iter().position(|p| p.category == name) {
+            Some(index) => index,
+            None => {
+                parents.push(ParentTotal {
+                    category: name.to_string(),
+                    color: parent.and_then(|p| p.color.clone()),
+                    kind: parent.and_then(|p| p.kind.clone()),
+                    total: 0.0,
+                    count: 0,
+                    avg_amount: 0.0,
+                    children: Vec::new(),
+                });
+                parents.len() - 1
+            }
+        };
+        let entry = &mut parents[index];
+        entry.total += leaf.total;
+        entry.count += leaf.count;
+        entry.children.push(leaf.clone());
+    }
+
+    for parent in &mut parents {
+        parent.total = cents(parent.total);
+        if parent.count > 0 {
+            parent.avg_amount = cents(parent.total / parent.count as f64);
+        }
+        parent.children.sort_by(|a, b| {
+            b.total
+                .total_cmp(&a.total)
+                .then(a.category.cmp(&b.category))
+        });
+    }
+    parents.sort_by(|a, b| {
+        b.total
+            .total_cmp(&a.total)
+            .then(a.category.cmp(&b.category))
+    });
+    parents
+}
+
+/// Spending split into essential, discretionary and unclassified. A subcategory
+/// without a kind of its own takes its parent's.
+pub fn by_kind(totals: &[CategoryTotal], all: &[Category]) -> (f64, f64, f64) {
+    let (mut essential, mut discretionary, mut other) = (0.0, 0.0, 0.0);
+    for leaf in totals {
+        let own = all.iter().find(|c| c.name == leaf.category);
+        let kind = own
+            .and_then(|c| c.kind.as_deref())
+            .or_else(|| top_level(&leaf.category, all).and_then(|p| p.kind.as_deref()));
+        match kind {
+            Some("essential") => essential += leaf.total,
+            Some("discretionary") => discretionary += leaf.total,
+            _ => other += leaf.total,
+        }
+    }
+    (cents(essential), cents(discretionary), cents(other))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(name: &str, parent: Option<&Category>, kind: Option<&str>) -> Category {
+        Category {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            parent_id: parent.map(|p| p.id),
+            parent: parent.map(|p| p.name.clone()),
+            color: None,
+            icon: None,
+            kind: kind.map(Into::into),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn total(category: &str, total: f64, count: i64) -> CategoryTotal {
+        CategoryTotal {
+            category: category.into(),
+            total,
+            count,
+            avg_amount: total / count as f64,
+        }
+    }
+
+    fn food_tree() -> Vec<Category> {
+        let food = category("Food", None, Some("essential"));
+        let dining = category("Dining", Some(&food), Some("discretionary"));
+        let groceries = category("Groceries", Some(&food), None);
+        let travel = category("Travel", None, Some("discretionary"));
+        vec![food, dining, groceries, travel]
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_name("  Coffee   shops ").unwrap(), "Coffee shops");
+        assert!(normalize_name("   ").is_err());
+        assert!(normalize_name(&"x".repeat(51)).is_err());
+
+        assert_eq!(
+            normalize_color("#10b981").unwrap().as_deref(),
+            Some("#10B981")
+        );
+        assert_eq!(normalize_color("#fff").unwrap().as_deref(), Some("#FFF"));
+        assert_eq!(normalize_color(" ").unwrap(), None);
+        assert!(normalize_color("10B981").is_err());
+        assert!(normalize_color("#12345G").is_err());
+
+        assert_eq!(
+            normalize_kind("Essential").unwrap().as_deref(),
+            Some("essential")
+        );
+        assert_eq!(normalize_kind("").unwrap(), None);
+        assert!(normalize_kind("luxury").is_err());
+    }
+
+    #[test]
+    fn test_find_and_resolve() {
+        let all = food_tree();
+        assert_eq!(find(" dining ", &all).unwrap().name, "Dining");
+        assert!(find("Dinning", &all).is_none());
+        assert_eq!(resolve("GROCERIES", &all), "Groceries");
+        assert_eq!(
+            resolve("Merchandise & Supplies-Internet Purchase", &all),
+            "Uncategorized"
+        );
+    }
+
+    #[test]
+    fn test_check_parent() {
+        let all = food_tree();
+        let (food, dining, travel) = (&all[0], &all[1], &all[3]);
+        assert!(check_parent(None, food, &all).is_ok());
+        assert!(check_parent(Some(travel.id), food, &all).is_ok());
+        assert!(check_parent(Some(food.id), food, &all)
+            .unwrap_err()
+            .contains("own parent"));
+        // Only one level of nesting
+        assert!(check_parent(None, dining, &all)
+            .unwrap_err()
+            .contains("subcategory"));
+        assert!(check_parent(Some(food.id), travel, &all)
+            .unwrap_err()
+            .contains("top-level"));
+    }
+
+    #[test]
+    fn test_family() {
+        let all = food_tree();
+        assert_eq!(family("food", &all), ["Food", "Dining", "Groceries"]);
+        assert_eq!(family("Dining", &all), ["Dining"]);
+        assert_eq!(family("Yachts", &all), ["Yachts"]);
+    }
+
+    #[test]
+    fn test_roll_up() {
+        let all = food_tree();
+        let totals = [
+            total("Travel", 300.0, 2),
+            total("Groceries", 200.0, 4),
+            total("Dining", 120.0, 6),
+            total("Food", 10.0, 1),
+            total("Legacy", 5.0, 1),
+        ];
+        let parents = roll_up(&totals, &all);
+        let names: Vec<_> = parents.iter().map(|p| p.category.as_str()).collect();
+        assert_eq!(names, ["Food", "Travel", "Legacy"]);
+
+        let food = &parents[0];
+        assert_eq!(food.total, 330.0);
+        assert_eq!(food.count, 11);
+        assert_eq!(food.avg_amount, 30.0);
+        assert_eq!(food.kind.as_deref(), Some("essential"));
+        let children: Vec<_> = food.children.iter().map(|c| c.category.as_str()).collect();
+        assert_eq!(children, ["Groceries", "Dining", "Food"]);
+        assert_eq!(parents[2].children.len(), 1);
+    }
+
+    #[test]
+    fn test_by_kind() {
+        let all = food_tree();
+        let totals = [
+            total("Groceries", 200.0, 4),
+            total("Dining", 120.0, 6),
+            total("Travel", 300.0, 2),
+            total("Legacy", 5.0, 1),
+        ];
+        // Groceries takes Food's kind; Dining has its own
+        assert_eq!(by_kind(&totals, &all), (200.0, 420.0, 5.0));
+    }
+}
@@ -0,0 +1,585 @@
+use axum::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::Ipv6Addr;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::models::notification::Alert;
+
+/// How long any one step of a delivery (connect, write, a reply) may take.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Somewhere alerts can be delivered. Built from a channel's kind and config by [`channel`].
+#[async_trait]
+pub trait Channel: Send + Sync {
+    async fn send(&self, alert: &Alert) -> Result<(), String>;
+}
+
+/// Builds the channel for a stored kind and config. Also used to validate config before
+/// it's saved, so a bad URL or a misspelt field fails at setup rather than at delivery.
+pub fn channel(kind: &str, config: &serde_json::Value) -> Result<Box<dyn Channel>, String> {
+    fn parse<T: for<'de> Deserialize<'de>>(
+        kind: &str,
+        config: &serde_json::Value,
+    ) -> Result<T, String> {
+        serde_json::from_value(config.clone()).map_err(|e| format!("Invalid {kind} config: {e}"))
+    }
+
+    match kind {
+        "webhook" => {
+            let config: WebhookConfig = parse(kind, config)?;
+            HttpUrl::parse(&config.url)?;
+            for (name, value) in &config.headers {
+                check_header(name, value)?;
+            }
+            Ok(Box::new(config))
+        }
+        "ntfy" => {
+            let config: NtfyConfig = parse(kind, config)?;
+            HttpUrl::parse(&config.url)?;
+            if config.topic.trim().is_empty() {
+                return Err("ntfy topic must not be empty".into());
+            }
+            if let Some(token) = &config.token {
+                check_header("Authorization", token)?;
+            }
+            if let Some(p) = config.priority {
+                if !(1..=5).contains(&p) {
+                    return Err("ntfy priority must be between 1 and 5".into());
+                }
+            }
+            Ok(Box::new(config))
+        }
+        "gotify" => {
+            let config: GotifyConfig = parse(kind, config)?;
+            HttpUrl::parse(&config.url)?;
+            check_header("X-Gotify-Key", &config.token)?;
+            Ok(Box::new(config))
+        }
+        "smtp" => {
+            let config: SmtpConfig = parse(kind, config)?;
+            if config.host.trim().is_empty() {
+                return Err("SMTP host must not be empty".into());
+            }
+            if config.to.is_empty() {
+                return Err("SMTP config needs at least one recipient".into());
+            }
+            for address in std::iter::once(&config.from).chain(&config.to) {
+                check_address(address)?;
+            }
+            if let Some(hello) = &config.hello {
+                check_hello(hello)?;
+            }
+            Ok(Box::new(config))
+        }
+        other => Err(format!("Unknown channel kind: {other}")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webhook, ntfy and Gotify: a JSON POST over plain HTTP
+// ---------------------------------------------------------------------------
+
+/// POSTs the alert as JSON (`key`, `alert_type`, `title`, `message`).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Extra request headers, e.g. an auth token
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[async_trait]
+impl Channel for WebhookConfig {
+    async fn send(&self, alert: &Alert) -> Result<(), String> {
+        let body = serde_json::to_vec(alert).map_err(|e| e.to_string())?;
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        http_post(&self.url, &headers, &body).await
+    }
+}
+
+/// Publishes to an ntfy topic with ntfy's JSON API (a POST to the server root).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NtfyConfig {
+    /// The server, e.g. `http://ntfy.local`
+    pub url: String,
+    pub topic: String,
+    /// Access token for protected topics
+    pub token: Option<String>,
+    /// 1 (min) to 5 (max); ntfy's default is 3
+    pub priority: Option<u8>,
+}
+
+#[async_trait]
+impl Channel for NtfyConfig {
+    async fn send(&self, alert: &Alert) -> Result<(), String> {
+        let mut body = serde_json::json!({
+            "topic": self.topic,
+            "title": alert.title,
+            "message": alert.message,
+            "tags": [alert.alert_type],
+        });
+        if let Some(priority) = self.priority {
+            body["priority"] = priority.into();
+        }
+        let auth = self.token.as_ref().map(|t| format!("Bearer {t}"));
+        let headers: Vec<(&str, &str)> =
+            auth.iter().map(|a| ("Authorization", a.as_str())).collect();
+        http_post(&self.url, &headers, body.to_string().as_bytes()).await
+    }
+}
+
+/// Pushes a message to a Gotify server with an application token.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GotifyConfig {
+    /// The server, e.g. `http://gotify.local`
+    pub url: String,
+    pub token: String,
+    pub priority: Option<i64>,
+}
+
+#[async_trait]
+impl Channel for GotifyConfig {
+    async fn send(&self, alert: &Alert) -> Result<(), String> {
+        let body = serde_json::json!({
+            "title": alert.title,
+            "message": alert.message,
+            "priority": self.priority.unwrap_or(5),
+        });
+        let url = format!("{}/message", self.url.trim_end_matches('/'));
+        http_post(
+            &url,
+            &[("X-Gotify-Key", &self.token)],
+            body.to_string().as_bytes(),
+        )
+        .await
+    }
+}
+
+/// An `http://host[:port]/path` URL. TLS isn't supported, so push servers and webhooks
+/// must be reachable over plain HTTP (typically on the local network or via a proxy).
+/// An IPv6 host is written in brackets (`http://[::1]:8080/`) and stored without them.
+#[derive(Debug, PartialEq)]
+struct HttpUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl HttpUrl {
+    fn parse(url: &str) -> Result<Self, String> {
+        if url.starts_with("https://") {
+            return Err(format!(
+                "{url}: https isn't supported; use an http:// address (e.g. through a local proxy)"
+            ));
+        }
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("{url}: expected an http:// URL"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, rest) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| format!("{url}: missing ] after the IPv6 address"))?;
+                if host.parse::<Ipv6Addr>().is_err() {
+                    return Err(format!("{url}: invalid IPv6 address"));
+                }
+                let port = match rest {
+                    "" => None,
+                    _ => Some(
+                        rest.strip_prefix(':')
+                            .ok_or_else(|| format!("{url}: invalid URL"))?,
+                    ),
+                };
+                (host, port)
+            }
+            None => match authority.split_once(':') {
+                Some((_, port)) if port.contains(':') => {
+                    return Err(format!(
+                        "{url}: an IPv6 address must be in brackets, e.g. http://[::1]:8080/"
+                    ));
+                }
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| format!("{url}: invalid port"))?,
+            None => 80,
+        };
+        if host.is_empty()
+            || authority.contains('@')
+            || url.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(format!("{url}: invalid URL"));
+        }
+        Ok(Self {
+            host: host.into(),
+            port,
+            path: path.into(),
+        })
+    }
+
+    /// The host as sent in the `Host` header, with an IPv6 address back in brackets.
+    fn host_header(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        }
+    }
+}
+
+fn check_header(name: &str, value: &str) -> Result<(), String> {
+    let valid_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if !valid_name || value.contains(['\r', '\n']) {
+        return Err(format!("Invalid header: {name}"));
+    }
+    Ok(())
+}
+
+async fn http_post(url: &str, headers: &[(&str, &str)], body: &[u8]) -> Result<(), String> {
+    let url = HttpUrl::parse(url)?;
+    let mut stream = connect(&url.host, url.port).await?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        url.path,
+        url.host_header(),
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    write_all(&mut stream, request.as_bytes()).await?;
+    write_all(&mut stream, body).await?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    timeout(IO_TIMEOUT, reader.read_line(&mut status_line))
+        .await
+        .map_err(|_| "Timed out waiting for a response".to_string())?
+        .map_err(|e| e.to_string())?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Unexpected response: {}", status_line.trim()))?;
+    if !(200..300).contains(&status) {
+        // Include the start of the body; services usually say what was wrong
+        let mut rest = Vec::new();
+        let _ = timeout(IO_TIMEOUT, reader.take(4096).read_to_end(&mut rest)).await;
+        let rest = String::from_utf8_lossy(&rest);
+        let detail = rest.split("\r\n\r\n").nth(1).unwrap_or("").trim();
+        return Err(format!("HTTP {status} {detail}").trim().to_string());
+    }
+    Ok(())
+}
+
+async fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    timeout(IO_TIMEOUT, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| format!("Timed out connecting to {host}:{port}"))?
+        .map_err(|e| format!("Couldn't connect to {host}:{port}: {e}"))
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(stream: &mut W, bytes: &[u8]) -> Result<(), String> {
+    timeout(IO_TIMEOUT, stream.write_all(bytes))
+        .await
+        .map_err(|_| "Timed out sending".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Email over plain SMTP
+// ---------------------------------------------------------------------------
+
+/// Sends a plain-text email through an SMTP relay that accepts mail without
+/// authentication or TLS (a local MTA or relay).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Name given in EHLO; defaults to `localhost`
+    pub hello: Option<String>,
+}
+
+fn default_smtp_port() -> u16 {
+    25
+}
+
+fn check_address(address: &str) -> Result<(), String> {
+    let valid = address.contains('@')
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ','));
+    if !valid {
+        return Err(format!("Invalid email address: {address}"));
+    }
+    Ok(())
+}
+
+/// The EHLO name goes into the command line as is, so it must be a single token.
+fn check_hello(hello: &str) -> Result<(), String> {
+    if hello.is_empty() || hello.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Invalid SMTP hello name: {hello:?}"));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Channel for SmtpConfig {
+    async fn send(&self, alert: &Alert) -> Result<(), String> {
+        let stream = connect(&self.host, self.port).await?;
+        let mut smtp = BufReader::new(stream);
+
+        reply(&mut smtp, 220).await?;
+        let hello = self.hello.as_deref().unwrap_or("localhost");
+        command(&mut smtp, &format!("EHLO {hello}"), 250).await?;
+        command(&mut smtp, &format!("MAIL FROM:<{}>", self.from), 250).await?;
+        for to in &self.to {
+            command(&mut smtp, &format!("RCPT TO:<{to}>"), 250).await?;
+        }
+        command(&mut smtp, "DATA", 354).await?;
+        let message = email(&self.from, &self.to, alert, chrono::Utc::now());
+        write_all(smtp.get_mut(), message.as_bytes()).await?;
+        command(&mut smtp, ".", 250).await?;
+        // The message is accepted; a failed QUIT doesn't matter
+        let _ = command(&mut smtp, "QUIT", 221).await;
+        Ok(())
+    }
+}
+
+/// Sends one SMTP command and checks the reply code.
+async fn command(smtp: &mut BufReader<TcpStream>, line: &str, expect: u16) -> Result<(), String> {
+    write_all(smtp.get_mut(), format!("{line}\r\n").as_bytes()).await?;
+    reply(smtp, expect).await.map_err(|e| {
+        let verb = line.split([' ', ':']).next().unwrap_or(line);
+        format!("{verb}: {e}")
+    })
+}
+
+/// Reads a (possibly multi-line) SMTP reply and checks its code.
+async fn reply(smtp: &mut BufReader<TcpStream>, expect: u16) -> Result<(), String> {
+    loop {
+        let mut line = String::new();
+        let n = timeout(IO_TIMEOUT, smtp.read_line(&mut line))
+            .await
+            .map_err(|_| "Timed out waiting for the SMTP server".to_string())?
+            .map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("SMTP server closed the connection".into());
+        }
+        let code: Option<u16> = line.get(..3).and_then(|c| c.parse().ok());
+        // "250-..." continues, "250 ..." ends the reply
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        return match code {
+            Some(code) if code == expect || (expect == 250 && code == 251) => Ok(()),
+            _ => Err(format!("SMTP server replied {}", line.trim())),
+        };
+    }
+}
+
+/// The DATA section: headers, then the message, with CRLF line endings, dot-stuffing and
+/// the terminating `.` left to the caller.
+fn email(from: &str, to: &[String], alert: &Alert, date: chrono::DateTime<chrono::Utc>) -> String {
+    let mut out = format!(
+        "From: {from}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        to.join(", "),
+        encode_subject(&alert.title),
+        date.to_rfc2822()
+    );
+    for line in alert.message.lines() {
+        if line.starts_with('.') {
+            out.push('.');
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Subjects are ASCII as-is; anything else is sent as an RFC 2047 Q-encoded word.
+fn encode_subject(subject: &str) -> String {
+    let subject: String = subject.chars().filter(|c| !c.is_control()).collect();
+    if subject.is_ascii() {
+        return subject;
+    }
+    let mut out = String::from("=?UTF-8?Q?");
+    for b in subject.bytes() {
+        match b {
+            b' ' => out.push('_'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'!' | b'*' | b'+' | b'-' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("={b:02X}")),
+        }
+    }
+    out.push_str("?=");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn alert(title: &str, message: &str) -> Alert {
+        Alert {
+            key: "budget:Dining:80:2026-02-01".into(),
+            alert_type: "budget_80".into(),
+            title: title.into(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_http_url() {
+        assert_eq!(
+            HttpUrl::parse("http://127.0.0.1:8090/hooks/ledgr").unwrap(),
+            HttpUrl {
+                host: "127.0.0.1".into(),
+                port: 8090,
+                path: "/hooks/ledgr".into()
+            }
+        );
+        assert_eq!(
+            HttpUrl::parse("http://ntfy.local").unwrap(),
+            HttpUrl {
+                host: "ntfy.local".into(),
+                port: 80,
+                path: "/".into()
+            }
+        );
+        assert!(HttpUrl::parse("https://ntfy.sh")
+            .unwrap_err()
+            .contains("https"));
+        assert!(HttpUrl::parse("ftp://example.com").is_err());
+        assert!(HttpUrl::parse("http://host:port/").is_err());
+        assert!(HttpUrl::parse("http://user@host/").is_err());
+        assert!(HttpUrl::parse("http://host/a b").is_err());
+    }
+
+    #[test]
+    fn test_parse_http_url_with_ipv6_host() {
+        let url = HttpUrl::parse("http://[::1]:8080/hooks").unwrap();
+        assert_eq!(
+            url,
+            HttpUrl {
+                host: "::1".into(),
+                port: 8080,
+                path: "/hooks".into()
+            }
+        );
+        assert_eq!(url.host_header(), "[::1]");
+        assert_eq!(HttpUrl::parse("http://[fe80::2]/").unwrap().port, 80);
+        assert_eq!(
+            HttpUrl::parse("http://ntfy.local").unwrap().host_header(),
+            "ntfy.local"
+        );
+
+        assert!(HttpUrl::parse("http://[::1:8080/")
+            .unwrap_err()
+            .contains("missing ]"));
+        assert!(HttpUrl::parse("http://[not-an-ip]/").is_err());
+        assert!(HttpUrl::parse("http://[::1]8080/").is_err());
+        assert!(HttpUrl::parse("http://[::1]:/").is_err());
+        assert!(HttpUrl::parse("http://::1:8080/")
+            .unwrap_err()
+            .contains("brackets"));
+    }
+
+    #[test]
+    fn test_channel_config_validation() {
+        assert!(channel("webhook", &json!({ "url": "http://localhost/hook" })).is_ok());
+        assert!(channel(
+            "webhook",
+            &json!({ "url": "http://localhost/hook", "secret": "x" })
+        )
+        .is_err());
+        assert!(channel(
+            "webhook",
+            &json!({ "url": "http://h/", "headers": { "X-Bad": "a\r\nb" } })
+        )
+        .is_err());
+        assert!(channel("ntfy", &json!({ "url": "http://ntfy.local", "topic": "" })).is_err());
+        assert!(channel(
+            "ntfy",
+            &json!({ "url": "http://ntfy.local", "topic": "t", "priority": 9 })
+        )
+        .is_err());
+        assert!(channel("gotify", &json!({ "url": "http://gotify.local" })).is_err());
+        assert!(channel(
+            "smtp",
+            &json!({ "host": "localhost", "from": "a@b.c", "to": [] })
+        )
+        .is_err());
+        assert!(channel(
+            "smtp",
+            &json!({ "host": "localhost", "from": "a@b.c", "to": ["x>@y"] })
+        )
+        .is_err());
+        assert!(channel(
+            "smtp",
+            &json!({ "host": "localhost", "from": "a@b.c", "to": ["d@e.f"] })
+        )
+        .is_ok());
+        assert!(channel(
+            "smtp",
+            &json!({ "host": "localhost", "from": "a@b.c", "to": ["d@e.f"], "hello": "mail.example.com" })
+        )
+        .is_ok());
+        for hello in ["", "a b", "x\r\nRCPT TO:<evil@example.com>"] {
+            assert!(channel(
+                "smtp",
+                &json!({ "host": "localhost", "from": "a@b.c", "to": ["d@e.f"], "hello": hello })
+            )
+            .is_err());
+        }
+        assert!(channel("sms", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_email_dot_stuffing_and_subject() {
+        let date = chrono::DateTime::parse_from_rfc3339("2026-02-20T08:00:00Z")
+            .unwrap()
+            .to_utc();
+        let message = email(
+            "ledgr@home.lan",
+            &["me@home.lan".into()],
+            &alert("Café budget at 85%", "Line one\n.hidden line"),
+            date,
+        );
+        assert!(message.contains("Subject: =?UTF-8?Q?Caf=C3=A9_budget_at_85=25?=\r\n"));
+        assert!(message.ends_with("\r\n\r\nLine one\r\n..hidden line\r\n"));
+        assert_eq!(
+            encode_subject("Dining budget exceeded"),
+            "Dining budget exceeded"
+        );
+    }
+}
@@ -5,7 +5,7 @@ use sha2::{Digest, Sha256};
 
 use crate::models::card::Card;
 use crate::models::transaction::NewTransaction;
-use crate::services::merchant_normalizer;
+use crate::services::{fx, merchant_normalizer};
 
 pub struct ParseResult {
     pub transactions: Vec<NewTransaction>,
@@ -54,9 +54,10 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
 
     let amount_idx = card.amount_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
     let debit_idx = card.debit_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
-    let _credit_idx = card.credit_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
+    let credit_idx = card.credit_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
     let category_idx = card.category_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
     let member_idx = card.member_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
+    let currency_idx = card.currency_column.as_deref().and_then(|c| find_column(&headers, Some(c)));
 
     let date_format = card.date_format.as_deref().unwrap_or("MM/DD/YY");
 
@@ -97,7 +98,8 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
             continue;
         }
 
-        // Parse amount
+        // Parse amount — normalized so positive is money out and negative is money in
+        let mut is_credit = false;
         let amount = if let Some(idx) = amount_idx {
             // Single amount column mode
             let val_str = fields.get(idx).map(|s| s.as_str()).unwrap_or("");
@@ -108,19 +110,36 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
                     continue;
                 }
             };
-            if card.skip_negative_amounts && val < 0.0 {
-                continue; // Skip credits/payments
+            let val = if card.amount_sign == "debit_negative" { -val } else { val };
+            if val < 0.0 {
+                if card.import_credits {
+                    is_credit = true;
+                } else if card.skip_negative_amounts {
+                    continue; // Skip credits/payments
+                }
             }
             val
         } else if let Some(d_idx) = debit_idx {
             // Debit/credit column mode
             let debit_str = fields.get(d_idx).map(|s| s.as_str()).unwrap_or("");
             if debit_str.is_empty() {
-                continue; // Credit-only row, skip
-            }
-            match debit_str.replace(',', "").parse::<f64>() {
-                Ok(v) if v > 0.0 => v,
-                _ => continue,
+                // Credit-only row: imported as money in when enabled, otherwise skipped
+                let credit = credit_idx
+                    .filter(|_| card.import_credits)
+                    .and_then(|c_idx| fields.get(c_idx))
+                    .and_then(|c| c.replace(',', "").parse::<f64>().ok());
+                match credit {
+                    Some(v) if v.abs() > 0.0 => {
+                        is_credit = true;
+                        -v.abs()
+                    }
+                    _ => continue,
+                }
+            } else {
+                match debit_str.replace(',', "").parse::<f64>() {
+                    Ok(v) if v > 0.0 => v,
+                    _ => continue,
+                }
             }
         } else {
             tracing::warn!("No amount or debit column configured");
@@ -138,8 +157,15 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
             }
         }
 
-        // Category
-        let category = if let Some(c_idx) = category_idx {
+        // Classification and category
+        let classification = if is_credit {
+            classify_credit(&description, &card.account_type)
+        } else {
+            "expense".to_string()
+        };
+        let category = if classification == "income" {
+            "Income".to_string()
+        } else if let Some(c_idx) = category_idx {
             let csv_cat = fields.get(c_idx).map(|s| s.as_str()).unwrap_or("");
             if csv_cat.is_empty() {
                 categorize(&description)
@@ -150,6 +176,16 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
             categorize(&description)
         };
 
+        // Currency: per-row column when present, otherwise the card's default
+        let currency = currency_idx
+            .and_then(|idx| fields.get(idx))
+            .and_then(|c| fx::currency_code(c))
+            .unwrap_or_else(|| card.currency.clone());
+        let (original_amount, original_currency) = match fx::extract_foreign_amount(&fields) {
+            Some((amt, ccy)) if ccy != currency => (Some(amt), Some(ccy)),
+            _ => (None, None),
+        };
+
         let hash = compute_hash(&date.to_string(), &description, amount, &card.code);
         let merchant_normalized = merchant_normalizer::normalize_merchant(&description);
 
@@ -163,6 +199,10 @@ pub fn parse_csv(data: &str, card: &Card, user_name: Option<&str>) -> Result<Par
             raw_data: Some(raw_data),
             hash,
             merchant_normalized,
+            currency,
+            original_amount,
+            original_currency,
+            classification,
         });
     }
 
@@ -244,8 +284,24 @@ fn fuzzy_name_match(configured: &str, csv_member: &str) -> bool {
 }
 
 /// Map verbose CSV category strings (like Amex's) to simplified categories.
+/// Whole bank category names (as Chase exports them) and the listed category each files under.
+const BANK_CATEGORIES: &[(&str, &str)] = &[
+    ("food & drink", "Dining"),
+    ("bills & utilities", "Utilities"),
+    ("home", "Housing"),
+    ("personal", "Shopping"),
+    ("shopping", "Shopping"),
+    ("automotive", "Transportation"),
+    ("entertainment", "Entertainment"),
+    ("gifts & donations", "Gifts"),
+    ("health & wellness", "Health"),
+];
+
 fn map_csv_category(csv_cat: &str) -> String {
-    let cat = csv_cat.to_lowercase();
+    let cat = csv_cat.trim().to_lowercase();
+    if let Some((_, listed)) = BANK_CATEGORIES.iter().find(|(bank, _)| *bank == cat) {
+        return listed.to_string();
+    }
     if cat.contains("groceries") {
         return "Groceries".into();
     }
@@ -276,6 +332,31 @@ fn map_csv_category(csv_cat: &str) -> String {
     csv_cat.to_string()
 }
 
+/// Classify money coming in. Paychecks and interest are income anywhere; other credits are
+/// income on bank accounts but refunds on credit cards, where they offset spending.
+fn classify_credit(description: &str, account_type: &str) -> String {
+    let desc = description.to_lowercase();
+
+    if desc.contains("payroll")
+        || desc.contains("direct dep")
+        || desc.contains("salary")
+        || desc.contains("interest paid")
+        || desc.contains("dividend")
+    {
+        return "income".into();
+    }
+
+    if desc.contains("refund") || desc.contains("reversal") || desc.contains("return") {
+        return "expense".into();
+    }
+
+    if account_type == "credit" {
+        "expense".into()
+    } else {
+        "income".into()
+    }
+}
+
 fn categorize(description: &str) -> String {
     let desc = description.to_lowercase();
 
@@ -384,6 +465,88 @@ fn categorize(description: &str) -> String {
 mod tests {
     use super::*;
 
+    fn test_card() -> Card {
+        Card {
+            id: uuid::Uuid::new_v4(),
+            code: "travel".into(),
+            label: "Travel Card".into(),
+            color: "#000000".into(),
+            header_pattern: None,
+            delimiter: ",".into(),
+            date_column: Some("Date".into()),
+            date_format: Some("YYYY-MM-DD".into()),
+            description_column: Some("Description".into()),
+            amount_column: Some("Amount".into()),
+            debit_column: None,
+            credit_column: None,
+            category_column: None,
+            member_column: None,
+            skip_negative_amounts: true,
+            created_at: chrono::Utc::now(),
+            currency: "USD".into(),
+            currency_column: Some("Currency".into()),
+            account_type: "credit".into(),
+            amount_sign: "debit_positive".into(),
+            import_credits: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_currency_column_and_default() {
+        let data = "Date,Description,Amount,Currency\n\
+                    2026-01-02,CAFE DE FLORE,12.50,eur\n\
+                    2026-01-03,STARBUCKS,5.00,\n";
+        let result = parse_csv(data, &test_card(), None).unwrap();
+        assert_eq!(result.transactions[0].currency, "EUR");
+        assert_eq!(result.transactions[1].currency, "USD");
+    }
+
+    #[test]
+    fn test_parse_csv_foreign_spend_amount() {
+        let data = "Date,Description,Amount,Extended Details\n\
+                    2026-01-02,HOTEL LUTETIA,110.00,Foreign Spend Amount: 100.00 Euro Commission Amount: 0.00\n";
+        let result = parse_csv(data, &test_card(), None).unwrap();
+        let txn = &result.transactions[0];
+        assert_eq!(txn.currency, "USD");
+        assert_eq!(txn.original_amount, Some(100.0));
+        assert_eq!(txn.original_currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn test_parse_csv_bank_export_with_credits() {
+        let mut card = test_card();
+        card.account_type = "checking".into();
+        card.amount_sign = "debit_negative".into();
+        card.import_credits = true;
+        let data = "Date,Description,Amount\n\
+                    2026-01-01,PAYROLL ACME CORP,2500.00\n\
+                    2026-01-02,RENT PAYMENT,-1500.00\n";
+        let result = parse_csv(data, &card, None).unwrap();
+        assert_eq!(result.transactions[0].amount, -2500.0);
+        assert_eq!(result.transactions[0].classification, "income");
+        assert_eq!(result.transactions[0].category, "Income");
+        assert_eq!(result.transactions[1].amount, 1500.0);
+        assert_eq!(result.transactions[1].classification, "expense");
+    }
+
+    #[test]
+    fn test_parse_csv_skips_credits_by_default() {
+        let data = "Date,Description,Amount\n\
+                    2026-01-01,PAYMENT THANK YOU,-500.00\n\
+                    2026-01-02,STARBUCKS,5.00\n";
+        let result = parse_csv(data, &test_card(), None).unwrap();
+        assert_eq!(result.transactions.len(), 1);
+        assert_eq!(result.transactions[0].classification, "expense");
+    }
+
+    #[test]
+    fn test_classify_credit() {
+        assert_eq!(classify_credit("ACME PAYROLL", "credit"), "income");
+        assert_eq!(classify_credit("AMAZON REFUND", "checking"), "expense");
+        assert_eq!(classify_credit("MOBILE DEPOSIT", "checking"), "income");
+        assert_eq!(classify_credit("MERCHANT CREDIT", "credit"), "expense");
+    }
+
     #[test]
     fn test_auto_detect_delimiter_csv() {
         assert_eq!(auto_detect_delimiter("a,b,c\n1,2,3"), b',');
@@ -491,6 +654,10 @@ mod tests {
         assert_eq!(map_csv_category("Restaurant-Bar & Café"), "Dining");
         assert_eq!(map_csv_category("Merchandise & Supplies-Groceries"), "Groceries");
         assert_eq!(map_csv_category("Transportation-Fuel"), "Gas");
+        assert_eq!(map_csv_category("Food & Drink"), "Dining");
+        assert_eq!(map_csv_category("Bills & Utilities"), "Utilities");
+        assert_eq!(map_csv_category("Personal"), "Shopping");
+        assert_eq!(map_csv_category(" home "), "Housing");
     }
 
     #[test]
@@ -0,0 +1,802 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::analytics::{
+    HorizonCategory, HorizonMonth, MethodAccuracy, RecurringTransaction,
+};
+use crate::services::analytics::{days_in_month, ewma, linear_projection, predicted_charges};
+
+/// Projection methods, in the order used by [`MethodSet`].
+pub const METHODS: [&str; 4] = ["linear", "day_weighted", "ewma", "seasonal"];
+
+/// Completed months needed before a same-month-last-year index is used.
+pub const SEASONAL_MIN_MONTHS: usize = 12;
+
+/// Completed months replayed to measure each method's error.
+const BACKTEST_MONTHS: usize = 12;
+
+/// Errors below this (%) don't earn a method more weight.
+const MIN_MAPE: f64 = 1.0;
+
+/// Two-sided 80% normal quantile.
+pub const INTERVAL_Z: f64 = 1.2816;
+
+/// One value per method in [`METHODS`]; `None` when there isn't enough history.
+pub type MethodSet = [Option<f64>; 4];
+
+/// Spending per day.
+pub type DailyTotals = BTreeMap<NaiveDate, f64>;
+
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+pub fn add_months(month: NaiveDate, n: u32) -> NaiveDate {
+    month.checked_add_months(Months::new(n)).unwrap_or(month)
+}
+
+fn month_days(month: NaiveDate) -> u32 {
+    days_in_month(month.year(), month.month())
+}
+
+/// Completed monthly totals from the first month with data up to (not including) `month`.
+/// Months without spending count as zero, so the series is contiguous.
+pub fn completed_months(daily: &DailyTotals, month: NaiveDate) -> Vec<(NaiveDate, f64)> {
+    let Some(first) = daily.keys().next().map(|d| month_start(*d)) else {
+        return Vec::new();
+    };
+    let mut totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut m = first;
+    while m < month {
+        totals.insert(m, 0.0);
+        m = add_months(m, 1);
+    }
+    for (date, amount) in daily.range(..month) {
+        *totals.entry(month_start(*date)).or_default() += amount;
+    }
+    totals.into_iter().collect()
+}
+
+/// Spending from the first of `month` through day `day`.
+pub fn spent_through(daily: &DailyTotals, month: NaiveDate, day: u32) -> f64 {
+    let end = month + Duration::days(day as i64);
+    daily.range(month..end).map(|(_, a)| a).sum()
+}
+
+/// Fill a sparse month → total map into a contiguous series from its first month up to
+/// (not including) `until`.
+pub fn contiguous(totals: &BTreeMap<NaiveDate, f64>, until: NaiveDate) -> Vec<f64> {
+    let Some(first) = totals.keys().next().copied() else {
+        return Vec::new();
+    };
+    let mut series = Vec::new();
+    let mut m = first;
+    while m < until {
+        series.push(totals.get(&m).copied().unwrap_or(0.0));
+        m = add_months(m, 1);
+    }
+    series
+}
+
+/// Same-month-last-year index: the month twelve back relative to the average of the
+/// twelve months before `history`'s end. `None` without a full year or with no spending.
+pub fn seasonal_index(history: &[f64]) -> Option<f64> {
+    if history.len() < SEASONAL_MIN_MONTHS {
+        return None;
+    }
+    let last_year = &history[history.len() - SEASONAL_MIN_MONTHS..];
+    let mean = last_year.iter().sum::<f64>() / last_year.len() as f64;
+    (mean > 0.0).then(|| last_year[0] / mean)
+}
+
+/// Every method's projection of `month`'s total, made on day `day` of it from the data
+/// available at that point.
+pub fn project_month(daily: &DailyTotals, month: NaiveDate, day: u32, alpha: f64) -> MethodSet {
+    let dim = month_days(month);
+    let day = day.min(dim);
+    let spent = spent_through(daily, month, day);
+    let history = completed_months(daily, month);
+    let totals: Vec<f64> = history.iter().map(|(_, t)| *t).collect();
+
+    let linear = Some(linear_projection(spent, day, dim));
+
+    // Average spend per day of month across completed months, zero days included
+    let day_weighted = if history.is_empty() {
+        None
+    } else {
+        let mut sums = [0.0; 32];
+        let mut counts = [0u32; 32];
+        for (m, _) in &history {
+            for dom in 1..=month_days(*m) {
+                counts[dom as usize] += 1;
+            }
+        }
+        for (date, amount) in daily.range(..month) {
+            sums[date.day() as usize] += amount;
+        }
+        let remaining: f64 = ((day + 1)..=dim)
+            .map(|dom| {
+                let c = counts[dom as usize];
+                if c > 0 {
+                    sums[dom as usize] / c as f64
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+        Some(spent + remaining)
+    };
+
+    let level = (!totals.is_empty()).then(|| ewma(&totals, alpha));
+    let ewma_projection = level.map(|l| l.max(spent));
+    let seasonal = level
+        .zip(seasonal_index(&totals))
+        .map(|(l, index)| (l * index).max(spent));
+
+    [linear, day_weighted, ewma_projection, seasonal]
+}
+
+/// A replayed month: what each method would have projected, and what was actually spent.
+#[derive(Debug, Clone)]
+pub struct BacktestSample {
+    pub projections: MethodSet,
+    pub actual: f64,
+}
+
+/// Replay up to twelve completed months before `as_of`'s month, projecting each from the
+/// same day of the month as today.
+pub fn backtest(daily: &DailyTotals, as_of: NaiveDate, alpha: f64) -> Vec<BacktestSample> {
+    let current = month_start(as_of);
+    let history = completed_months(daily, current);
+    history
+        .iter()
+        .enumerate()
+        .skip(1)
+        .rev()
+        .take(BACKTEST_MONTHS)
+        .filter(|(_, (_, actual))| *actual > 0.0)
+        .map(|(_, (month, actual))| BacktestSample {
+            projections: project_month(daily, *month, as_of.day(), alpha),
+            actual: *actual,
+        })
+        .collect()
+}
+
+/// Mean absolute percentage error per method over the samples where it had a projection.
+pub fn method_accuracy(samples: &[BacktestSample]) -> Vec<(Option<f64>, usize)> {
+    (0..METHODS.len())
+        .map(|i| {
+            let errors: Vec<f64> = samples
+                .iter()
+                .filter_map(|s| s.projections[i].map(|p| (p - s.actual).abs() / s.actual * 100.0))
+                .collect();
+            if errors.is_empty() {
+                (None, 0)
+            } else {
+                (
+                    Some(errors.iter().sum::<f64>() / errors.len() as f64),
+                    errors.len(),
+                )
+            }
+        })
+        .collect()
+}
+
+/// Inverse-MAPE weights over the methods that have a projection now. Methods never
+/// backtested get no weight unless none were, in which case the average is flat.
+pub fn method_weights(current: &MethodSet, accuracy: &[(Option<f64>, usize)]) -> [f64; 4] {
+    let mut weights = [0.0; 4];
+    let scored = (0..METHODS.len()).any(|i| current[i].is_some() && accuracy[i].0.is_some());
+    for i in 0..METHODS.len() {
+        if current[i].is_none() {
+            continue;
+        }
+        weights[i] = match accuracy[i].0 {
+            Some(mape) => 1.0 / mape.max(MIN_MAPE),
+            None if !scored => 1.0,
+            None => 0.0,
+        };
+    }
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        for w in &mut weights {
+            *w /= total;
+        }
+    }
+    weights
+}
+
+/// Weighted projection; weights of methods without a value are spread over the rest.
+pub fn blend(projections: &MethodSet, weights: &[f64; 4]) -> Option<f64> {
+    let (sum, weight) = projections
+        .iter()
+        .zip(weights)
+        .filter_map(|(p, w)| p.map(|p| (p * w, *w)))
+        .fold((0.0, 0.0), |(s, tw), (pw, w)| (s + pw, tw + w));
+    (weight > 0.0).then(|| sum / weight)
+}
+
+/// Relative spread of the blended projection's past errors; falls back to the month-to-month
+/// variation scaled by how much of the month is left when there are fewer than three samples.
+pub fn relative_error(
+    samples: &[BacktestSample],
+    weights: &[f64; 4],
+    monthly_totals: &[f64],
+    remaining_fraction: f64,
+) -> f64 {
+    let errors: Vec<f64> = samples
+        .iter()
+        .filter_map(|s| {
+            blend(&s.projections, weights)
+                .filter(|p| *p > 0.0)
+                .map(|p| (s.actual - p) / p)
+        })
+        .collect();
+    if errors.len() >= 3 {
+        let mean = errors.iter().sum::<f64>() / errors.len() as f64;
+        let var =
+            errors.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / (errors.len() - 1) as f64;
+        return var.sqrt();
+    }
+    let n = monthly_totals.len();
+    let mean = if n > 0 {
+        monthly_totals.iter().sum::<f64>() / n as f64
+    } else {
+        0.0
+    };
+    let cv = if n >= 2 && mean > 0.0 {
+        (monthly_totals
+            .iter()
+            .map(|t| (t - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64)
+            .sqrt()
+            / mean
+    } else {
+        0.25
+    };
+    cv * remaining_fraction
+}
+
+/// Accuracy report for each method, with the weight it got.
+pub fn accuracy_report(
+    accuracy: &[(Option<f64>, usize)],
+    weights: &[f64; 4],
+) -> Vec<MethodAccuracy> {
+    METHODS
+        .iter()
+        .enumerate()
+        .map(|(i, method)| MethodAccuracy {
+            method: method.to_string(),
+            mape: accuracy[i].0,
+            samples: accuracy[i].1,
+            weight: weights[i],
+        })
+        .collect()
+}
+
+/// A category's projection: linear from this month's pace, EWMA of its completed months,
+/// and a blend that trusts the pace more as the month goes on.
+pub fn category_projection(
+    spent: f64,
+    history: &[f64],
+    day: u32,
+    dim: u32,
+    alpha: f64,
+) -> (f64, Option<f64>, f64) {
+    let linear = linear_projection(spent, day, dim);
+    let level = (!history.is_empty()).then(|| ewma(history, alpha));
+    let projected = match level {
+        Some(level) => {
+            let elapsed = day as f64 / dim as f64;
+            (elapsed * linear + (1.0 - elapsed) * level).max(spent)
+        }
+        None => linear,
+    };
+    (linear, level, projected)
+}
+
+/// Monthly totals by category keyed by month start, from (category, month, total) rows.
+pub fn by_category(rows: &[(String, NaiveDate, f64)]) -> HashMap<String, BTreeMap<NaiveDate, f64>> {
+    let mut map: HashMap<String, BTreeMap<NaiveDate, f64>> = HashMap::new();
+    for (category, month, total) in rows {
+        *map.entry(category.clone())
+            .or_default()
+            .entry(month_start(*month))
+            .or_default() += total;
+    }
+    map
+}
+
+// ── Horizon ──
+
+/// A past charge with its category, for splitting history into recurring and the rest.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CategoryCharge {
+    pub category: String,
+    pub merchant: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+}
+
+/// A predicted charge of a recurring series in one of the horizon's months.
+#[derive(Debug, Clone)]
+pub struct SeriesCharge {
+    /// Index into the recurring series the model was built from
+    pub series: usize,
+    pub category: String,
+    /// Index into [`HorizonModel::months`]
+    pub month: usize,
+    pub amount: f64,
+    pub confidence: f64,
+}
+
+/// Inputs for projecting the months after the current one: each category's completed
+/// monthly spending without its recurring charges, and the charges the active series will make.
+#[derive(Debug, Clone)]
+pub struct HorizonModel {
+    pub months: Vec<NaiveDate>,
+    pub categories: BTreeMap<String, Vec<f64>>,
+    pub charges: Vec<SeriesCharge>,
+    pub alpha: f64,
+}
+
+/// A what-if change to a [`HorizonModel`] projection.
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// Multiply a category's projection
+    Scale { category: String, factor: f64 },
+    /// Drop the predicted charges of these series
+    Drop { series: Vec<usize> },
+    /// Add a fixed amount every month
+    Add { category: String, amount: f64 },
+}
+
+fn in_series(c: &CategoryCharge, r: &RecurringTransaction, tolerance: f64) -> bool {
+    c.merchant == r.merchant
+        && (c.amount - r.next_expected_amount).abs() <= tolerance * r.next_expected_amount
+}
+
+/// Build the model for the `months` months after `as_of`'s month. Past charges within
+/// `tolerance` of a detected series' amount are taken out of the category history, active
+/// or not, so a cancelled subscription doesn't linger in the baseline; only active series
+/// are predicted forward, under the category of their merchant's latest charge.
+pub fn horizon_model(
+    rows: &[CategoryCharge],
+    recurring: &[RecurringTransaction],
+    as_of: NaiveDate,
+    months: u32,
+    alpha: f64,
+    tolerance: f64,
+) -> HorizonModel {
+    let current = month_start(as_of);
+    let horizon: Vec<NaiveDate> = (1..=months).map(|k| add_months(current, k)).collect();
+
+    let mut totals: HashMap<&str, BTreeMap<NaiveDate, f64>> = HashMap::new();
+    let mut merchant_category: HashMap<&str, &str> = HashMap::new();
+    let mut by_date: Vec<&CategoryCharge> = rows.iter().collect();
+    by_date.sort_by_key(|c| c.date);
+    for c in by_date {
+        merchant_category.insert(&c.merchant, &c.category);
+        if c.date >= current {
+            continue;
+        }
+        let month = totals
+            .entry(&c.category)
+            .or_default()
+            .entry(month_start(c.date))
+            .or_default();
+        if !recurring.iter().any(|r| in_series(c, r, tolerance)) {
+            *month += c.amount;
+        }
+    }
+    let categories = totals
+        .into_iter()
+        .map(|(category, months)| (category.to_string(), contiguous(&months, current)))
+        .collect();
+
+    let mut charges = Vec::new();
+    for (series, r) in recurring.iter().enumerate() {
+        if r.status != "active" {
+            continue;
+        }
+        let category = merchant_category
+            .get(r.merchant.as_str())
+            .copied()
+            .unwrap_or("Uncategorized");
+        for (month, start) in horizon.iter().enumerate() {
+            let end = add_months(*start, 1) - Duration::days(1);
+            for p in predicted_charges(r, *start, end) {
+                charges.push(SeriesCharge {
+                    series,
+                    category: category.to_string(),
+                    month,
+                    amount: p.amount,
+                    confidence: r.confidence,
+                });
+            }
+        }
+    }
+
+    HorizonModel {
+        months: horizon,
+        categories,
+        charges,
+        alpha,
+    }
+}
+
+/// Projected spending per month and category. A category's baseline is the EWMA of its
+/// non-recurring history, scaled by its same-month-last-year index once it has a year of
+/// history. The band widens with the horizon as exponential smoothing's error does,
+/// and each recurring charge adds its amount times (1 − confidence) of uncertainty.
+pub fn project_horizon(model: &HorizonModel, changes: &[Change]) -> Vec<HorizonMonth> {
+    let mut names: Vec<&str> = model.categories.keys().map(String::as_str).collect();
+    for c in &model.charges {
+        names.push(&c.category);
+    }
+    for change in changes {
+        if let Change::Add { category, .. } = change {
+            names.push(category);
+        }
+    }
+    names.sort_unstable();
+    names.dedup();
+
+    let dropped: Vec<usize> = changes
+        .iter()
+        .flat_map(|c| match c {
+            Change::Drop { series } => series.clone(),
+            _ => Vec::new(),
+        })
+        .collect();
+
+    model
+        .months
+        .iter()
+        .enumerate()
+        .map(|(k, month)| {
+            // The current month is one step past the history, so month k is k + 2 steps out
+            let steps = k as f64 + 2.0;
+            let mut categories: Vec<HorizonCategory> = names
+                .iter()
+                .map(|name| {
+                    let history = model
+                        .categories
+                        .get(*name)
+                        .map(Vec::as_slice)
+                        .unwrap_or(&[]);
+                    let (base, base_var) = baseline(history, k, steps, model.alpha);
+                    let (recurring, recurring_var) = model
+                        .charges
+                        .iter()
+                        .filter(|c| {
+                            c.month == k && c.category == *name && !dropped.contains(&c.series)
+                        })
+                        .fold((0.0, 0.0), |(sum, var), c| {
+                            (
+                                sum + c.amount,
+                                var + (c.amount * (1.0 - c.confidence)).powi(2),
+                            )
+                        });
+                    let mut factor = 1.0;
+                    let mut added = 0.0;
+                    for change in changes {
+                        match change {
+                            Change::Scale {
+                                category,
+                                factor: f,
+                            } if category == name => factor *= f,
+                            Change::Add { category, amount } if category == name => added += amount,
+                            _ => {}
+                        }
+                    }
+                    let projected = (base + recurring) * factor + added;
+                    let spread = INTERVAL_Z * (base_var + recurring_var).sqrt() * factor;
+                    HorizonCategory {
+                        category: name.to_string(),
+                        projected,
+                        recurring: recurring * factor,
+                        lower: (projected - spread).max(0.0),
+                        upper: projected + spread,
+                    }
+                })
+                .filter(|c| c.upper > 0.0)
+                .collect();
+            categories.sort_by(|a, b| b.projected.total_cmp(&a.projected));
+
+            let total: f64 = categories.iter().map(|c| c.projected).sum();
+            // Categories are treated as independent, so their spreads add in quadrature
+            let spread = categories
+                .iter()
+                .map(|c| ((c.upper - c.projected) / INTERVAL_Z).powi(2))
+                .sum::<f64>()
+                .sqrt()
+                * INTERVAL_Z;
+            HorizonMonth {
+                month: month.format("%Y-%m").to_string(),
+                total,
+                recurring: categories.iter().map(|c| c.recurring).sum(),
+                lower: (total - spread).max(0.0),
+                upper: total + spread,
+                categories,
+            }
+        })
+        .collect()
+}
+
+/// Same-month-last-year index for the month `k` months after next, from completed monthly
+/// history ending last month; 1.0 without a full year or with no spending.
+pub fn seasonal_factor(history: &[f64], k: usize) -> f64 {
+    let n = history.len();
+    // Same month last year sits k + 1 + n - 12 into the history; the twelfth month out
+    // would need the current, unfinished month
+    if n < SEASONAL_MIN_MONTHS || k + 1 >= SEASONAL_MIN_MONTHS {
+        return 1.0;
+    }
+    let last_year = &history[n - SEASONAL_MIN_MONTHS..];
+    let year_mean = last_year.iter().sum::<f64>() / last_year.len() as f64;
+    if year_mean > 0.0 {
+        last_year[k + 1] / year_mean
+    } else {
+        1.0
+    }
+}
+
+/// Baseline level and its variance for the month `k` months after next.
+fn baseline(history: &[f64], k: usize, steps: f64, alpha: f64) -> (f64, f64) {
+    if history.is_empty() {
+        return (0.0, 0.0);
+    }
+    let level = ewma(history, alpha);
+    let n = history.len();
+    let mean = history.iter().sum::<f64>() / n as f64;
+    let sigma = if n >= 2 {
+        (history.iter().map(|h| (h - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+    } else {
+        level * 0.25
+    };
+    let index = seasonal_factor(history, k);
+    let var = sigma.powi(2) * (1.0 + (steps - 1.0) * alpha.powi(2));
+    (level * index, var * index.powi(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    /// `per_day` spent every day of each month from `start` for `months` months,
+    /// scaled by `factor(month)`.
+    fn daily_series(
+        start: NaiveDate,
+        months: u32,
+        per_day: f64,
+        factor: impl Fn(u32) -> f64,
+    ) -> DailyTotals {
+        let mut daily = DailyTotals::new();
+        for i in 0..months {
+            let m = add_months(start, i);
+            for day in 0..month_days(m) {
+                daily.insert(m + Duration::days(day as i64), per_day * factor(m.month()));
+            }
+        }
+        daily
+    }
+
+    #[test]
+    fn test_completed_months_fills_gaps() {
+        let mut daily = DailyTotals::new();
+        daily.insert(d(2025, 10, 5), 100.0);
+        daily.insert(d(2025, 12, 7), 50.0);
+        daily.insert(d(2026, 1, 2), 20.0);
+        let months = completed_months(&daily, d(2026, 1, 1));
+        assert_eq!(
+            months,
+            vec![
+                (d(2025, 10, 1), 100.0),
+                (d(2025, 11, 1), 0.0),
+                (d(2025, 12, 1), 50.0)
+            ]
+        );
+        assert_eq!(spent_through(&daily, d(2026, 1, 1), 1), 0.0);
+        assert_eq!(spent_through(&daily, d(2026, 1, 1), 2), 20.0);
+    }
+
+    #[test]
+    fn test_seasonal_index_needs_a_year() {
+        assert_eq!(seasonal_index(&[100.0; 11]), None);
+        let mut history = vec![200.0];
+        history.extend([100.0; 11]);
+        let index = seasonal_index(&history).unwrap();
+        assert!((index - 200.0 / (1300.0 / 12.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_seasonal_method_wins_backtest_on_seasonal_data() {
+        // December spending is three times the rest of the year
+        let factor = |m: u32| if m == 12 { 3.0 } else { 1.0 };
+        let daily = daily_series(d(2023, 1, 1), 36, 10.0, factor);
+        let as_of = d(2026, 1, 5);
+
+        let samples = backtest(&daily, as_of, 0.3);
+        assert_eq!(samples.len(), 12);
+        let accuracy = method_accuracy(&samples);
+        let seasonal_mape = accuracy[3].0.unwrap();
+        let ewma_mape = accuracy[2].0.unwrap();
+        assert!(
+            seasonal_mape < ewma_mape,
+            "seasonal {seasonal_mape} vs ewma {ewma_mape}"
+        );
+
+        // Projecting December 2025 from its 5th day
+        let december = project_month(&daily, d(2025, 12, 1), 5, 0.3);
+        let seasonal = december[3].unwrap();
+        assert!((seasonal - 930.0).abs() / 930.0 < 0.25, "{seasonal}");
+        assert!(december[2].unwrap() < 600.0);
+    }
+
+    #[test]
+    fn test_method_weights_favour_accurate_methods() {
+        let current: MethodSet = [Some(100.0), Some(120.0), Some(110.0), None];
+        let accuracy = vec![(Some(20.0), 6), (Some(5.0), 6), (Some(10.0), 6), (None, 0)];
+        let weights = method_weights(&current, &accuracy);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(weights[1] > weights[2] && weights[2] > weights[0]);
+        assert_eq!(weights[3], 0.0);
+        let blended = blend(&current, &weights).unwrap();
+        assert!(blended > 110.0 && blended < 120.0);
+
+        // Nothing backtested yet: flat average of what's available
+        let flat = method_weights(&[Some(90.0), None, None, None], &[(None, 0); 4]);
+        assert_eq!(flat, [1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_relative_error_falls_back_to_monthly_variation() {
+        let weights = [1.0, 0.0, 0.0, 0.0];
+        let err = relative_error(&[], &weights, &[100.0, 100.0, 100.0], 0.5);
+        assert_eq!(err, 0.0);
+        let err = relative_error(&[], &weights, &[], 0.5);
+        assert!((err - 0.125).abs() < 1e-9);
+
+        let samples: Vec<BacktestSample> = [90.0, 100.0, 110.0]
+            .iter()
+            .map(|a| BacktestSample {
+                projections: [Some(100.0), None, None, None],
+                actual: *a,
+            })
+            .collect();
+        assert!((relative_error(&samples, &weights, &[], 0.5) - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_category_projection_blends_pace_and_history() {
+        let (linear, level, projected) = category_projection(50.0, &[300.0, 300.0], 10, 30, 0.3);
+        assert_eq!(linear, 150.0);
+        assert_eq!(level, Some(300.0));
+        assert!((projected - 250.0).abs() < 1e-9);
+        let (_, level, projected) = category_projection(50.0, &[], 10, 30, 0.3);
+        assert_eq!(level, None);
+        assert_eq!(projected, 150.0);
+    }
+
+    fn row(category: &str, merchant: &str, date: NaiveDate, amount: f64) -> CategoryCharge {
+        CategoryCharge {
+            category: category.to_string(),
+            merchant: merchant.to_string(),
+            date,
+            amount,
+        }
+    }
+
+    fn netflix() -> RecurringTransaction {
+        RecurringTransaction {
+            merchant: "NETFLIX".to_string(),
+            avg_amount: 15.99,
+            frequency: "monthly".to_string(),
+            active_months: 4,
+            first_seen: d(2025, 10, 15),
+            last_seen: d(2026, 1, 15),
+            estimated_annual: 191.88,
+            status: "active".to_string(),
+            last_gap_days: 26,
+            potentially_forgotten: false,
+            charge_count: 4,
+            median_gap_days: 31.0,
+            gap_stddev_days: 0.5,
+            monthly_amount: 15.99,
+            card: "citi".to_string(),
+            next_expected_date: d(2026, 2, 15),
+            next_expected_amount: 15.99,
+            confidence: 0.9,
+        }
+    }
+
+    fn horizon_rows() -> Vec<CategoryCharge> {
+        let mut rows = Vec::new();
+        for (month, dining) in [(10, 280.0), (11, 320.0), (12, 300.0), (1, 300.0)] {
+            let year = if month == 1 { 2026 } else { 2025 };
+            rows.push(row("Dining", "CHIPOTLE", d(year, month, 3), dining));
+            rows.push(row("Entertainment", "NETFLIX", d(year, month, 15), 15.99));
+        }
+        rows.push(row("Entertainment", "CINEMA", d(2026, 1, 20), 24.0));
+        rows
+    }
+
+    #[test]
+    fn test_horizon_model_separates_recurring_charges() {
+        let model = horizon_model(&horizon_rows(), &[netflix()], d(2026, 2, 10), 3, 0.3, 0.2);
+        assert_eq!(
+            model.months,
+            vec![d(2026, 3, 1), d(2026, 4, 1), d(2026, 5, 1)]
+        );
+        assert_eq!(model.categories["Dining"], vec![280.0, 320.0, 300.0, 300.0]);
+        // Netflix is predicted forward instead of sitting in the baseline
+        assert_eq!(model.categories["Entertainment"], vec![0.0, 0.0, 0.0, 24.0]);
+        assert_eq!(model.charges.len(), 3);
+        assert!(model
+            .charges
+            .iter()
+            .all(|c| c.category == "Entertainment" && c.amount == 15.99));
+
+        let months = project_horizon(&model, &[]);
+        assert_eq!(months.len(), 3);
+        assert_eq!(months[0].month, "2026-03");
+        assert!((months[0].recurring - 15.99).abs() < 1e-9);
+        let dining = months[0]
+            .categories
+            .iter()
+            .find(|c| c.category == "Dining")
+            .unwrap();
+        assert!((dining.projected - ewma(&[280.0, 320.0, 300.0, 300.0], 0.3)).abs() < 1e-9);
+        // Bands widen further out
+        let width = |m: &HorizonMonth| m.upper - m.lower;
+        assert!(width(&months[2]) > width(&months[0]));
+        assert!(months[0].lower < months[0].total && months[0].total < months[0].upper);
+    }
+
+    #[test]
+    fn test_project_horizon_applies_changes() {
+        let model = horizon_model(&horizon_rows(), &[netflix()], d(2026, 2, 10), 2, 0.3, 0.2);
+        let baseline = project_horizon(&model, &[]);
+        let changes = [
+            Change::Scale {
+                category: "Dining".to_string(),
+                factor: 0.8,
+            },
+            Change::Drop { series: vec![0] },
+            Change::Add {
+                category: "Fitness".to_string(),
+                amount: 40.0,
+            },
+        ];
+        let scenario = project_horizon(&model, &changes);
+        let dining = baseline[0]
+            .categories
+            .iter()
+            .find(|c| c.category == "Dining")
+            .unwrap()
+            .projected;
+        let expected = baseline[0].total - dining * 0.2 - 15.99 + 40.0;
+        assert!((scenario[0].total - expected).abs() < 1e-9);
+        assert_eq!(scenario[0].recurring, 0.0);
+        assert!(scenario[1]
+            .categories
+            .iter()
+            .any(|c| c.category == "Fitness" && c.projected == 40.0));
+    }
+
+    #[test]
+    fn test_horizon_baseline_uses_seasonal_index() {
+        // History ends in February; last March spent 400 against 100 every other month
+        let mut history = vec![100.0; 12];
+        history[1] = 400.0;
+        let (march, _) = baseline(&history, 0, 2.0, 0.3);
+        let (april, _) = baseline(&history, 1, 3.0, 0.3);
+        assert!(march > 2.5 * april, "{march} vs {april}");
+    }
+}
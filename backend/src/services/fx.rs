@@ -0,0 +1,235 @@
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// A single exchange rate: 1 unit of `base` = `rate` units of `quote`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxRate {
+    pub date: NaiveDate,
+    pub base: String,
+    pub quote: String,
+    pub rate: f64,
+}
+
+static RE_ECB_CUBE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<Cube\s+(time|currency)\s*=\s*['"]([^'"]+)['"](?:\s+rate\s*=\s*['"]([^'"]+)['"])?"#)
+        .unwrap()
+});
+
+static RE_FOREIGN_SPEND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)foreign spend amount:?\s*([\d,]+(?:\.\d+)?)\s+([a-z][a-z ]*?)\s*(?:commission|currency exchange|$)")
+        .unwrap()
+});
+
+/// True for a three-letter ISO 4217-style code (e.g. `USD`, `eur`).
+pub fn is_currency_code(s: &str) -> bool {
+    s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Resolve a currency code or common currency name (as printed on statements) to its code.
+pub fn currency_code(s: &str) -> Option<String> {
+    let s = s.trim();
+    if is_currency_code(s) {
+        return Some(s.to_uppercase());
+    }
+    let code = match s.to_lowercase().as_str() {
+        "euro" | "euros" => "EUR",
+        "pound sterling" | "british pound" | "pounds sterling" => "GBP",
+        "japanese yen" | "yen" => "JPY",
+        "canadian dollar" | "canadian dollars" => "CAD",
+        "australian dollar" | "australian dollars" => "AUD",
+        "mexican peso" | "mexican pesos" => "MXN",
+        "swiss franc" | "swiss francs" => "CHF",
+        "us dollar" | "us dollars" | "u.s. dollar" => "USD",
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+/// Find an Amex-style "Foreign Spend Amount: 12.34 EURO" in any raw CSV field.
+pub fn extract_foreign_amount(fields: &[String]) -> Option<(f64, String)> {
+    fields.iter().find_map(|field| {
+        let caps = RE_FOREIGN_SPEND.captures(field)?;
+        let amount: f64 = caps[1].replace(',', "").parse().ok()?;
+        let currency = currency_code(&caps[2])?;
+        Some((amount, currency))
+    })
+}
+
+/// Parse an uploaded rate file, detecting ECB XML vs CSV from its content.
+pub fn parse_rates(data: &str) -> Result<Vec<FxRate>, String> {
+    if data.trim_start().starts_with('<') {
+        parse_ecb_xml(data)
+    } else {
+        parse_rates_csv(data)
+    }
+}
+
+/// Parse the ECB reference-rate XML (`eurofxref-*.xml`). All rates are EUR-based.
+pub fn parse_ecb_xml(data: &str) -> Result<Vec<FxRate>, String> {
+    let mut rates = Vec::new();
+    let mut current_date: Option<NaiveDate> = None;
+
+    for caps in RE_ECB_CUBE.captures_iter(data) {
+        match &caps[1] {
+            "time" => {
+                current_date = Some(
+                    NaiveDate::parse_from_str(&caps[2], "%Y-%m-%d")
+                        .map_err(|_| format!("Invalid date '{}' in ECB XML", &caps[2]))?,
+                );
+            }
+            _ => {
+                let date = current_date.ok_or("ECB XML rate found before any time attribute")?;
+                let rate_str = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+                let rate: f64 = rate_str
+                    .parse()
+                    .map_err(|_| format!("Invalid rate '{}' for {}", rate_str, &caps[2]))?;
+                rates.push(FxRate {
+                    date,
+                    base: "EUR".into(),
+                    quote: caps[2].to_uppercase(),
+                    rate,
+                });
+            }
+        }
+    }
+
+    if rates.is_empty() {
+        return Err("No rates found in ECB XML".into());
+    }
+    Ok(rates)
+}
+
+/// Parse a rate CSV. Accepts `date,base,quote,rate`, or `date,currency,rate` with EUR as
+/// the implied base (the layout of the ECB CSV download).
+pub fn parse_rates_csv(data: &str) -> Result<Vec<FxRate>, String> {
+    let mut rdr = ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(data.as_bytes());
+
+    let headers: Vec<String> = rdr
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+
+    let col = |name: &str| headers.iter().position(|h| h == name);
+    let date_idx = col("date").ok_or("Rate CSV needs a 'date' column")?;
+    let rate_idx = col("rate").ok_or("Rate CSV needs a 'rate' column")?;
+    let base_idx = col("base");
+    let quote_idx = col("quote")
+        .or_else(|| col("currency"))
+        .ok_or("Rate CSV needs a 'quote' or 'currency' column")?;
+
+    let mut rates = Vec::new();
+    for (line, result) in rdr.records().enumerate() {
+        let record = result.map_err(|e| format!("Row {}: {}", line + 2, e))?;
+        let field = |idx: usize| record.get(idx).map(|s| s.trim()).unwrap_or("");
+
+        let date = NaiveDate::parse_from_str(field(date_idx), "%Y-%m-%d")
+            .map_err(|_| format!("Row {}: invalid date '{}'", line + 2, field(date_idx)))?;
+        let base = base_idx.map(field).unwrap_or("EUR");
+        let quote = field(quote_idx);
+        if !is_currency_code(base) || !is_currency_code(quote) {
+            return Err(format!("Row {}: invalid currency code", line + 2));
+        }
+        let rate: f64 = field(rate_idx)
+            .parse()
+            .map_err(|_| format!("Row {}: invalid rate '{}'", line + 2, field(rate_idx)))?;
+        if rate <= 0.0 {
+            return Err(format!("Row {}: rate must be positive", line + 2));
+        }
+
+        rates.push(FxRate {
+            date,
+            base: base.to_uppercase(),
+            quote: quote.to_uppercase(),
+            rate,
+        });
+    }
+
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_code_passthrough_and_names() {
+        assert_eq!(currency_code("eur"), Some("EUR".into()));
+        assert_eq!(currency_code("Euro"), Some("EUR".into()));
+        assert_eq!(currency_code("POUND STERLING"), Some("GBP".into()));
+        assert_eq!(currency_code("Galleons"), None);
+    }
+
+    #[test]
+    fn test_extract_foreign_amount_amex() {
+        let fields = vec![
+            "STARBUCKS".to_string(),
+            "Foreign Spend Amount: 1,234.50 Euro Commission Amount: 0.00".to_string(),
+        ];
+        assert_eq!(extract_foreign_amount(&fields), Some((1234.5, "EUR".into())));
+    }
+
+    #[test]
+    fn test_extract_foreign_amount_none() {
+        let fields = vec!["NETFLIX.COM".to_string(), "15.99".to_string()];
+        assert_eq!(extract_foreign_amount(&fields), None);
+    }
+
+    #[test]
+    fn test_parse_ecb_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope>
+  <Cube>
+    <Cube time='2026-01-02'>
+      <Cube currency='USD' rate='1.0345'/>
+      <Cube currency='GBP' rate='0.8312'/>
+    </Cube>
+    <Cube time='2026-01-01'>
+      <Cube currency='USD' rate='1.0401'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+        let rates = parse_rates(xml).unwrap();
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].base, "EUR");
+        assert_eq!(rates[0].quote, "USD");
+        assert_eq!(rates[0].date.to_string(), "2026-01-02");
+        assert_eq!(rates[2].date.to_string(), "2026-01-01");
+        assert!((rates[1].rate - 0.8312).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_rates_csv_explicit_base() {
+        let csv = "date,base,quote,rate\n2026-01-02,usd,cad,1.37\n";
+        let rates = parse_rates(csv).unwrap();
+        assert_eq!(
+            rates,
+            vec![FxRate {
+                date: NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+                base: "USD".into(),
+                quote: "CAD".into(),
+                rate: 1.37,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rates_csv_implied_eur_base() {
+        let csv = "Date,Currency,Rate\n2026-01-02,USD,1.0345\n";
+        let rates = parse_rates(csv).unwrap();
+        assert_eq!(rates[0].base, "EUR");
+        assert_eq!(rates[0].quote, "USD");
+    }
+
+    #[test]
+    fn test_parse_rates_csv_rejects_bad_rows() {
+        assert!(parse_rates("date,currency,rate\n2026-01-02,USD,abc\n").is_err());
+        assert!(parse_rates("date,currency,rate\n2026-01-02,DOLLARS,1.0\n").is_err());
+        assert!(parse_rates("currency,rate\nUSD,1.0\n").is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{Months, NaiveDate};
+
+use crate::models::goal::{Goal, GoalProgress};
+
+/// Average month length, for turning day counts into months.
+pub const AVG_MONTH_DAYS: f64 = 365.25 / 12.0;
+
+/// How far back contributions count toward a goal's monthly pace.
+const PACE_MONTHS: u32 = 3;
+
+/// Check a goal's amounts and dates before saving.
+pub fn validate(
+    target_amount: f64,
+    initial_amount: f64,
+    start_date: NaiveDate,
+    target_date: NaiveDate,
+) -> Result<(), String> {
+    if !target_amount.is_finite() || target_amount <= 0.0 {
+        return Err("target_amount must be positive".into());
+    }
+    if !initial_amount.is_finite() || initial_amount < 0.0 {
+        return Err("initial_amount must not be negative".into());
+    }
+    if target_date <= start_date {
+        return Err("target_date must be after start_date".into());
+    }
+    Ok(())
+}
+
+fn cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> f64 {
+    (to - from).num_days() as f64 / AVG_MONTH_DAYS
+}
+
+/// Where a goal stands on `as_of`, given every contribution to it (manual entries and
+/// linked transactions, any order). Contributions after `as_of` are ignored; those
+/// before the start date count toward what's saved but not toward the pace.
+pub fn progress(goal: &Goal, contributions: &[(NaiveDate, f64)], as_of: NaiveDate) -> GoalProgress {
+    let mut counted: Vec<(NaiveDate, f64)> = contributions
+        .iter()
+        .copied()
+        .filter(|(date, _)| *date <= as_of)
+        .collect();
+    counted.sort_by_key(|(date, _)| *date);
+
+    let target = goal.target_amount;
+    let saved = goal.initial_amount + counted.iter().fold(0.0, |sum, (_, amount)| sum + amount);
+    let remaining = (target - saved).max(0.0);
+    let completed = remaining < 0.005;
+
+    let pace_start = goal.start_date.max(as_of - Months::new(PACE_MONTHS));
+    let recent = counted
+        .iter()
+        .filter(|(date, _)| *date >= pace_start)
+        .fold(0.0, |sum, (_, amount)| sum + amount);
+    let monthly_pace = if as_of < goal.start_date {
+        0.0
+    } else {
+        recent / months_between(pace_start, as_of).max(1.0)
+    };
+
+    let projected_completion = if completed {
+        // The day the running total first reached the target
+        let mut total = goal.initial_amount;
+        if total + 0.005 >= target {
+            Some(goal.start_date)
+        } else {
+            counted.iter().find_map(|(date, amount)| {
+                total += amount;
+                (total + 0.005 >= target).then_some(*date)
+            })
+        }
+    } else if monthly_pace > 0.0 {
+        let days = (remaining / monthly_pace * AVG_MONTH_DAYS).ceil() as u64;
+        as_of.checked_add_days(chrono::Days::new(days))
+    } else {
+        None
+    };
+
+    let plan_days = (goal.target_date - goal.start_date).num_days().max(1);
+    let elapsed = (as_of - goal.start_date).num_days().clamp(0, plan_days);
+    let expected_saved =
+        goal.initial_amount + (target - goal.initial_amount) * elapsed as f64 / plan_days as f64;
+
+    let months_left = months_between(as_of, goal.target_date).max(0.0);
+    let required_monthly = if completed {
+        0.0
+    } else {
+        remaining / months_left.max(1.0)
+    };
+
+    let overdue = !completed && as_of > goal.target_date;
+    let on_track = completed
+        || (!overdue
+            && (saved + 0.005 >= expected_saved
+                || projected_completion.is_some_and(|d| d <= goal.target_date)));
+    let status = if completed {
+        "completed"
+    } else if overdue {
+        "overdue"
+    } else if on_track {
+        "on_track"
+    } else {
+        "behind"
+    };
+
+    GoalProgress {
+        id: goal.id,
+        name: goal.name.clone(),
+        target_amount: target,
+        target_date: goal.target_date,
+        start_date: goal.start_date,
+        account: goal.account.clone(),
+        tag: goal.tag.clone(),
+        saved: cents(saved),
+        remaining: cents(remaining),
+        pct_complete: cents(saved / target * 100.0),
+        expected_saved: cents(expected_saved),
+        months_left: cents(months_left),
+        required_monthly: cents(required_monthly),
+        monthly_pace: cents(monthly_pace),
+        projected_completion,
+        on_track,
+        status: status.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn goal(target: f64, initial: f64) -> Goal {
+        Goal {
+            id: Uuid::nil(),
+            name: "Vacation".into(),
+            target_amount: target,
+            target_date: d(2026, 12, 31),
+            start_date: d(2026, 1, 1),
+            initial_amount: initial,
+            account: None,
+            tag: Some("vacation".into()),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(1000.0, 0.0, d(2026, 1, 1), d(2026, 6, 1)).is_ok());
+        assert!(validate(0.0, 0.0, d(2026, 1, 1), d(2026, 6, 1)).is_err());
+        assert!(validate(1000.0, -5.0, d(2026, 1, 1), d(2026, 6, 1)).is_err());
+        assert!(validate(1000.0, 0.0, d(2026, 6, 1), d(2026, 6, 1)).is_err());
+    }
+
+    #[test]
+    fn test_on_pace_goal() {
+        // 1200 by year end, 100 a month so far
+        let contributions: Vec<_> = (1..=3).map(|m| (d(2026, m, 15), 100.0)).collect();
+        let p = progress(&goal(1200.0, 0.0), &contributions, d(2026, 4, 1));
+        assert_eq!(p.saved, 300.0);
+        assert_eq!(p.remaining, 900.0);
+        assert_eq!(p.pct_complete, 25.0);
+        // Three contributions over the 90 days since the start
+        assert!((p.monthly_pace - 101.46).abs() < 0.01);
+        // 274 days, about nine months, left
+        assert_eq!(p.required_monthly, 99.98);
+        assert_eq!(p.status, "on_track");
+        assert!(p.projected_completion.unwrap() <= d(2026, 12, 31));
+    }
+
+    #[test]
+    fn test_behind_and_overdue() {
+        let contributions = [(d(2026, 1, 10), 50.0), (d(2026, 7, 1), 50.0)];
+        let p = progress(&goal(1200.0, 0.0), &contributions, d(2026, 7, 1));
+        assert_eq!(p.status, "behind");
+        assert!(!p.on_track);
+        assert!(p.expected_saved > 590.0);
+        // 50 over the last three months is far too slow
+        assert!(p.projected_completion.unwrap() > d(2026, 12, 31));
+        assert!(p.required_monthly > 180.0);
+
+        let late = progress(&goal(1200.0, 0.0), &contributions, d(2027, 1, 15));
+        assert_eq!(late.status, "overdue");
+        assert_eq!(late.months_left, 0.0);
+        assert_eq!(late.required_monthly, 1100.0);
+    }
+
+    #[test]
+    fn test_no_contributions_yet() {
+        let p = progress(&goal(1200.0, 200.0), &[], d(2026, 1, 1));
+        assert_eq!(p.saved, 200.0);
+        assert_eq!(p.monthly_pace, 0.0);
+        assert_eq!(p.projected_completion, None);
+        // Nothing was expected yet
+        assert_eq!(p.status, "on_track");
+
+        // A month in with nothing added, the initial amount doesn't count as pace
+        let p = progress(&goal(1200.0, 200.0), &[], d(2026, 2, 1));
+        assert_eq!(p.status, "behind");
+    }
+
+    #[test]
+    fn test_completed_goal_reports_when() {
+        let contributions = [
+            (d(2026, 2, 1), 600.0),
+            (d(2026, 3, 1), 700.0),
+            (d(2026, 5, 1), -50.0),
+        ];
+        let p = progress(&goal(1200.0, 0.0), &contributions, d(2026, 6, 1));
+        assert_eq!(p.status, "completed");
+        assert_eq!(p.remaining, 0.0);
+        assert_eq!(p.required_monthly, 0.0);
+        assert_eq!(p.projected_completion, Some(d(2026, 3, 1)));
+
+        let p = progress(&goal(100.0, 150.0), &[], d(2026, 6, 1));
+        assert_eq!(p.projected_completion, Some(d(2026, 1, 1)));
+    }
+}
@@ -0,0 +1,1073 @@
+use axum::async_trait;
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+use crate::models::analytics::{CashflowMonth, Insight, RecurringTransaction};
+use crate::models::budget::BudgetProgress;
+use crate::models::goal::GoalProgress;
+use crate::models::subscription::SubscriptionAlert;
+use crate::models::suspicious::SuspiciousFinding;
+use crate::services::analytics::{
+    self, days_in_month, linear_projection, CategoryBaseline, SmallPurchaseStats, Thresholds,
+};
+use crate::services::budgets;
+
+/// Everything the generators look at, fetched once per request. Plain data, so
+/// generators can be tested against hand-built fixtures.
+#[derive(Debug, Default)]
+pub struct InsightContext {
+    pub thresholds: Thresholds,
+    /// Reference date: the filter's end date, or today
+    pub as_of: NaiveDate,
+    /// Completed-month baselines per category
+    pub baselines: Vec<CategoryBaseline>,
+    /// Spending so far this month per category
+    pub current_by_category: HashMap<String, f64>,
+    pub this_month: f64,
+    /// Last month's total
+    pub last_month: f64,
+    pub avg_monthly: f64,
+    pub small_purchases: SmallPurchaseStats,
+    /// (category, YYYY-MM, total) for the last seven months
+    pub category_months: Vec<(String, String, f64)>,
+    pub weekend_avg_daily: f64,
+    pub weekday_avg_daily: f64,
+    /// Recurring series with the registry's decisions applied
+    pub recurring: Vec<RecurringTransaction>,
+    pub subscription_alerts: Vec<SubscriptionAlert>,
+    /// Each budget's progress through its current period
+    pub budgets: Vec<BudgetProgress>,
+    /// The last seven months of income and expenses, oldest first
+    pub cashflow: Vec<CashflowMonth>,
+    /// Suspicious findings, newest first, dismissed ones included
+    pub suspicious: Vec<SuspiciousFinding>,
+    /// Every savings goal's progress as of the reference date
+    pub goals: Vec<GoalProgress>,
+}
+
+impl InsightContext {
+    pub fn days_elapsed(&self) -> u32 {
+        self.as_of.day()
+    }
+
+    pub fn days_in_month(&self) -> u32 {
+        days_in_month(self.as_of.year(), self.as_of.month())
+    }
+
+    /// This month's spending at the current pace.
+    pub fn projected(&self) -> f64 {
+        linear_projection(self.this_month, self.days_elapsed(), self.days_in_month())
+    }
+}
+
+/// One source of insights. Generators are registered in [`registry`] and can be switched
+/// off by name through the `insight_generators` config key.
+#[async_trait]
+pub trait InsightGenerator: Send + Sync {
+    /// Stable identifier used in config
+    fn name(&self) -> &'static str;
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight>;
+
+    /// Rank of one of this generator's insights against everyone else's; higher comes first.
+    fn priority(&self, insight: &Insight) -> f64;
+}
+
+/// Every generator, in the order their insights are ranked on ties.
+pub fn registry() -> Vec<Box<dyn InsightGenerator>> {
+    vec![
+        Box::new(AnomalyInsights),
+        Box::new(TrendInsights),
+        Box::new(ForecastInsights),
+        Box::new(HabitInsights),
+        Box::new(RecurringInsights),
+        Box::new(SubscriptionInsights),
+        Box::new(BudgetInsights),
+        Box::new(SavingsInsights),
+        Box::new(GoalInsights),
+        Box::new(PositiveInsights),
+        Box::new(SuspiciousInsights),
+    ]
+}
+
+pub fn generator_names() -> Vec<&'static str> {
+    registry().iter().map(|g| g.name()).collect()
+}
+
+/// Run the enabled generators and rank their output, highest priority first.
+pub async fn generate_all(
+    generators: &[Box<dyn InsightGenerator>],
+    ctx: &InsightContext,
+    enabled: impl Fn(&str) -> bool,
+) -> Vec<(Insight, f64)> {
+    let mut scored = Vec::new();
+    for generator in generators.iter().filter(|g| enabled(g.name())) {
+        for insight in generator.generate(ctx).await {
+            let priority = generator.priority(&insight);
+            scored.push((insight, priority));
+        }
+    }
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+}
+
+fn metric(insight: &Insight, key: &str) -> Option<f64> {
+    insight.metric.as_ref()?.get(key)?.as_f64()
+}
+
+fn metric_str<'a>(insight: &'a Insight, key: &str) -> Option<&'a str> {
+    insight.metric.as_ref()?.get(key)?.as_str()
+}
+
+/// Category spikes in the high and critical bands.
+pub struct AnomalyInsights;
+
+#[async_trait]
+impl InsightGenerator for AnomalyInsights {
+    fn name(&self) -> &'static str {
+        "anomaly"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        analytics::category_anomalies(&ctx.baselines, &ctx.current_by_category, &ctx.thresholds)
+            .into_iter()
+            .filter(|a| a.severity != "elevated")
+            .map(|anomaly| Insight {
+                r#type: "anomaly".into(),
+                severity: if anomaly.severity == "critical" {
+                    "high"
+                } else {
+                    "medium"
+                }
+                .into(),
+                icon: "AlertTriangle".into(),
+                title: format!("{} spending spike", anomaly.category),
+                message: anomaly.message,
+                metric: Some(serde_json::json!({
+                    "z_score": anomaly.z_score,
+                    "pct_above": anomaly.pct_above_avg,
+                    "projected": anomaly.projected
+                })),
+                action: Some(format!("Review your {} transactions", anomaly.category)),
+                category: Some(anomaly.category),
+            })
+            .collect()
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        90.0 + metric(insight, "z_score").unwrap_or(0.0).min(5.0) * 2.0
+    }
+}
+
+/// Month-to-date spending more than 20% away from last month's.
+pub struct TrendInsights;
+
+#[async_trait]
+impl InsightGenerator for TrendInsights {
+    fn name(&self) -> &'static str {
+        "trend"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        if ctx.last_month <= 0.0 {
+            return Vec::new();
+        }
+        let mom_pct = (ctx.this_month - ctx.last_month) / ctx.last_month * 100.0;
+        if mom_pct.abs() <= 20.0 {
+            return Vec::new();
+        }
+        let (title, message, severity, icon) = if mom_pct > 0.0 {
+            (
+                "Spending up vs last month",
+                format!("You've spent {:.0}% more than last month so far", mom_pct),
+                "medium",
+                "TrendingUp",
+            )
+        } else {
+            (
+                "Spending down vs last month",
+                format!(
+                    "You've spent {:.0}% less than last month so far",
+                    mom_pct.abs()
+                ),
+                "low",
+                "TrendingDown",
+            )
+        };
+        vec![Insight {
+            r#type: "trend".into(),
+            severity: severity.into(),
+            icon: icon.into(),
+            title: title.into(),
+            message,
+            metric: Some(serde_json::json!({ "mom_change_pct": mom_pct })),
+            action: None,
+            category: None,
+        }]
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        70.0 + metric(insight, "mom_change_pct")
+            .unwrap_or(0.0)
+            .abs()
+            .min(50.0)
+            * 0.3
+    }
+}
+
+/// The month on pace to end more than 15% above the monthly average.
+pub struct ForecastInsights;
+
+#[async_trait]
+impl InsightGenerator for ForecastInsights {
+    fn name(&self) -> &'static str {
+        "forecast"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        if ctx.avg_monthly <= 0.0 {
+            return Vec::new();
+        }
+        let projected = ctx.projected();
+        let vs_avg_pct = (projected - ctx.avg_monthly) / ctx.avg_monthly * 100.0;
+        if vs_avg_pct <= 15.0 {
+            return Vec::new();
+        }
+        vec![Insight {
+            r#type: "forecast".into(),
+            severity: if vs_avg_pct > 30.0 { "high" } else { "medium" }.into(),
+            icon: "Activity".into(),
+            title: "On track to overspend".into(),
+            message: format!(
+                "Projected spending is {:.0}% above your monthly average",
+                vs_avg_pct
+            ),
+            metric: Some(serde_json::json!({
+                "projected": projected,
+                "avg_monthly": ctx.avg_monthly,
+                "vs_avg_pct": vs_avg_pct
+            })),
+            action: Some("Consider reducing discretionary spending".into()),
+            category: None,
+        }]
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        60.0 + metric(insight, "vs_avg_pct").unwrap_or(0.0).min(40.0) * 0.375
+    }
+}
+
+/// Frequent small purchases, categories creeping up, and weekend splurges.
+pub struct HabitInsights;
+
+#[async_trait]
+impl InsightGenerator for HabitInsights {
+    fn name(&self) -> &'static str {
+        "habit"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        let t = &ctx.thresholds;
+        let mut insights = Vec::new();
+
+        let impulse = analytics::impulse_spending(&ctx.small_purchases, t);
+        if matches!(impulse.label.as_str(), "high" | "moderate") {
+            insights.push(Insight {
+                r#type: "habit".into(),
+                severity: "medium".into(),
+                icon: "Coffee".into(),
+                title: "Frequent small purchases".into(),
+                message: format!(
+                    "{:.0}% of your transactions are under ${:.0} — totaling ${:.0}/month",
+                    impulse.small_transaction_pct, t.impulse_amount, impulse.monthly_small_total
+                ),
+                metric: Some(serde_json::json!({ "small_pct": impulse.small_transaction_pct })),
+                action: Some("Track small daily expenses".into()),
+                category: None,
+            });
+        }
+
+        for creep in analytics::category_creep(&ctx.category_months, t) {
+            if creep.trend != "increasing" {
+                continue;
+            }
+            let change = creep.three_month_change_pct;
+            insights.push(Insight {
+                r#type: "habit".into(),
+                severity: "medium".into(),
+                icon: "TrendingUp".into(),
+                title: format!("{} spending creeping up", creep.category),
+                message: format!(
+                    "{} spending up {:.0}% over the last 3 months",
+                    creep.category, change
+                ),
+                metric: Some(serde_json::json!({ "change_pct": change })),
+                action: Some(format!("Set a {} budget", creep.category)),
+                category: Some(creep.category),
+            });
+        }
+
+        let weekend = analytics::weekend_splurge(ctx.weekend_avg_daily, ctx.weekday_avg_daily, t);
+        if matches!(weekend.label.as_str(), "high" | "moderate") {
+            insights.push(Insight {
+                r#type: "habit".into(),
+                severity: "low".into(),
+                icon: "Calendar".into(),
+                title: "Weekend spending spike".into(),
+                message: format!(
+                    "You spend {:.1}x more on weekends than weekdays",
+                    weekend.ratio
+                ),
+                metric: Some(serde_json::json!({ "ratio": weekend.ratio })),
+                action: Some("Plan weekend activities with a budget".into()),
+                category: None,
+            });
+        }
+        insights
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        if let Some(small_pct) = metric(insight, "small_pct") {
+            45.0 + small_pct * 0.3
+        } else if let Some(change) = metric(insight, "change_pct") {
+            50.0 + change.min(50.0) * 0.2
+        } else {
+            40.0 + (metric(insight, "ratio").unwrap_or(1.0) - 1.0).min(2.0) * 10.0
+        }
+    }
+}
+
+/// Forgotten subscriptions and the recurring total.
+pub struct RecurringInsights;
+
+#[async_trait]
+impl InsightGenerator for RecurringInsights {
+    fn name(&self) -> &'static str {
+        "recurring"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        let bloat = analytics::subscription_bloat(&ctx.recurring, ctx.as_of);
+        let mut insights = Vec::new();
+
+        let forgotten = &bloat.potentially_forgotten;
+        if !forgotten.is_empty() {
+            insights.push(Insight {
+                r#type: "recurring".into(),
+                severity: "medium".into(),
+                icon: "AlertCircle".into(),
+                title: "Potentially forgotten subscriptions".into(),
+                message: format!(
+                    "{} subscriptions haven't been charged recently: {}",
+                    forgotten.len(),
+                    forgotten.join(", ")
+                ),
+                metric: Some(serde_json::json!({ "count": forgotten.len() })),
+                action: Some("Check if these subscriptions are still needed".into()),
+                category: None,
+            });
+        }
+
+        let total = bloat.total_monthly;
+        if total > 100.0 {
+            insights.push(Insight {
+                r#type: "recurring".into(),
+                severity: "low".into(),
+                icon: "Repeat".into(),
+                title: "Monthly recurring summary".into(),
+                message: format!(
+                    "Your recurring charges total ${:.0}/month (${:.0}/year)",
+                    total,
+                    total * 12.0
+                ),
+                metric: Some(serde_json::json!({
+                    "monthly": total,
+                    "annual": total * 12.0
+                })),
+                action: None,
+                category: None,
+            });
+        }
+        insights
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        match metric(insight, "count") {
+            Some(count) => 55.0 + count * 5.0,
+            None => 50.0,
+        }
+    }
+}
+
+/// Subscription registry alerts: charges after cancellation, price increases, overdue charges.
+pub struct SubscriptionInsights;
+
+#[async_trait]
+impl InsightGenerator for SubscriptionInsights {
+    fn name(&self) -> &'static str {
+        "subscription"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        ctx.subscription_alerts
+            .iter()
+            .map(|alert| {
+                let (severity, icon, title, action) = match alert.alert_type.as_str() {
+                    "cancelled_charged" => (
+                        "high",
+                        "XCircle",
+                        format!("{} is still charging", alert.merchant),
+                        "Dispute the charge or contact the merchant",
+                    ),
+                    "price_increase" => (
+                        "medium",
+                        "TrendingUp",
+                        format!("{} raised its price", alert.merchant),
+                        "Decide whether it's still worth it",
+                    ),
+                    _ => (
+                        "low",
+                        "Clock",
+                        format!("{} charge is overdue", alert.merchant),
+                        "Check the card on file or whether it was cancelled",
+                    ),
+                };
+                Insight {
+                    r#type: "subscription".into(),
+                    severity: severity.into(),
+                    icon: icon.into(),
+                    title,
+                    message: alert.message.clone(),
+                    metric: Some(serde_json::json!({
+                        "subscription_id": alert.subscription_id,
+                        "alert_type": alert.alert_type,
+                        "amount": alert.amount,
+                        "previous_amount": alert.previous_amount,
+                        "days_overdue": alert.days_overdue,
+                    })),
+                    action: Some(action.into()),
+                    category: None,
+                }
+            })
+            .collect()
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        match metric_str(insight, "alert_type") {
+            Some("cancelled_charged") => 88.0,
+            Some("price_increase") => 62.0,
+            _ => 52.0,
+        }
+    }
+}
+
+/// Budgets at 80% or more of their limit.
+pub struct BudgetInsights;
+
+#[async_trait]
+impl InsightGenerator for BudgetInsights {
+    fn name(&self) -> &'static str {
+        "budget"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        let mut insights = Vec::new();
+
+        for budget in &ctx.budgets {
+            let noun = budgets::period_noun(&budget.period);
+            let metric = Some(serde_json::json!({
+                "value": budget.spent,
+                "comparison": budget.available,
+                "unit": "dollars"
+            }));
+
+            if budget.pct_used >= 100.0 {
+                insights.push(Insight {
+                    r#type: "budget".into(),
+                    severity: "high".into(),
+                    icon: "AlertCircle".into(),
+                    title: format!("{} budget exceeded", budget.category),
+                    message: format!(
+                        "You've spent ${:.0} on {} this {}, exceeding your ${:.0} budget.",
+                        budget.spent, budget.category, noun, budget.available
+                    ),
+                    metric,
+                    action: Some(format!("Review {} transactions", budget.category)),
+                    category: Some(budget.category.clone()),
+                });
+            } else if budget.pct_used >= 80.0 {
+                insights.push(Insight {
+                    r#type: "budget".into(),
+                    severity: "medium".into(),
+                    icon: "AlertTriangle".into(),
+                    title: format!("{} budget at {:.0}%", budget.category, budget.pct_used),
+                    message: format!(
+                        "${:.0} of ${:.0} {} budget used with {} days remaining.",
+                        budget.spent, budget.available, budget.category, budget.days_remaining
+                    ),
+                    metric,
+                    action: None,
+                    category: Some(budget.category.clone()),
+                });
+            }
+        }
+        insights
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        if insight.severity == "high" {
+            85.0
+        } else {
+            55.0
+        }
+    }
+}
+
+/// Last month in the red, a savings rate slipping below its average, or a healthy one.
+pub struct SavingsInsights;
+
+#[async_trait]
+impl InsightGenerator for SavingsInsights {
+    fn name(&self) -> &'static str {
+        "savings"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        let months = &ctx.cashflow;
+        let current_key = ctx.as_of.format("%Y-%m").to_string();
+        let last_completed = months
+            .iter()
+            .rev()
+            .find(|m| m.month < current_key && m.income > 0.0);
+
+        if let Some(last) = last_completed.filter(|m| m.net < 0.0) {
+            return vec![Insight {
+                r#type: "savings".into(),
+                severity: "high".into(),
+                icon: "AlertTriangle".into(),
+                title: "Spent more than you earned".into(),
+                message: format!(
+                    "In {} you spent ${:.0} against ${:.0} of income",
+                    last.month, last.expenses, last.income
+                ),
+                metric: Some(serde_json::json!({
+                    "income": last.income,
+                    "expenses": last.expenses,
+                    "savings_rate": last.savings_rate
+                })),
+                action: Some("Review discretionary spending".into()),
+                category: None,
+            }];
+        }
+
+        let Some(trailing) = analytics::completed_savings_rate(months, &current_key) else {
+            return Vec::new();
+        };
+        let current_income = months
+            .iter()
+            .find(|m| m.month == current_key)
+            .map(|m| m.income)
+            .unwrap_or(0.0);
+        let recent: Vec<f64> = months
+            .iter()
+            .filter(|m| m.month < current_key && m.income > 0.0)
+            .map(|m| m.income)
+            .collect();
+        let expected_income =
+            current_income.max(recent.iter().sum::<f64>() / recent.len().max(1) as f64);
+        let projected_rate = analytics::savings_rate(expected_income, ctx.projected());
+
+        if let Some(rate) = projected_rate.filter(|r| *r < trailing - 10.0) {
+            vec![Insight {
+                r#type: "savings".into(),
+                severity: "medium".into(),
+                icon: "TrendingDown".into(),
+                title: "Savings rate slipping".into(),
+                message: format!(
+                    "On pace to save {:.0}% of income this month, down from your {:.0}% average",
+                    rate, trailing
+                ),
+                metric: Some(serde_json::json!({
+                    "projected_savings_rate": rate,
+                    "avg_savings_rate": trailing
+                })),
+                action: Some("Hold back on discretionary purchases this month".into()),
+                category: None,
+            }]
+        } else if trailing >= 20.0 {
+            vec![Insight {
+                r#type: "positive".into(),
+                severity: "low".into(),
+                icon: "PiggyBank".into(),
+                title: "Healthy savings rate".into(),
+                message: format!(
+                    "You've saved {:.0}% of your income over recent months",
+                    trailing
+                ),
+                metric: Some(serde_json::json!({ "avg_savings_rate": trailing })),
+                action: None,
+                category: None,
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        if metric(insight, "income").is_some() {
+            80.0
+        } else if let Some(rate) = metric(insight, "projected_savings_rate") {
+            let trailing = metric(insight, "avg_savings_rate").unwrap_or(rate);
+            60.0 + (trailing - rate).min(40.0) * 0.5
+        } else {
+            30.0
+        }
+    }
+}
+
+/// Savings goals that have fallen behind their plan or missed their date.
+pub struct GoalInsights;
+
+#[async_trait]
+impl InsightGenerator for GoalInsights {
+    fn name(&self) -> &'static str {
+        "goals"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        ctx.goals
+            .iter()
+            .filter(|goal| goal.status == "behind" || goal.status == "overdue")
+            .map(|goal| {
+                let overdue = goal.status == "overdue";
+                let pace = match goal.projected_completion {
+                    Some(date) if !overdue => format!(
+                        "at ${:.0} a month you'd get there around {}",
+                        goal.monthly_pace,
+                        date.format("%b %Y")
+                    ),
+                    _ if goal.monthly_pace > 0.0 => {
+                        format!("you're adding ${:.0} a month", goal.monthly_pace)
+                    }
+                    _ => "nothing has been added in the last three months".into(),
+                };
+                let (title, message) = if overdue {
+                    (
+                        format!("{} goal missed its date", goal.name),
+                        format!(
+                            "${:.0} of ${:.0} saved by {}; {}.",
+                            goal.saved,
+                            goal.target_amount,
+                            goal.target_date.format("%b %-d, %Y"),
+                            pace
+                        ),
+                    )
+                } else {
+                    (
+                        format!("{} goal is falling behind", goal.name),
+                        format!(
+                            "${:.0} of ${:.0} saved, ${:.0} short of plan; {}.",
+                            goal.saved,
+                            goal.target_amount,
+                            goal.expected_saved - goal.saved,
+                            pace
+                        ),
+                    )
+                };
+                Insight {
+                    r#type: "goal".into(),
+                    severity: if overdue { "high" } else { "medium" }.into(),
+                    icon: "Target".into(),
+                    title,
+                    message,
+                    metric: Some(serde_json::json!({
+                        "goal_id": goal.id.to_string(),
+                        "value": goal.saved,
+                        "comparison": goal.expected_saved,
+                        "required_monthly": goal.required_monthly,
+                        "monthly_pace": goal.monthly_pace,
+                        "unit": "dollars"
+                    })),
+                    action: Some(if overdue {
+                        "Move the target date or adjust the target".into()
+                    } else {
+                        format!(
+                            "Save ${:.0} a month to finish by {}",
+                            goal.required_monthly,
+                            goal.target_date.format("%b %Y")
+                        )
+                    }),
+                    category: None,
+                }
+            })
+            .collect()
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        if insight.severity == "high" {
+            66.0
+        } else {
+            58.0
+        }
+    }
+}
+
+/// Spending this month at least 10% under the monthly average.
+pub struct PositiveInsights;
+
+#[async_trait]
+impl InsightGenerator for PositiveInsights {
+    fn name(&self) -> &'static str {
+        "positive"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        if ctx.avg_monthly <= 0.0 || ctx.this_month >= ctx.avg_monthly * 0.9 {
+            return Vec::new();
+        }
+        vec![Insight {
+            r#type: "positive".into(),
+            severity: "low".into(),
+            icon: "ThumbsUp".into(),
+            title: "Great month so far!".into(),
+            message: format!(
+                "You're spending {:.0}% below your monthly average",
+                (1.0 - ctx.this_month / ctx.avg_monthly) * 100.0
+            ),
+            metric: None,
+            action: None,
+            category: None,
+        }]
+    }
+
+    fn priority(&self, _insight: &Insight) -> f64 {
+        25.0
+    }
+}
+
+/// The three newest suspicious-charge findings the user hasn't dismissed.
+pub struct SuspiciousInsights;
+
+#[async_trait]
+impl InsightGenerator for SuspiciousInsights {
+    fn name(&self) -> &'static str {
+        "suspicious"
+    }
+
+    async fn generate(&self, ctx: &InsightContext) -> Vec<Insight> {
+        ctx.suspicious
+            .iter()
+            .filter(|f| !f.dismissed)
+            .take(3)
+            .map(|finding| {
+                let (icon, title) = match finding.kind.as_str() {
+                    "duplicate_charge" => (
+                        "AlertTriangle",
+                        format!("Possible duplicate at {}", finding.merchant),
+                    ),
+                    "test_charge" => (
+                        "AlertTriangle",
+                        format!("Possible card test at {}", finding.merchant),
+                    ),
+                    "foreign_on_domestic" => (
+                        "AlertCircle",
+                        format!("Foreign charge at {}", finding.merchant),
+                    ),
+                    _ => (
+                        "AlertCircle",
+                        format!("Round first charge at {}", finding.merchant),
+                    ),
+                };
+                Insight {
+                    r#type: "suspicious".into(),
+                    severity: finding.severity.clone(),
+                    icon: icon.into(),
+                    title,
+                    message: finding.message.clone(),
+                    metric: Some(serde_json::json!({
+                        "finding_id": finding.id,
+                        "kind": finding.kind,
+                        "amount": finding.amount,
+                    })),
+                    action: Some(
+                        "Check these charges with your card issuer, or dismiss if expected".into(),
+                    ),
+                    category: None,
+                }
+            })
+            .collect()
+    }
+
+    fn priority(&self, insight: &Insight) -> f64 {
+        match insight.severity.as_str() {
+            "high" => 86.0,
+            "medium" => 66.0,
+            _ => 46.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::budget::Budget;
+    use crate::services::analytics::CategoryMonth;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn ctx() -> InsightContext {
+        InsightContext {
+            as_of: d(2026, 2, 14),
+            ..InsightContext::default()
+        }
+    }
+
+    fn cashflow(month: &str, income: f64, expenses: f64) -> CashflowMonth {
+        CashflowMonth {
+            month: month.into(),
+            income,
+            expenses,
+            net: income - expenses,
+            savings_rate: analytics::savings_rate(income, expenses),
+        }
+    }
+
+    async fn run(generator: &dyn InsightGenerator, ctx: &InsightContext) -> Vec<(Insight, f64)> {
+        let mut out = Vec::new();
+        for insight in generator.generate(ctx).await {
+            let priority = generator.priority(&insight);
+            out.push((insight, priority));
+        }
+        out
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        let mut names = generator_names();
+        assert_eq!(names.len(), 11);
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), 11);
+        assert!(names.contains(&"anomaly") && names.contains(&"suspicious"));
+    }
+
+    #[tokio::test]
+    async fn test_anomaly_generator_skips_elevated() {
+        let mut rows = Vec::new();
+        for m in 7..=12 {
+            rows.push(CategoryMonth {
+                category: "Dining".into(),
+                month: d(2025, m, 1),
+                total: 100.0,
+                to_date: 50.0,
+            });
+        }
+        let mut ctx = ctx();
+        ctx.baselines = analytics::category_baselines(&rows, d(2026, 2, 1));
+        ctx.current_by_category = HashMap::from([("Dining".to_string(), 120.0)]);
+
+        let out = run(&AnomalyInsights, &ctx).await;
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0.category.as_deref(), Some("Dining"));
+        assert_eq!(out[0].0.severity, "high");
+        assert!(out[0].1 > 90.0);
+
+        ctx.current_by_category = HashMap::from([("Dining".to_string(), 50.0)]);
+        assert!(run(&AnomalyInsights, &ctx).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trend_forecast_and_positive_generators() {
+        let mut ctx = ctx();
+        ctx.this_month = 700.0;
+        ctx.last_month = 500.0;
+        ctx.avg_monthly = 1000.0;
+
+        let trend = run(&TrendInsights, &ctx).await;
+        assert_eq!(trend[0].0.title, "Spending up vs last month");
+        assert!((trend[0].1 - 82.0).abs() < 1e-9);
+
+        // 700 by the 14th of a 28-day month projects 1400, 40% over the average
+        let forecast = run(&ForecastInsights, &ctx).await;
+        assert_eq!(forecast[0].0.severity, "high");
+        assert!((forecast[0].1 - 75.0).abs() < 1e-9);
+
+        let positive = run(&PositiveInsights, &ctx).await;
+        assert_eq!(positive[0].0.title, "Great month so far!");
+        assert_eq!(positive[0].1, 25.0);
+
+        ctx.last_month = 650.0;
+        ctx.avg_monthly = 0.0;
+        assert!(run(&TrendInsights, &ctx).await.is_empty());
+        assert!(run(&ForecastInsights, &ctx).await.is_empty());
+        assert!(run(&PositiveInsights, &ctx).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_budget_generator_thresholds() {
+        let mut ctx = ctx();
+        let monthly = |category: &str, spent: f64| {
+            let budget = Budget {
+                id: uuid::Uuid::new_v4(),
+                category: category.into(),
+                amount: 100.0,
+                period: "monthly".into(),
+                start_date: None,
+                end_date: None,
+                rollover: false,
+                rollover_cap: None,
+                scope: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            };
+            let window = budgets::window(&budget, ctx.as_of).unwrap();
+            let totals = budgets::PeriodTotals {
+                amount: 100.0,
+                spent,
+                transferred: 0.0,
+            };
+            budgets::progress(&budget, window, totals, 0.0, ctx.as_of)
+        };
+        ctx.budgets = vec![
+            monthly("Dining", 120.0),
+            monthly("Gas", 85.0),
+            monthly("Travel", 10.0),
+        ];
+
+        let out = run(&BudgetInsights, &ctx).await;
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].0.title, "Dining budget exceeded");
+        assert_eq!(out[0].1, 85.0);
+        assert_eq!(out[1].0.title, "Gas budget at 85%");
+        assert!(out[1].0.message.contains("14 days remaining"));
+        assert_eq!(out[1].1, 55.0);
+    }
+
+    #[tokio::test]
+    async fn test_savings_generator() {
+        let mut ctx = ctx();
+        ctx.cashflow = vec![
+            cashflow("2025-12", 5000.0, 3000.0),
+            cashflow("2026-01", 5000.0, 5500.0),
+        ];
+        let out = run(&SavingsInsights, &ctx).await;
+        assert_eq!(out[0].0.title, "Spent more than you earned");
+        assert_eq!(out[0].1, 80.0);
+
+        // 40% saved on average, but this month's pace leaves 10%
+        ctx.cashflow = vec![
+            cashflow("2025-12", 5000.0, 3000.0),
+            cashflow("2026-01", 5000.0, 3000.0),
+        ];
+        ctx.this_month = 2250.0;
+        let out = run(&SavingsInsights, &ctx).await;
+        assert_eq!(out[0].0.title, "Savings rate slipping");
+        assert!((out[0].1 - 75.0).abs() < 1e-9);
+
+        ctx.this_month = 1000.0;
+        let out = run(&SavingsInsights, &ctx).await;
+        assert_eq!(out[0].0.r#type, "positive");
+        assert_eq!(out[0].1, 30.0);
+    }
+
+    #[tokio::test]
+    async fn test_goal_generator() {
+        use crate::models::goal::Goal;
+        use crate::services::goals;
+
+        let goal = Goal {
+            id: uuid::Uuid::nil(),
+            name: "Vacation".into(),
+            target_amount: 1200.0,
+            target_date: d(2026, 12, 31),
+            start_date: d(2025, 12, 31),
+            initial_amount: 0.0,
+            account: None,
+            tag: Some("vacation".into()),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let mut ctx = ctx();
+        ctx.goals = vec![
+            goals::progress(&goal, &[(d(2026, 1, 20), 200.0)], ctx.as_of),
+            goals::progress(&goal, &[(d(2026, 1, 20), 30.0)], ctx.as_of),
+        ];
+        let out = run(&GoalInsights, &ctx).await;
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0.title, "Vacation goal is falling behind");
+        assert_eq!(out[0].0.action.as_deref(), Some("Save $111 a month to finish by Dec 2026"));
+        assert_eq!(out[0].1, 58.0);
+
+        ctx.as_of = d(2027, 1, 10);
+        ctx.goals = vec![goals::progress(&goal, &[(d(2026, 1, 20), 30.0)], ctx.as_of)];
+        let out = run(&GoalInsights, &ctx).await;
+        assert_eq!(out[0].0.title, "Vacation goal missed its date");
+        assert_eq!(out[0].0.severity, "high");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_and_suspicious_priorities() {
+        let mut ctx = ctx();
+        let alert = |alert_type: &str| SubscriptionAlert {
+            subscription_id: uuid::Uuid::new_v4(),
+            merchant: "NETFLIX.COM".into(),
+            alert_type: alert_type.into(),
+            date: d(2026, 2, 12),
+            amount: 17.99,
+            previous_amount: Some(15.99),
+            days_overdue: None,
+            message: "m".into(),
+        };
+        ctx.subscription_alerts = vec![
+            alert("cancelled_charged"),
+            alert("price_increase"),
+            alert("overdue"),
+        ];
+        let priorities: Vec<f64> = run(&SubscriptionInsights, &ctx)
+            .await
+            .iter()
+            .map(|(_, p)| *p)
+            .collect();
+        assert_eq!(priorities, vec![88.0, 62.0, 52.0]);
+
+        let finding = |kind: &str, severity: &str, dismissed: bool| SuspiciousFinding {
+            id: kind.into(),
+            kind: kind.into(),
+            severity: severity.into(),
+            date: d(2026, 2, 10),
+            merchant: "SHOP".into(),
+            amount: 100.0,
+            transactions: Vec::new(),
+            message: "m".into(),
+            dismissed,
+        };
+        ctx.suspicious = vec![
+            finding("duplicate_charge", "high", true),
+            finding("foreign_on_domestic", "medium", false),
+            finding("round_amount_new_merchant", "low", false),
+        ];
+        let out = run(&SuspiciousInsights, &ctx).await;
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].0.title, "Foreign charge at SHOP");
+        assert_eq!(out[0].1, 66.0);
+        assert_eq!(out[1].1, 46.0);
+    }
+
+    #[tokio::test]
+    async fn test_generate_all_ranks_enabled_generators() {
+        let mut ctx = ctx();
+        ctx.this_month = 700.0;
+        ctx.last_month = 500.0;
+        ctx.avg_monthly = 1000.0;
+
+        let all = generate_all(&registry(), &ctx, |_| true).await;
+        let types: Vec<&str> = all.iter().map(|(i, _)| i.r#type.as_str()).collect();
+        assert_eq!(types, vec!["trend", "forecast", "positive"]);
+
+        let some = generate_all(&registry(), &ctx, |name| name != "trend").await;
+        assert_eq!(some.len(), 2);
+        assert_eq!(some[0].0.r#type, "forecast");
+    }
+}
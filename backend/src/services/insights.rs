@@ -0,0 +1,300 @@
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::analytics::Insight;
+use crate::models::insight::{InsightHistoryQuery, StoredInsight};
+
+const COLUMNS: &str = "id, fingerprint, type, subject, category, period, severity, icon, title, \
+     message, metric, action, priority, first_seen_at, last_seen_at, dismissed_at, snoozed_until, helpful";
+
+/// The month an insight is about.
+pub fn period(as_of: NaiveDate) -> String {
+    as_of.format("%Y-%m").to_string()
+}
+
+/// What an insight is about: its category, or for insights without one the subscription
+/// alert, suspicious finding or goal it reports, or failing that its title. A budget
+/// insight adds its stage, so dismissing the warning doesn't hide the overspend alert.
+pub fn subject(insight: &Insight) -> String {
+    if let Some(category) = &insight.category {
+        if insight.r#type == "budget" {
+            let stage = if insight.severity == "high" {
+                "exceeded"
+            } else {
+                "warning"
+            };
+            return format!("{category}:{stage}");
+        }
+        return category.clone();
+    }
+    let field = |name: &str| {
+        insight
+            .metric
+            .as_ref()
+            .and_then(|m| m.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    if let Some(finding) = field("finding_id") {
+        return format!("finding:{finding}");
+    }
+    if let (Some(subscription), Some(alert)) = (field("subscription_id"), field("alert_type")) {
+        return format!("subscription:{subscription}:{alert}");
+    }
+    if let Some(goal) = field("goal_id") {
+        return format!("goal:{goal}");
+    }
+    insight.title.clone()
+}
+
+/// Stable id for an insight: regenerating it in the same period gives the same fingerprint.
+pub fn fingerprint(r#type: &str, subject: &str, period: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [r#type, subject, period] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+/// Not dismissed, and not snoozed past `today`.
+pub fn is_active(insight: &StoredInsight, today: NaiveDate) -> bool {
+    insight.dismissed_at.is_none() && insight.snoozed_until.is_none_or(|until| until <= today)
+}
+
+/// Save a freshly generated insight, or refresh the stored copy while keeping the user's
+/// dismissal, snooze and feedback.
+pub async fn record(
+    pool: &PgPool,
+    insight: &Insight,
+    priority: f64,
+    period: &str,
+) -> Result<StoredInsight, sqlx::Error> {
+    let subject = subject(insight);
+    sqlx::query_as(&format!(
+        "INSERT INTO insights (fingerprint, type, subject, category, period, severity, icon, title, \
+           message, metric, action, priority) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) \
+         ON CONFLICT (fingerprint) DO UPDATE SET \
+           severity = EXCLUDED.severity, icon = EXCLUDED.icon, title = EXCLUDED.title, \
+           message = EXCLUDED.message, metric = EXCLUDED.metric, action = EXCLUDED.action, \
+           priority = EXCLUDED.priority, last_seen_at = NOW() \
+         RETURNING {COLUMNS}"
+    ))
+    .bind(fingerprint(&insight.r#type, &subject, period))
+    .bind(&insight.r#type)
+    .bind(&subject)
+    .bind(&insight.category)
+    .bind(period)
+    .bind(&insight.severity)
+    .bind(&insight.icon)
+    .bind(&insight.title)
+    .bind(&insight.message)
+    .bind(&insight.metric)
+    .bind(&insight.action)
+    .bind(priority)
+    .fetch_one(pool)
+    .await
+}
+
+/// A generated insight as the feed shows it without saving it, carrying over the stored
+/// copy's id and the user's response when there is one.
+pub fn preview(
+    insight: &Insight,
+    priority: f64,
+    period: &str,
+    stored: Option<&StoredInsight>,
+) -> StoredInsight {
+    let subject = subject(insight);
+    let now = chrono::Utc::now();
+    StoredInsight {
+        id: stored.map_or(Uuid::nil(), |s| s.id),
+        fingerprint: fingerprint(&insight.r#type, &subject, period),
+        r#type: insight.r#type.clone(),
+        subject,
+        category: insight.category.clone(),
+        period: period.to_string(),
+        severity: insight.severity.clone(),
+        icon: insight.icon.clone(),
+        title: insight.title.clone(),
+        message: insight.message.clone(),
+        metric: insight.metric.clone(),
+        action: insight.action.clone(),
+        priority,
+        first_seen_at: stored.map_or(now, |s| s.first_seen_at),
+        last_seen_at: stored.map_or(now, |s| s.last_seen_at),
+        dismissed_at: stored.and_then(|s| s.dismissed_at),
+        snoozed_until: stored.and_then(|s| s.snoozed_until),
+        helpful: stored.and_then(|s| s.helpful),
+    }
+}
+
+/// Stored insights with any of these fingerprints.
+pub async fn find_by_fingerprints(
+    pool: &PgPool,
+    fingerprints: &[String],
+) -> Result<Vec<StoredInsight>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {COLUMNS} FROM insights WHERE fingerprint = ANY($1)"
+    ))
+    .bind(fingerprints)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_insight(pool: &PgPool, id: Uuid) -> Result<Option<StoredInsight>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {COLUMNS} FROM insights WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Past insights, newest period first, most recently generated first within a period.
+pub async fn history(
+    pool: &PgPool,
+    query: &InsightHistoryQuery,
+    limit: i64,
+) -> Result<Vec<StoredInsight>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {COLUMNS} FROM insights \
+         WHERE ($1::text IS NULL OR type = $1) \
+           AND ($2::text IS NULL OR category = $2) \
+           AND ($3::text IS NULL OR period = $3) \
+           AND (NOT $4 OR dismissed_at IS NULL) \
+         ORDER BY period DESC, last_seen_at DESC, priority DESC \
+         LIMIT $5"
+    ))
+    .bind(&query.r#type)
+    .bind(&query.category)
+    .bind(&query.period)
+    .bind(query.hide_dismissed.unwrap_or(false))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn insight(r#type: &str, category: Option<&str>, metric: Option<serde_json::Value>) -> Insight {
+        Insight {
+            r#type: r#type.to_string(),
+            severity: "medium".to_string(),
+            icon: "AlertCircle".to_string(),
+            title: "Some title".to_string(),
+            message: "Some message".to_string(),
+            metric,
+            action: None,
+            category: category.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_budget_warning_and_overspend_have_separate_fingerprints() {
+        let warning = insight("budget", Some("Dining"), None);
+        let exceeded = Insight {
+            severity: "high".to_string(),
+            ..insight("budget", Some("Dining"), None)
+        };
+        assert_eq!(subject(&exceeded), "Dining:exceeded");
+        assert_ne!(
+            fingerprint("budget", &subject(&warning), "2026-02"),
+            fingerprint("budget", &subject(&exceeded), "2026-02")
+        );
+    }
+
+    #[test]
+    fn test_subject_prefers_category_then_metric_keys() {
+        assert_eq!(subject(&insight("trend", Some("Dining"), None)), "Dining");
+        assert_eq!(
+            subject(&insight("budget", Some("Dining"), None)),
+            "Dining:warning"
+        );
+        let alert = serde_json::json!({ "subscription_id": "abc", "alert_type": "overdue" });
+        assert_eq!(
+            subject(&insight("subscription", None, Some(alert))),
+            "subscription:abc:overdue"
+        );
+        let finding = serde_json::json!({ "finding_id": "f00d", "kind": "test_charge" });
+        assert_eq!(
+            subject(&insight("suspicious", None, Some(finding))),
+            "finding:f00d"
+        );
+        let goal = serde_json::json!({ "goal_id": "9e1f", "required_monthly": 120.0 });
+        assert_eq!(subject(&insight("goal", None, Some(goal))), "goal:9e1f");
+        assert_eq!(subject(&insight("habit", None, None)), "Some title");
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_period() {
+        let a = fingerprint("anomaly", "Dining", "2026-02");
+        assert_eq!(a, fingerprint("anomaly", "Dining", "2026-02"));
+        assert_ne!(a, fingerprint("anomaly", "Dining", "2026-03"));
+        assert_ne!(a, fingerprint("budget", "Dining", "2026-02"));
+        // Parts are separated, so shifting text between them doesn't collide
+        assert_ne!(fingerprint("ab", "c", "p"), fingerprint("a", "bc", "p"));
+        assert_eq!(period(d(2026, 2, 28)), "2026-02");
+    }
+
+    #[test]
+    fn test_is_active() {
+        let mut stored = StoredInsight {
+            id: Uuid::new_v4(),
+            fingerprint: "f".into(),
+            r#type: "budget".into(),
+            subject: "Dining".into(),
+            category: Some("Dining".into()),
+            period: "2026-02".into(),
+            severity: "medium".into(),
+            icon: "AlertCircle".into(),
+            title: "t".into(),
+            message: "m".into(),
+            metric: None,
+            action: None,
+            priority: 50.0,
+            first_seen_at: Utc::now(),
+            last_seen_at: Utc::now(),
+            dismissed_at: None,
+            snoozed_until: None,
+            helpful: None,
+        };
+        let today = d(2026, 2, 10);
+        assert!(is_active(&stored, today));
+        stored.snoozed_until = Some(d(2026, 2, 11));
+        assert!(!is_active(&stored, today));
+        stored.snoozed_until = Some(today);
+        assert!(is_active(&stored, today));
+        stored.dismissed_at = Some(Utc::now());
+        assert!(!is_active(&stored, today));
+    }
+
+    #[test]
+    fn test_preview_keeps_the_stored_response() {
+        let generated = insight("budget", Some("Dining"), None);
+        let fresh = preview(&generated, 40.0, "2026-02", None);
+        assert!(fresh.id.is_nil());
+        assert_eq!(
+            fresh.fingerprint,
+            fingerprint("budget", "Dining:warning", "2026-02")
+        );
+        assert!(is_active(&fresh, d(2026, 2, 10)));
+
+        let mut stored = preview(&generated, 10.0, "2026-02", None);
+        stored.id = Uuid::new_v4();
+        stored.message = "Old message".into();
+        stored.dismissed_at = Some(Utc::now());
+        let seen = preview(&generated, 40.0, "2026-02", Some(&stored));
+        assert_eq!(seen.id, stored.id);
+        assert_eq!(seen.message, "Some message");
+        assert_eq!(seen.priority, 40.0);
+        assert!(!is_active(&seen, d(2026, 2, 10)));
+    }
+}
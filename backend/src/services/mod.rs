@@ -1,3 +1,18 @@
+pub mod analytics;
+pub mod balances;
+pub mod budgets;
+pub mod categories;
+pub mod channels;
 pub mod csv_parser;
 pub mod dedup;
+pub mod forecast;
+pub mod fx;
+pub mod goals;
+pub mod insight_generators;
+pub mod insights;
 pub mod merchant_normalizer;
+pub mod notifications;
+pub mod reconciliation;
+pub mod subscriptions;
+pub mod suspicious;
+pub mod transfers;
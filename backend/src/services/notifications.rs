@@ -0,0 +1,172 @@
+use crate::models::budget::BudgetProgress;
+use crate::models::notification::Alert;
+use crate::models::subscription::SubscriptionAlert;
+use crate::services::budgets;
+
+/// Share of a budget used before the early warning goes out.
+pub const WARNING_PCT: f64 = 80.0;
+
+/// Threshold alerts for each budget's current period. Only the highest threshold crossed
+/// is raised; a projected overspend is raised alongside the 80% warning, but not once the
+/// budget is already exceeded or the period is over. Keys carry the period start, so each
+/// alert goes out once per period.
+pub fn budget_alerts(progress: &[BudgetProgress]) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    for budget in progress {
+        let noun = budgets::period_noun(&budget.period);
+        let key = |kind: &str| {
+            format!(
+                "budget:{}:{}:{}",
+                budget.category, kind, budget.period_start
+            )
+        };
+
+        if budget.pct_used >= 100.0 {
+            alerts.push(Alert {
+                key: key("100"),
+                alert_type: "budget_exceeded".into(),
+                title: format!("{} budget exceeded", budget.category),
+                message: format!(
+                    "You've spent ${:.2} on {} this {}, exceeding your ${:.2} budget.",
+                    budget.spent, budget.category, noun, budget.available
+                ),
+            });
+            continue;
+        }
+        if budget.pct_used >= WARNING_PCT {
+            alerts.push(Alert {
+                key: key("80"),
+                alert_type: "budget_80".into(),
+                title: format!("{} budget at {:.0}%", budget.category, budget.pct_used),
+                message: format!(
+                    "${:.2} of ${:.2} {} budget used with {} days remaining.",
+                    budget.spent, budget.available, budget.category, budget.days_remaining
+                ),
+            });
+        }
+        if budget.projected_pct >= 100.0 && budget.days_remaining > 0 {
+            alerts.push(Alert {
+                key: key("projected"),
+                alert_type: "budget_projected".into(),
+                title: format!("{} on track to overspend", budget.category),
+                message: format!(
+                    "At the current pace you'll spend ${:.2} on {} this {}, over your ${:.2} budget.",
+                    budget.projected_spend, budget.category, noun, budget.available
+                ),
+            });
+        }
+    }
+    alerts
+}
+
+/// One alert per subscription problem. The key carries the charge (or missed) date, so a
+/// new price increase or another missed charge is a new alert.
+pub fn subscription_alerts(alerts: &[SubscriptionAlert]) -> Vec<Alert> {
+    alerts
+        .iter()
+        .map(|alert| {
+            let title = match alert.alert_type.as_str() {
+                "cancelled_charged" => format!("{} is still charging", alert.merchant),
+                "price_increase" => format!("{} raised its price", alert.merchant),
+                _ => format!("{} charge is overdue", alert.merchant),
+            };
+            Alert {
+                key: format!(
+                    "subscription:{}:{}:{}",
+                    alert.alert_type, alert.subscription_id, alert.date
+                ),
+                alert_type: format!("subscription_{}", alert.alert_type),
+                title,
+                message: alert.message.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn progress(category: &str, spent: f64, projected: f64, days_remaining: u32) -> BudgetProgress {
+        let available = 100.0;
+        BudgetProgress {
+            category: category.into(),
+            period: "monthly".into(),
+            period_start: NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+            period_end: NaiveDate::from_ymd_opt(2026, 2, 28).unwrap(),
+            amount: available,
+            carried_in: 0.0,
+            transferred: 0.0,
+            available,
+            spent,
+            remaining: available - spent,
+            pct_used: spent,
+            projected_spend: projected,
+            projected_pct: projected,
+            status: "on_track".into(),
+            days_remaining,
+        }
+    }
+
+    fn kinds(alerts: &[Alert]) -> Vec<&str> {
+        alerts.iter().map(|a| a.alert_type.as_str()).collect()
+    }
+
+    #[test]
+    fn test_budget_alerts_thresholds() {
+        assert!(budget_alerts(&[progress("Dining", 50.0, 90.0, 10)]).is_empty());
+        assert_eq!(
+            kinds(&budget_alerts(&[progress("Dining", 85.0, 95.0, 10)])),
+            ["budget_80"]
+        );
+        assert_eq!(
+            kinds(&budget_alerts(&[progress("Dining", 85.0, 130.0, 10)])),
+            ["budget_80", "budget_projected"]
+        );
+        assert_eq!(
+            kinds(&budget_alerts(&[progress("Dining", 40.0, 130.0, 10)])),
+            ["budget_projected"]
+        );
+        // Exceeded replaces the others
+        assert_eq!(
+            kinds(&budget_alerts(&[progress("Dining", 120.0, 200.0, 10)])),
+            ["budget_exceeded"]
+        );
+        // Nothing left to project once the period is over
+        assert!(budget_alerts(&[progress("Dining", 50.0, 130.0, 0)]).is_empty());
+    }
+
+    #[test]
+    fn test_budget_alert_keys_are_per_period() {
+        let feb = budget_alerts(&[progress("Dining", 120.0, 120.0, 5)]);
+        assert_eq!(feb[0].key, "budget:Dining:100:2026-02-01");
+
+        let mut march = progress("Dining", 120.0, 120.0, 5);
+        march.period_start = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_ne!(budget_alerts(&[march])[0].key, feb[0].key);
+    }
+
+    #[test]
+    fn test_subscription_alerts() {
+        let id = Uuid::nil();
+        let alerts = subscription_alerts(&[SubscriptionAlert {
+            subscription_id: id,
+            merchant: "NETFLIX".into(),
+            alert_type: "price_increase".into(),
+            date: NaiveDate::from_ymd_opt(2026, 2, 3).unwrap(),
+            amount: 17.99,
+            previous_amount: Some(15.99),
+            days_overdue: None,
+            message: "NETFLIX went from $15.99 to $17.99".into(),
+        }]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, "subscription_price_increase");
+        assert_eq!(alerts[0].title, "NETFLIX raised its price");
+        assert_eq!(
+            alerts[0].key,
+            format!("subscription:price_increase:{id}:2026-02-03")
+        );
+    }
+}
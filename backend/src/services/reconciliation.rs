@@ -0,0 +1,281 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use crate::models::import::{CoverageGap, Reconciliation, StatementSummary, UnmatchedDay};
+use crate::services::balances::balance_sign;
+
+/// Differences under half a cent are rounding noise.
+const TOLERANCE: f64 = 0.005;
+
+/// A summary block is a handful of lines; anything longer is the transaction table.
+const MAX_SUMMARY_LINES: usize = 12;
+
+static RE_BALANCE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)^\W*(beginning|opening|starting|previous|ending|closing|new)\s+balance(?:\s+as\s+of\s+([0-9/]+|\d{4}-\d{2}-\d{2}))?(.*)$"#)
+        .unwrap()
+});
+
+static RE_PERIOD_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)statement\s+period\W*(\d{4}-\d{2}-\d{2}|[0-9/]+)\s*(?:-|to|through)\s*(\d{4}-\d{2}-\d{2}|[0-9/]+)")
+        .unwrap()
+});
+
+static RE_MONEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"-?\$?-?\d[\d,]*\.\d{2}").unwrap());
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn parse_summary_date(s: &str) -> Option<NaiveDate> {
+    ["%m/%d/%Y", "%m/%d/%y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(s.trim(), fmt).ok())
+}
+
+fn parse_money(s: &str) -> Option<f64> {
+    let m = RE_MONEY.find_iter(s).last()?;
+    m.as_str().replace(['$', ','], "").parse().ok()
+}
+
+/// Split a bank export's summary block (balances and statement period above the
+/// transaction table, separated by a blank line) from the CSV itself.
+///
+/// Files without a recognisable summary are returned unchanged.
+pub fn split_statement_summary(data: &str) -> (StatementSummary, &str) {
+    let mut summary = StatementSummary::default();
+    let mut offset = 0;
+    let mut found = false;
+    let mut block_lines = 0;
+
+    for line in data.split_inclusive('\n') {
+        offset += line.len();
+        let text = line.trim();
+        if text.is_empty() {
+            if found {
+                return (summary, &data[offset..]);
+            }
+            if block_lines > 0 {
+                break;
+            }
+            continue;
+        }
+
+        block_lines += 1;
+        if block_lines > MAX_SUMMARY_LINES {
+            break;
+        }
+
+        if let Some(caps) = RE_PERIOD_LINE.captures(text) {
+            summary.period_start = parse_summary_date(&caps[1]);
+            summary.period_end = parse_summary_date(&caps[2]);
+            found = true;
+        } else if let Some(caps) = RE_BALANCE_LINE.captures(text) {
+            let as_of = caps.get(2).and_then(|d| parse_summary_date(d.as_str()));
+            let amount = parse_money(&caps[3]);
+            match caps[1].to_lowercase().as_str() {
+                "ending" | "closing" | "new" => {
+                    summary.closing_balance = amount;
+                    summary.period_end = summary.period_end.or(as_of);
+                }
+                _ => {
+                    summary.opening_balance = amount;
+                    summary.period_start = summary.period_start.or(as_of);
+                }
+            }
+            found = true;
+        }
+    }
+
+    (StatementSummary::default(), data)
+}
+
+/// Daily totals as a date → amount map.
+fn by_day(totals: &[(NaiveDate, f64)]) -> BTreeMap<NaiveDate, f64> {
+    let mut map = BTreeMap::new();
+    for (date, amount) in totals {
+        *map.entry(*date).or_insert(0.0) += amount;
+    }
+    map
+}
+
+/// Compare a statement with what the ledger holds for the same account and period.
+///
+/// `statement_days` are the daily totals of the rows in the file and `ledger_days` the
+/// daily totals stored for the card (both positive = money out). The balance movement
+/// is checked when both balances are known; days whose totals differ point at missing
+/// or extra transactions.
+pub fn reconcile(
+    account_type: &str,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    opening_balance: Option<f64>,
+    closing_balance: Option<f64>,
+    statement_days: &[(NaiveDate, f64)],
+    ledger_days: &[(NaiveDate, f64)],
+) -> Reconciliation {
+    let in_period = |d: &NaiveDate| *d >= period_start && *d <= period_end;
+    let statement = by_day(statement_days);
+    let ledger = by_day(ledger_days);
+
+    let computed_delta = round_cents(
+        ledger
+            .iter()
+            .filter(|(d, _)| in_period(d))
+            .map(|(_, amount)| amount)
+            .sum::<f64>()
+            * balance_sign(account_type),
+    );
+    let expected_delta = match (opening_balance, closing_balance) {
+        (Some(open), Some(close)) => Some(round_cents(close - open)),
+        _ => None,
+    };
+    let difference = expected_delta.map(|e| round_cents(computed_delta - e));
+
+    let mut days: Vec<NaiveDate> = statement.keys().chain(ledger.keys()).copied().collect();
+    days.sort();
+    days.dedup();
+    let unmatched_days: Vec<UnmatchedDay> = days
+        .into_iter()
+        .filter(in_period)
+        .filter_map(|date| {
+            let statement_total = round_cents(statement.get(&date).copied().unwrap_or(0.0));
+            let ledger_total = round_cents(ledger.get(&date).copied().unwrap_or(0.0));
+            let diff = round_cents(ledger_total - statement_total);
+            (diff.abs() >= TOLERANCE).then_some(UnmatchedDay {
+                date,
+                statement_total,
+                ledger_total,
+                difference: diff,
+            })
+        })
+        .collect();
+
+    let status = match difference {
+        Some(d) if d.abs() >= TOLERANCE => "unbalanced",
+        _ if !unmatched_days.is_empty() => "unbalanced",
+        Some(_) => "balanced",
+        None => "unverified",
+    };
+
+    Reconciliation {
+        period_start,
+        period_end,
+        opening_balance,
+        closing_balance,
+        expected_delta,
+        computed_delta,
+        difference,
+        unmatched_days,
+        status: status.to_string(),
+    }
+}
+
+/// Days between consecutive statement periods that no statement covers.
+pub fn coverage_gaps(periods: &[(NaiveDate, NaiveDate)]) -> Vec<CoverageGap> {
+    let mut sorted = periods.to_vec();
+    sorted.sort();
+
+    let mut gaps = Vec::new();
+    let mut covered_until: Option<NaiveDate> = None;
+    for (start, end) in sorted {
+        if let Some(until) = covered_until {
+            let days = (start - until).num_days() - 1;
+            if days > 0 {
+                gaps.push(CoverageGap {
+                    start: until.succ_opt().unwrap_or(until),
+                    end: start.pred_opt().unwrap_or(start),
+                    days,
+                });
+            }
+        }
+        covered_until = Some(covered_until.map_or(end, |u| u.max(end)));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, day).unwrap()
+    }
+
+    #[test]
+    fn test_split_summary_block() {
+        let data = "Description,,Summary Amt.\n\
+            Beginning balance as of 01/01/2026,,\"1,000.00\"\n\
+            Total credits,,\"2,500.00\"\n\
+            Ending balance as of 01/31/2026,,\"3,250.50\"\n\
+            \n\
+            Date,Description,Amount\n\
+            01/05/2026,PAYROLL,2500.00\n";
+        let (summary, rest) = split_statement_summary(data);
+        assert_eq!(summary.opening_balance, Some(1000.0));
+        assert_eq!(summary.closing_balance, Some(3250.5));
+        assert_eq!(summary.period_start, Some(d(1, 1)));
+        assert_eq!(summary.period_end, Some(d(1, 31)));
+        assert!(rest.starts_with("Date,Description,Amount"));
+    }
+
+    #[test]
+    fn test_split_statement_period_line() {
+        let data = "Statement Period: 2026-02-01 to 2026-02-28\nOpening Balance,500.00\nClosing Balance,-25.00\n\nDate,Amount\n";
+        let (summary, rest) = split_statement_summary(data);
+        assert_eq!(summary.period_start, Some(d(2, 1)));
+        assert_eq!(summary.period_end, Some(d(2, 28)));
+        assert_eq!(summary.closing_balance, Some(-25.0));
+        assert_eq!(rest, "Date,Amount\n");
+    }
+
+    #[test]
+    fn test_plain_csv_unchanged() {
+        let data = "Date,Description,Amount\n01/05/2026,COFFEE,4.50\n";
+        let (summary, rest) = split_statement_summary(data);
+        assert_eq!(summary, StatementSummary::default());
+        assert_eq!(rest, data);
+    }
+
+    #[test]
+    fn test_reconcile_balanced_credit_card() {
+        let days = vec![(d(1, 5), 100.0), (d(1, 9), 50.0)];
+        let r = reconcile("credit", d(1, 1), d(1, 31), Some(200.0), Some(350.0), &days, &days);
+        assert_eq!(r.computed_delta, 150.0);
+        assert_eq!(r.difference, Some(0.0));
+        assert!(r.unmatched_days.is_empty());
+        assert_eq!(r.status, "balanced");
+    }
+
+    #[test]
+    fn test_reconcile_flags_missing_and_extra_days() {
+        let statement = vec![(d(1, 5), 100.0), (d(1, 9), 50.0)];
+        let ledger = vec![(d(1, 5), 100.0), (d(1, 12), 20.0)];
+        let r = reconcile("checking", d(1, 1), d(1, 31), Some(1000.0), Some(850.0), &statement, &ledger);
+        assert_eq!(r.computed_delta, -120.0);
+        assert_eq!(r.difference, Some(30.0));
+        assert_eq!(r.status, "unbalanced");
+        let dates: Vec<NaiveDate> = r.unmatched_days.iter().map(|u| u.date).collect();
+        assert_eq!(dates, vec![d(1, 9), d(1, 12)]);
+        assert_eq!(r.unmatched_days[0].difference, -50.0);
+    }
+
+    #[test]
+    fn test_reconcile_without_balances_is_unverified() {
+        let days = vec![(d(1, 5), 10.0)];
+        let r = reconcile("credit", d(1, 1), d(1, 31), None, None, &days, &days);
+        assert_eq!(r.status, "unverified");
+        assert_eq!(r.expected_delta, None);
+    }
+
+    #[test]
+    fn test_coverage_gaps_between_statements() {
+        let gaps = coverage_gaps(&[(d(3, 1), d(3, 31)), (d(1, 1), d(1, 31)), (d(2, 1), d(2, 20))]);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].start, d(2, 21));
+        assert_eq!(gaps[0].end, d(2, 28));
+        assert_eq!(gaps[0].days, 8);
+    }
+}
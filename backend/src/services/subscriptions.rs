@@ -0,0 +1,406 @@
+use chrono::{Duration, NaiveDate};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+use crate::models::analytics::RecurringTransaction;
+use crate::models::subscription::{Subscription, SubscriptionAlert, SubscriptionSync};
+use crate::services::analytics::{self, Charge, Thresholds};
+
+/// A detected series belongs to a registered subscription at the same merchant when its
+/// latest amount is within this fraction of the registered amount.
+const MATCH_TOLERANCE: f64 = 0.5;
+
+/// Increases smaller than this are rounding, not a price change.
+const PRICE_TOLERANCE: f64 = 0.01;
+
+const SUBSCRIPTION_COLUMNS: &str = "id, merchant, frequency, amount::float8 as amount, \
+     expected_amount::float8 as expected_amount, status, cancelled_on, first_seen, \
+     last_charge_date, next_expected_date, created_at, updated_at";
+
+/// The amount a subscription is expected to charge.
+pub fn reference_amount(sub: &Subscription) -> f64 {
+    sub.expected_amount.unwrap_or(sub.amount)
+}
+
+/// Registered subscription (index into `subs`) for each detected series: same merchant,
+/// closest amount within tolerance. Each subscription is claimed by at most one series.
+pub fn match_series(subs: &[Subscription], series: &[RecurringTransaction]) -> Vec<Option<usize>> {
+    let mut claimed = vec![false; subs.len()];
+    series
+        .iter()
+        .map(|r| {
+            let best = subs
+                .iter()
+                .enumerate()
+                .filter(|(i, s)| !claimed[*i] && s.merchant == r.merchant && s.amount > 0.0)
+                .map(|(i, s)| (i, (r.next_expected_amount - s.amount).abs() / s.amount))
+                .filter(|(_, diff)| *diff <= MATCH_TOLERANCE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i);
+            if let Some(i) = best {
+                claimed[i] = true;
+            }
+            best
+        })
+        .collect()
+}
+
+/// Apply the user's decisions to detected series: ignored ones are dropped and cancelled
+/// ones no longer count as active.
+pub fn apply_registry(series: Vec<RecurringTransaction>, subs: &[Subscription]) -> Vec<RecurringTransaction> {
+    let matches = match_series(subs, &series);
+    series
+        .into_iter()
+        .zip(matches)
+        .filter_map(|(mut r, m)| match m.map(|i| subs[i].status.as_str()) {
+            Some("ignored") => None,
+            Some("cancelled") => {
+                r.status = "inactive".to_string();
+                r.potentially_forgotten = false;
+                Some(r)
+            }
+            _ => Some(r),
+        })
+        .collect()
+}
+
+/// Price increases, charges after cancellation and missed charges.
+///
+/// Each charge at a registered merchant is attributed to the subscription whose amount is
+/// closest, so a price jump still lands on the subscription it belongs to.
+pub fn subscription_alerts(subs: &[Subscription], charges: &[Charge], today: NaiveDate) -> Vec<SubscriptionAlert> {
+    let mut by_sub: HashMap<usize, Vec<&Charge>> = HashMap::new();
+    for charge in charges.iter().filter(|c| c.amount > 0.0 && c.date <= today) {
+        let owner = subs
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.merchant == charge.merchant)
+            .min_by(|a, b| {
+                let da = (charge.amount - a.1.amount).abs();
+                let db = (charge.amount - b.1.amount).abs();
+                da.total_cmp(&db)
+            })
+            .map(|(i, _)| i);
+        if let Some(i) = owner {
+            by_sub.entry(i).or_default().push(charge);
+        }
+    }
+
+    let mut alerts = Vec::new();
+    for (i, sub) in subs.iter().enumerate() {
+        let mut own = by_sub.remove(&i).unwrap_or_default();
+        own.sort_by_key(|c| c.date);
+        let alert = |alert_type: &str, date, amount, previous_amount, days_overdue, message| SubscriptionAlert {
+            subscription_id: sub.id,
+            merchant: sub.merchant.clone(),
+            alert_type: alert_type.to_string(),
+            date,
+            amount,
+            previous_amount,
+            days_overdue,
+            message,
+        };
+
+        match sub.status.as_str() {
+            "ignored" => {}
+            "cancelled" => {
+                let cancelled_on = sub.cancelled_on.unwrap_or(sub.last_charge_date);
+                let after: Vec<&&Charge> = own.iter().filter(|c| c.date > cancelled_on).collect();
+                if let Some(last) = after.last() {
+                    alerts.push(alert(
+                        "cancelled_charged",
+                        last.date,
+                        last.amount,
+                        None,
+                        None,
+                        format!(
+                            "{} charged ${:.2} on {} after you cancelled it on {} ({} charge{} since)",
+                            sub.merchant,
+                            last.amount,
+                            last.date,
+                            cancelled_on,
+                            after.len(),
+                            if after.len() == 1 { "" } else { "s" }
+                        ),
+                    ));
+                }
+            }
+            _ => {
+                if let Some(last) = own.last() {
+                    let previous = match sub.expected_amount {
+                        Some(expected) => Some(expected),
+                        None if own.len() >= 2 => Some(own[own.len() - 2].amount),
+                        None => None,
+                    };
+                    if let Some(previous) = previous.filter(|p| last.amount > p + PRICE_TOLERANCE) {
+                        alerts.push(alert(
+                            "price_increase",
+                            last.date,
+                            last.amount,
+                            Some(previous),
+                            None,
+                            format!(
+                                "{} went up from ${:.2} to ${:.2} (+{:.0}%)",
+                                sub.merchant,
+                                previous,
+                                last.amount,
+                                (last.amount - previous) / previous * 100.0
+                            ),
+                        ));
+                    }
+                }
+
+                let Some(cadence) = analytics::cadence(&sub.frequency) else {
+                    continue;
+                };
+                let last_date = own.last().map_or(sub.last_charge_date, |c| c.date.max(sub.last_charge_date));
+                let expected = cadence.nth_after(last_date, 1);
+                let grace = (cadence.days * 0.2).ceil().max(2.0) as i64;
+                let days_overdue = (today - expected).num_days();
+                // Unconfirmed series that stopped long ago are just inactive, not overdue
+                let relevant = sub.status == "confirmed" || (days_overdue as f64) <= cadence.days;
+                if days_overdue > grace && relevant {
+                    alerts.push(alert(
+                        "overdue",
+                        expected,
+                        reference_amount(sub),
+                        None,
+                        Some(days_overdue),
+                        format!(
+                            "{} was expected around {} (${:.2}) and hasn't charged for {} days past that",
+                            sub.merchant,
+                            expected,
+                            reference_amount(sub),
+                            days_overdue
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    alerts.sort_by(|a, b| a.merchant.cmp(&b.merchant).then(a.date.cmp(&b.date)));
+    alerts
+}
+
+pub async fn list_subscriptions(pool: &PgPool, status: Option<&str>) -> Result<Vec<Subscription>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        "SELECT {SUBSCRIPTION_COLUMNS} FROM subscriptions \
+         WHERE $1::text IS NULL OR status = $1 \
+         ORDER BY merchant, amount DESC"
+    ))
+    .bind(status)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_subscription(pool: &PgPool, id: uuid::Uuid) -> Result<Option<Subscription>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {SUBSCRIPTION_COLUMNS} FROM subscriptions WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Every expense charge up to `until`, in the home currency.
+pub async fn fetch_charges(pool: &PgPool, until: NaiveDate) -> Result<Vec<Charge>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT COALESCE(merchant_normalized, description) as merchant, date, amount::float8 as amount, card \
+         FROM spending_home WHERE amount > 0 AND date <= $1 \
+         ORDER BY merchant, date",
+    )
+    .bind(until)
+    .fetch_all(pool)
+    .await
+}
+
+/// Register newly detected series and refresh the detected fields of known ones.
+/// The user's status, cancellation date and expected amount are never touched.
+pub async fn sync_subscriptions(pool: &PgPool, t: &Thresholds, today: NaiveDate) -> Result<SubscriptionSync, sqlx::Error> {
+    let charges = fetch_charges(pool, today).await?;
+    let series = analytics::detect_recurring(&charges, today, t);
+    let subs = list_subscriptions(pool, None).await?;
+
+    let mut summary = SubscriptionSync { created: 0, updated: 0 };
+    for (r, matched) in series.iter().zip(match_series(&subs, &series)) {
+        match matched {
+            Some(i) => {
+                sqlx::query(
+                    "UPDATE subscriptions SET frequency = $1, amount = $2, \
+                       first_seen = LEAST(first_seen, $3), last_charge_date = $4, \
+                       next_expected_date = $5, updated_at = NOW() \
+                     WHERE id = $6",
+                )
+                .bind(&r.frequency)
+                .bind(r.next_expected_amount)
+                .bind(r.first_seen)
+                .bind(r.last_seen)
+                .bind(r.next_expected_date)
+                .bind(subs[i].id)
+                .execute(pool)
+                .await?;
+                summary.updated += 1;
+            }
+            None => {
+                sqlx::query(
+                    "INSERT INTO subscriptions \
+                       (merchant, frequency, amount, first_seen, last_charge_date, next_expected_date) \
+                     VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(&r.merchant)
+                .bind(&r.frequency)
+                .bind(r.next_expected_amount)
+                .bind(r.first_seen)
+                .bind(r.last_seen)
+                .bind(r.next_expected_date)
+                .execute(pool)
+                .await?;
+                summary.created += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Alerts for the registry as of `today`.
+pub async fn current_alerts(pool: &PgPool, today: NaiveDate) -> Result<Vec<SubscriptionAlert>, sqlx::Error> {
+    let subs = list_subscriptions(pool, None).await?;
+    if subs.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Charges before the oldest subscription can't raise an alert
+    let since = subs.iter().map(|s| s.first_seen).min().unwrap_or(today) - Duration::days(1);
+    let charges: Vec<Charge> = fetch_charges(pool, today)
+        .await?
+        .into_iter()
+        .filter(|c| c.date > since)
+        .collect();
+    Ok(subscription_alerts(&subs, &charges, today))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn sub(merchant: &str, amount: f64, status: &str, last: NaiveDate) -> Subscription {
+        Subscription {
+            id: Uuid::new_v4(),
+            merchant: merchant.to_string(),
+            frequency: "monthly".to_string(),
+            amount,
+            expected_amount: None,
+            status: status.to_string(),
+            cancelled_on: None,
+            first_seen: d(2025, 6, 1),
+            last_charge_date: last,
+            next_expected_date: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn charge(merchant: &str, date: NaiveDate, amount: f64) -> Charge {
+        Charge { merchant: merchant.to_string(), date, amount, card: "citi".to_string() }
+    }
+
+    fn series(merchant: &str, amount: f64) -> RecurringTransaction {
+        RecurringTransaction {
+            merchant: merchant.to_string(),
+            avg_amount: amount,
+            frequency: "monthly".to_string(),
+            active_months: 4,
+            first_seen: d(2025, 10, 1),
+            last_seen: d(2026, 1, 1),
+            estimated_annual: amount * 12.0,
+            status: "active".to_string(),
+            last_gap_days: 10,
+            potentially_forgotten: false,
+            charge_count: 4,
+            median_gap_days: 31.0,
+            gap_stddev_days: 0.5,
+            monthly_amount: amount,
+            card: "citi".to_string(),
+            next_expected_date: d(2026, 2, 1),
+            next_expected_amount: amount,
+            confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_match_series_by_merchant_and_amount() {
+        let subs = vec![
+            sub("APPLE", 0.99, "confirmed", d(2026, 1, 3)),
+            sub("APPLE", 9.99, "ignored", d(2026, 1, 20)),
+            sub("NETFLIX", 15.99, "detected", d(2026, 1, 15)),
+        ];
+        let detected = vec![series("APPLE", 10.99), series("NETFLIX", 17.99), series("SPOTIFY", 10.99), series("APPLE", 1.09)];
+        assert_eq!(match_series(&subs, &detected), vec![Some(1), Some(2), None, Some(0)]);
+
+        // The ignored plan is dropped, the rest pass through
+        let applied = apply_registry(detected, &subs);
+        assert_eq!(applied.len(), 3);
+        assert!(!applied.iter().any(|r| r.merchant == "APPLE" && r.next_expected_amount == 10.99));
+    }
+
+    #[test]
+    fn test_cancelled_subscription_charging_again() {
+        let mut gym = sub("GYM", 40.0, "cancelled", d(2025, 12, 1));
+        gym.cancelled_on = Some(d(2025, 12, 15));
+        let charges = vec![
+            charge("GYM", d(2025, 12, 1), 40.0),
+            charge("GYM", d(2026, 1, 1), 40.0),
+            charge("GYM", d(2026, 2, 1), 40.0),
+        ];
+        let alerts = subscription_alerts(std::slice::from_ref(&gym), &charges, d(2026, 2, 10));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, "cancelled_charged");
+        assert_eq!(alerts[0].date, d(2026, 2, 1));
+        assert!(alerts[0].message.contains("2 charges"));
+
+        // Cancelled series no longer count as active
+        let applied = apply_registry(vec![series("GYM", 40.0)], &[gym]);
+        assert_eq!(applied[0].status, "inactive");
+        assert!(!applied[0].potentially_forgotten);
+    }
+
+    #[test]
+    fn test_price_increase_against_previous_and_expected() {
+        let netflix = sub("NETFLIX", 15.99, "confirmed", d(2026, 2, 15));
+        let charges = vec![
+            charge("NETFLIX", d(2025, 12, 15), 15.99),
+            charge("NETFLIX", d(2026, 1, 15), 15.99),
+            charge("NETFLIX", d(2026, 2, 15), 17.99),
+        ];
+        let alerts = subscription_alerts(std::slice::from_ref(&netflix), &charges, d(2026, 2, 20));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, "price_increase");
+        assert_eq!(alerts[0].previous_amount, Some(15.99));
+        assert_eq!(alerts[0].amount, 17.99);
+
+        // Once the user records the new price, the alert clears
+        let accepted = Subscription { expected_amount: Some(17.99), ..netflix };
+        assert!(subscription_alerts(&[accepted], &charges, d(2026, 2, 20)).is_empty());
+    }
+
+    #[test]
+    fn test_overdue_charge() {
+        let confirmed = sub("SPOTIFY", 10.99, "confirmed", d(2025, 12, 20));
+        let detected = sub("HULU", 7.99, "detected", d(2025, 9, 5));
+        let charges = vec![
+            charge("SPOTIFY", d(2025, 11, 20), 10.99),
+            charge("SPOTIFY", d(2025, 12, 20), 10.99),
+            charge("HULU", d(2025, 9, 5), 7.99),
+        ];
+        // Spotify expected 2026-01-20, grace 7 days
+        assert!(subscription_alerts(std::slice::from_ref(&confirmed), &charges, d(2026, 1, 25)).is_empty());
+        let alerts = subscription_alerts(&[confirmed, detected], &charges, d(2026, 2, 1));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].merchant, "SPOTIFY");
+        assert_eq!(alerts[0].alert_type, "overdue");
+        assert_eq!(alerts[0].days_overdue, Some(12));
+    }
+}
@@ -0,0 +1,404 @@
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::models::suspicious::{SuspectTransaction, SuspiciousFinding};
+use crate::services::analytics::Thresholds;
+use crate::services::fx;
+
+/// Days of history needed before a merchant's first charge counts as a new merchant,
+/// so a first import doesn't flag everything.
+const NEW_MERCHANT_HISTORY_DAYS: i64 = 30;
+
+/// A charge in the detection window, with what's needed to judge where it was made.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SuspectCharge {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub description: String,
+    pub merchant: String,
+    pub card: String,
+    pub amount: f64,
+    pub currency: String,
+    pub original_currency: Option<String>,
+    pub card_currency: String,
+    pub raw_data: Option<serde_json::Value>,
+}
+
+/// Stable id for a finding: its kind and the transactions involved, in any order.
+pub fn fingerprint(kind: &str, ids: &[Uuid]) -> String {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    let mut hasher = Sha256::new();
+    hasher.update(kind.as_bytes());
+    for id in ids {
+        hasher.update(id.as_bytes());
+    }
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+fn finding(
+    kind: &str,
+    severity: &str,
+    amount: f64,
+    charges: &[&SuspectCharge],
+    message: String,
+) -> SuspiciousFinding {
+    let ids: Vec<Uuid> = charges.iter().map(|c| c.id).collect();
+    SuspiciousFinding {
+        id: fingerprint(kind, &ids),
+        kind: kind.to_string(),
+        severity: severity.to_string(),
+        date: charges.iter().map(|c| c.date).max().unwrap_or_default(),
+        merchant: charges
+            .first()
+            .map(|c| c.merchant.clone())
+            .unwrap_or_default(),
+        amount,
+        transactions: charges
+            .iter()
+            .map(|c| SuspectTransaction {
+                id: c.id,
+                date: c.date,
+                description: c.description.clone(),
+                card: c.card.clone(),
+                amount: c.amount,
+            })
+            .collect(),
+        message,
+        dismissed: false,
+    }
+}
+
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// The same amount at the same merchant more than once within `duplicate_window_days`,
+/// on one card or several. Each run of repeats is one finding.
+pub fn duplicate_charges(charges: &[SuspectCharge], t: &Thresholds) -> Vec<SuspiciousFinding> {
+    let mut groups: BTreeMap<(&str, i64), Vec<&SuspectCharge>> = BTreeMap::new();
+    for c in charges
+        .iter()
+        .filter(|c| c.amount >= t.duplicate_min_amount)
+    {
+        groups
+            .entry((&c.merchant, cents(c.amount)))
+            .or_default()
+            .push(c);
+    }
+
+    let mut findings = Vec::new();
+    for group in groups.values_mut() {
+        group.sort_by_key(|c| c.date);
+        let mut run: Vec<&SuspectCharge> = Vec::new();
+        for &c in group.iter() {
+            match run.last() {
+                Some(last) if (c.date - last.date).num_days() <= t.duplicate_window_days => {
+                    run.push(c)
+                }
+                _ => {
+                    flush_duplicates(&mut findings, &run, t);
+                    run = vec![c];
+                }
+            }
+        }
+        flush_duplicates(&mut findings, &run, t);
+    }
+    findings
+}
+
+fn flush_duplicates(findings: &mut Vec<SuspiciousFinding>, run: &[&SuspectCharge], t: &Thresholds) {
+    if run.len() < 2 {
+        return;
+    }
+    let mut cards: Vec<&str> = run.iter().map(|c| c.card.as_str()).collect();
+    cards.sort_unstable();
+    cards.dedup();
+    let message = format!(
+        "{} charged ${:.2} {} times within {} days on {}",
+        run[0].merchant,
+        run[0].amount,
+        run.len(),
+        t.duplicate_window_days,
+        cards.join(", ")
+    );
+    findings.push(finding(
+        "duplicate_charge",
+        "high",
+        run[0].amount,
+        run,
+        message,
+    ));
+}
+
+/// A whole multiple of $10.
+fn is_round(amount: f64) -> bool {
+    cents(amount) % 1000 == 0
+}
+
+/// Round amounts (whole multiples of $10, at least `new_merchant_min_amount`) on the first
+/// day a merchant is ever seen, once there's a month of history before it.
+pub fn round_amounts_at_new_merchants(
+    charges: &[SuspectCharge],
+    first_seen: &HashMap<String, NaiveDate>,
+    history_start: NaiveDate,
+    t: &Thresholds,
+) -> Vec<SuspiciousFinding> {
+    let mut reported: Vec<&str> = Vec::new();
+    let mut findings = Vec::new();
+    for c in charges {
+        let is_new = first_seen.get(&c.merchant) == Some(&c.date)
+            && (c.date - history_start).num_days() >= NEW_MERCHANT_HISTORY_DAYS;
+        if !is_new
+            || !is_round(c.amount)
+            || c.amount < t.new_merchant_min_amount
+            || reported.contains(&c.merchant.as_str())
+        {
+            continue;
+        }
+        reported.push(&c.merchant);
+        let message = format!(
+            "First charge at {} is a round ${:.0} on {}",
+            c.merchant, c.amount, c.card
+        );
+        findings.push(finding(
+            "round_amount_new_merchant",
+            "low",
+            c.amount,
+            &[c],
+            message,
+        ));
+    }
+    findings
+}
+
+/// The foreign currency a charge was made in, if it differs from its card's: from the
+/// stored currencies, or a foreign-spend note anywhere in the raw statement row.
+pub fn foreign_currency(c: &SuspectCharge) -> Option<String> {
+    let differs = |currency: &str| !currency.eq_ignore_ascii_case(&c.card_currency);
+    if let Some(original) = c.original_currency.as_deref().filter(|o| differs(o)) {
+        return Some(original.to_uppercase());
+    }
+    if differs(&c.currency) {
+        return Some(c.currency.to_uppercase());
+    }
+    let fields: Vec<String> = match &c.raw_data {
+        Some(serde_json::Value::Object(map)) => map
+            .values()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    fx::extract_foreign_amount(&fields)
+        .map(|(_, currency)| currency)
+        .filter(|currency| differs(currency))
+}
+
+/// Foreign-currency charges on cards held in the home currency.
+pub fn foreign_on_domestic(charges: &[SuspectCharge], home: &str) -> Vec<SuspiciousFinding> {
+    charges
+        .iter()
+        .filter(|c| c.card_currency.eq_ignore_ascii_case(home))
+        .filter_map(|c| {
+            let currency = foreign_currency(c)?;
+            let message = format!(
+                "{} was charged in {} on {}, a {} card",
+                c.merchant, currency, c.card, home
+            );
+            Some(finding(
+                "foreign_on_domestic",
+                "medium",
+                c.amount,
+                &[c],
+                message,
+            ))
+        })
+        .collect()
+}
+
+/// A charge of at most `test_charge_max` followed within `test_charge_window_days` by one
+/// of at least `new_merchant_min_amount` at the same merchant on the same card.
+pub fn test_charges(charges: &[SuspectCharge], t: &Thresholds) -> Vec<SuspiciousFinding> {
+    let mut groups: BTreeMap<(&str, &str), Vec<&SuspectCharge>> = BTreeMap::new();
+    for c in charges {
+        groups.entry((&c.card, &c.merchant)).or_default().push(c);
+    }
+
+    let mut findings = Vec::new();
+    for group in groups.values_mut() {
+        group.sort_by_key(|c| c.date);
+        for (i, small) in group.iter().enumerate() {
+            if small.amount <= 0.0 || small.amount > t.test_charge_max {
+                continue;
+            }
+            let large = group[i + 1..].iter().find(|c| {
+                let days = (c.date - small.date).num_days();
+                days <= t.test_charge_window_days && c.amount >= t.new_merchant_min_amount
+            });
+            if let Some(large) = large {
+                let message = format!(
+                    "${:.2} at {} on {} was followed by ${:.2} {} days later",
+                    small.amount,
+                    small.merchant,
+                    small.card,
+                    large.amount,
+                    (large.date - small.date).num_days()
+                );
+                findings.push(finding(
+                    "test_charge",
+                    "high",
+                    large.amount,
+                    &[small, large],
+                    message,
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// Every detector over the window's charges, newest first.
+pub fn detect(
+    charges: &[SuspectCharge],
+    first_seen: &HashMap<String, NaiveDate>,
+    history_start: NaiveDate,
+    home: &str,
+    t: &Thresholds,
+) -> Vec<SuspiciousFinding> {
+    let mut findings = duplicate_charges(charges, t);
+    findings.extend(round_amounts_at_new_merchants(
+        charges,
+        first_seen,
+        history_start,
+        t,
+    ));
+    findings.extend(foreign_on_domestic(charges, home));
+    findings.extend(test_charges(charges, t));
+    findings.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.kind.cmp(&b.kind)));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn charge(merchant: &str, card: &str, amount: f64, date: NaiveDate) -> SuspectCharge {
+        SuspectCharge {
+            id: Uuid::new_v4(),
+            date,
+            description: merchant.to_string(),
+            merchant: merchant.to_string(),
+            card: card.to_string(),
+            amount,
+            currency: "USD".to_string(),
+            original_currency: None,
+            card_currency: "USD".to_string(),
+            raw_data: None,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(
+            fingerprint("duplicate_charge", &[a, b]),
+            fingerprint("duplicate_charge", &[b, a])
+        );
+        assert_ne!(
+            fingerprint("duplicate_charge", &[a, b]),
+            fingerprint("test_charge", &[a, b])
+        );
+        assert_eq!(fingerprint("test_charge", &[a]).len(), 16);
+    }
+
+    #[test]
+    fn test_duplicate_charges_across_cards() {
+        let t = Thresholds::default();
+        let charges = vec![
+            charge("BEST BUY", "amex", 129.99, d(2026, 2, 1)),
+            charge("BEST BUY", "citi", 129.99, d(2026, 2, 3)),
+            // Too far from the pair, and a different amount
+            charge("BEST BUY", "amex", 129.99, d(2026, 2, 20)),
+            charge("BEST BUY", "amex", 19.99, d(2026, 2, 2)),
+            // Under duplicate_min_amount
+            charge("STARBUCKS", "amex", 5.75, d(2026, 2, 1)),
+            charge("STARBUCKS", "amex", 5.75, d(2026, 2, 1)),
+        ];
+        let found = duplicate_charges(&charges, &t);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, "duplicate_charge");
+        assert_eq!(found[0].transactions.len(), 2);
+        assert_eq!(found[0].date, d(2026, 2, 3));
+        assert!(found[0].message.contains("amex, citi"));
+    }
+
+    #[test]
+    fn test_round_amounts_only_at_new_merchants_with_history() {
+        let t = Thresholds::default();
+        let start = d(2025, 10, 1);
+        let charges = vec![
+            charge("GIFT SHOP", "amex", 200.0, d(2026, 2, 5)),
+            charge("TARGET", "amex", 200.0, d(2026, 2, 5)),
+            charge("ODD SHOP", "amex", 203.5, d(2026, 2, 6)),
+            charge("EARLY SHOP", "amex", 100.0, d(2025, 10, 10)),
+        ];
+        let first_seen: HashMap<String, NaiveDate> = [
+            ("GIFT SHOP", d(2026, 2, 5)),
+            ("TARGET", d(2025, 11, 1)),
+            ("ODD SHOP", d(2026, 2, 6)),
+            ("EARLY SHOP", d(2025, 10, 10)),
+        ]
+        .into_iter()
+        .map(|(m, date)| (m.to_string(), date))
+        .collect();
+
+        let found = round_amounts_at_new_merchants(&charges, &first_seen, start, &t);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].merchant, "GIFT SHOP");
+        assert_eq!(found[0].severity, "low");
+    }
+
+    #[test]
+    fn test_foreign_on_domestic_cards() {
+        let mut eur = charge("CAFE DE FLORE", "amex", 42.0, d(2026, 2, 5));
+        eur.original_currency = Some("EUR".to_string());
+        let mut noted = charge("HOTEL LONDON", "amex", 300.0, d(2026, 2, 6));
+        noted.raw_data = Some(serde_json::json!({
+            "Extended Details": "Foreign Spend Amount: 236.00 British Pound"
+        }));
+        // A euro card abroad is expected
+        let mut euro_card = charge("BOULANGERIE", "revolut", 8.0, d(2026, 2, 6));
+        euro_card.currency = "EUR".to_string();
+        euro_card.card_currency = "EUR".to_string();
+        let domestic = charge("TARGET", "amex", 50.0, d(2026, 2, 6));
+
+        let found = foreign_on_domestic(&[eur, noted, euro_card, domestic], "USD");
+        assert_eq!(found.len(), 2);
+        assert!(found[0].message.contains("EUR"));
+        assert!(found[1].message.contains("GBP"));
+    }
+
+    #[test]
+    fn test_small_charge_followed_by_large() {
+        let t = Thresholds::default();
+        let charges = vec![
+            charge("SKETCHY STORE", "citi", 1.0, d(2026, 2, 1)),
+            charge("SKETCHY STORE", "citi", 480.0, d(2026, 2, 2)),
+            // Large charge on another card, or too late
+            charge("OTHER STORE", "citi", 0.5, d(2026, 2, 1)),
+            charge("OTHER STORE", "amex", 480.0, d(2026, 2, 2)),
+            charge("OTHER STORE", "citi", 480.0, d(2026, 2, 10)),
+        ];
+        let found = test_charges(&charges, &t);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].merchant, "SKETCHY STORE");
+        assert_eq!(found[0].amount, 480.0);
+        assert_eq!(found[0].transactions.len(), 2);
+    }
+}
@@ -0,0 +1,149 @@
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::transfer::TransferCandidate;
+
+/// Bank transfers usually post on both sides within a few business days.
+pub const DEFAULT_WINDOW_DAYS: i64 = 5;
+
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Pair money-out rows with money-in rows of the same amount and currency on a different
+/// account within `window_days`. Closest dates are paired first; each row is used once and
+/// pairs in `rejected` (debit, credit) are never proposed again.
+pub fn match_transfers(
+    candidates: &[TransferCandidate],
+    window_days: i64,
+    rejected: &HashSet<(Uuid, Uuid)>,
+) -> Vec<(Uuid, Uuid)> {
+    let mut edges: Vec<(i64, &TransferCandidate, &TransferCandidate)> = Vec::new();
+    for debit in candidates.iter().filter(|c| c.amount > 0.0) {
+        for credit in candidates.iter().filter(|c| c.amount < 0.0) {
+            if debit.card == credit.card
+                || debit.currency != credit.currency
+                || cents(debit.amount) != -cents(credit.amount)
+                || rejected.contains(&(debit.id, credit.id))
+            {
+                continue;
+            }
+            let gap = (credit.date - debit.date).num_days().abs();
+            if gap <= window_days {
+                edges.push((gap, debit, credit));
+            }
+        }
+    }
+    edges.sort_by_key(|(gap, debit, credit)| (*gap, debit.date, credit.date));
+
+    let mut used = HashSet::new();
+    let mut pairs = Vec::new();
+    for (_, debit, credit) in edges {
+        if used.contains(&debit.id) || used.contains(&credit.id) {
+            continue;
+        }
+        used.insert(debit.id);
+        used.insert(credit.id);
+        pairs.push((debit.id, credit.id));
+    }
+    pairs
+}
+
+/// Find unpaired transactions that look like transfers and store them as suggested pairs.
+/// Returns the number of new pairs.
+pub async fn suggest_transfers(pool: &PgPool, window_days: i64) -> Result<usize, sqlx::Error> {
+    let rows: Vec<(Uuid, String, chrono::NaiveDate, f64, String)> = sqlx::query_as(
+        "SELECT t.id, t.card, t.date, t.amount::float8, t.currency FROM transactions t \
+         WHERE NOT EXISTS ( \
+             SELECT 1 FROM transfer_pairs p \
+             WHERE p.status <> 'unlinked' AND (p.debit_id = t.id OR p.credit_id = t.id)) \
+           AND EXISTS ( \
+             SELECT 1 FROM transactions o \
+             WHERE o.amount = -t.amount AND o.card <> t.card AND o.currency = t.currency \
+               AND abs(o.date - t.date) <= $1)",
+    )
+    .bind(window_days as i32)
+    .fetch_all(pool)
+    .await?;
+
+    let candidates: Vec<TransferCandidate> = rows
+        .into_iter()
+        .map(|(id, card, date, amount, currency)| TransferCandidate {
+            id,
+            card,
+            date,
+            amount,
+            currency,
+        })
+        .collect();
+
+    let rejected: HashSet<(Uuid, Uuid)> =
+        sqlx::query_as("SELECT debit_id, credit_id FROM transfer_pairs WHERE status = 'unlinked'")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut created = 0;
+    for (debit_id, credit_id) in match_transfers(&candidates, window_days, &rejected) {
+        let result = sqlx::query(
+            "INSERT INTO transfer_pairs (debit_id, credit_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(debit_id)
+        .bind(credit_id)
+        .execute(pool)
+        .await?;
+        created += result.rows_affected() as usize;
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn candidate(card: &str, day: u32, amount: f64) -> TransferCandidate {
+        TransferCandidate {
+            id: Uuid::new_v4(),
+            card: card.to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, day).unwrap(),
+            amount,
+            currency: "USD".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pairs_opposite_amounts_across_accounts() {
+        let debit = candidate("checking", 1, 500.0);
+        let credit = candidate("citi", 3, -500.0);
+        let pairs = match_transfers(&[debit.clone(), credit.clone()], 5, &HashSet::new());
+        assert_eq!(pairs, vec![(debit.id, credit.id)]);
+    }
+
+    #[test]
+    fn test_ignores_same_account_and_outside_window() {
+        let debit = candidate("checking", 1, 500.0);
+        let refund = candidate("checking", 2, -500.0);
+        let late = candidate("citi", 20, -500.0);
+        assert!(match_transfers(&[debit, refund, late], 5, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_closest_date_wins_and_rows_used_once() {
+        let debit = candidate("checking", 10, 250.0);
+        let far = candidate("citi", 6, -250.0);
+        let near = candidate("amex", 11, -250.0);
+        let pairs = match_transfers(&[debit.clone(), far, near.clone()], 5, &HashSet::new());
+        assert_eq!(pairs, vec![(debit.id, near.id)]);
+    }
+
+    #[test]
+    fn test_rejected_pairs_not_suggested_again() {
+        let debit = candidate("checking", 1, 75.0);
+        let credit = candidate("citi", 2, -75.0);
+        let rejected = HashSet::from([(debit.id, credit.id)]);
+        assert!(match_transfers(&[debit, credit], 5, &rejected).is_empty());
+    }
+}
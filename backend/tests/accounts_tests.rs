@@ -0,0 +1,312 @@
+mod common;
+
+use common::*;
+
+async fn create_checking(app: &axum::Router) -> String {
+    let (_, json) = post_json(
+        app,
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_checking",
+            "label": "Test Checking",
+            "color": "#10B981",
+            "account_type": "checking"
+        }),
+    )
+    .await;
+    assert_eq!(json["data"]["account_type"].as_str().unwrap(), "checking");
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_card_rejects_unknown_account_type() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = post_json(
+        &app,
+        "/api/cards",
+        serde_json::json!({ "code": "test_bad", "label": "Bad", "color": "#000", "account_type": "brokerage" }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_balance_history_and_assertions() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool.clone());
+    let id = create_checking(&app).await;
+
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash) VALUES
+        ('2026-01-05', 'RENT', 1500.00, 'Housing', 'test_checking', 'Test Checking', 'acct_hash_001'),
+        ('2026-01-20', 'UTILITIES', 120.00, 'Utilities', 'test_checking', 'Test Checking', 'acct_hash_002')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let balances = format!("/api/accounts/{}/balances", id);
+    post_json(&app, &balances, serde_json::json!({ "kind": "opening", "date": "2026-01-01", "balance": 5000.0 })).await;
+    post_json(&app, &balances, serde_json::json!({ "kind": "assertion", "date": "2026-01-10", "balance": 3500.0 })).await;
+    post_json(&app, &balances, serde_json::json!({ "kind": "assertion", "date": "2026-01-31", "balance": 3300.0 })).await;
+
+    let (status, json) = get_json(&app, &format!("/api/accounts/{}/balance-history", id)).await;
+    assert_eq!(status, 200);
+
+    let data = &json["data"];
+    assert_eq!(data["opening_balance"].as_f64().unwrap(), 5000.0);
+    let history = data["history"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1]["balance"].as_f64().unwrap(), 3380.0);
+
+    // The second assertion is off by $80 — a missing import
+    let assertions = data["assertions"].as_array().unwrap();
+    assert!(assertions[0]["passed"].as_bool().unwrap());
+    assert!(!assertions[1]["passed"].as_bool().unwrap());
+    assert_eq!(assertions[1]["difference"].as_f64().unwrap(), 80.0);
+    assert_eq!(data["account"]["failed_assertions"].as_u64().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_balance_record_validates_kind() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    let id = create_checking(&app).await;
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/accounts/{}/balances", id),
+        serde_json::json!({ "kind": "closing", "date": "2026-01-01", "balance": 1.0 }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_net_worth_over_time() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    let id = create_checking(&app).await;
+
+    post_json(
+        &app,
+        &format!("/api/accounts/{}/balances", id),
+        serde_json::json!({ "kind": "opening", "date": "2020-01-01", "balance": 2500.0 }),
+    )
+    .await;
+
+    let (status, json) = get_json(&app, "/api/accounts/net-worth?months=3").await;
+    assert_eq!(status, 200);
+
+    let points = json["data"]["points"].as_array().unwrap();
+    assert_eq!(points.len(), 3);
+    let last = points.last().unwrap();
+    assert_eq!(last["assets"].as_f64().unwrap(), 2500.0);
+    assert_eq!(last["by_account"]["test_checking"].as_f64().unwrap(), 2500.0);
+    assert!(last["net_worth"].is_number());
+}
+
+#[tokio::test]
+async fn test_accounts_without_a_rate_stay_out_of_net_worth() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool.clone());
+    let id = create_checking(&app).await;
+    let (_, json) = post_json(
+        &app,
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_euro", "label": "Euro Savings", "color": "#000000",
+            "account_type": "savings", "currency": "EUR"
+        }),
+    )
+    .await;
+    let euro = json["data"]["id"].as_str().unwrap().to_string();
+    for account in [&id, &euro] {
+        post_json(
+            &app,
+            &format!("/api/accounts/{account}/balances"),
+            serde_json::json!({ "kind": "opening", "date": "2020-01-01", "balance": 1000.0 }),
+        )
+        .await;
+    }
+
+    // A pound charge on the dollar account has no rate either
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, currency) \
+         VALUES (CURRENT_DATE, 'PRET A MANGER', 50.00, 'Dining', 'test_checking', 'Test Checking', 'fx_acct_001', 'GBP')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (_, json) = get_json(&app, "/api/accounts/net-worth?months=1").await;
+    let point = &json["data"]["points"][0];
+    assert_eq!(point["assets"].as_f64().unwrap(), 1000.0);
+    assert!(point["by_account"]["test_euro"].is_null());
+    assert_eq!(point["unconverted_accounts"], serde_json::json!(["test_euro"]));
+
+    let (_, json) = get_json(&app, &format!("/api/accounts/{id}/balance-history")).await;
+    assert_eq!(json["data"]["account"]["balance"].as_f64().unwrap(), 1000.0);
+    assert_eq!(json["data"]["account"]["unconverted_count"], 1);
+
+    // With rates, both convert: 1000 EUR @ 1.10, and the charge at 1.25 USD per GBP
+    sqlx::query(
+        "INSERT INTO fx_rates (date, base, quote, rate, source) VALUES \
+         ('2020-01-01', 'EUR', 'USD', 1.10, 'manual'), ('2020-01-01', 'GBP', 'USD', 1.25, 'manual')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let (_, json) = get_json(&app, "/api/accounts/net-worth?months=1").await;
+    let point = &json["data"]["points"][0];
+    assert!((point["assets"].as_f64().unwrap() - 2037.5).abs() < 0.01, "{point}");
+    assert!(point["unconverted_accounts"].as_array().unwrap().is_empty());
+}
+
+async fn create_bank_checking(app: &axum::Router) -> String {
+    let (_, json) = post_json(
+        app,
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_bank",
+            "label": "Test Bank",
+            "color": "#10B981",
+            "account_type": "checking",
+            "date_column": "Date",
+            "date_format": "MM/DD/YYYY",
+            "description_column": "Description",
+            "amount_column": "Amount",
+            "amount_sign": "debit_negative",
+            "import_credits": true
+        }),
+    )
+    .await;
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_import_reconciles_parsed_statement_summary() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    let id = create_bank_checking(&app).await;
+
+    let csv = "Description,,Summary Amt.\n\
+        Beginning balance as of 01/01/2026,,\"1,000.00\"\n\
+        Ending balance as of 01/31/2026,,\"3,300.00\"\n\
+        \n\
+        Date,Description,Amount\n\
+        01/02/2026,ACME PAYROLL,2500.00\n\
+        01/10/2026,GROCERY OUTLET,-200.00\n";
+    let (status, json) =
+        post_file(&app, "/api/transactions/import", "stmt.csv", csv, &[("card_code", "test_bank")]).await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["new_count"].as_i64().unwrap(), 2);
+
+    let rec = &json["data"]["reconciliation"];
+    assert_eq!(rec["status"].as_str().unwrap(), "balanced");
+    assert_eq!(rec["expected_delta"].as_f64().unwrap(), 2300.0);
+    assert_eq!(rec["computed_delta"].as_f64().unwrap(), 2300.0);
+    assert_eq!(rec["period_start"].as_str().unwrap(), "2026-01-01");
+
+    let (_, json) = get_json(&app, "/api/import-history").await;
+    assert_eq!(json["data"][0]["closing_balance"].as_f64().unwrap(), 3300.0);
+    assert_eq!(json["data"][0]["reconciliation"]["status"].as_str().unwrap(), "balanced");
+
+    let (_, json) = get_json(&app, &format!("/api/accounts/{}/reconciliation", id)).await;
+    assert_eq!(json["data"]["balanced"].as_u64().unwrap(), 1);
+    assert_eq!(json["data"]["statements"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_import_reconciliation_flags_missing_rows() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    let id = create_bank_checking(&app).await;
+
+    // The statement moved by -250 but the file only has -200 of spending
+    let csv = "Date,Description,Amount\n01/10/2026,GROCERY OUTLET,-200.00\n";
+    let (_, json) = post_file(
+        &app,
+        "/api/transactions/import",
+        "stmt.csv",
+        csv,
+        &[
+            ("card_code", "test_bank"),
+            ("statement_start", "2026-01-01"),
+            ("statement_end", "2026-01-31"),
+            ("opening_balance", "1000.00"),
+            ("closing_balance", "750.00"),
+        ],
+    )
+    .await;
+    let rec = &json["data"]["reconciliation"];
+    assert_eq!(rec["status"].as_str().unwrap(), "unbalanced");
+    assert_eq!(rec["difference"].as_f64().unwrap(), 50.0);
+
+    // A second statement leaves a gap in February
+    let csv = "Date,Description,Amount\n03/05/2026,GROCERY OUTLET,-80.00\n";
+    post_file(
+        &app,
+        "/api/transactions/import",
+        "march.csv",
+        csv,
+        &[("card_code", "test_bank"), ("statement_start", "2026-03-01"), ("statement_end", "2026-03-31")],
+    )
+    .await;
+
+    let (_, json) = get_json(&app, &format!("/api/accounts/{}/reconciliation", id)).await;
+    let data = &json["data"];
+    assert_eq!(data["unbalanced"].as_u64().unwrap(), 1);
+    assert_eq!(data["unverified"].as_u64().unwrap(), 1);
+    assert_eq!(data["gaps"][0]["start"].as_str().unwrap(), "2026-02-01");
+    assert_eq!(data["gaps"][0]["days"].as_i64().unwrap(), 28);
+}
+
+#[tokio::test]
+async fn test_import_reconciliation_compares_with_stored_rows() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    create_bank_checking(&app).await;
+    let balances = [
+        ("card_code", "test_bank"),
+        ("statement_start", "2026-01-01"),
+        ("statement_end", "2026-01-31"),
+        ("opening_balance", "1000.00"),
+        ("closing_balance", "800.00"),
+    ];
+
+    // The ledger includes the import's own rows, so a complete file balances
+    let csv = "Date,Description,Amount\n01/10/2026,GROCERY OUTLET,-200.00\n";
+    let (_, json) = post_file(&app, "/api/transactions/import", "stmt.csv", csv, &balances).await;
+    let rec = &json["data"]["reconciliation"];
+    assert_eq!(rec["status"], "balanced");
+    assert!(rec["unmatched_days"].as_array().unwrap().is_empty());
+
+    // Re-importing it skips the stored rows as duplicates and still balances
+    let (_, json) = post_file(&app, "/api/transactions/import", "stmt.csv", csv, &balances).await;
+    assert_eq!(json["data"]["duplicate_count"], 1);
+    assert_eq!(json["data"]["reconciliation"]["status"], "balanced");
+
+    // A stored row the statement doesn't list, here from a differently worded export,
+    // shows up as an unmatched day and throws the movement off
+    let other = "Date,Description,Amount\n01/10/2026,GROCERY OUTLET #12,-200.00\n";
+    post_file(&app, "/api/transactions/import", "other.csv", other, &[("card_code", "test_bank")]).await;
+    let (_, json) = post_file(&app, "/api/transactions/import", "stmt.csv", csv, &balances).await;
+    let rec = &json["data"]["reconciliation"];
+    assert_eq!(rec["status"], "unbalanced");
+    assert_eq!(rec["difference"].as_f64().unwrap(), -200.0);
+    let day = &rec["unmatched_days"][0];
+    assert_eq!(day["date"], "2026-01-10");
+    assert_eq!(day["statement_total"].as_f64().unwrap(), 200.0);
+    assert_eq!(day["ledger_total"].as_f64().unwrap(), 400.0);
+}
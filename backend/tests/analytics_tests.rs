@@ -29,6 +29,85 @@ async fn test_recurring_detection() {
     }
 }
 
+#[tokio::test]
+async fn test_recurring_periodicity_and_prediction() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    // A weekly charge alongside the monthly Netflix series
+    for (i, day) in ["2026-01-06", "2026-01-13", "2026-01-20", "2026-01-27", "2026-02-03", "2026-02-10"]
+        .iter()
+        .enumerate()
+    {
+        sqlx::query(
+            "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized) \
+             VALUES ($1::date, 'CLASSPASS', 12.00, 'Fitness', 'amex', 'Amex Gold', $2, 'CLASSPASS')",
+        )
+        .bind(day)
+        .bind(format!("test_weekly_{i}"))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let app = app(pool);
+
+    let (status, json) = get_json(&app, "/api/stats/recurring?end_date=2026-02-12").await;
+    assert_eq!(status, 200);
+    let recurring = json["data"]["recurring"].as_array().unwrap();
+
+    let netflix = recurring.iter().find(|r| r["merchant"] == "NETFLIX.COM").unwrap();
+    assert_eq!(netflix["frequency"], "monthly");
+    assert_eq!(netflix["status"], "active");
+    assert_eq!(netflix["next_expected_date"], "2026-03-12");
+    assert_eq!(netflix["next_expected_amount"], 15.99);
+
+    let weekly = recurring.iter().find(|r| r["merchant"] == "CLASSPASS").unwrap();
+    assert_eq!(weekly["frequency"], "weekly");
+    assert_eq!(weekly["next_expected_date"], "2026-02-17");
+    assert_eq!(weekly["charge_count"], 6);
+
+    let (_, json) = get_json(&app, "/api/stats/habits?end_date=2026-02-12").await;
+    let bloat = &json["data"]["subscription_bloat"];
+    let next = bloat["next_charges"].as_array().unwrap();
+    assert_eq!(next[0]["merchant"], "CLASSPASS");
+    assert_eq!(next[0]["date"], "2026-02-17");
+    assert!(next.iter().any(|c| c["merchant"] == "NETFLIX.COM" && c["date"] == "2026-03-12"));
+    assert!(bloat["next_30_days"].as_f64().unwrap() >= 15.99 + 4.0 * 12.0);
+}
+
+#[tokio::test]
+async fn test_upcoming_charges_with_month_end_impact() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (status, json) = get_json(&app, "/api/stats/upcoming?end_date=2026-02-10&days=45").await;
+    assert_eq!(status, 200);
+    let data = &json["data"];
+    assert_eq!(data["from"], "2026-02-10");
+    assert_eq!(data["to"], "2026-03-27");
+    assert_eq!(data["month_end"], "2026-02-28");
+
+    let netflix: Vec<&serde_json::Value> = data["charges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["merchant"] == "NETFLIX.COM")
+        .collect();
+    assert_eq!(netflix.len(), 2);
+    assert_eq!(netflix[0]["date"], "2026-02-15");
+    assert_eq!(netflix[0]["card"], "citi");
+    assert_eq!(netflix[0]["amount"], 15.99);
+    assert!(netflix[0]["confidence"].as_f64().unwrap() > 0.5);
+    assert_eq!(netflix[1]["date"], "2026-03-15");
+
+    let citi = data["by_card"].as_array().unwrap().iter().find(|c| c["card"] == "citi").unwrap();
+    assert!(citi["due_by_month_end"].as_f64().unwrap() >= 15.99);
+    assert_eq!(citi["balance_change_by_month_end"], citi["due_by_month_end"]);
+    assert!(data["due_by_month_end"].as_f64().unwrap() < data["total"].as_f64().unwrap());
+}
+
 #[tokio::test]
 async fn test_anomalies_returns_valid_shape() {
     let pool = test_pool().await;
@@ -54,6 +133,143 @@ async fn test_anomalies_returns_valid_shape() {
     }
 }
 
+#[tokio::test]
+async fn test_anomalies_use_completed_months_and_merchant_history() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    for (i, (description, amount, category)) in
+        [("IKEA", 220.0, "Shopping"), ("CHEVRON GAS", 95.0, "Gas")].iter().enumerate()
+    {
+        sqlx::query(
+            "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized) \
+             VALUES ('2026-02-14', $1, $2, $3, 'citi', 'Citi Costco', $4, $1)",
+        )
+        .bind(description)
+        .bind(amount)
+        .bind(category)
+        .bind(format!("test_anomaly_{i}"))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let app = app(pool);
+
+    let (status, json) = get_json(&app, "/api/stats/anomalies?end_date=2026-02-20").await;
+    assert_eq!(status, 200);
+    let data = &json["data"];
+
+    let merchants = data["merchant_anomalies"].as_array().unwrap();
+    let ikea = merchants.iter().find(|a| a["merchant"] == "IKEA").unwrap();
+    assert_eq!(ikea["kind"], "first_time");
+    assert_eq!(ikea["prior_charges"], 0);
+    let chevron = merchants.iter().find(|a| a["merchant"] == "CHEVRON GAS").unwrap();
+    assert_eq!(chevron["kind"], "unusual_amount");
+    assert_eq!(chevron["merchant_median"], 45.0);
+    assert!(chevron["z_score"].as_f64().unwrap() > 3.0);
+    // The small first Uber ride isn't worth reporting
+    assert!(!merchants.iter().any(|a| a["merchant"] == "UBER"));
+
+    // Shopping is measured against what it usually has by the 20th of completed months
+    let shopping = data["category_anomalies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["category"] == "Shopping")
+        .unwrap();
+    assert_eq!(shopping["current_month"], 570.0);
+    assert!(shopping["expected_to_date"].as_f64().unwrap() < 100.0);
+    assert!(shopping["projected"].as_f64().unwrap() >= 570.0);
+    assert_eq!(shopping["severity"], "critical");
+}
+
+#[tokio::test]
+async fn test_forecast_horizon_with_recurring_charges() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (status, json) = get_json(&app, "/api/stats/forecast/horizon?end_date=2026-02-20&months=3").await;
+    assert_eq!(status, 200);
+    let data = &json["data"];
+    let months = data["months"].as_array().unwrap();
+    let keys: Vec<&str> = months.iter().map(|m| m["month"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["2026-03", "2026-04", "2026-05"]);
+
+    for m in months {
+        let total = m["total"].as_f64().unwrap();
+        assert!(m["lower"].as_f64().unwrap() <= total && total <= m["upper"].as_f64().unwrap());
+        // Netflix is a known monthly charge
+        let subs = m["categories"].as_array().unwrap().iter().find(|c| c["category"] == "Subscriptions").unwrap();
+        assert!(subs["recurring"].as_f64().unwrap() >= 15.99);
+        let sum: f64 = m["categories"].as_array().unwrap().iter().map(|c| c["projected"].as_f64().unwrap()).sum();
+        assert!((sum - total).abs() < 1e-6);
+    }
+    let total: f64 = months.iter().map(|m| m["total"].as_f64().unwrap()).sum();
+    assert!((data["total"].as_f64().unwrap() - total).abs() < 1e-6);
+    assert_eq!(data["confidence"].as_f64().unwrap(), 0.8);
+
+    // Out-of-range month counts are clamped
+    let (_, json) = get_json(&app, "/api/stats/forecast/horizon?end_date=2026-02-20&months=40").await;
+    assert_eq!(json["data"]["months"].as_array().unwrap().len(), 12);
+}
+
+#[tokio::test]
+async fn test_forecast_scenario_differences() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+    let url = "/api/stats/forecast/scenario?end_date=2026-02-20&months=3";
+
+    let (status, json) = post_json(
+        &app,
+        url,
+        serde_json::json!({ "adjustments": [
+            { "type": "cancel_subscription", "merchant": "netflix.com" },
+            { "type": "add_expense", "amount": 400, "description": "Car payment" },
+            { "type": "category_pct", "category": "Dining", "pct": -20 }
+        ]}),
+    )
+    .await;
+    assert_eq!(status, 200);
+    let data = &json["data"];
+    let impacts = data["adjustments"].as_array().unwrap();
+    assert_eq!(impacts.len(), 3);
+    assert_eq!(impacts[0]["description"], "Cancel netflix.com");
+    assert!((impacts[0]["difference"].as_f64().unwrap() + 3.0 * 15.99).abs() < 1e-6);
+    assert_eq!(impacts[1]["description"], "Car payment");
+    assert!((impacts[1]["difference"].as_f64().unwrap() - 1200.0).abs() < 1e-6);
+    assert!(impacts[2]["difference"].as_f64().unwrap() < 0.0);
+
+    let difference = data["difference"].as_f64().unwrap();
+    let sum: f64 = impacts.iter().map(|i| i["difference"].as_f64().unwrap()).sum();
+    assert!((difference - sum).abs() < 1e-6);
+    assert!((data["scenario_total"].as_f64().unwrap() - data["baseline_total"].as_f64().unwrap() - difference).abs() < 1e-6);
+    assert!((data["avg_monthly_difference"].as_f64().unwrap() - difference / 3.0).abs() < 1e-6);
+    for m in data["months"].as_array().unwrap() {
+        let d = m["scenario"].as_f64().unwrap() - m["baseline"].as_f64().unwrap();
+        assert!((m["difference"].as_f64().unwrap() - d).abs() < 1e-6);
+    }
+
+    for (body, error) in [
+        (serde_json::json!({ "type": "freeze" }), "Invalid adjustment type"),
+        (serde_json::json!({ "type": "category_pct", "category": "Dining", "pct": -150 }), "pct"),
+        (serde_json::json!({ "type": "category_pct", "category": "Yachts", "pct": -10 }), "Yachts"),
+        (serde_json::json!({ "type": "cancel_subscription", "merchant": "NO SUCH SHOP" }), "No upcoming"),
+        (
+            serde_json::json!({ "type": "cancel_subscription", "subscription_id": "00000000-0000-0000-0000-000000000000" }),
+            "Subscription not found",
+        ),
+        (serde_json::json!({ "type": "add_expense" }), "amount"),
+    ] {
+        let (_, json) = post_json(&app, url, serde_json::json!({ "adjustments": [body] })).await;
+        let message = json["error"].as_str().unwrap_or_default();
+        assert!(message.contains(error), "{body}: {json}");
+    }
+}
+
 #[tokio::test]
 async fn test_forecast_projections() {
     let pool = test_pool().await;
@@ -95,6 +311,49 @@ async fn test_forecast_projections() {
     }
 }
 
+#[tokio::test]
+async fn test_forecast_backtest_weights_and_interval() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    // Mid-February 2026: October through January are completed history
+    let (status, json) = get_json(&app, "/api/stats/forecast?end_date=2026-02-10").await;
+    assert_eq!(status, 200);
+    let p = &json["data"]["projections"];
+
+    let accuracy = p["accuracy"].as_array().unwrap();
+    let methods: Vec<&str> = accuracy.iter().map(|a| a["method"].as_str().unwrap()).collect();
+    assert_eq!(methods, vec!["linear", "day_weighted", "ewma", "seasonal"]);
+    let weight_sum: f64 = accuracy.iter().map(|a| a["weight"].as_f64().unwrap()).sum();
+    assert!((weight_sum - 1.0).abs() < 1e-6);
+    // Backtested on November–January; less than a year of history means no seasonal index
+    assert_eq!(accuracy[0]["samples"], 3);
+    assert!(accuracy[0]["mape"].is_number());
+    assert!(accuracy[3]["mape"].is_null());
+    assert_eq!(accuracy[3]["weight"].as_f64().unwrap(), 0.0);
+    assert!(p["seasonal"].is_null());
+
+    let recommended = p["recommended"].as_f64().unwrap();
+    let lower = p["interval"]["lower"].as_f64().unwrap();
+    let upper = p["interval"]["upper"].as_f64().unwrap();
+    assert!(lower <= recommended && recommended <= upper);
+    assert!(lower >= json["data"]["current_month"]["spent_so_far"].as_f64().unwrap());
+    assert_eq!(p["interval"]["confidence"].as_f64().unwrap(), 0.8);
+
+    // Categories with history get an EWMA alongside their pace; new ones rely on pace alone
+    let categories = json["data"]["category_forecasts"].as_array().unwrap();
+    assert!(categories.iter().any(|cf| cf["ewma"].is_number()));
+    for cf in categories {
+        assert!(cf["linear"].is_number());
+        assert!(cf["projected"].as_f64().unwrap() >= cf["spent_so_far"].as_f64().unwrap());
+        if cf["ewma"].is_null() {
+            assert_eq!(cf["projected"], cf["linear"], "{cf}");
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_habits_all_detectors() {
     let pool = test_pool().await;
@@ -135,6 +394,77 @@ async fn test_habits_all_detectors() {
     assert!(conc["label"].is_string());
 }
 
+#[tokio::test]
+async fn test_analytics_settings_apply_to_habits() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool.clone());
+
+    let (status, json) = get_json(&app, "/api/analytics/settings").await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["settings"]["impulse_amount"], 15.0);
+    assert_eq!(json["data"]["defaults"]["ewma_alpha"], 0.3);
+
+    let (_, json) = put_json(
+        &app,
+        "/api/analytics/settings",
+        serde_json::json!({ "impulse_amount": 25, "inactive_after_days": 60 }),
+    )
+    .await;
+    assert_eq!(json["data"]["impulse_amount"], 25.0);
+    assert_eq!(json["data"]["anomaly_z_bands"], serde_json::json!([1.5, 2.0, 3.0]));
+
+    let (_, json) = get_json(&app, "/api/stats/habits?end_date=2026-02-28").await;
+    let message = json["data"]["impulse_spending"]["message"].as_str().unwrap();
+    assert!(message.contains("under $25"), "{message}");
+
+    // Invalid settings are rejected and leave the stored ones alone
+    let (_, json) = put_json(
+        &app,
+        "/api/analytics/settings",
+        serde_json::json!({ "anomaly_z_bands": [3, 2, 1] }),
+    )
+    .await;
+    assert!(json["error"].as_str().unwrap().contains("anomaly_z_bands"));
+    let (_, json) = put_json(&app, "/api/analytics/settings", serde_json::json!({ "bogus": 1 })).await;
+    assert!(json["error"].is_string());
+    let (_, json) = put_json(&app, "/api/config", serde_json::json!({ "analytics_settings": "{\"ewma_alpha\": 2}" })).await;
+    assert!(json["error"].is_string());
+
+    // Stored fields that are no longer settings are ignored
+    sqlx::query("UPDATE user_config SET value = $1 WHERE key = 'analytics_settings'")
+        .bind(r#"{"impulse_amount": 30, "inactive_after_days": 60, "biweekly_frequency": 1.3}"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, json) = get_json(&app, "/api/analytics/settings").await;
+    assert_eq!(json["data"]["settings"]["impulse_amount"], 30.0);
+
+    // A stored field that's out of range falls back to its default alone
+    sqlx::query("UPDATE user_config SET value = $1 WHERE key = 'analytics_settings'")
+        .bind(r#"{"impulse_amount": 30, "inactive_after_days": 30}"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, json) = get_json(&app, "/api/analytics/settings").await;
+    assert_eq!(json["data"]["settings"]["impulse_amount"], 30.0);
+    assert_eq!(
+        json["data"]["settings"]["inactive_after_days"],
+        json["data"]["defaults"]["inactive_after_days"]
+    );
+    sqlx::query("UPDATE user_config SET value = $1 WHERE key = 'analytics_settings'")
+        .bind(r#"{"impulse_amount": 30, "inactive_after_days": 60, "biweekly_frequency": 1.3}"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // null resets a field to its default
+    let (_, json) = put_json(&app, "/api/analytics/settings", serde_json::json!({ "impulse_amount": null })).await;
+    assert_eq!(json["data"]["impulse_amount"], 15.0);
+    assert_eq!(json["data"]["inactive_after_days"], 60);
+}
+
 #[tokio::test]
 async fn test_daily_spending_with_date_range() {
     let pool = test_pool().await;
@@ -231,7 +561,7 @@ async fn test_insights_returns_ranked_list() {
     seed_transactions(&pool).await;
     let app = app(pool);
 
-    let (status, json) = get_json(&app, "/api/insights").await;
+    let (status, json) = get_json(&app, "/api/stats/insights").await;
     assert_eq!(status, 200);
 
     let insights = json["data"].as_array().unwrap();
@@ -260,7 +590,7 @@ async fn test_insights_empty_db() {
     clean(&pool).await;
     let app = app(pool);
 
-    let (status, json) = get_json(&app, "/api/insights").await;
+    let (status, json) = get_json(&app, "/api/stats/insights").await;
     assert_eq!(status, 200);
     assert!(json["data"].as_array().unwrap().is_empty());
 }
@@ -0,0 +1,452 @@
+mod common;
+
+use common::*;
+
+fn by_category<'a>(json: &'a serde_json::Value, category: &str) -> &'a serde_json::Value {
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|b| b["category"] == category)
+        .unwrap_or_else(|| panic!("no progress for {category}"))
+}
+
+#[tokio::test]
+async fn test_budget_progress_across_periods() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    // Old clients still send monthly_limit
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "monthly_limit": 150.0 }),
+    )
+    .await;
+    assert_eq!(json["data"]["period"], "monthly");
+    assert_eq!(json["data"]["amount"], 150.0);
+
+    for body in [
+        serde_json::json!({ "category": "Shopping", "amount": 100.0, "period": "weekly" }),
+        serde_json::json!({ "category": "Subscriptions", "amount": 200.0, "period": "annual", "start_date": "2025-04-01" }),
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "custom", "start_date": "2026-02-01", "end_date": "2026-02-15" }),
+        serde_json::json!({ "category": "Gas", "amount": 80.0, "period": "monthly", "start_date": "2026-03-01" }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets", body).await;
+        assert!(json["error"].is_null(), "{json}");
+    }
+
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    // Gas hasn't started yet
+    assert_eq!(json["data"].as_array().unwrap().len(), 4);
+
+    let groceries = by_category(&json, "Groceries");
+    assert_eq!(groceries["period_start"], "2026-02-01");
+    assert_eq!(groceries["period_end"], "2026-02-28");
+    assert_eq!(groceries["spent"], 110.0);
+    assert_eq!(groceries["days_remaining"], 8);
+    assert_eq!(groceries["status"], "warning");
+
+    // Amazon's 350 was the week before
+    let shopping = by_category(&json, "Shopping");
+    assert_eq!(shopping["period_start"], "2026-02-16");
+    assert_eq!(shopping["period_end"], "2026-02-22");
+    assert_eq!(shopping["spent"], 0.0);
+
+    let subscriptions = by_category(&json, "Subscriptions");
+    assert_eq!(subscriptions["period_start"], "2025-04-01");
+    assert_eq!(subscriptions["period_end"], "2026-03-31");
+    assert!((subscriptions["spent"].as_f64().unwrap() - 79.95).abs() < 0.001);
+
+    let travel = by_category(&json, "Travel");
+    assert_eq!(travel["spent"], 15.5);
+    assert_eq!(travel["days_remaining"], 0);
+
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-08").await;
+    let shopping = by_category(&json, "Shopping");
+    assert_eq!(shopping["period_start"], "2026-02-02");
+    assert_eq!(shopping["spent"], 350.0);
+    assert_eq!(shopping["status"], "over_budget");
+}
+
+#[tokio::test]
+async fn test_budget_upsert_keeps_period_and_validates() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Subscriptions", "amount": 200.0, "period": "annual", "start_date": "2025-04-01" }),
+    )
+    .await;
+
+    // Changing only the amount leaves the period alone
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Subscriptions", "amount": 250.0 }),
+    )
+    .await;
+    assert_eq!(json["data"]["amount"], 250.0);
+    assert_eq!(json["data"]["period"], "annual");
+    assert_eq!(json["data"]["start_date"], "2025-04-01");
+
+    for body in [
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "custom", "start_date": "2026-02-01" }),
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "custom", "start_date": "2026-02-15", "end_date": "2026-02-01" }),
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "fortnightly" }),
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "weekly", "end_date": "2026-02-01" }),
+        serde_json::json!({ "category": "Travel", "amount": -5.0 }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets", body.clone()).await;
+        assert!(json["error"].is_string(), "{body} should be rejected");
+    }
+
+    let (_, json) = get_json(&app, "/api/budgets").await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+}
+
+fn close(value: &serde_json::Value, expected: f64) -> bool {
+    (value.as_f64().unwrap() - expected).abs() < 0.001
+}
+
+#[tokio::test]
+async fn test_budget_rollover_with_cap() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "amount": 120.0, "start_date": "2025-10-01", "period": "monthly", "rollover": true }),
+    )
+    .await;
+    assert_eq!(json["data"]["rollover"], true);
+
+    // Oct leaves 32.68; Nov overspends to -96.20; Dec -81.40; Jan -56.80
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    let groceries = by_category(&json, "Groceries");
+    assert!(close(&groceries["carried_in"], -56.8));
+    assert!(close(&groceries["available"], 63.2));
+    assert_eq!(groceries["status"], "over_budget");
+
+    // With a cap of 50, Nov is held at -50: Dec -35.20, Jan -10.60
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "amount": 120.0, "rollover": true, "rollover_cap": 50.0 }),
+    )
+    .await;
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    let groceries = by_category(&json, "Groceries");
+    assert!(close(&groceries["carried_in"], -10.6));
+    assert!(close(&groceries["available"], 109.4));
+
+    // The first period has nothing to carry
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2025-10-20").await;
+    assert_eq!(by_category(&json, "Groceries")["carried_in"], 0.0);
+
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Travel", "amount": 50.0, "period": "custom", "start_date": "2026-02-01", "end_date": "2026-02-15", "rollover": true }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_envelope_transfers() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    for (category, amount) in [("Groceries", 100.0), ("Dining", 50.0)] {
+        post_json(
+            &app,
+            "/api/budgets",
+            serde_json::json!({ "category": category, "amount": amount }),
+        )
+        .await;
+    }
+
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets/transfers",
+        serde_json::json!({ "from_category": "Dining", "to_category": "Groceries", "amount": 30.0, "date": "2026-02-15", "note": "Big shop" }),
+    )
+    .await;
+    assert_eq!(json["data"]["amount"], 30.0);
+    assert_eq!(json["data"]["note"], "Big shop");
+
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    let groceries = by_category(&json, "Groceries");
+    assert_eq!(groceries["transferred"], 30.0);
+    assert_eq!(groceries["available"], 130.0);
+    assert!(close(&groceries["pct_used"], 110.0 / 130.0 * 100.0));
+    let dining = by_category(&json, "Dining");
+    assert_eq!(dining["transferred"], -30.0);
+    assert_eq!(dining["available"], 20.0);
+
+    // Before the move it isn't counted
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-10").await;
+    assert_eq!(by_category(&json, "Groceries")["transferred"], 0.0);
+
+    // Dining has 50 - 5.25 - 30 = 14.75 left
+    for body in [
+        serde_json::json!({ "from_category": "Dining", "to_category": "Groceries", "amount": 20.0, "date": "2026-02-20" }),
+        serde_json::json!({ "from_category": "Dining", "to_category": "Shopping", "amount": 5.0, "date": "2026-02-20" }),
+        serde_json::json!({ "from_category": "Dining", "to_category": "Dining", "amount": 5.0 }),
+        serde_json::json!({ "from_category": "Dining", "to_category": "Groceries", "amount": 0.0 }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets/transfers", body.clone()).await;
+        assert!(json["error"].is_string(), "{body} should be rejected");
+    }
+
+    // Moving it back is another entry in the trail
+    post_json(
+        &app,
+        "/api/budgets/transfers",
+        serde_json::json!({ "from_category": "Groceries", "to_category": "Dining", "amount": 10.0, "date": "2026-02-18" }),
+    )
+    .await;
+    let (_, json) = get_json(&app, "/api/budgets/transfers?category=Dining").await;
+    let trail = json["data"].as_array().unwrap();
+    assert_eq!(trail.len(), 2);
+    assert_eq!(trail[0]["date"], "2026-02-18");
+    let (_, json) = get_json(&app, "/api/budgets/transfers?category=Gas").await;
+    assert!(json["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_budget_history_with_limit_versions() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "amount": 100.0, "effective_from": "2025-10-01" }),
+    )
+    .await;
+    // Raised in January; the old limit still applies to earlier months
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "amount": 150.0, "effective_from": "2026-01-01" }),
+    )
+    .await;
+    assert_eq!(json["data"]["amount"], 150.0);
+    let id = json["data"]["id"].as_str().unwrap().to_string();
+
+    // Backdating a version doesn't change the current amount
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Groceries", "amount": 100.0, "effective_from": "2025-10-01" }),
+    )
+    .await;
+    assert_eq!(json["data"]["amount"], 150.0);
+
+    let (_, json) = get_json(&app, &format!("/api/budgets/{id}/limits")).await;
+    let limits = json["data"].as_array().unwrap();
+    assert_eq!(limits.len(), 2);
+    assert_eq!(limits[0]["effective_from"], "2025-10-01");
+
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Gas", "amount": 80.0, "period": "monthly", "start_date": "2026-03-01" }),
+    )
+    .await;
+
+    let (_, json) = get_json(&app, "/api/budgets/history?months=5&as_of=2026-02-20").await;
+    let data = &json["data"];
+    assert_eq!(data["from"], "2025-10-01");
+    // Gas hasn't started
+    let history = data["budgets"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+
+    let groceries = &history[0];
+    let periods = groceries["periods"].as_array().unwrap();
+    let limits: Vec<f64> = periods.iter().map(|p| p["limit"].as_f64().unwrap()).collect();
+    assert_eq!(limits, vec![100.0, 100.0, 100.0, 150.0, 150.0]);
+    assert_eq!(periods[1]["hit"], false);
+    assert_eq!(periods[4]["complete"], false);
+
+    // Complete months: hit, miss, miss, hit
+    assert_eq!(groceries["hits"], 2);
+    assert_eq!(groceries["misses"], 2);
+    assert_eq!(groceries["longest_miss_streak"], 2);
+    assert_eq!(groceries["current_streak"]["kind"], "hit");
+    assert_eq!(groceries["current_streak"]["length"], 1);
+    // (12.68 - 148.88 - 5.20 + 54.60) / 4
+    assert!(close(&groceries["avg_variance"], -21.7));
+
+    // The current progress uses the new limit
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    assert_eq!(by_category(&json, "Groceries")["amount"], 150.0);
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2025-12-20").await;
+    assert_eq!(by_category(&json, "Groceries")["amount"], 100.0);
+
+    let (_, json) = get_json(&app, "/api/budgets/history?months=1&as_of=2026-02-20").await;
+    assert_eq!(json["data"]["budgets"][0]["periods"].as_array().unwrap().len(), 1);
+    let (_, json) = get_json(&app, "/api/budgets/history?months=0").await;
+    assert!(json["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_scoped_budgets() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+
+    let amazon: uuid::Uuid = sqlx::query_scalar("SELECT id FROM transactions WHERE hash = 'test_hash_023'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    // Amex exports name the card member; the match is exact, so Janet isn't Jane
+    for (hash, member) in [("test_hash_006", " JANE "), ("test_hash_007", "JANET")] {
+        sqlx::query("UPDATE transactions SET raw_data = jsonb_build_object('Card Member', $2::text) WHERE hash = $1")
+            .bind(hash)
+            .bind(member)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let app = app(pool);
+
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/transactions/{amazon}/tags"),
+        serde_json::json!({ "tags": [" gifts ", "gifts", "", "birthday"] }),
+    )
+    .await;
+    assert_eq!(json["data"], serde_json::json!(["gifts", "birthday"]));
+
+    for body in [
+        serde_json::json!({ "category": "Coffee shops", "amount": 60.0, "scope": { "merchants": ["starbucks", "Dunkin Donuts"] } }),
+        serde_json::json!({ "category": "Amex total", "amount": 2000.0, "scope": { "cards": ["amex"] } }),
+        serde_json::json!({ "category": "Gifts", "amount": 300.0, "scope": { "tags": ["gifts"] } }),
+        serde_json::json!({ "category": "Costco groceries", "amount": 100.0, "scope": { "categories": ["Groceries"], "cards": ["citi"] } }),
+        serde_json::json!({ "category": "Jane", "amount": 100.0, "scope": { "member": "jane" } }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets", body).await;
+        assert!(json["error"].is_null(), "{json}");
+    }
+
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    assert_eq!(by_category(&json, "Coffee shops")["spent"], 5.25);
+    assert!(close(&by_category(&json, "Amex total")["spent"], 115.25));
+    let gifts = by_category(&json, "Gifts");
+    assert_eq!(gifts["spent"], 350.0);
+    assert_eq!(gifts["status"], "over_budget");
+    assert_eq!(by_category(&json, "Costco groceries")["spent"], 0.0);
+
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2025-11-20").await;
+    assert_eq!(by_category(&json, "Costco groceries")["spent"], 156.78);
+    assert_eq!(by_category(&json, "Coffee shops")["spent"], 6.25);
+    assert_eq!(by_category(&json, "Jane")["spent"], 92.1);
+
+    // Updating the amount keeps the scope; an empty scope goes back to the category
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Coffee shops", "amount": 80.0 }),
+    )
+    .await;
+    assert_eq!(json["data"]["scope"]["merchants"], serde_json::json!(["STARBUCKS", "DUNKIN DONUTS"]));
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Gifts", "amount": 300.0, "scope": {} }),
+    )
+    .await;
+    assert!(json["data"]["scope"].is_null());
+
+    for body in [
+        serde_json::json!({ "category": "Bad", "amount": 10.0, "scope": { "cards": [] } }),
+        serde_json::json!({ "category": "Bad", "amount": 10.0, "scope": { "merchant": ["X"] } }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets", body.clone()).await;
+        assert!(json["data"].is_null(), "{body} should be rejected");
+    }
+}
+
+#[tokio::test]
+async fn test_budget_suggestions_and_apply() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/budgets/suggestions?as_of=2026-02-20").await;
+    // Oct–Jan: 87.32, 248.88, 105.20, 95.40 → median 100.30
+    let groceries = by_category(&json, "Groceries");
+    assert_eq!(groceries["month"], "2026-03");
+    assert!(close(&groceries["variable"], 100.3));
+    assert_eq!(groceries["suggested"], 105.0);
+    assert_eq!(groceries["months_used"], 4);
+    assert_eq!(groceries["months_exceeded"], 2);
+    assert_eq!(groceries["exceed_rate"], 50.0);
+
+    // Netflix is all recurring
+    let subscriptions = by_category(&json, "Subscriptions");
+    assert_eq!(subscriptions["variable"], 0.0);
+    assert!(close(&subscriptions["recurring"], 15.99));
+    assert_eq!(subscriptions["suggested"], 20.0);
+    assert_eq!(subscriptions["exceed_rate"], 0.0);
+
+    // Travel has no completed months
+    assert!(json["data"].as_array().unwrap().iter().all(|s| s["category"] != "Travel"));
+
+    let (_, json) = get_json(&app, "/api/budgets/suggestions?as_of=2026-02-20&percentile=90").await;
+    assert!(by_category(&json, "Groceries")["suggested"].as_f64().unwrap() > 105.0);
+    let (_, json) = get_json(&app, "/api/budgets/suggestions?months=2").await;
+    assert!(json["error"].is_string());
+
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Gas", "amount": 15.0, "period": "weekly" }),
+    )
+    .await;
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets/suggestions/apply",
+        serde_json::json!({ "categories": ["Groceries", "Gas", "Travel"], "as_of": "2026-02-20" }),
+    )
+    .await;
+    let applied = json["data"]["applied"].as_array().unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0]["category"], "Groceries");
+    assert_eq!(applied[0]["amount"], 105.0);
+    assert_eq!(applied[0]["period"], "monthly");
+    let id = applied[0]["id"].as_str().unwrap().to_string();
+    let skipped: Vec<&str> = json["data"]["skipped"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["category"].as_str().unwrap())
+        .collect();
+    assert_eq!(skipped, vec!["Gas", "Travel"]);
+
+    // The limit starts with the suggested month, not the one containing today
+    let (_, json) = get_json(&app, &format!("/api/budgets/{id}/limits")).await;
+    let limits = json["data"].as_array().unwrap();
+    assert_eq!(limits.len(), 1);
+    assert_eq!(limits[0]["effective_from"], "2026-03-01");
+
+    let (_, json) = get_json(&app, "/api/budgets/suggestions?as_of=2026-02-20").await;
+    assert_eq!(by_category(&json, "Groceries")["current_amount"], 105.0);
+    assert_eq!(by_category(&json, "Gas")["current_period"], "weekly");
+}
@@ -0,0 +1,276 @@
+mod common;
+
+use common::*;
+
+async fn create_category(app: &axum::Router, body: serde_json::Value) -> String {
+    let (_, json) = post_json(app, "/api/categories", body).await;
+    assert!(json["error"].is_null(), "{json}");
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+fn category_id(list: &serde_json::Value, name: &str) -> String {
+    list["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == name)
+        .unwrap_or_else(|| panic!("no {name} category"))["id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Make Dining and Groceries subcategories of a new top-level test_Food.
+async fn food_tree(app: &axum::Router) -> String {
+    let food = create_category(
+        app,
+        serde_json::json!({ "name": " test_Food ", "color": "#f59e0b", "icon": "Utensils", "kind": "essential" }),
+    )
+    .await;
+    let (_, list) = get_json(app, "/api/categories").await;
+    for name in ["Dining", "Groceries"] {
+        let (_, json) = patch_json(
+            app,
+            &format!("/api/categories/{}", category_id(&list, name)),
+            serde_json::json!({ "parent": "test_food" }),
+        )
+        .await;
+        assert_eq!(json["data"]["parent"], "test_Food", "{json}");
+    }
+    food
+}
+
+#[tokio::test]
+async fn test_category_hierarchy() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/categories").await;
+    let groceries = json["data"].as_array().unwrap().iter().find(|c| c["name"] == "Groceries").unwrap();
+    assert_eq!(groceries["kind"], "essential");
+    assert_eq!(groceries["color"], "#10B981");
+
+    let food = food_tree(&app).await;
+    let (_, json) = get_json(&app, "/api/categories").await;
+    let names: Vec<&str> = json["data"].as_array().unwrap().iter().map(|c| c["name"].as_str().unwrap()).collect();
+    let at = names.iter().position(|n| *n == "test_Food").unwrap();
+    assert_eq!(names[at..at + 3], ["test_Food", "Dining", "Groceries"]);
+    let dining = category_id(&json, "Dining");
+
+    for (body, expected) in [
+        (serde_json::json!({ "name": "TEST_FOOD" }), "test_Food already exists"),
+        (serde_json::json!({ "name": "test_Snacks", "parent": "Dining" }), "Dining is a subcategory"),
+        (serde_json::json!({ "name": "test_Snacks", "parent": "test_Nope" }), "Unknown parent category"),
+        (serde_json::json!({ "name": "test_Snacks", "color": "orange" }), "hex colour"),
+        (serde_json::json!({ "name": "test_Snacks", "kind": "luxury" }), "kind must be one of"),
+        (serde_json::json!({ "name": "  " }), "name must not be empty"),
+    ] {
+        let (_, json) = post_json(&app, "/api/categories", body).await;
+        let error = json["error"].as_str().unwrap_or_else(|| panic!("expected an error: {json}"));
+        assert!(error.contains(expected), "{error}");
+    }
+
+    // One level of nesting only
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/categories/{food}"),
+        serde_json::json!({ "parent": "Travel" }),
+    )
+    .await;
+    assert!(json["error"].as_str().unwrap().contains("must stay top-level"));
+    let (_, json) = delete_json(&app, &format!("/api/categories/{food}")).await;
+    assert!(json["error"].as_str().unwrap().contains("has subcategories"));
+
+    // Back to top-level
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/categories/{dining}"),
+        serde_json::json!({ "parent": "" }),
+    )
+    .await;
+    assert!(json["data"]["parent"].is_null(), "{json}");
+    assert_eq!(json["data"]["kind"], "discretionary");
+
+    let (_, json) = get_json(&app, "/api/categories").await;
+    let uncategorized = category_id(&json, "Uncategorized");
+    let (_, json) = delete_json(&app, &format!("/api/categories/{uncategorized}")).await;
+    assert_eq!(json["error"], "Uncategorized can't be deleted");
+}
+
+#[tokio::test]
+async fn test_summary_and_deep_dive_roll_up_by_parent() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+    food_tree(&app).await;
+
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    let data = &json["data"];
+    let leaf = |name: &str| {
+        data["by_category"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["category"] == name)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(leaf("Dining")["parent"], "test_Food");
+    let food_total = leaf("Dining")["total"].as_f64().unwrap() + leaf("Groceries")["total"].as_f64().unwrap();
+
+    let food = data["by_parent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["category"] == "test_Food")
+        .unwrap();
+    assert!((food["total"].as_f64().unwrap() - food_total).abs() < 0.01);
+    assert_eq!(food["count"], 11);
+    assert_eq!(food["kind"], "essential");
+    assert_eq!(food["children"][0]["category"], "Groceries");
+    assert!(data["by_parent"].as_array().unwrap().iter().all(|p| p["category"] != "Dining"));
+
+    // Every dollar is essential, discretionary or neither
+    let kinds = &data["by_kind"];
+    let split = kinds["essential"].as_f64().unwrap()
+        + kinds["discretionary"].as_f64().unwrap()
+        + kinds["unclassified"].as_f64().unwrap();
+    assert!((split - data["total_spent"].as_f64().unwrap()).abs() < 0.01);
+
+    let (_, json) = get_json(&app, "/api/stats/category/test_food").await;
+    let dive = &json["data"];
+    assert_eq!(dive["category"], "test_Food");
+    assert!((dive["total_spent"].as_f64().unwrap() - food_total).abs() < 0.01);
+    assert_eq!(dive["transaction_count"], 11);
+    assert_eq!(dive["subcategories"].as_array().unwrap().len(), 2);
+    let (_, json) = get_json(&app, "/api/stats/category/Dining").await;
+    assert_eq!(json["data"]["transaction_count"], 5);
+    assert!(json["data"]["subcategories"].as_array().unwrap().is_empty());
+
+    // A budget on the parent counts its subcategories
+    let (_, json) = post_json(&app, "/api/budgets", serde_json::json!({ "category": "test_food", "amount": 500.0 })).await;
+    assert_eq!(json["data"]["category"], "test_Food", "{json}");
+    let (_, json) = get_json(&app, "/api/budgets/progress?as_of=2026-02-20").await;
+    assert_eq!(json["data"][0]["spent"], 115.25);
+}
+
+#[tokio::test]
+async fn test_category_references_are_validated() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let starbucks: uuid::Uuid = sqlx::query_scalar("SELECT id FROM transactions WHERE hash = 'test_hash_022'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let app = app(pool.clone());
+
+    let (_, json) = patch_json(&app, &format!("/api/transactions/{starbucks}"), serde_json::json!({ "category": "Dinning" })).await;
+    assert_eq!(json["error"], "Unknown category: Dinning");
+    let (_, json) = patch_json(
+        &app,
+        "/api/transactions/bulk-category",
+        serde_json::json!({ "ids": [starbucks], "category": "Yachts" }),
+    )
+    .await;
+    assert_eq!(json["error"], "Unknown category: Yachts");
+
+    for body in [
+        serde_json::json!({ "category": "Yachts", "amount": 100.0 }),
+        serde_json::json!({ "category": "Boat fund", "amount": 100.0, "scope": { "categories": ["Yachts"] } }),
+    ] {
+        let (_, json) = post_json(&app, "/api/budgets", body).await;
+        assert!(json["error"].as_str().unwrap().contains("Unknown category: Yachts"), "{json}");
+    }
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Treats", "amount": 50.0, "scope": { "categories": ["dining", "Dining"] } }),
+    )
+    .await;
+    assert_eq!(json["data"]["scope"]["categories"], serde_json::json!(["Dining"]));
+    // Dropping the scope would count a category that doesn't exist
+    let (_, json) = post_json(&app, "/api/budgets", serde_json::json!({ "category": "Treats", "amount": 50.0, "scope": {} })).await;
+    assert!(json["error"].as_str().unwrap().contains("give the budget a scope"), "{json}");
+
+    // Renaming follows the category into transactions and budgets
+    create_category(&app, serde_json::json!({ "name": "test_Coffee" })).await;
+    let (_, json) = patch_json(&app, &format!("/api/transactions/{starbucks}"), serde_json::json!({ "category": "TEST_COFFEE" })).await;
+    assert!(json["error"].is_null(), "{json}");
+    post_json(&app, "/api/budgets", serde_json::json!({ "category": "test_Coffee", "amount": 20.0 })).await;
+    post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Caffeine", "amount": 30.0, "scope": { "categories": ["test_Coffee"], "cards": ["amex"] } }),
+    )
+    .await;
+
+    let (_, list) = get_json(&app, "/api/categories").await;
+    let coffee = category_id(&list, "test_Coffee");
+    let (_, json) = patch_json(&app, &format!("/api/categories/{coffee}"), serde_json::json!({ "name": "test_Cafe" })).await;
+    assert_eq!(json["data"]["name"], "test_Cafe", "{json}");
+    let category: String = sqlx::query_scalar("SELECT category FROM transactions WHERE id = $1")
+        .bind(starbucks)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(category, "test_Cafe");
+    let (_, json) = get_json(&app, "/api/budgets").await;
+    let budgets = json["data"].as_array().unwrap();
+    assert!(budgets.iter().any(|b| b["category"] == "test_Cafe"));
+    let caffeine = budgets.iter().find(|b| b["category"] == "Caffeine").unwrap();
+    assert_eq!(caffeine["scope"]["categories"], serde_json::json!(["test_Cafe"]));
+
+    // Deleting needs the budgets gone and the transactions moved
+    let (_, json) = delete_json(&app, &format!("/api/categories/{coffee}")).await;
+    assert!(json["error"].as_str().unwrap().contains("used by 2 budget(s)"), "{json}");
+    for budget in budgets.iter().filter(|b| b["category"] == "test_Cafe" || b["category"] == "Caffeine") {
+        delete_json(&app, &format!("/api/budgets/{}", budget["id"].as_str().unwrap())).await;
+    }
+    let (_, json) = delete_json(&app, &format!("/api/categories/{coffee}")).await;
+    assert_eq!(json["error"], "test_Cafe has transactions; pass reassign_to to move them");
+    let (_, json) = delete_json(&app, &format!("/api/categories/{coffee}?reassign_to=dining")).await;
+    assert_eq!(json["data"]["reassigned"], 1, "{json}");
+    assert_eq!(json["data"]["reassigned_to"], "Dining");
+}
+
+#[tokio::test]
+async fn test_import_files_unlisted_categories_as_uncategorized() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+    let (_, json) = post_json(
+        &app,
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_cat", "label": "Test Categories", "color": "#000000",
+            "date_column": "Date", "date_format": "MM/DD/YYYY", "description_column": "Description",
+            "amount_column": "Amount", "category_column": "Category"
+        }),
+    )
+    .await;
+    assert!(json["error"].is_null(), "{json}");
+
+    let csv = "Date,Description,Amount,Category\n\
+        01/05/2026,OFFICE DEPOT,25.00,Business Services-Office Supplies\n\
+        01/06/2026,CLASSPASS,15.00,FITNESS\n\
+        01/07/2026,SAFEWAY,40.00,Merchandise & Supplies-Groceries\n\
+        01/08/2026,BLUE BOTTLE,6.00,Food & Drink\n\
+        01/09/2026,PG&E,80.00,Bills & Utilities\n\
+        01/10/2026,KINKOS,9.00,Business Services-Office Supplies\n";
+    let (_, json) = post_file(&app, "/api/transactions/import", "cat.csv", csv, &[("card_code", "test_cat")]).await;
+    assert_eq!(json["data"]["new_count"], 6, "{json}");
+    // The bank's own category is reported, and kept in the raw row
+    assert_eq!(
+        json["data"]["unlisted_categories"],
+        serde_json::json!([{ "category": "Business Services-Office Supplies", "count": 2 }])
+    );
+
+    let (_, json) = get_json(&app, "/api/transactions?sort_by=date&sort_order=asc").await;
+    let rows = json["data"].as_array().unwrap();
+    let categories: Vec<&str> = rows.iter().map(|t| t["category"].as_str().unwrap()).collect();
+    assert_eq!(categories, ["Uncategorized", "Fitness", "Groceries", "Dining", "Utilities", "Uncategorized"]);
+    assert_eq!(rows[0]["raw_data"]["Category"], "Business Services-Office Supplies");
+}
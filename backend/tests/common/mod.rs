@@ -1,3 +1,6 @@
+// Shared by every integration test binary; not all helpers are used by each one.
+#![allow(dead_code)]
+
 use axum::{body::Body, http::Request, Router};
 use http_body_util::BodyExt;
 use sqlx::PgPool;
@@ -33,7 +36,22 @@ pub fn app(pool: PgPool) -> Router {
 pub async fn clean(pool: &PgPool) {
     sqlx::query("DELETE FROM import_history").execute(pool).await.unwrap();
     sqlx::query("DELETE FROM transactions").execute(pool).await.unwrap();
-    // Don't delete cards — they're seeded by migration 002 and tests need them
+    sqlx::query("DELETE FROM fx_rates").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM subscriptions").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM suspicious_dismissals").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM insights").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM budgets").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM budget_transfers").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM notification_channels").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM goals").execute(pool).await.unwrap();
+    // Preset categories are seeded by migration 020; tests name their own test_*
+    sqlx::query("DELETE FROM categories WHERE name LIKE 'test_%'").execute(pool).await.unwrap();
+    sqlx::query("DELETE FROM user_config WHERE key IN ('home_currency', 'analytics_settings', 'insight_generators')")
+        .execute(pool)
+        .await
+        .unwrap();
+    // Don't delete preset cards — they're seeded by migration 002 and tests need them
+    sqlx::query("DELETE FROM cards WHERE code LIKE 'test_%'").execute(pool).await.unwrap();
 }
 
 /// Seed a set of test transactions spanning multiple months, categories, and cards.
@@ -163,3 +181,63 @@ pub async fn put_json(app: &Router, path: &str, body: serde_json::Value) -> (u16
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap_or(serde_json::json!(null));
     (status, json)
 }
+
+/// Send a POST request with a JSON body.
+pub async fn post_json(app: &Router, path: &str, body: serde_json::Value) -> (u16, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(path)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status().as_u16();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap_or(serde_json::json!(null));
+    (status, json)
+}
+
+/// Send a multipart POST with a single `file` field (plus optional text fields).
+pub async fn post_file(
+    app: &Router,
+    path: &str,
+    file_name: &str,
+    contents: &str,
+    fields: &[(&str, &str)],
+) -> (u16, serde_json::Value) {
+    let boundary = "ledgr-test-boundary";
+    let mut body = String::new();
+    for (name, value) in fields {
+        body.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        ));
+    }
+    body.push_str(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\
+         Content-Type: text/plain\r\n\r\n{contents}\r\n--{boundary}--\r\n"
+    ));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(path)
+                .header("content-type", format!("multipart/form-data; boundary={boundary}"))
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status().as_u16();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap_or(serde_json::json!(null));
+    (status, json)
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+
+#[tokio::test]
+async fn test_fx_rates_import_csv() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let csv = "date,base,quote,rate\n2026-01-02,EUR,USD,1.10\n2026-01-05,EUR,USD,1.20\n";
+    let (status, json) = post_file(&app, "/api/fx-rates/import", "rates.csv", csv, &[]).await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["rate_count"].as_i64().unwrap(), 2);
+    assert_eq!(json["data"]["source"].as_str().unwrap(), "csv");
+
+    let (_, json) = get_json(&app, "/api/fx-rates").await;
+    let pairs = json["data"]["pairs"].as_array().unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0]["base"].as_str().unwrap(), "EUR");
+    assert_eq!(pairs[0]["latest_rate"].as_f64().unwrap(), 1.2);
+}
+
+#[tokio::test]
+async fn test_fx_rates_import_rejects_garbage() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = post_file(&app, "/api/fx-rates/import", "rates.csv", "foo,bar\n1,2\n", &[]).await;
+    assert!(json["error"].is_string());
+}
+
+#[tokio::test]
+async fn test_stats_convert_to_home_currency() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized, currency) VALUES
+        ('2026-01-03', 'CAFE DE FLORE', 100.00, 'Dining', 'amex', 'Amex Gold', 'fx_hash_001', 'CAFE DE FLORE', 'EUR'),
+        ('2026-01-06', 'CAFE DE FLORE', 100.00, 'Dining', 'amex', 'Amex Gold', 'fx_hash_002', 'CAFE DE FLORE', 'EUR'),
+        ('2026-01-06', 'STARBUCKS', 10.00, 'Dining', 'amex', 'Amex Gold', 'fx_hash_003', 'STARBUCKS', 'USD'),
+        ('2026-01-07', 'PRET A MANGER', 50.00, 'Dining', 'amex', 'Amex Gold', 'fx_hash_004', 'PRET A MANGER', 'GBP')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let app = app(pool);
+    let xml = "<Cube><Cube time='2026-01-05'><Cube currency='USD' rate='1.20'/></Cube>\
+               <Cube time='2026-01-02'><Cube currency='USD' rate='1.10'/></Cube></Cube>";
+    let (_, json) = post_file(&app, "/api/fx-rates/import", "eurofxref.xml", xml, &[]).await;
+    assert_eq!(json["data"]["source"].as_str().unwrap(), "ecb");
+
+    // Home currency defaults to USD: 100 EUR @ 1.10 + 100 EUR @ 1.20 + 10 USD. There's no
+    // GBP rate, so that row is counted as unconverted instead of added in as dollars
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    let total = json["data"]["total_spent"].as_f64().unwrap();
+    assert!((total - 240.0).abs() < 0.01, "Expected 240.00, got {}", total);
+    assert_eq!(json["data"]["transaction_count"], 3);
+    assert_eq!(json["data"]["unconverted_count"], 1);
+    let (_, json) = get_json(&app, "/api/stats/cashflow").await;
+    assert_eq!(json["data"]["unconverted_count"], 1);
+
+    // Switching home currency to EUR converts the USD row by the inverse rate
+    put_json(&app, "/api/config", serde_json::json!({ "home_currency": "eur" })).await;
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    let total = json["data"]["total_spent"].as_f64().unwrap();
+    assert!((total - 208.33).abs() < 0.01, "Expected 208.33, got {}", total);
+}
+
+#[tokio::test]
+async fn test_home_currency_validated() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = put_json(&app, "/api/config", serde_json::json!({ "home_currency": "dollars" })).await;
+    assert!(json["error"].is_string());
+}
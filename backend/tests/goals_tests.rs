@@ -0,0 +1,216 @@
+mod common;
+
+use common::*;
+
+async fn create_goal(app: &axum::Router, body: serde_json::Value) -> String {
+    let (_, json) = post_json(app, "/api/goals", body).await;
+    assert!(json["error"].is_null(), "{json}");
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_tag_goal_progress_and_insight() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    sqlx::query("UPDATE transactions SET tags = '{trip}' WHERE hash IN ('test_hash_013', 'test_hash_024')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let app = app(pool);
+
+    let id = create_goal(
+        &app,
+        serde_json::json!({
+            "name": "Trip", "target_amount": 2000.0, "target_date": "2026-12-31",
+            "start_date": "2025-11-01", "tag": " trip "
+        }),
+    )
+    .await;
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/goals/{id}/contributions"),
+        serde_json::json!({ "date": "2026-01-15", "amount": 150.0, "note": "Birthday money" }),
+    )
+    .await;
+    assert_eq!(json["data"]["source"], "manual");
+    let manual_id = json["data"]["id"].as_str().unwrap().to_string();
+
+    // Target's 200 in December, Uber's 15.50 in February, plus the manual 150
+    let (_, json) = get_json(&app, "/api/goals?as_of=2026-02-20").await;
+    let goal = &json["data"][0];
+    assert_eq!(goal["tag"], "trip");
+    assert_eq!(goal["saved"], 365.5);
+    assert_eq!(goal["remaining"], 1634.5);
+    assert_eq!(goal["status"], "behind");
+    assert_eq!(goal["on_track"], false);
+    assert!(goal["expected_saved"].as_f64().unwrap() > 365.5);
+    assert!(goal["required_monthly"].as_f64().unwrap() > 80.0);
+
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}/contributions")).await;
+    let entries = json["data"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["date"], "2026-02-10");
+    assert_eq!(entries[0]["source"], "transaction");
+    assert_eq!(entries[0]["note"], "UBER *TRIP");
+
+    // Falling behind shows up in the insights engine
+    get_json(&app, "/api/stats/insights?end_date=2026-02-20").await;
+    let (_, json) = get_json(&app, "/api/insights/history?type=goal").await;
+    let insights = json["data"].as_array().unwrap();
+    assert_eq!(insights.len(), 1);
+    assert_eq!(insights[0]["title"], "Trip goal is falling behind");
+    assert_eq!(insights[0]["subject"], format!("goal:{id}"));
+
+    // Enough saved to be on plan again
+    post_json(
+        &app,
+        &format!("/api/goals/{id}/contributions"),
+        serde_json::json!({ "date": "2026-02-15", "amount": 300.0 }),
+    )
+    .await;
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}?as_of=2026-02-20")).await;
+    assert_eq!(json["data"]["status"], "on_track");
+
+    let (_, json) = delete_json(&app, &format!("/api/goals/{id}/contributions/{manual_id}")).await;
+    assert_eq!(json["data"], "Contribution deleted");
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}?as_of=2026-02-20")).await;
+    assert_eq!(json["data"]["saved"], 515.5);
+
+    let (_, json) = delete_json(&app, &format!("/api/goals/{id}")).await;
+    assert_eq!(json["data"], "Goal deleted");
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}/contributions")).await;
+    assert_eq!(json["error"], "Goal not found");
+}
+
+#[tokio::test]
+async fn test_account_goal_counts_deposits_and_withdrawals() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let (_, json) = post_json(
+        &app(pool.clone()),
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_savings", "label": "Test Savings", "color": "#10B981",
+            "account_type": "savings", "date_column": "Date", "date_format": "MM/DD/YYYY",
+            "description_column": "Description", "amount_column": "Amount"
+        }),
+    )
+    .await;
+    assert!(json["error"].is_null(), "{json}");
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, classification) VALUES
+        ('2025-12-20', 'TRANSFER FROM CHECKING', -300.00, 'Income', 'test_savings', 'Test Savings', 'test_goal_1', 'income'),
+        ('2026-01-05', 'TRANSFER FROM CHECKING', -500.00, 'Income', 'test_savings', 'Test Savings', 'test_goal_2', 'income'),
+        ('2026-02-01', 'WITHDRAWAL', 100.00, 'Transfer', 'test_savings', 'Test Savings', 'test_goal_3', 'expense')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let app = app(pool);
+
+    let id = create_goal(
+        &app,
+        serde_json::json!({
+            "name": "Emergency fund", "target_amount": 3000.0, "target_date": "2026-06-30",
+            "start_date": "2026-01-01", "initial_amount": 1000.0, "account": "test_savings"
+        }),
+    )
+    .await;
+
+    // December's deposit predates the goal; it's in the initial amount
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}?as_of=2026-02-20")).await;
+    let goal = &json["data"];
+    assert_eq!(goal["saved"], 1400.0);
+    assert_eq!(goal["account"], "test_savings");
+
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}/contributions")).await;
+    let amounts: Vec<f64> = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["amount"].as_f64().unwrap())
+        .collect();
+    assert_eq!(amounts, [-100.0, 500.0]);
+
+    // Switch to a tag: the account's transactions stop counting
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/goals/{id}"),
+        serde_json::json!({ "account": "", "tag": "rainy-day", "target_amount": 2500.0 }),
+    )
+    .await;
+    assert!(json["data"]["account"].is_null(), "{json}");
+    assert_eq!(json["data"]["tag"], "rainy-day");
+    assert_eq!(json["data"]["target_amount"], 2500.0);
+    let (_, json) = get_json(&app, &format!("/api/goals/{id}?as_of=2026-02-20")).await;
+    assert_eq!(json["data"]["saved"], 1000.0);
+}
+
+#[tokio::test]
+async fn test_goal_validation() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    for (body, expected) in [
+        (
+            serde_json::json!({ "name": "Car", "target_amount": 0.0, "target_date": "2027-01-01" }),
+            "target_amount",
+        ),
+        (
+            serde_json::json!({ "name": "Car", "target_amount": 5000.0, "target_date": "2026-01-01", "start_date": "2026-03-01" }),
+            "target_date must be after start_date",
+        ),
+        (
+            serde_json::json!({ "name": "Car", "target_amount": 5000.0, "target_date": "2030-01-01", "account": "amex" }),
+            "checking, savings or cash",
+        ),
+        (
+            serde_json::json!({ "name": "Car", "target_amount": 5000.0, "target_date": "2030-01-01", "account": "nope" }),
+            "Unknown account",
+        ),
+        (
+            serde_json::json!({ "name": "Car", "target_amount": 5000.0, "target_date": "2030-01-01", "account": "nope", "tag": "car" }),
+            "not both",
+        ),
+        (
+            serde_json::json!({ "name": "", "target_amount": 5000.0, "target_date": "2030-01-01" }),
+            "name",
+        ),
+    ] {
+        let (_, json) = post_json(&app, "/api/goals", body).await;
+        let error = json["error"].as_str().unwrap_or_else(|| panic!("expected an error: {json}"));
+        assert!(error.contains(expected), "{error}");
+    }
+
+    let id = create_goal(
+        &app,
+        serde_json::json!({ "name": "Car", "target_amount": 5000.0, "target_date": "2030-01-01" }),
+    )
+    .await;
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/goals/{id}"),
+        serde_json::json!({ "target_date": "2020-01-01" }),
+    )
+    .await;
+    assert!(json["error"].as_str().unwrap().contains("target_date"));
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/goals/{id}/contributions"),
+        serde_json::json!({ "amount": 0.0 }),
+    )
+    .await;
+    assert_eq!(json["error"], "amount must not be zero");
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/goals/{}/contributions", uuid::Uuid::new_v4()),
+        serde_json::json!({ "amount": 10.0 }),
+    )
+    .await;
+    assert_eq!(json["error"], "Goal not found");
+}
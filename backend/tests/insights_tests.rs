@@ -0,0 +1,208 @@
+mod common;
+
+use common::*;
+
+const FEED: &str = "/api/stats/insights?end_date=2026-02-20";
+
+fn ids(json: &serde_json::Value) -> Vec<String> {
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_insights_are_persisted_with_stable_ids() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, FEED).await;
+    let first = ids(&json);
+    assert!(!first.is_empty());
+    for insight in json["data"].as_array().unwrap() {
+        assert_eq!(insight["period"], "2026-02");
+        assert_eq!(insight["fingerprint"].as_str().unwrap().len(), 16);
+        assert!(insight["dismissed_at"].is_null());
+    }
+
+    // Regenerating refreshes the same rows
+    let (_, json) = get_json(&app, FEED).await;
+    assert_eq!(ids(&json), first);
+
+    // Another month is another set of insights, and history keeps both
+    get_json(&app, "/api/stats/insights?end_date=2026-01-20").await;
+    let (_, json) = get_json(&app, "/api/insights/history").await;
+    let history = json["data"].as_array().unwrap();
+    assert!(history.iter().any(|i| i["period"] == "2026-01"));
+    assert_eq!(history[0]["period"], "2026-02");
+
+    let (_, json) = get_json(&app, "/api/insights/history?period=2026-01").await;
+    assert!(json["data"].as_array().unwrap().iter().all(|i| i["period"] == "2026-01"));
+    let (_, json) = get_json(&app, "/api/insights/history?type=no_such_type").await;
+    assert!(json["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_insight_dismiss_snooze_and_feedback() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, FEED).await;
+    let feed = ids(&json);
+    assert!(feed.len() >= 2, "seed should produce at least two insights");
+    let (dismissed, snoozed) = (&feed[0], &feed[1]);
+
+    let (_, json) = post_json(&app, &format!("/api/insights/{dismissed}/dismiss"), serde_json::json!({})).await;
+    assert!(json["data"]["dismissed_at"].is_string());
+
+    let next_week = chrono::Local::now().date_naive() + chrono::Duration::days(7);
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/insights/{snoozed}/snooze"),
+        serde_json::json!({ "until": next_week }),
+    )
+    .await;
+    assert_eq!(json["data"]["snoozed_until"], next_week.to_string());
+
+    let (_, json) = get_json(&app, FEED).await;
+    let now = ids(&json);
+    assert!(!now.contains(dismissed));
+    assert!(!now.contains(snoozed));
+
+    // History still has the dismissed insight unless asked to hide it
+    let (_, json) = get_json(&app, "/api/insights/history").await;
+    assert!(ids(&json).contains(dismissed));
+    let (_, json) = get_json(&app, "/api/insights/history?hide_dismissed=true").await;
+    assert!(!ids(&json).contains(dismissed));
+
+    // Restoring and unsnoozing bring both back
+    delete_json(&app, &format!("/api/insights/{dismissed}/dismiss")).await;
+    delete_json(&app, &format!("/api/insights/{snoozed}/snooze")).await;
+    let (_, json) = get_json(&app, FEED).await;
+    assert_eq!(ids(&json), feed);
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/insights/{snoozed}/snooze"),
+        serde_json::json!({ "until": "2020-01-01" }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/insights/{dismissed}/feedback"),
+        serde_json::json!({ "helpful": false }),
+    )
+    .await;
+    assert_eq!(json["data"]["helpful"], false);
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/insights/{dismissed}/feedback"),
+        serde_json::json!({ "helpful": null }),
+    )
+    .await;
+    assert!(json["data"]["helpful"].is_null());
+
+    let missing = uuid::Uuid::new_v4();
+    let (_, json) = post_json(&app, &format!("/api/insights/{missing}/dismiss"), serde_json::json!({})).await;
+    assert_eq!(json["error"], "Insight not found");
+}
+
+#[tokio::test]
+async fn test_filtered_feed_is_not_persisted() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, FEED).await;
+    let dismissed = json["data"][0].clone();
+    post_json(&app, &format!("/api/insights/{}/dismiss", dismissed["id"].as_str().unwrap()), serde_json::json!({})).await;
+    let (_, before) = get_json(&app, "/api/insights/history").await;
+
+    for filter in ["start_date=2026-02-01", "cards=amex", "exclude_categories=Dining"] {
+        let (_, json) = get_json(&app, &format!("{FEED}&{filter}")).await;
+        let feed = json["data"].as_array().unwrap();
+        assert!(feed.iter().all(|i| i["fingerprint"] != dismissed["fingerprint"]));
+        assert!(feed.iter().all(|i| i["period"] == "2026-02"));
+    }
+
+    // The month's stored insights are untouched
+    let (_, after) = get_json(&app, "/api/insights/history").await;
+    assert_eq!(after, before);
+}
+
+#[tokio::test]
+async fn test_insight_generators_can_be_switched_off() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/insights/generators").await;
+    let generators = json["data"].as_array().unwrap();
+    assert_eq!(generators.len(), 11);
+    assert!(generators.iter().all(|g| g["enabled"] == true));
+
+    let (_, json) = get_json(&app, FEED).await;
+    let types: Vec<String> = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["type"].as_str().unwrap().to_string())
+        .collect();
+    let switched_off = types[0].clone();
+
+    let (_, json) = put_json(
+        &app,
+        "/api/insights/generators",
+        serde_json::json!({ switched_off.clone(): false }),
+    )
+    .await;
+    let entry = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|g| g["name"] == switched_off.as_str())
+        .unwrap()
+        .clone();
+    assert_eq!(entry["enabled"], false);
+
+    let (_, json) = get_json(&app, FEED).await;
+    assert!(json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|i| i["type"] != switched_off.as_str()));
+
+    let (_, json) = put_json(&app, "/api/insights/generators", serde_json::json!({ "bogus": true })).await;
+    assert!(json["error"].as_str().unwrap().contains("bogus"));
+    let (_, json) = put_json(
+        &app,
+        "/api/config",
+        serde_json::json!({ "insight_generators": "{\"anomaly\": \"yes\"}" }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+
+    // null goes back to the default
+    put_json(
+        &app,
+        "/api/insights/generators",
+        serde_json::json!({ switched_off.clone(): null }),
+    )
+    .await;
+    let (_, json) = get_json(&app, FEED).await;
+    assert!(json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|i| i["type"] == switched_off.as_str()));
+}
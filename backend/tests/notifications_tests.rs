@@ -0,0 +1,497 @@
+mod common;
+
+use common::*;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// A request received by [`http_stub`]: the request line, headers (lower-cased names) and body.
+#[derive(Debug, Clone)]
+struct Received {
+    line: String,
+    headers: Vec<(String, String)>,
+    body: serde_json::Value,
+}
+
+impl Received {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A local HTTP server that records every request and answers 200.
+async fn http_stub() -> (String, Arc<Mutex<Vec<Received>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut line = String::new();
+            stream.read_line(&mut line).await.unwrap();
+            let mut headers = Vec::new();
+            loop {
+                let mut header = String::new();
+                stream.read_line(&mut header).await.unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_once(':').unwrap();
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+            let length: usize = headers
+                .iter()
+                .find(|(n, _)| n == "content-length")
+                .map(|(_, v)| v.parse().unwrap())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.unwrap();
+            log.lock().unwrap().push(Received {
+                line: line.trim().to_string(),
+                headers,
+                body: serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+            });
+            stream
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+        }
+    });
+    (base, received)
+}
+
+/// A local SMTP server that accepts everything and records each message's DATA.
+async fn smtp_sink() -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let log = messages.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            stream
+                .get_mut()
+                .write_all(b"220 sink ESMTP\r\n")
+                .await
+                .unwrap();
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                let reply: &[u8] = match line.trim_end() {
+                    l if l.starts_with("EHLO") => b"250-sink\r\n250 8BITMIME\r\n",
+                    "DATA" => {
+                        stream
+                            .get_mut()
+                            .write_all(b"354 go ahead\r\n")
+                            .await
+                            .unwrap();
+                        let mut data = String::new();
+                        loop {
+                            let mut line = String::new();
+                            stream.read_line(&mut line).await.unwrap();
+                            if line == ".\r\n" {
+                                break;
+                            }
+                            data.push_str(&line);
+                        }
+                        log.lock().unwrap().push(data);
+                        b"250 queued\r\n"
+                    }
+                    "QUIT" => {
+                        stream.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+                        break;
+                    }
+                    _ => b"250 OK\r\n",
+                };
+                stream.get_mut().write_all(reply).await.unwrap();
+            }
+        }
+    });
+    (port, messages)
+}
+
+/// A port with nothing listening on it.
+async fn dead_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+async fn add_channel(app: &axum::Router, body: serde_json::Value) -> String {
+    let (_, json) = post_json(app, "/api/notifications/channels", body).await;
+    assert!(json["error"].is_null(), "{json}");
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+/// Groceries exceeded; Dining and Shopping past 80% and heading over, as of 2026-02-20.
+async fn seed_budgets(app: &axum::Router) {
+    for (category, amount) in [("Groceries", 100.0), ("Dining", 6.0), ("Shopping", 400.0)] {
+        let (_, json) = post_json(
+            app,
+            "/api/budgets",
+            serde_json::json!({ "category": category, "amount": amount }),
+        )
+        .await;
+        assert!(json["error"].is_null(), "{json}");
+    }
+}
+
+#[tokio::test]
+async fn test_alerts_delivered_to_every_channel_once_per_period() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+    seed_budgets(&app).await;
+
+    let (base, requests) = http_stub().await;
+    let (smtp_port, emails) = smtp_sink().await;
+    add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Home Assistant", "kind": "webhook",
+            "config": { "url": format!("{base}/hook"), "headers": { "X-Token": "s3cret" } }
+        }),
+    )
+    .await;
+    add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Phone", "kind": "ntfy",
+            "config": { "url": base, "topic": "ledgr", "priority": 4 }
+        }),
+    )
+    .await;
+    add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Gotify", "kind": "gotify",
+            "config": { "url": format!("{base}/gotify/"), "token": "AbC123" }
+        }),
+    )
+    .await;
+    add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Email", "kind": "smtp",
+            "config": { "host": "127.0.0.1", "port": smtp_port, "from": "ledgr@home.lan", "to": ["me@home.lan"] }
+        }),
+    )
+    .await;
+    add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Muted", "kind": "webhook", "enabled": false,
+            "config": { "url": format!("{base}/muted") }
+        }),
+    )
+    .await;
+
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-20",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["alerts"], 5, "{json}");
+    assert_eq!(json["data"]["sent"], 20);
+    assert_eq!(json["data"]["failed"], 0);
+
+    let requests = requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 15);
+    assert!(requests.iter().all(|r| r.line != "POST /muted HTTP/1.1"));
+
+    let hooks: Vec<&Received> = requests
+        .iter()
+        .filter(|r| r.line == "POST /hook HTTP/1.1")
+        .collect();
+    assert_eq!(hooks.len(), 5);
+    assert_eq!(hooks[0].header("x-token"), Some("s3cret"));
+    let mut types: Vec<&str> = hooks
+        .iter()
+        .map(|r| r.body["alert_type"].as_str().unwrap())
+        .collect();
+    types.sort();
+    assert_eq!(
+        types,
+        [
+            "budget_80",
+            "budget_80",
+            "budget_exceeded",
+            "budget_projected",
+            "budget_projected"
+        ]
+    );
+    let exceeded = hooks
+        .iter()
+        .find(|r| r.body["alert_type"] == "budget_exceeded")
+        .unwrap();
+    assert_eq!(exceeded.body["key"], "budget:Groceries:100:2026-02-01");
+    assert_eq!(exceeded.body["title"], "Groceries budget exceeded");
+
+    let ntfy = requests
+        .iter()
+        .find(|r| r.line == "POST / HTTP/1.1")
+        .unwrap();
+    assert_eq!(ntfy.body["topic"], "ledgr");
+    assert_eq!(ntfy.body["priority"], 4);
+
+    let gotify = requests
+        .iter()
+        .find(|r| r.line == "POST /gotify/message HTTP/1.1")
+        .unwrap();
+    assert_eq!(gotify.header("x-gotify-key"), Some("AbC123"));
+
+    let emails = emails.lock().unwrap().clone();
+    assert_eq!(emails.len(), 5);
+    assert!(emails
+        .iter()
+        .any(|m| m.contains("Subject: Groceries budget exceeded\r\n")));
+    assert!(emails.iter().all(|m| m.contains("To: me@home.lan\r\n")));
+
+    // Same period, nothing new
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-25",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["sent"], 0);
+    assert_eq!(
+        json["data"]["already_sent"],
+        json["data"]["alerts"].as_u64().unwrap() * 4
+    );
+
+    let (_, json) = get_json(&app, "/api/notifications/deliveries?status=sent").await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 20);
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_recorded_and_retried() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+    seed_budgets(&app).await;
+
+    let port = dead_port().await;
+    let id = add_channel(
+        &app,
+        serde_json::json!({
+            "name": "Flaky", "kind": "webhook",
+            "config": { "url": format!("http://127.0.0.1:{port}/hook") }
+        }),
+    )
+    .await;
+
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-20",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["sent"], 0);
+    assert_eq!(json["data"]["failed"], 5);
+
+    let (_, json) = get_json(
+        &app,
+        &format!("/api/notifications/deliveries?channel_id={id}&status=failed"),
+    )
+    .await;
+    let failed = json["data"].as_array().unwrap();
+    assert_eq!(failed.len(), 5);
+    assert!(failed[0]["error"].as_str().unwrap().contains("connect"));
+    assert_eq!(failed[0]["attempts"], 1);
+
+    // Retries update the same rows until they run out of attempts
+    for _ in 0..5 {
+        post_json(&app, "/api/notifications/run?as_of=2026-02-20", serde_json::json!({})).await;
+    }
+    let (_, json) = get_json(
+        &app,
+        &format!("/api/notifications/deliveries?channel_id={id}&status=failed"),
+    )
+    .await;
+    let failed = json["data"].as_array().unwrap();
+    assert_eq!(failed.len(), 5);
+    assert!(failed.iter().all(|d| d["attempts"] == 5));
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-20",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["failed"], 0);
+    assert_eq!(json["data"]["gave_up"], 5);
+
+    // Once the endpoint is back, the failed alerts go out
+    let (base, requests) = http_stub().await;
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/notifications/channels/{id}"),
+        serde_json::json!({ "config": { "url": format!("{base}/hook") } }),
+    )
+    .await;
+    assert_eq!(json["data"]["name"], "Flaky");
+
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-20",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["sent"], 5);
+    assert_eq!(requests.lock().unwrap().len(), 5);
+
+    let (_, json) = post_json(
+        &app,
+        "/api/notifications/run?as_of=2026-02-20",
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["sent"], 0);
+    assert_eq!(requests.lock().unwrap().len(), 5);
+}
+
+#[tokio::test]
+async fn test_channel_config_is_validated() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    for (body, expected) in [
+        (
+            serde_json::json!({ "name": "Push", "kind": "ntfy", "config": { "url": "https://ntfy.sh", "topic": "t" } }),
+            "https",
+        ),
+        (
+            serde_json::json!({ "name": "Hook", "kind": "webhook", "config": { "url": "http://h/", "secret": "x" } }),
+            "unknown field",
+        ),
+        (
+            serde_json::json!({ "name": "Text", "kind": "sms", "config": {} }),
+            "kind must be one of",
+        ),
+        (
+            serde_json::json!({ "name": " ", "kind": "webhook", "config": { "url": "http://h/" } }),
+            "name",
+        ),
+    ] {
+        let (_, json) = post_json(&app, "/api/notifications/channels", body).await;
+        let error = json["error"]
+            .as_str()
+            .unwrap_or_else(|| panic!("expected an error: {json}"));
+        assert!(error.contains(expected), "{error}");
+    }
+
+    let (base, requests) = http_stub().await;
+    let id = add_channel(
+        &app,
+        serde_json::json!({ "name": "Hook", "kind": "webhook", "config": { "url": format!("{base}/hook") } }),
+    )
+    .await;
+
+    // Config changes are checked against the channel's kind
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/notifications/channels/{id}"),
+        serde_json::json!({ "config": { "host": "localhost" } }),
+    )
+    .await;
+    assert!(json["error"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid webhook config"));
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/notifications/channels/{id}/test"),
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["sent"], true);
+    assert_eq!(requests.lock().unwrap()[0].body["alert_type"], "test");
+
+    // Test sends aren't deliveries
+    let (_, json) = get_json(&app, "/api/notifications/deliveries").await;
+    assert!(json["data"].as_array().unwrap().is_empty());
+
+    let (_, json) = delete_json(&app, &format!("/api/notifications/channels/{id}")).await;
+    assert_eq!(json["data"], "Channel deleted");
+    let (_, json) = get_json(&app, "/api/notifications/channels").await;
+    assert!(json["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_import_triggers_notifications() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = post_json(
+        &app,
+        "/api/cards",
+        serde_json::json!({
+            "code": "test_bank",
+            "label": "Test Bank",
+            "color": "#10B981",
+            "account_type": "checking",
+            "date_column": "Date",
+            "date_format": "MM/DD/YYYY",
+            "description_column": "Description",
+            "amount_column": "Amount",
+            "amount_sign": "debit_negative"
+        }),
+    )
+    .await;
+    assert!(json["error"].is_null(), "{json}");
+    let (_, json) = post_json(
+        &app,
+        "/api/budgets",
+        serde_json::json!({ "category": "Bank", "amount": 100.0, "scope": { "cards": ["test_bank"] } }),
+    )
+    .await;
+    assert!(json["error"].is_null(), "{json}");
+
+    let (base, requests) = http_stub().await;
+    add_channel(
+        &app,
+        serde_json::json!({ "name": "Hook", "kind": "webhook", "config": { "url": format!("{base}/hook") } }),
+    )
+    .await;
+
+    let today = chrono::Local::now().naive_local().date();
+    let csv = format!(
+        "Date,Description,Amount\n{},HARDWARE STORE,-150.00\n",
+        today.format("%m/%d/%Y")
+    );
+    let (_, json) = post_file(
+        &app,
+        "/api/transactions/import",
+        "stmt.csv",
+        &csv,
+        &[("card_code", "test_bank")],
+    )
+    .await;
+    assert_eq!(json["data"]["new_count"], 1, "{json}");
+
+    // Alerts go out in the background after the import responds
+    for _ in 0..50 {
+        if !requests.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let requests = requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].body["alert_type"], "budget_exceeded");
+    assert_eq!(requests[0].body["title"], "Bank budget exceeded");
+}
@@ -126,3 +126,130 @@ async fn test_patterns_returns_day_data() {
         assert!(entry["count"].is_number());
     }
 }
+
+/// Insert a paycheck and some spending in the previous calendar month.
+async fn seed_cashflow(pool: &sqlx::PgPool) {
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, classification) VALUES
+        ((date_trunc('month', CURRENT_DATE) - interval '1 month' + interval '1 day')::date, 'ACME PAYROLL', -4000.00, 'Income', 'amex', 'Amex Gold', 'cash_hash_001', 'income'),
+        ((date_trunc('month', CURRENT_DATE) - interval '1 month' + interval '2 days')::date, 'WHOLE FOODS', 600.00, 'Groceries', 'amex', 'Amex Gold', 'cash_hash_002', 'expense'),
+        ((date_trunc('month', CURRENT_DATE) - interval '1 month' + interval '3 days')::date, 'RENT', 2400.00, 'Housing', 'amex', 'Amex Gold', 'cash_hash_003', 'expense')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_cashflow_reports_income_and_savings_rate() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_cashflow(&pool).await;
+    let app = app(pool);
+
+    let (status, json) = get_json(&app, "/api/stats/cashflow?months=3").await;
+    assert_eq!(status, 200);
+
+    let data = &json["data"];
+    assert_eq!(data["total_income"].as_f64().unwrap(), 4000.0);
+    assert_eq!(data["total_expenses"].as_f64().unwrap(), 3000.0);
+    assert_eq!(data["net"].as_f64().unwrap(), 1000.0);
+    assert_eq!(data["avg_savings_rate"].as_f64().unwrap(), 25.0);
+
+    let months = data["months"].as_array().unwrap();
+    assert_eq!(months.len(), 1);
+    assert_eq!(months[0]["savings_rate"].as_f64().unwrap(), 25.0);
+}
+
+#[tokio::test]
+async fn test_income_excluded_from_spending_and_feeds_forecast() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_cashflow(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    assert_eq!(json["data"]["total_spent"].as_f64().unwrap(), 3000.0);
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 2);
+
+    let (_, json) = get_json(&app, "/api/stats/forecast").await;
+    let cashflow = &json["data"]["cashflow"];
+    assert_eq!(cashflow["projected_income"].as_f64().unwrap(), 4000.0);
+    assert!(cashflow["projected_net"].is_number());
+}
+
+#[tokio::test]
+async fn test_stats_filter_scopes_summary() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/stats/summary?cards=amex").await;
+    let data = &json["data"];
+    assert_eq!(data["transaction_count"].as_i64().unwrap(), 10);
+    let by_card = data["by_card"].as_array().unwrap();
+    assert_eq!(by_card.len(), 1);
+    assert_eq!(by_card[0]["card"].as_str().unwrap(), "amex");
+
+    let (_, json) = get_json(
+        &app,
+        "/api/stats/summary?start_date=2025-12-01&end_date=2026-01-31&exclude_categories=Shopping,Gas",
+    )
+    .await;
+    let data = &json["data"];
+    // Dec: groceries, dining, netflix; Jan: groceries, dining, netflix
+    assert_eq!(data["transaction_count"].as_i64().unwrap(), 6);
+    let categories: Vec<&str> = data["by_category"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["category"].as_str().unwrap())
+        .collect();
+    assert!(!categories.contains(&"Shopping"));
+    assert!(!categories.contains(&"Gas"));
+    // "This month" is the month of the end date
+    let jan = 95.40 + 6.00 + 15.99;
+    assert!((data["this_month"].as_f64().unwrap() - jan).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn test_stats_filter_anchors_windows_to_end_date() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    for (hash, member) in [("test_hash_006", " JANE "), ("test_hash_007", "JANET")] {
+        sqlx::query("UPDATE transactions SET raw_data = jsonb_build_object('Card Member', $2::text) WHERE hash = $1")
+            .bind(hash)
+            .bind(member)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let app = app(pool);
+
+    // Netflix charged every month through February 2026: active as of the end of that month
+    let (_, json) = get_json(&app, "/api/stats/recurring?end_date=2026-02-28").await;
+    let netflix = json["data"]["recurring"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["merchant"].as_str().unwrap_or("").contains("NETFLIX"))
+        .cloned()
+        .expect("Netflix should be detected");
+    assert_eq!(netflix["status"].as_str().unwrap(), "active");
+
+    let (_, json) = get_json(&app, "/api/stats/merchants?categories=Subscriptions").await;
+    let merchants = json["data"].as_array().unwrap();
+    assert_eq!(merchants.len(), 1);
+    assert_eq!(merchants[0]["merchant"].as_str().unwrap(), "NETFLIX.COM");
+
+    // The card member must match exactly, ignoring case: Janet isn't Jane, and `%` is literal
+    let (_, json) = get_json(&app, "/api/stats/summary?member=jane").await;
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 1);
+    let (_, json) = get_json(&app, "/api/stats/summary?member=%25").await;
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 0);
+
+    let (status, _) = get_json(&app, "/api/stats/habits?start_date=not-a-date").await;
+    assert_eq!(status, 400);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::*;
+
+async fn netflix_id(app: &axum::Router) -> String {
+    let (_, json) = get_json(app, "/api/subscriptions").await;
+    let subs = json["data"].as_array().unwrap();
+    let netflix = subs.iter().find(|s| s["merchant"] == "NETFLIX.COM").unwrap();
+    netflix["id"].as_str().unwrap().to_string()
+}
+
+fn alert_types(json: &serde_json::Value, merchant: &str) -> Vec<String> {
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["merchant"] == merchant)
+        .map(|a| a["alert_type"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_sync_seeds_registry_and_keeps_user_decisions() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool);
+
+    let (status, json) = post_json(&app, "/api/subscriptions/sync", serde_json::json!({})).await;
+    assert_eq!(status, 200);
+    assert!(json["data"]["created"].as_u64().unwrap() >= 1);
+
+    let (_, json) = get_json(&app, "/api/subscriptions").await;
+    let netflix = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["merchant"] == "NETFLIX.COM")
+        .unwrap()
+        .clone();
+    assert_eq!(netflix["status"], "detected");
+    assert_eq!(netflix["frequency"], "monthly");
+    assert_eq!(netflix["amount"], 15.99);
+    assert_eq!(netflix["last_charge_date"], "2026-02-12");
+
+    let id = netflix["id"].as_str().unwrap();
+    let (_, json) = post_json(&app, &format!("/api/subscriptions/{id}/confirm"), serde_json::json!({})).await;
+    assert_eq!(json["data"]["status"], "confirmed");
+
+    // A second sync updates the row instead of creating another one
+    let (_, json) = post_json(&app, "/api/subscriptions/sync", serde_json::json!({})).await;
+    assert_eq!(json["data"]["created"], 0);
+    let (_, json) = get_json(&app, "/api/subscriptions?status=confirmed").await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (_, json) = get_json(&app, "/api/subscriptions?status=bogus").await;
+    assert!(json["error"].is_string());
+
+    // Ignored series drop out of recurring detection
+    post_json(&app, &format!("/api/subscriptions/{id}/ignore"), serde_json::json!({})).await;
+    let (_, json) = get_json(&app, "/api/stats/recurring?end_date=2026-02-20").await;
+    let recurring = json["data"]["recurring"].as_array().unwrap();
+    assert!(!recurring.iter().any(|r| r["merchant"] == "NETFLIX.COM"));
+}
+
+#[tokio::test]
+async fn test_subscription_alerts_reach_insights() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool.clone());
+    post_json(&app, "/api/subscriptions/sync", serde_json::json!({})).await;
+    let id = netflix_id(&app).await;
+
+    // Cancelled in December, but January and February still charged
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/subscriptions/{id}/cancel"),
+        serde_json::json!({ "cancelled_on": "2025-12-20" }),
+    )
+    .await;
+    assert_eq!(json["data"]["status"], "cancelled");
+    assert_eq!(json["data"]["cancelled_on"], "2025-12-20");
+
+    let (_, json) = get_json(&app, "/api/subscriptions/alerts").await;
+    assert_eq!(alert_types(&json, "NETFLIX.COM"), vec!["cancelled_charged"]);
+
+    let (_, json) = get_json(&app, "/api/stats/insights?end_date=2026-02-20").await;
+    let insights = json["data"].as_array().unwrap();
+    let alert = insights
+        .iter()
+        .find(|i| i["type"] == "subscription")
+        .expect("subscription alert insight");
+    assert_eq!(alert["severity"], "high");
+    assert_eq!(alert["metric"]["alert_type"], "cancelled_charged");
+
+    // Reactivated, then charged more than before
+    post_json(&app, &format!("/api/subscriptions/{id}/confirm"), serde_json::json!({})).await;
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized) \
+         VALUES ('2026-03-12', 'NETFLIX.COM', 17.99, 'Subscriptions', 'citi', 'Citi Costco', 'test_hash_sub_001', 'NETFLIX.COM')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let (_, json) = get_json(&app, "/api/stats/insights?end_date=2026-03-20").await;
+    let increase = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|i| i["metric"]["alert_type"] == "price_increase")
+        .expect("price increase insight")
+        .clone();
+    assert_eq!(increase["metric"]["previous_amount"], 15.99);
+    assert_eq!(increase["metric"]["amount"], 17.99);
+
+    // Recording the new price clears it; the confirmed subscription is overdue by now
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/subscriptions/{id}"),
+        serde_json::json!({ "expected_amount": 17.99 }),
+    )
+    .await;
+    assert_eq!(json["data"]["expected_amount"], 17.99);
+    let (_, json) = get_json(&app, "/api/subscriptions/alerts").await;
+    assert_eq!(alert_types(&json, "NETFLIX.COM"), vec!["overdue"]);
+
+    let (_, json) = patch_json(
+        &app,
+        &format!("/api/subscriptions/{id}"),
+        serde_json::json!({ "expected_amount": -1 }),
+    )
+    .await;
+    assert!(json["error"].is_string());
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::*;
+
+const SCOPE: &str = "end_date=2026-02-28";
+
+async fn seed_suspicious(pool: &sqlx::PgPool) {
+    seed_transactions(pool).await;
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized) VALUES
+            ('2026-02-14', 'BEST BUY #123', 129.99, 'Shopping', 'amex', 'Amex Gold', 'sus_hash_001', 'BEST BUY'),
+            ('2026-02-15', 'BEST BUY #123', 129.99, 'Shopping', 'citi', 'Citi Costco', 'sus_hash_002', 'BEST BUY'),
+            ('2026-02-16', 'GIFT CARD KIOSK', 200.00, 'Shopping', 'amex', 'Amex Gold', 'sus_hash_003', 'GIFT CARD KIOSK'),
+            ('2026-02-18', 'SKETCHY STORE', 1.00, 'Shopping', 'citi', 'Citi Costco', 'sus_hash_004', 'SKETCHY STORE'),
+            ('2026-02-19', 'SKETCHY STORE', 480.00, 'Shopping', 'citi', 'Citi Costco', 'sus_hash_005', 'SKETCHY STORE')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash, merchant_normalized, \
+           original_amount, original_currency) \
+         VALUES ('2026-02-17', 'CAFE DE FLORE PARIS', 42.00, 'Dining', 'amex', 'Amex Gold', 'sus_hash_006', 'CAFE DE FLORE', 38.50, 'EUR')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn kinds(json: &serde_json::Value) -> Vec<String> {
+    let mut kinds: Vec<String> = json["data"]["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["kind"].as_str().unwrap().to_string())
+        .collect();
+    kinds.sort();
+    kinds
+}
+
+#[tokio::test]
+async fn test_suspicious_detectors() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_transactions(&pool).await;
+    let app = app(pool.clone());
+
+    // The seed alone has only the round first charge at TARGET
+    let (status, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    assert_eq!(status, 200);
+    assert_eq!(kinds(&json), vec!["round_amount_new_merchant"]);
+    assert_eq!(json["data"]["findings"][0]["merchant"], "TARGET STORE");
+
+    clean(&pool).await;
+    seed_suspicious(&pool).await;
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    assert_eq!(
+        kinds(&json),
+        vec![
+            "duplicate_charge",
+            "foreign_on_domestic",
+            "round_amount_new_merchant",
+            "round_amount_new_merchant",
+            "test_charge"
+        ]
+    );
+    let findings = json["data"]["findings"].as_array().unwrap();
+    // Newest first
+    assert_eq!(findings[0]["kind"], "test_charge");
+    assert_eq!(findings[0]["amount"], 480.0);
+    assert_eq!(findings[0]["transactions"].as_array().unwrap().len(), 2);
+    let duplicate = findings.iter().find(|f| f["kind"] == "duplicate_charge").unwrap();
+    assert_eq!(duplicate["merchant"], "BEST BUY");
+    assert_eq!(duplicate["severity"], "high");
+    let foreign = findings.iter().find(|f| f["kind"] == "foreign_on_domestic").unwrap();
+    assert!(foreign["message"].as_str().unwrap().contains("EUR"));
+
+    // Outside the 90-day window nothing is reported
+    let (_, json) = get_json(&app, "/api/stats/suspicious?end_date=2026-09-30").await;
+    assert!(json["data"]["findings"].as_array().unwrap().is_empty());
+
+    // A tighter duplicate window drops the day-apart pair
+    put_json(
+        &app,
+        "/api/analytics/settings",
+        serde_json::json!({ "duplicate_window_days": 0 }),
+    )
+    .await;
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    assert!(!kinds(&json).contains(&"duplicate_charge".to_string()));
+}
+
+#[tokio::test]
+async fn test_suspicious_dismiss_and_restore() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_suspicious(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    let duplicate = json["data"]["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["kind"] == "duplicate_charge")
+        .unwrap()
+        .clone();
+    let id = duplicate["id"].as_str().unwrap();
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/stats/suspicious/{id}/dismiss?{SCOPE}"),
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["data"]["dismissed"], true);
+
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    assert_eq!(json["data"]["findings"].as_array().unwrap().len(), 4);
+    assert_eq!(json["data"]["dismissed_count"], 1);
+
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?include_dismissed=true&{SCOPE}")).await;
+    let findings = json["data"]["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 5);
+    let dismissed = findings.iter().find(|f| f["id"] == id).unwrap();
+    assert_eq!(dismissed["dismissed"], true);
+
+    // Dismissed findings don't reach the insights feed
+    let (_, json) = get_json(&app, &format!("/api/stats/insights?{SCOPE}")).await;
+    let suspicious: Vec<&serde_json::Value> = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|i| i["type"] == "suspicious")
+        .collect();
+    assert!(!suspicious.is_empty());
+    assert!(suspicious.iter().all(|i| i["metric"]["finding_id"] != id));
+
+    let (_, json) = post_json(
+        &app,
+        &format!("/api/stats/suspicious/0000000000000000/dismiss?{SCOPE}"),
+        serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(json["error"], "Finding not found");
+
+    let (_, json) = delete_json(&app, &format!("/api/stats/suspicious/{id}/dismiss")).await;
+    assert_eq!(json["data"]["dismissed"], false);
+    let (_, json) = get_json(&app, &format!("/api/stats/suspicious?{SCOPE}")).await;
+    assert_eq!(json["data"]["findings"].as_array().unwrap().len(), 5);
+    assert_eq!(json["data"]["dismissed_count"], 0);
+
+    let (_, json) = delete_json(&app, &format!("/api/stats/suspicious/{id}/dismiss")).await;
+    assert!(json["error"].is_string());
+}
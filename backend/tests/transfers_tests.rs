@@ -0,0 +1,80 @@
+mod common;
+
+use common::*;
+
+/// A card payment from checking to Citi, plus one ordinary purchase.
+async fn seed_card_payment(pool: &sqlx::PgPool) {
+    sqlx::query(
+        "INSERT INTO cards (code, label, color, account_type) VALUES ('test_checking', 'Test Checking', '#10B981', 'checking')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO transactions (date, description, amount, category, card, card_label, hash) VALUES
+        ('2026-03-01', 'CITI AUTOPAY', 500.00, 'Uncategorized', 'test_checking', 'Test Checking', 'xfer_hash_001'),
+        ('2026-03-03', 'PAYMENT THANK YOU', -500.00, 'Uncategorized', 'citi', 'Citi Costco', 'xfer_hash_002'),
+        ('2026-03-04', 'WHOLE FOODS', 100.00, 'Groceries', 'citi', 'Citi Costco', 'xfer_hash_003')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_matched_transfers_excluded_from_spending() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_card_payment(&pool).await;
+    let app = app(pool);
+
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 3);
+
+    let (status, json) = post_json(&app, "/api/transfers/match", serde_json::json!({})).await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["suggested"].as_u64().unwrap(), 1);
+
+    let (_, json) = get_json(&app, "/api/transfers").await;
+    let pairs = json["data"].as_array().unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0]["status"].as_str().unwrap(), "suggested");
+    assert_eq!(pairs[0]["debit_card"].as_str().unwrap(), "test_checking");
+    assert_eq!(pairs[0]["credit_card"].as_str().unwrap(), "citi");
+    assert_eq!(pairs[0]["amount"].as_f64().unwrap(), 500.0);
+
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 1);
+    assert_eq!(json["data"]["total_spent"].as_f64().unwrap(), 100.0);
+
+    // Running again finds nothing new
+    let (_, json) = post_json(&app, "/api/transfers/match", serde_json::json!({})).await;
+    assert_eq!(json["data"]["suggested"].as_u64().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_confirm_and_unlink_transfer() {
+    let pool = test_pool().await;
+    clean(&pool).await;
+    seed_card_payment(&pool).await;
+    let app = app(pool);
+
+    post_json(&app, "/api/transfers/match", serde_json::json!({})).await;
+    let (_, json) = get_json(&app, "/api/transfers").await;
+    let id = json["data"][0]["id"].as_str().unwrap().to_string();
+
+    let (_, json) = post_json(&app, &format!("/api/transfers/{}/confirm", id), serde_json::json!({})).await;
+    assert_eq!(json["data"]["status"].as_str().unwrap(), "confirmed");
+
+    let (_, json) = delete_json(&app, &format!("/api/transfers/{}", id)).await;
+    assert_eq!(json["data"]["status"].as_str().unwrap(), "unlinked");
+
+    let (_, json) = get_json(&app, "/api/stats/summary").await;
+    assert_eq!(json["data"]["transaction_count"].as_i64().unwrap(), 3);
+
+    // An unlinked pair is not suggested again
+    let (_, json) = post_json(&app, "/api/transfers/match", serde_json::json!({})).await;
+    assert_eq!(json["data"]["suggested"].as_u64().unwrap(), 0);
+    let (_, json) = get_json(&app, "/api/transfers?status=unlinked").await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+}
@@ -24,9 +24,17 @@ import {
   deleteBudget,
 } from "@/lib/api";
 import { CATEGORIES } from "@/lib/constants";
-import type { Card, Budget } from "@/types";
+import type { Card, Budget, BudgetPeriod } from "@/types";
 
 // ── Expand/collapse animation variants ──
+const BUDGET_PERIOD_SUFFIX: Record<BudgetPeriod, string> = {
+  weekly: "wk",
+  monthly: "mo",
+  quarterly: "qtr",
+  annual: "yr",
+  custom: "period",
+};
+
 const expandVariants = {
   hidden: { opacity: 0, height: 0, overflow: "hidden" as const },
   visible: {
@@ -1061,14 +1069,14 @@ export default function SettingsPage() {
                                         color: theme.textMuted,
                                       }}
                                     >
-                                      ${budget.monthly_limit.toFixed(2)}/mo
+                                      ${budget.amount.toFixed(2)}/{BUDGET_PERIOD_SUFFIX[budget.period]}
                                     </span>
                                     <ThemedButton
                                       variant="ghost"
                                       size="sm"
                                       onClick={() => {
                                         setEditingBudgetId(budget.id);
-                                        setEditBudgetLimit(budget.monthly_limit.toFixed(2));
+                                        setEditBudgetLimit(budget.amount.toFixed(2));
                                       }}
                                     >
                                       Edit
@@ -939,7 +939,7 @@ export function Meridian({
                               className="font-mono text-[11px] tabular-nums"
                               style={{ color: theme.textMuted }}
                             >
-                              / {formatCurrency(bp.monthly_limit)}
+                              / {formatCurrency(bp.available)}
                             </span>
                             <span
                               className="rounded-full px-2 py-0.5 text-[10px] font-medium"
@@ -17,8 +17,25 @@ import type {
   HabitAnalysis,
   DailySpending,
   CategoryDeepDive,
+  Category,
+  NewCategory,
+  AppliedBudgetSuggestions,
   Budget,
+  BudgetHistory,
+  BudgetLimit,
+  BudgetPeriod,
+  BudgetScope,
+  BudgetSuggestion,
   BudgetProgress,
+  BudgetTransfer,
+  Goal,
+  GoalContribution,
+  GoalProgress,
+  NewGoal,
+  NotificationChannel,
+  NotificationChannelKind,
+  NotificationDelivery,
+  NotificationRun,
 } from "@/types";
 
 async function fetcher<T>(url: string, options?: RequestInit): Promise<T> {
@@ -107,6 +124,16 @@ export async function bulkUpdateCategory(
   });
 }
 
+export async function updateTags(
+  id: string,
+  tags: string[]
+): Promise<{ data: string[] }> {
+  return fetcher(`/transactions/${id}/tags`, {
+    method: "PATCH",
+    body: JSON.stringify({ tags }),
+  });
+}
+
 // ── Import ──
 
 export async function importCSV(
@@ -214,11 +241,68 @@ export async function getBudgets(): Promise<{ data: Budget[] }> {
 
 export async function upsertBudget(
   category: string,
-  monthlyLimit: number
+  amount: number,
+  period?: { period: BudgetPeriod; start_date?: string; end_date?: string },
+  rollover?: { rollover: boolean; rollover_cap?: number | null },
+  scope?: BudgetScope
 ): Promise<{ data: Budget }> {
   return fetcher("/budgets", {
     method: "POST",
-    body: JSON.stringify({ category, monthly_limit: monthlyLimit }),
+    body: JSON.stringify({ category, amount, ...period, ...rollover, scope }),
+  });
+}
+
+export async function getBudgetHistory(
+  months = 12
+): Promise<{ data: BudgetHistory }> {
+  return fetcher(`/budgets/history?months=${months}`);
+}
+
+export async function getBudgetSuggestions(
+  months = 12,
+  percentile = 50
+): Promise<{ data: BudgetSuggestion[] }> {
+  return fetcher(`/budgets/suggestions?months=${months}&percentile=${percentile}`);
+}
+
+export async function applyBudgetSuggestions(
+  categories: string[],
+  months = 12,
+  percentile = 50
+): Promise<{ data: AppliedBudgetSuggestions }> {
+  return fetcher("/budgets/suggestions/apply", {
+    method: "POST",
+    body: JSON.stringify({ categories, months, percentile }),
+  });
+}
+
+export async function getBudgetLimits(
+  id: string
+): Promise<{ data: BudgetLimit[] }> {
+  return fetcher(`/budgets/${id}/limits`);
+}
+
+export async function getBudgetTransfers(
+  category?: string
+): Promise<{ data: BudgetTransfer[] }> {
+  const query = category ? `?category=${encodeURIComponent(category)}` : "";
+  return fetcher(`/budgets/transfers${query}`);
+}
+
+export async function transferBudget(
+  fromCategory: string,
+  toCategory: string,
+  amount: number,
+  note?: string
+): Promise<{ data: BudgetTransfer }> {
+  return fetcher("/budgets/transfers", {
+    method: "POST",
+    body: JSON.stringify({
+      from_category: fromCategory,
+      to_category: toCategory,
+      amount,
+      note,
+    }),
   });
 }
 
@@ -229,3 +313,143 @@ export async function deleteBudget(id: string): Promise<void> {
 export async function getBudgetProgress(): Promise<{ data: BudgetProgress[] }> {
   return fetcher("/budgets/progress");
 }
+
+// ── Categories ──
+
+export async function getCategories(): Promise<{ data: Category[] }> {
+  return fetcher("/categories");
+}
+
+export async function createCategory(category: NewCategory): Promise<{ data: Category }> {
+  return fetcher("/categories", {
+    method: "POST",
+    body: JSON.stringify(category),
+  });
+}
+
+export async function editCategory(
+  id: string,
+  updates: Partial<NewCategory>
+): Promise<{ data: Category }> {
+  return fetcher(`/categories/${id}`, {
+    method: "PATCH",
+    body: JSON.stringify(updates),
+  });
+}
+
+export async function deleteCategory(
+  id: string,
+  reassignTo?: string
+): Promise<{ data: { deleted: string; reassigned: number; reassigned_to: string | null } }> {
+  const query = reassignTo ? `?reassign_to=${encodeURIComponent(reassignTo)}` : "";
+  return fetcher(`/categories/${id}${query}`, { method: "DELETE" });
+}
+
+// ── Goals ──
+
+export async function getGoals(): Promise<{ data: GoalProgress[] }> {
+  return fetcher("/goals");
+}
+
+export async function getGoal(id: string): Promise<{ data: GoalProgress }> {
+  return fetcher(`/goals/${id}`);
+}
+
+export async function createGoal(goal: NewGoal): Promise<{ data: Goal }> {
+  return fetcher("/goals", {
+    method: "POST",
+    body: JSON.stringify(goal),
+  });
+}
+
+export async function updateGoal(
+  id: string,
+  updates: Partial<NewGoal>
+): Promise<{ data: Goal }> {
+  return fetcher(`/goals/${id}`, {
+    method: "PATCH",
+    body: JSON.stringify(updates),
+  });
+}
+
+export async function deleteGoal(id: string): Promise<void> {
+  return fetcher(`/goals/${id}`, { method: "DELETE" });
+}
+
+export async function getGoalContributions(
+  id: string
+): Promise<{ data: GoalContribution[] }> {
+  return fetcher(`/goals/${id}/contributions`);
+}
+
+export async function addGoalContribution(
+  id: string,
+  amount: number,
+  date?: string,
+  note?: string
+): Promise<{ data: GoalContribution }> {
+  return fetcher(`/goals/${id}/contributions`, {
+    method: "POST",
+    body: JSON.stringify({ amount, date, note }),
+  });
+}
+
+export async function deleteGoalContribution(
+  id: string,
+  contributionId: string
+): Promise<void> {
+  return fetcher(`/goals/${id}/contributions/${contributionId}`, { method: "DELETE" });
+}
+
+// ── Notifications ──
+
+export async function getNotificationChannels(): Promise<{ data: NotificationChannel[] }> {
+  return fetcher("/notifications/channels");
+}
+
+export async function createNotificationChannel(channel: {
+  name: string;
+  kind: NotificationChannelKind;
+  config: Record<string, unknown>;
+  enabled?: boolean;
+}): Promise<{ data: NotificationChannel }> {
+  return fetcher("/notifications/channels", {
+    method: "POST",
+    body: JSON.stringify(channel),
+  });
+}
+
+export async function updateNotificationChannel(
+  id: string,
+  updates: { name?: string; config?: Record<string, unknown>; enabled?: boolean }
+): Promise<{ data: NotificationChannel }> {
+  return fetcher(`/notifications/channels/${id}`, {
+    method: "PATCH",
+    body: JSON.stringify(updates),
+  });
+}
+
+export async function deleteNotificationChannel(id: string): Promise<void> {
+  return fetcher(`/notifications/channels/${id}`, { method: "DELETE" });
+}
+
+export async function testNotificationChannel(
+  id: string
+): Promise<{ data: { sent: boolean } }> {
+  return fetcher(`/notifications/channels/${id}/test`, { method: "POST" });
+}
+
+export async function runNotifications(): Promise<{ data: NotificationRun }> {
+  return fetcher("/notifications/run", { method: "POST" });
+}
+
+export async function getNotificationDeliveries(
+  params: { channel_id?: string; status?: "sent" | "failed"; limit?: number } = {}
+): Promise<{ data: NotificationDelivery[] }> {
+  const query = new URLSearchParams();
+  Object.entries(params).forEach(([key, value]) => {
+    if (value !== undefined) query.set(key, String(value));
+  });
+  const qs = query.toString();
+  return fetcher(`/notifications/deliveries${qs ? `?${qs}` : ""}`);
+}
@@ -1,4 +1,4 @@
-import type { Card } from "@/types";
+import type { Card, Category } from "@/types";
 
 export function getCardColor(cards: Card[], code: string): string {
   return cards.find((c) => c.code === code)?.color ?? "#6B7280";
@@ -32,4 +32,10 @@ export const CATEGORY_COLORS: Record<string, string> = {
   Uncategorized: "#6B7280",
 };
 
+export function getCategoryColor(categories: Category[], name: string): string {
+  const category = categories.find((c) => c.name === name);
+  const parent = categories.find((c) => c.name === category?.parent);
+  return category?.color ?? parent?.color ?? CATEGORY_COLORS[name] ?? "#6B7280";
+}
+
 export const API_BASE = "http://localhost:8080/api";
@@ -1,5 +1,6 @@
 import type {
   EnhancedSummaryStats,
+  ParentCategoryTotal,
   MonthlyData,
   EnhancedMerchant,
   Card,
@@ -122,6 +123,41 @@ export const TEST_CARDS: Card[] = [
   },
 ];
 
+const TEST_CATEGORY_TOTALS: ParentCategoryTotal[] = [
+  {
+    category: "Dining", total: 3245.67, count: 68, avg_amount: 47.73, color: "#F59E0B", kind: "discretionary",
+    children: [{ category: "Dining", total: 3245.67, count: 68, avg_amount: 47.73 }],
+  },
+  {
+    category: "Groceries", total: 2876.43, count: 52, avg_amount: 55.32, color: "#10B981", kind: "essential",
+    children: [{ category: "Groceries", total: 2876.43, count: 52, avg_amount: 55.32 }],
+  },
+  {
+    category: "Shopping", total: 2134.89, count: 34, avg_amount: 62.79, color: "#8B5CF6", kind: "discretionary",
+    children: [{ category: "Shopping", total: 2134.89, count: 34, avg_amount: 62.79 }],
+  },
+  {
+    category: "Subscriptions", total: 1567.32, count: 24, avg_amount: 65.31, color: "#EC4899", kind: "discretionary",
+    children: [{ category: "Subscriptions", total: 1567.32, count: 24, avg_amount: 65.31 }],
+  },
+  {
+    category: "Gas", total: 1432.11, count: 28, avg_amount: 51.15, color: "#EF4444", kind: "essential",
+    children: [{ category: "Gas", total: 1432.11, count: 28, avg_amount: 51.15 }],
+  },
+  {
+    category: "Transportation", total: 1123.45, count: 18, avg_amount: 62.41, color: "#06B6D4", kind: "essential",
+    children: [{ category: "Transportation", total: 1123.45, count: 18, avg_amount: 62.41 }],
+  },
+  {
+    category: "Travel", total: 987.65, count: 8, avg_amount: 123.46, color: "#3B82F6", kind: "discretionary",
+    children: [{ category: "Travel", total: 987.65, count: 8, avg_amount: 123.46 }],
+  },
+  {
+    category: "Health", total: 756.35, count: 15, avg_amount: 50.42, color: "#14B8A6", kind: "essential",
+    children: [{ category: "Health", total: 756.35, count: 15, avg_amount: 50.42 }],
+  },
+];
+
 export const TEST_SUMMARY: EnhancedSummaryStats = {
   total_spent: 14523.87,
   transaction_count: 247,
@@ -138,15 +174,17 @@ export const TEST_SUMMARY: EnhancedSummaryStats = {
     { card: "CAPONE", total: 3398.20, count: 62, avg_amount: 54.81 },
   ],
   by_category: [
-    { category: "Dining", total: 3245.67, count: 68, avg_amount: 47.73 },
-    { category: "Groceries", total: 2876.43, count: 52, avg_amount: 55.32 },
-    { category: "Shopping", total: 2134.89, count: 34, avg_amount: 62.79 },
-    { category: "Subscriptions", total: 1567.32, count: 24, avg_amount: 65.31 },
-    { category: "Gas", total: 1432.11, count: 28, avg_amount: 51.15 },
-    { category: "Transportation", total: 1123.45, count: 18, avg_amount: 62.41 },
-    { category: "Travel", total: 987.65, count: 8, avg_amount: 123.46 },
-    { category: "Health", total: 756.35, count: 15, avg_amount: 50.42 },
+    { category: "Dining", parent: null, total: 3245.67, count: 68, avg_amount: 47.73 },
+    { category: "Groceries", parent: null, total: 2876.43, count: 52, avg_amount: 55.32 },
+    { category: "Shopping", parent: null, total: 2134.89, count: 34, avg_amount: 62.79 },
+    { category: "Subscriptions", parent: null, total: 1567.32, count: 24, avg_amount: 65.31 },
+    { category: "Gas", parent: null, total: 1432.11, count: 28, avg_amount: 51.15 },
+    { category: "Transportation", parent: null, total: 1123.45, count: 18, avg_amount: 62.41 },
+    { category: "Travel", parent: null, total: 987.65, count: 8, avg_amount: 123.46 },
+    { category: "Health", parent: null, total: 756.35, count: 15, avg_amount: 50.42 },
   ],
+  by_parent: TEST_CATEGORY_TOTALS,
+  by_kind: { essential: 6188.34, discretionary: 7935.53, unclassified: 400 },
 };
 
 export const TEST_MONTHLY: MonthlyData = generateMonthly();
@@ -225,7 +263,15 @@ export const TEST_FORECAST: ForecastData = {
     linear: 2478.12,
     day_weighted: 2512.34,
     ewma: 2456.78,
-    recommended: 2478.12,
+    seasonal: null,
+    recommended: 2489.04,
+    interval: { lower: 2401.77, upper: 2576.31, confidence: 0.8 },
+    accuracy: [
+      { method: "linear", mape: 6.4, samples: 11, weight: 0.28 },
+      { method: "day_weighted", mape: 4.1, samples: 11, weight: 0.43 },
+      { method: "ewma", mape: 6.1, samples: 11, weight: 0.29 },
+      { method: "seasonal", mape: null, samples: 0, weight: 0 },
+    ],
   },
   vs_last_month: {
     last_month_total: 2187.32,
@@ -236,12 +282,12 @@ export const TEST_FORECAST: ForecastData = {
     projected_diff_pct: 2.37,
   },
   category_forecasts: [
-    { category: "Dining", spent_so_far: 612.34, projected: 1008.45, avg_monthly: 876.23, vs_avg_pct: 15.09, trend: "up" },
-    { category: "Groceries", spent_so_far: 489.12, projected: 805.67, avg_monthly: 812.45, vs_avg_pct: -0.83, trend: "stable" },
-    { category: "Shopping", spent_so_far: 367.89, projected: 605.45, avg_monthly: 534.23, vs_avg_pct: 13.33, trend: "up" },
-    { category: "Subscriptions", spent_so_far: 287.45, projected: 312.34, avg_monthly: 298.56, vs_avg_pct: 4.61, trend: "stable" },
-    { category: "Gas", spent_so_far: 198.76, projected: 327.12, avg_monthly: 345.67, vs_avg_pct: -5.37, trend: "down" },
-    { category: "Transportation", spent_so_far: 156.23, projected: 257.34, avg_monthly: 234.56, vs_avg_pct: 9.71, trend: "up" },
+    { category: "Dining", spent_so_far: 612.34, projected: 1008.45, linear: 1008.45, ewma: 921.4, avg_monthly: 876.23, vs_avg_pct: 15.09, trend: "up" },
+    { category: "Groceries", spent_so_far: 489.12, projected: 805.67, linear: 805.67, ewma: 809.1, avg_monthly: 812.45, vs_avg_pct: -0.83, trend: "stable" },
+    { category: "Shopping", spent_so_far: 367.89, projected: 605.45, linear: 605.45, ewma: 561.2, avg_monthly: 534.23, vs_avg_pct: 13.33, trend: "up" },
+    { category: "Subscriptions", spent_so_far: 287.45, projected: 312.34, linear: 312.34, ewma: 301.8, avg_monthly: 298.56, vs_avg_pct: 4.61, trend: "stable" },
+    { category: "Gas", spent_so_far: 198.76, projected: 327.12, linear: 327.12, ewma: 340.5, avg_monthly: 345.67, vs_avg_pct: -5.37, trend: "down" },
+    { category: "Transportation", spent_so_far: 156.23, projected: 257.34, linear: 257.34, ewma: 241.9, avg_monthly: 234.56, vs_avg_pct: 9.71, trend: "up" },
   ],
   trajectory: "near_average",
 };
@@ -300,6 +346,8 @@ export const TEST_ANOMALIES: CategoryAnomaly[] = [
     z_score: 2.67,
     severity: "high",
     pct_above_avg: 42.04,
+    expected_to_date: 380.5,
+    projected: 663.7,
     message: "Dining is significantly above average this month.",
   },
   {
@@ -310,6 +358,8 @@ export const TEST_ANOMALIES: CategoryAnomaly[] = [
     z_score: 1.78,
     severity: "elevated",
     pct_above_avg: 27.98,
+    expected_to_date: 250.1,
+    projected: 405.2,
     message: "Shopping is moderately above average.",
   },
   {
@@ -320,19 +370,21 @@ export const TEST_ANOMALIES: CategoryAnomaly[] = [
     z_score: 1.87,
     severity: "elevated",
     pct_above_avg: 39.07,
+    expected_to_date: 98.4,
+    projected: 170.1,
     message: "Transportation spending has increased this month.",
   },
 ];
 
 export const TEST_RECURRING: RecurringTransaction[] = [
-  { merchant: "Netflix", avg_amount: 15.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-15", last_seen: "2026-02-15", estimated_annual: 191.88, status: "active", last_gap_days: 30, potentially_forgotten: false },
-  { merchant: "Spotify", avg_amount: 10.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-20", last_seen: "2026-02-20", estimated_annual: 131.88, status: "active", last_gap_days: 30, potentially_forgotten: false },
-  { merchant: "iCloud Storage", avg_amount: 2.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-01", last_seen: "2026-02-01", estimated_annual: 35.88, status: "active", last_gap_days: 31, potentially_forgotten: false },
-  { merchant: "ChatGPT Plus", avg_amount: 20.00, frequency: "monthly", active_months: 4, first_seen: "2025-10-15", last_seen: "2026-02-15", estimated_annual: 240.00, status: "active", last_gap_days: 31, potentially_forgotten: false },
-  { merchant: "Gym Membership", avg_amount: 49.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-01", last_seen: "2026-02-01", estimated_annual: 599.88, status: "active", last_gap_days: 31, potentially_forgotten: false },
-  { merchant: "NYT Digital", avg_amount: 17.00, frequency: "monthly", active_months: 5, first_seen: "2025-09-10", last_seen: "2026-02-10", estimated_annual: 204.00, status: "active", last_gap_days: 31, potentially_forgotten: false },
-  { merchant: "Adobe CC", avg_amount: 54.99, frequency: "monthly", active_months: 3, first_seen: "2025-11-15", last_seen: "2026-01-15", estimated_annual: 659.88, status: "active", last_gap_days: 33, potentially_forgotten: false },
-  { merchant: "Crunchyroll", avg_amount: 7.99, frequency: "monthly", active_months: 2, first_seen: "2025-12-01", last_seen: "2026-01-01", estimated_annual: 95.88, status: "inactive", last_gap_days: 47, potentially_forgotten: true },
+  { merchant: "Netflix", avg_amount: 15.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-15", last_seen: "2026-02-15", estimated_annual: 191.88, status: "active", last_gap_days: 30, potentially_forgotten: false, charge_count: 6, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 15.99, card: "amex", next_expected_date: "2026-03-15", next_expected_amount: 15.99, confidence: 0.9 },
+  { merchant: "Spotify", avg_amount: 10.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-20", last_seen: "2026-02-20", estimated_annual: 131.88, status: "active", last_gap_days: 30, potentially_forgotten: false, charge_count: 6, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 10.99, card: "amex", next_expected_date: "2026-03-20", next_expected_amount: 10.99, confidence: 0.9 },
+  { merchant: "iCloud Storage", avg_amount: 2.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-01", last_seen: "2026-02-01", estimated_annual: 35.88, status: "active", last_gap_days: 31, potentially_forgotten: false, charge_count: 6, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 2.99, card: "amex", next_expected_date: "2026-03-01", next_expected_amount: 2.99, confidence: 0.9 },
+  { merchant: "ChatGPT Plus", avg_amount: 20.00, frequency: "monthly", active_months: 4, first_seen: "2025-10-15", last_seen: "2026-02-15", estimated_annual: 240.00, status: "active", last_gap_days: 31, potentially_forgotten: false, charge_count: 4, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 20.00, card: "amex", next_expected_date: "2026-03-15", next_expected_amount: 20.00, confidence: 0.9 },
+  { merchant: "Gym Membership", avg_amount: 49.99, frequency: "monthly", active_months: 6, first_seen: "2025-08-01", last_seen: "2026-02-01", estimated_annual: 599.88, status: "active", last_gap_days: 31, potentially_forgotten: false, charge_count: 6, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 49.99, card: "amex", next_expected_date: "2026-03-01", next_expected_amount: 49.99, confidence: 0.9 },
+  { merchant: "NYT Digital", avg_amount: 17.00, frequency: "monthly", active_months: 5, first_seen: "2025-09-10", last_seen: "2026-02-10", estimated_annual: 204.00, status: "active", last_gap_days: 31, potentially_forgotten: false, charge_count: 5, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 17.00, card: "amex", next_expected_date: "2026-03-10", next_expected_amount: 17.00, confidence: 0.9 },
+  { merchant: "Adobe CC", avg_amount: 54.99, frequency: "monthly", active_months: 3, first_seen: "2025-11-15", last_seen: "2026-01-15", estimated_annual: 659.88, status: "active", last_gap_days: 33, potentially_forgotten: false, charge_count: 3, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 54.99, card: "amex", next_expected_date: "2026-02-15", next_expected_amount: 54.99, confidence: 0.9 },
+  { merchant: "Crunchyroll", avg_amount: 7.99, frequency: "monthly", active_months: 2, first_seen: "2025-12-01", last_seen: "2026-01-01", estimated_annual: 95.88, status: "inactive", last_gap_days: 47, potentially_forgotten: true, charge_count: 2, median_gap_days: 30.5, gap_stddev_days: 1.2, monthly_amount: 7.99, card: "amex", next_expected_date: "2026-02-01", next_expected_amount: 7.99, confidence: 0.6 },
 ];
 
 export const TEST_HABITS: HabitAnalysis = {
@@ -363,6 +415,16 @@ export const TEST_HABITS: HabitAnalysis = {
     count: 8,
     potentially_forgotten: ["Crunchyroll"],
     message: "8 subscriptions at $287/mo. Crunchyroll may be unused.",
+    next_30_days: 179.95,
+    next_charges: [
+      { merchant: "Gym Membership", date: "2026-03-01", amount: 49.99, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "iCloud Storage", date: "2026-03-01", amount: 2.99, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "NYT Digital", date: "2026-03-10", amount: 17.00, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "Netflix", date: "2026-03-15", amount: 15.99, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "ChatGPT Plus", date: "2026-03-15", amount: 20.00, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "Adobe CC", date: "2026-03-15", amount: 54.99, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+      { merchant: "Spotify", date: "2026-03-20", amount: 10.99, card: "amex", frequency: "monthly", confidence: 0.9, overdue: false },
+    ],
   },
   merchant_concentration: {
     top_merchant: "Whole Foods",
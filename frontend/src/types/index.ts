@@ -53,6 +53,7 @@ export interface Transaction {
   card_label: string;
   raw_data: Record<string, string> | null;
   hash: string;
+  tags: string[];
   created_at: string;
 }
 
@@ -77,6 +78,8 @@ export interface ImportResult {
   duplicate_count: number;
   skipped_user_count: number;
   total_parsed: number;
+  /** The parser's categories that aren't listed, with how many rows were filed as Uncategorized */
+  unlisted_categories: { category: string; count: number }[];
 }
 
 export interface ImportRecord {
@@ -123,12 +126,17 @@ export interface EnhancedSummaryStats extends SummaryStats {
   vs_avg_pct: number | null;
   daily_rate: number;
   projected_month_total: number;
+  /** Transactions left out of the totals for lack of a rate into the home currency */
+  unconverted_count: number;
   by_category: Array<{
     category: string;
+    parent: string | null;
     total: number;
     count: number;
     avg_amount: number;
   }>;
+  by_parent: ParentCategoryTotal[];
+  by_kind: { essential: number; discretionary: number; unclassified: number };
   by_card: Array<{
     card: string;
     total: number;
@@ -159,7 +167,15 @@ export interface ForecastData {
     linear: number;
     day_weighted: number;
     ewma: number;
+    seasonal: number | null;
     recommended: number;
+    interval: { lower: number; upper: number; confidence: number };
+    accuracy: Array<{
+      method: "linear" | "day_weighted" | "ewma" | "seasonal";
+      mape: number | null;
+      samples: number;
+      weight: number;
+    }>;
   };
   vs_last_month: {
     last_month_total: number;
@@ -173,6 +189,8 @@ export interface ForecastData {
     category: string;
     spent_so_far: number;
     projected: number;
+    linear: number;
+    ewma: number | null;
     avg_monthly: number;
     vs_avg_pct: number;
     trend: "up" | "down" | "stable";
@@ -180,19 +198,75 @@ export interface ForecastData {
   trajectory: "below_average" | "near_average" | "above_average" | "well_above_average";
 }
 
+export interface ForecastHorizon {
+  months: Array<{
+    month: string;
+    total: number;
+    recurring: number;
+    lower: number;
+    upper: number;
+    categories: Array<{
+      category: string;
+      projected: number;
+      recurring: number;
+      lower: number;
+      upper: number;
+    }>;
+  }>;
+  total: number;
+  recurring_total: number;
+  confidence: number;
+}
+
+export type ScenarioAdjustment =
+  | { type: "category_pct"; category: string; pct: number }
+  | { type: "cancel_subscription"; subscription_id?: string; merchant?: string }
+  | { type: "add_expense"; amount: number; category?: string; description?: string };
+
+export interface ScenarioResult {
+  months: Array<{ month: string; baseline: number; scenario: number; difference: number }>;
+  baseline_total: number;
+  scenario_total: number;
+  difference: number;
+  avg_monthly_difference: number;
+  adjustments: Array<{ description: string; difference: number }>;
+}
+
 // ── Budget ──
 
+export interface BudgetScope {
+  categories?: string[];
+  merchants?: string[];
+  cards?: string[];
+  member?: string;
+  tags?: string[];
+}
+
+export type BudgetPeriod = "weekly" | "monthly" | "quarterly" | "annual" | "custom";
+
 export interface Budget {
   id: string;
   category: string;
-  monthly_limit: number;
+  amount: number;
+  period: BudgetPeriod;
+  start_date: string | null;
+  end_date: string | null;
+  rollover: boolean;
+  rollover_cap: number | null;
+  scope: BudgetScope | null;
   created_at: string;
   updated_at: string;
 }
 
 export interface BudgetProgress {
   category: string;
-  monthly_limit: number;
+  period: BudgetPeriod;
+  period_start: string;
+  period_end: string;
+  amount: number;
+  carried_in: number;
+  transferred: number;
+  available: number;
   spent: number;
   remaining: number;
   pct_used: number;
@@ -202,8 +276,215 @@ export interface BudgetProgress {
   days_remaining: number;
 }
 
+export interface BudgetLimit {
+  id: string;
+  budget_id: string;
+  amount: number;
+  effective_from: string;
+  created_at: string;
+}
+
+export interface BudgetPeriodResult {
+  period_start: string;
+  period_end: string;
+  limit: number;
+  carried_in: number;
+  transferred: number;
+  available: number;
+  spent: number;
+  variance: number;
+  variance_pct: number;
+  hit: boolean;
+  complete: boolean;
+}
+
+export interface BudgetCategoryHistory {
+  category: string;
+  period: BudgetPeriod;
+  periods: BudgetPeriodResult[];
+  hits: number;
+  misses: number;
+  current_streak: { kind: "hit" | "miss"; length: number } | null;
+  longest_hit_streak: number;
+  longest_miss_streak: number;
+  avg_variance: number;
+  avg_variance_pct: number;
+}
+
+export interface BudgetHistory {
+  from: string;
+  to: string;
+  budgets: BudgetCategoryHistory[];
+}
+
+export interface BudgetSuggestion {
+  category: string;
+  month: string;
+  suggested: number;
+  variable: number;
+  seasonal_index: number;
+  recurring: number;
+  months_used: number;
+  months_exceeded: number;
+  exceed_rate: number;
+  current_amount: number | null;
+  current_period: BudgetPeriod | null;
+}
+
+export interface AppliedBudgetSuggestions {
+  applied: Budget[];
+  skipped: { category: string; reason: string }[];
+}
+
+export interface BudgetTransfer {
+  id: string;
+  from_category: string;
+  to_category: string;
+  amount: number;
+  date: string;
+  note: string | null;
+  created_at: string;
+}
+
+export type CategoryKind = "essential" | "discretionary";
+
+export interface Category {
+  id: string;
+  name: string;
+  parent_id: string | null;
+  parent: string | null;
+  color: string | null;
+  icon: string | null;
+  kind: CategoryKind | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface NewCategory {
+  name: string;
+  parent?: string;
+  color?: string;
+  icon?: string;
+  kind?: CategoryKind | "";
+}
+
+export interface CategoryTotal {
+  category: string;
+  total: number;
+  count: number;
+  avg_amount: number;
+}
+
+export interface ParentCategoryTotal extends CategoryTotal {
+  color: string | null;
+  kind: CategoryKind | null;
+  children: CategoryTotal[];
+}
+
+export interface Goal {
+  id: string;
+  name: string;
+  target_amount: number;
+  target_date: string;
+  start_date: string;
+  initial_amount: number;
+  /** Card code of a linked checking, savings or cash account */
+  account: string | null;
+  tag: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export type GoalStatus = "completed" | "on_track" | "behind" | "overdue";
+
+export interface GoalProgress {
+  id: string;
+  name: string;
+  target_amount: number;
+  target_date: string;
+  start_date: string;
+  account: string | null;
+  tag: string | null;
+  saved: number;
+  remaining: number;
+  pct_complete: number;
+  expected_saved: number;
+  months_left: number;
+  required_monthly: number;
+  monthly_pace: number;
+  projected_completion: string | null;
+  on_track: boolean;
+  status: GoalStatus;
+}
+
+export interface GoalContribution {
+  id: string;
+  source: "manual" | "transaction";
+  date: string;
+  amount: number;
+  note: string | null;
+}
+
+export interface NewGoal {
+  name: string;
+  target_amount: number;
+  target_date: string;
+  start_date?: string;
+  initial_amount?: number;
+  account?: string;
+  tag?: string;
+}
+
+export type NotificationChannelKind = "webhook" | "smtp" | "ntfy" | "gotify";
+
+export interface NotificationChannel {
+  id: string;
+  name: string;
+  kind: NotificationChannelKind;
+  /** The kind's settings, e.g. { url } for a webhook */
+  config: Record<string, unknown>;
+  enabled: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface NotificationDelivery {
+  id: string;
+  channel_id: string;
+  alert_key: string;
+  alert_type: string;
+  title: string;
+  message: string;
+  status: "sent" | "failed";
+  error: string | null;
+  attempts: number;
+  /** First attempt */
+  created_at: string;
+  last_attempt_at: string;
+}
+
+export interface NotificationRun {
+  alerts: number;
+  sent: number;
+  failed: number;
+  already_sent: number;
+  gave_up: number;
+}
+
 export interface Insight {
-  type: "anomaly" | "trend" | "forecast" | "habit" | "recurring" | "milestone" | "positive" | "budget";
+  type:
+    | "anomaly"
+    | "trend"
+    | "forecast"
+    | "habit"
+    | "recurring"
+    | "milestone"
+    | "positive"
+    | "budget"
+    | "savings"
+    | "goal"
+    | "subscription"
+    | "suspicious";
   severity: "low" | "medium" | "high";
   icon: string;
   title: string;
@@ -217,6 +498,30 @@ export interface Insight {
   category?: string;
 }
 
+/**
+ * An insight as persisted: the feed and `/api/insights/history` both return these. A feed
+ * narrowed by more than the end date isn't saved, so an insight without a stored copy has a nil `id`.
+ */
+export interface StoredInsight extends Omit<Insight, "metric"> {
+  id: string;
+  fingerprint: string;
+  subject: string;
+  /** YYYY-MM */
+  period: string;
+  priority: number;
+  metric?: Record<string, unknown> | null;
+  first_seen_at: string;
+  last_seen_at: string;
+  dismissed_at: string | null;
+  snoozed_until: string | null;
+  helpful: boolean | null;
+}
+
+export interface InsightGeneratorSetting {
+  name: string;
+  enabled: boolean;
+}
+
 export interface CategoryAnomaly {
   category: string;
   current_month: number;
@@ -225,9 +530,50 @@ export interface CategoryAnomaly {
   z_score: number;
   severity: "elevated" | "high" | "critical";
   pct_above_avg: number;
+  expected_to_date: number;
+  projected: number;
+  message: string;
+}
+
+export interface MerchantAnomaly {
+  id: string;
+  date: string;
+  merchant: string;
+  description: string;
+  amount: number;
+  category: string;
+  kind: "first_time" | "unusual_amount";
+  prior_charges: number;
+  merchant_median: number | null;
+  z_score: number | null;
   message: string;
 }
 
+export interface SuspectTransaction {
+  id: string;
+  date: string;
+  description: string;
+  card: string;
+  amount: number;
+}
+
+export interface SuspiciousFinding {
+  id: string;
+  kind: "duplicate_charge" | "round_amount_new_merchant" | "foreign_on_domestic" | "test_charge";
+  severity: "low" | "medium" | "high";
+  date: string;
+  merchant: string;
+  amount: number;
+  transactions: SuspectTransaction[];
+  message: string;
+  dismissed: boolean;
+}
+
+export interface SuspiciousData {
+  findings: SuspiciousFinding[];
+  dismissed_count: number;
+}
+
 export interface RecurringTransaction {
   merchant: string;
   avg_amount: number;
@@ -239,6 +585,40 @@ export interface RecurringTransaction {
   status: "active" | "inactive";
   last_gap_days: number;
   potentially_forgotten: boolean;
+  charge_count: number;
+  median_gap_days: number;
+  gap_stddev_days: number;
+  monthly_amount: number;
+  card: string;
+  next_expected_date: string;
+  next_expected_amount: number;
+  confidence: number;
+}
+
+export interface PredictedCharge {
+  merchant: string;
+  date: string;
+  amount: number;
+  card: string;
+  frequency: string;
+  confidence: number;
+  overdue: boolean;
+}
+
+export interface UpcomingCharges {
+  from: string;
+  to: string;
+  month_end: string;
+  charges: PredictedCharge[];
+  total: number;
+  due_by_month_end: number;
+  by_card: Array<{
+    card: string;
+    account_type: string;
+    total: number;
+    due_by_month_end: number;
+    balance_change_by_month_end: number;
+  }>;
 }
 
 export interface HabitAnalysis {
@@ -270,6 +650,8 @@ export interface HabitAnalysis {
     count: number;
     potentially_forgotten: string[];
     message: string;
+    next_30_days: number;
+    next_charges: PredictedCharge[];
   };
   merchant_concentration: {
     top_merchant: string;
@@ -289,6 +671,7 @@ export interface DailySpending {
 
 export interface CategoryDeepDive {
   category: string;
+  subcategories: CategoryTotal[];
   total_spent: number;
   transaction_count: number;
   avg_amount: number;